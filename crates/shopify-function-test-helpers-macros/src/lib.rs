@@ -0,0 +1,230 @@
+//! Procedural macros for `shopify-function-test-helpers`
+//!
+//! Use these through the re-exports in `shopify_function_test_helpers`
+//! rather than depending on this crate directly.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Ident, ItemFn, LitStr, Token};
+
+const DEFAULT_FIXTURES_DIR: &str = "tests/fixtures";
+
+/// Generate one `#[test]` per JSON fixture file
+///
+/// The attribute goes on a function returning the `FunctionInfo` the tests
+/// run against. It takes the fixtures directory relative to the crate root,
+/// `tests/fixtures` by default, and optionally a metafield schema registry to
+/// check the fixtures' metafields against (see `load_metafield_schemas`):
+///
+/// ```ignore
+/// use shopify_function_test_helpers::{fixture_tests, get_function_info, FunctionInfo};
+///
+/// #[fixture_tests("tests/fixtures", metafield_schemas = "tests/metafield-schemas.json")]
+/// fn discount_function() -> FunctionInfo {
+///     get_function_info(env!("CARGO_MANIFEST_DIR")).unwrap()
+/// }
+/// ```
+///
+/// This expands to a `discount_function` module holding a test for each
+/// fixture, named after the file (`cart-lines-valid-fixture.json` becomes
+/// `discount_function::cart_lines_valid_fixture`). Each test validates the
+/// fixture, runs the export named in `payload.export` and compares the result
+/// to `payload.output`. The function is called once and shared by all tests.
+///
+/// Fixtures are discovered at compile time: editing a fixture recompiles the
+/// tests, but a newly added fixture is only picked up once the test file is
+/// rebuilt.
+#[proc_macro_attribute]
+pub fn fixture_tests(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as FixtureTestsArgs);
+    let function = parse_macro_input!(item as ItemFn);
+
+    match expand_fixture_tests(&args, &function) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => {
+            let error = error.to_compile_error();
+            quote!(#function #error).into()
+        }
+    }
+}
+
+/// `#[fixture_tests("dir", metafield_schemas = "path")]`, where both are optional
+struct FixtureTestsArgs {
+    fixtures_dir: LitStr,
+    metafield_schemas: Option<LitStr>,
+}
+
+impl Parse for FixtureTestsArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fixtures_dir = if input.peek(LitStr) {
+            input.parse()?
+        } else {
+            LitStr::new(DEFAULT_FIXTURES_DIR, Span::call_site())
+        };
+
+        let mut metafield_schemas = None;
+        if !input.is_empty() {
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            }
+            let name: Ident = input.parse()?;
+            if name != "metafield_schemas" {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("Unknown argument `{name}`, expected `metafield_schemas`"),
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            metafield_schemas = Some(input.parse()?);
+        }
+        if !input.is_empty() {
+            return Err(input.error("Unexpected tokens after the arguments"));
+        }
+
+        Ok(Self {
+            fixtures_dir,
+            metafield_schemas,
+        })
+    }
+}
+
+fn expand_fixture_tests(
+    args: &FixtureTestsArgs,
+    function: &ItemFn,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let fixtures_dir = &args.fixtures_dir;
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new(fixtures_dir.span(), "CARGO_MANIFEST_DIR is not set"))?;
+    let fixtures_path = Path::new(&manifest_dir).join(fixtures_dir.value());
+    let fixture_files = find_fixture_files(&fixtures_path)
+        .map_err(|message| syn::Error::new(fixtures_dir.span(), message))?;
+
+    let mut test_names = HashSet::new();
+    let mut tests = vec![];
+    for fixture_file in &fixture_files {
+        let test_name = test_name(fixture_file);
+        if !test_names.insert(test_name.to_string()) {
+            return Err(syn::Error::new(
+                fixtures_dir.span(),
+                format!(
+                    "Fixture {} maps to the test name `{test_name}`, which is already used by another fixture",
+                    fixture_file.display()
+                ),
+            ));
+        }
+
+        let fixture_file = fixture_file.to_string_lossy();
+        let assert_fixture = if args.metafield_schemas.is_some() {
+            quote! {
+                ::shopify_function_test_helpers::assert_fixture_with_metafield_schemas(
+                    #fixture_file,
+                    __function_info(),
+                    __metafield_schemas(),
+                )
+            }
+        } else {
+            quote!(::shopify_function_test_helpers::assert_fixture(#fixture_file, __function_info()))
+        };
+        tests.push(quote! {
+            #[test]
+            fn #test_name() {
+                // Recompile the tests when the fixture changes
+                const _: &[u8] = include_bytes!(#fixture_file);
+                #assert_fixture;
+            }
+        });
+    }
+
+    let metafield_schemas = args.metafield_schemas.as_ref().map(|path| {
+        let path = Path::new(&manifest_dir).join(path.value());
+        let path = path.to_string_lossy();
+        quote! {
+            fn __metafield_schemas() -> &'static [::shopify_function_test_helpers::MetafieldSchema] {
+                // Recompile the tests when the registry changes
+                const _: &[u8] = include_bytes!(#path);
+                static METAFIELD_SCHEMAS: ::std::sync::OnceLock<
+                    ::std::vec::Vec<::shopify_function_test_helpers::MetafieldSchema>,
+                > = ::std::sync::OnceLock::new();
+                METAFIELD_SCHEMAS.get_or_init(|| {
+                    ::shopify_function_test_helpers::load_metafield_schemas(#path)
+                        .unwrap_or_else(|e| panic!("{e}"))
+                })
+            }
+        }
+    });
+
+    let function_name = &function.sig.ident;
+    Ok(quote! {
+        #function
+
+        mod #function_name {
+            fn __function_info() -> &'static ::shopify_function_test_helpers::FunctionInfo {
+                static FUNCTION_INFO: ::std::sync::OnceLock<
+                    ::shopify_function_test_helpers::FunctionInfo,
+                > = ::std::sync::OnceLock::new();
+                FUNCTION_INFO.get_or_init(super::#function_name)
+            }
+
+            #metafield_schemas
+
+            #(#tests)*
+        }
+    })
+}
+
+/// The `.json` files directly inside `dir`, sorted by name
+fn find_fixture_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read fixtures directory {}: {e}", dir.display()))?;
+
+    let mut fixture_files = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read fixtures directory {}: {e}", dir.display()))?
+            .path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+            fixture_files.push(path);
+        }
+    }
+
+    if fixture_files.is_empty() {
+        return Err(format!("No fixture files found in {}", dir.display()));
+    }
+    fixture_files.sort();
+    Ok(fixture_files)
+}
+
+/// Turn a fixture file name into a test function name,
+/// e.g. `cart-lines-valid-fixture.json` into `cart_lines_valid_fixture`
+fn test_name(fixture_file: &Path) -> Ident {
+    let stem = fixture_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    let mut name: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+
+    // Keywords such as `type` or `self` are not valid test names
+    if syn::parse_str::<Ident>(&name).is_err() {
+        name.push('_');
+    }
+    format_ident!("{}", name)
+}
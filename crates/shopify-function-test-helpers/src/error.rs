@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+/// Errors raised while loading test assets and function information
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid JSON in fixture file {}: {message}", path.display())]
+    InvalidFixtureJson { path: PathBuf, message: String },
+
+    #[error("Failed to load fixture file {}: {message}", path.display())]
+    LoadFixture { path: PathBuf, message: String },
+
+    #[error("Failed to build schema from {}: {message}", path.display())]
+    LoadSchema { path: PathBuf, message: String },
+
+    #[error("Failed to load input query from {}: {message}", path.display())]
+    LoadInputQuery { path: PathBuf, message: String },
+
+    #[error("Failed to load metafield schemas from {}: {message}", path.display())]
+    LoadMetafieldSchemas { path: PathBuf, message: String },
+
+    #[error("Failed to load function info from {}: {message}", path.display())]
+    LoadFunctionInfo { path: PathBuf, message: String },
+
+    #[error(
+        "The \"shopify app function info\" command is not available in your CLI version.\n\
+         Please upgrade to the latest version:\n  npm install -g @shopify/cli@latest\n\n"
+    )]
+    FunctionInfoUnavailable,
+
+    #[error("{0}")]
+    FunctionInfo(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,60 @@
+//! Shopify Functions WASM Testing Helpers
+//!
+//! A Rust companion to the `@shopify/shopify-function-test-helpers` package.
+//! It exposes the same operations (loading fixtures, schemas and input
+//! queries, validating test assets and running functions) so Rust functions
+//! can be tested with `cargo test`, without installing Node.
+
+mod error;
+mod methods;
+mod schema;
+mod utils;
+
+pub use error::{Error, Result};
+pub use schema::{QueryDocument, Schema};
+pub use shopify_function_test_helpers_macros::fixture_tests;
+
+// Re-export all methods from their separate modules
+pub use methods::assert_fixture::{assert_fixture, assert_fixture_with_metafield_schemas};
+pub use methods::create_function_runner::create_function_runner;
+pub use methods::diff_function_output::diff_function_output;
+pub use methods::get_function_info::get_function_info;
+pub use methods::inject_fetch_result::inject_fetch_result;
+pub use methods::load_fixture::load_fixture;
+pub use methods::load_function_info::{load_function_info, FUNCTION_RUNNER_PATH_ENV};
+pub use methods::load_input_query::load_input_query;
+pub use methods::load_metafield_schemas::load_metafield_schemas;
+pub use methods::load_schema::load_schema;
+pub use methods::record_fixture::record_fixture;
+pub use methods::resolve_input_variables::resolve_input_variables;
+pub use methods::run_function::run_function;
+pub use methods::validate_fixture_input::{
+    validate_fixture_input, validate_fixture_input_with_options,
+};
+pub use methods::validate_fixture_output::validate_fixture_output;
+pub use methods::validate_input_query::{validate_input_query, validate_input_query_variables};
+pub use methods::validate_test_assets::validate_test_assets;
+
+// Re-export types for consumers
+pub use methods::create_function_runner::{CreateFunctionRunnerOptions, FunctionRunner};
+pub use methods::diff_function_output::{
+    DiffFunctionOutputOptions, DiffFunctionOutputResult, OutputDifference,
+};
+pub use methods::get_function_info::{FunctionInfo, InputVariables, TargetingInfo};
+pub use methods::load_fixture::{
+    FetchFixtureData, FixtureData, HttpHeaderFixture, HttpResponseFixture,
+};
+pub use methods::load_metafield_schemas::MetafieldSchema;
+pub use methods::record_fixture::RecordFixtureResult;
+pub use methods::run_function::{RunFunctionOutput, RunFunctionResult};
+pub use methods::validate_fixture_input::{
+    FixtureInputValidationError, PathSegment, ValidateFixtureInputOptions,
+    ValidateFixtureInputResult,
+};
+pub use methods::validate_fixture_output::{OutputValidationError, OutputValidationResult};
+pub use methods::validate_input_query::GraphQLError;
+pub use methods::validate_test_assets::{
+    CompleteValidationResult, InputFixtureValidation, InputQueryValidation,
+    OutputFixtureValidation, ValidateTestAssetsOptions,
+};
+pub use utils::determine_mutation_from_target::{determine_mutation_from_target, MutationTarget};
@@ -0,0 +1,302 @@
+//! Validate and run a single fixture, panicking on any failure
+//!
+//! This is what the tests generated by `#[fixture_tests]` call. It follows
+//! the same steps as the per-fixture cases in the example `default.test.js`.
+
+use std::env;
+use std::path::Path;
+
+use crate::methods::diff_function_output::{diff_function_output, DiffFunctionOutputOptions};
+use crate::methods::get_function_info::FunctionInfo;
+use crate::methods::inject_fetch_result::inject_fetch_result;
+use crate::methods::load_fixture::{load_fixture, FixtureData};
+use crate::methods::load_input_query::load_input_query;
+use crate::methods::load_metafield_schemas::MetafieldSchema;
+use crate::methods::load_schema::load_schema;
+use crate::methods::record_fixture::record_fixture;
+use crate::methods::resolve_input_variables::resolve_input_variables;
+use crate::methods::run_function::run_function;
+use crate::methods::validate_test_assets::{
+    validate_test_assets, CompleteValidationResult, ValidateTestAssetsOptions,
+};
+use crate::schema::{QueryDocument, Schema};
+
+/// Validate a fixture against the function's schema and input query, run the
+/// export named in `payload.export` and compare the result to `payload.output`
+///
+/// When the `UPDATE_FIXTURES` environment variable is set (to anything but `0`),
+/// the outputs are recorded into the fixture with [`record_fixture`] instead of being
+/// compared, so `UPDATE_FIXTURES=1 cargo test` updates every fixture like a snapshot.
+/// The inputs of every stage are still validated first.
+///
+/// A fixture with a `payload.fetch` stage runs the fetch export first and compares the
+/// HTTP request it produces to `payload.fetch.output`. The run export then runs with
+/// `payload.fetch.response` as its `fetchResult` input (see [`inject_fetch_result`]).
+///
+/// When the function binds its input query variables with `[extensions.input.variables]`,
+/// each stage takes them from that metafield in its input (see [`resolve_input_variables`]).
+///
+/// # Panics
+///
+/// Panics if any asset fails to load, if validation reports errors, if the
+/// function fails to run or if its output differs from the expected output.
+pub fn assert_fixture(fixture_path: impl AsRef<Path>, function_info: &FunctionInfo) {
+    assert_fixture_with_metafield_schemas(fixture_path, function_info, &[]);
+}
+
+/// Like [`assert_fixture`], also checking the fixture's metafields against a registry of
+/// metafield schemas (see [`load_metafield_schemas`](crate::load_metafield_schemas))
+///
+/// # Panics
+///
+/// Panics like [`assert_fixture`], or if a metafield does not match its schema.
+pub fn assert_fixture_with_metafield_schemas(
+    fixture_path: impl AsRef<Path>,
+    function_info: &FunctionInfo,
+    metafield_schemas: &[MetafieldSchema],
+) {
+    let fixture_path = fixture_path.as_ref();
+    let fixture = load_fixture(fixture_path).unwrap_or_else(|e| panic!("{e}"));
+    let schema = load_schema(&function_info.schema_path).unwrap_or_else(|e| panic!("{e}"));
+
+    // The fetch stage runs first, and its canned response becomes the run input's fetchResult
+    let fetch_query_path = fixture
+        .fetch
+        .as_ref()
+        .map(|fetch| input_query_path(fixture_path, function_info, &fetch.target));
+    let fixture = match (&fixture.fetch, fetch_query_path) {
+        (Some(fetch), Some(fetch_query_path)) => {
+            let fetch_fixture = fetch.fixture();
+            if update_fixtures() {
+                let input_query =
+                    load_input_query(fetch_query_path).unwrap_or_else(|e| panic!("{e}"));
+                validate_inputs(
+                    fixture_path,
+                    &with_input_variables(
+                        fixture_path,
+                        &fetch_fixture,
+                        &input_query,
+                        function_info,
+                    ),
+                    fetch_query_path,
+                    &input_query,
+                    &schema,
+                    metafield_schemas,
+                );
+            } else {
+                assert_stage(
+                    "Fetch request",
+                    fixture_path,
+                    &fetch_fixture,
+                    function_info,
+                    &schema,
+                    metafield_schemas,
+                );
+            }
+            let input_query_path = input_query_path(fixture_path, function_info, &fixture.target);
+            let input_query = load_input_query(input_query_path).unwrap_or_else(|e| panic!("{e}"));
+            inject_fetch_result(&fixture, &input_query).unwrap_or_else(|e| panic!("{e}"))
+        }
+        _ => fixture,
+    };
+
+    if update_fixtures() {
+        let input_query_path = input_query_path(fixture_path, function_info, &fixture.target);
+        let input_query = load_input_query(input_query_path).unwrap_or_else(|e| panic!("{e}"));
+        validate_inputs(
+            fixture_path,
+            &with_input_variables(fixture_path, &fixture, &input_query, function_info),
+            input_query_path,
+            &input_query,
+            &schema,
+            metafield_schemas,
+        );
+
+        let record_result = record_fixture(
+            fixture_path,
+            &function_info.function_runner_path,
+            &function_info.wasm_path,
+            input_query_path,
+            &function_info.schema_path,
+            fetch_query_path,
+            function_info.input_variables.as_ref(),
+        );
+        if let Some(error) = record_result.error {
+            panic!("Failed to record {}: {error}", fixture_path.display());
+        }
+        return;
+    }
+
+    assert_stage(
+        "Function output",
+        fixture_path,
+        &fixture,
+        function_info,
+        &schema,
+        metafield_schemas,
+    );
+}
+
+/// Validate one stage of a fixture, run its export and compare the result to its
+/// expected output
+fn assert_stage(
+    stage: &str,
+    fixture_path: &Path,
+    fixture: &FixtureData,
+    function_info: &FunctionInfo,
+    schema: &Schema,
+    metafield_schemas: &[MetafieldSchema],
+) {
+    let input_query_path = input_query_path(fixture_path, function_info, &fixture.target);
+    let input_query = load_input_query(input_query_path).unwrap_or_else(|e| panic!("{e}"));
+    let fixture = &with_input_variables(fixture_path, fixture, &input_query, function_info);
+
+    let validation_result = validate_inputs(
+        fixture_path,
+        fixture,
+        input_query_path,
+        &input_query,
+        schema,
+        metafield_schemas,
+    );
+    assert_no_errors(
+        "Output fixture",
+        fixture_path,
+        validation_result
+            .output_fixture
+            .errors
+            .iter()
+            .map(|error| error.message.clone()),
+    );
+
+    // Run the actual function
+    let run_result = run_function(
+        fixture,
+        &function_info.function_runner_path,
+        &function_info.wasm_path,
+        input_query_path,
+        &function_info.schema_path,
+    );
+    if let Some(error) = run_result.error {
+        panic!("Failed to run {}: {error}", fixture_path.display());
+    }
+    let Some(result) = run_result.result else {
+        panic!("Failed to run {}: no output", fixture_path.display());
+    };
+
+    let diff = diff_function_output(DiffFunctionOutputOptions {
+        schema,
+        actual: &result.output,
+        expected: &fixture.expected_output,
+        target: Some(&fixture.target),
+        mutation_name: validation_result.mutation_name.as_deref(),
+        result_parameter_name: validation_result.result_parameter_name.as_deref(),
+    })
+    .unwrap_or_else(|e| panic!("{e}"));
+    let differences: Vec<String> = diff
+        .differences
+        .iter()
+        .map(|difference| format!("  - {}: {}", difference.path, difference.message))
+        .collect();
+    if !differences.is_empty() {
+        panic!(
+            "{stage} does not match the expected output of {}:\n{}",
+            fixture_path.display(),
+            differences.join("\n")
+        );
+    }
+}
+
+/// Validate a fixture's input query and input, returning the full validation result
+fn validate_inputs(
+    fixture_path: &Path,
+    fixture: &FixtureData,
+    input_query_path: &Path,
+    input_query: &QueryDocument,
+    schema: &Schema,
+    metafield_schemas: &[MetafieldSchema],
+) -> CompleteValidationResult {
+    // Validate fixture using our comprehensive validation system
+    let validation_result = validate_test_assets(ValidateTestAssetsOptions {
+        schema,
+        fixture,
+        input_query,
+        target: Some(&fixture.target),
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas,
+    });
+    if let Some(error) = &validation_result.error {
+        panic!("Failed to validate {}: {error}", fixture_path.display());
+    }
+    assert_no_errors(
+        "Input query",
+        input_query_path,
+        validation_result
+            .input_query
+            .errors
+            .iter()
+            .map(|error| error.message.clone()),
+    );
+    assert_no_errors(
+        "Input fixture",
+        fixture_path,
+        validation_result.input_fixture.errors.iter().map(|error| {
+            let path: Vec<String> = error.path.iter().map(ToString::to_string).collect();
+            format!("{} At \"{}\"", error.message, path.join("."))
+        }),
+    );
+    validation_result
+}
+
+/// The fixture stage with the input query variables bound by the function's
+/// `[extensions.input.variables]` metafield, if its input has it
+fn with_input_variables(
+    fixture_path: &Path,
+    fixture: &FixtureData,
+    input_query: &QueryDocument,
+    function_info: &FunctionInfo,
+) -> FixtureData {
+    resolve_input_variables(fixture, input_query, function_info.input_variables.as_ref())
+        .unwrap_or_else(|e| {
+            panic!(
+                "Failed to resolve the input variables of {}: {e}",
+                fixture_path.display()
+            )
+        })
+}
+
+/// The input query path of one of the function's targets
+fn input_query_path<'a>(
+    fixture_path: &Path,
+    function_info: &'a FunctionInfo,
+    target: &str,
+) -> &'a Path {
+    &function_info
+        .targeting
+        .get(target)
+        .unwrap_or_else(|| {
+            panic!(
+                "Fixture {} targets '{}', which is not a target of this function",
+                fixture_path.display(),
+                target
+            )
+        })
+        .input_query_path
+}
+
+/// Whether fixtures should be recorded rather than compared
+fn update_fixtures() -> bool {
+    env::var_os("UPDATE_FIXTURES").is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+fn assert_no_errors(phase: &str, path: &Path, messages: impl Iterator<Item = String>) {
+    let errors: Vec<String> = messages.map(|message| format!("  - {message}")).collect();
+    if !errors.is_empty() {
+        panic!(
+            "{phase} validation failed for {}:\n{}",
+            path.display(),
+            errors.join("\n")
+        );
+    }
+}
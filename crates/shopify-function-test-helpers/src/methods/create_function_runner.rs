@@ -0,0 +1,221 @@
+//! Run function exports in-process with an embedded WASI runtime
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use wasmtime::{Config, Engine, Instance, Linker, Module, Store};
+use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{I32Exit, WasiCtxBuilder};
+
+use crate::methods::load_fixture::FixtureData;
+use crate::methods::run_function::{RunFunctionOutput, RunFunctionResult};
+use crate::utils::msgpack;
+
+const WASI_MODULE: &str = "wasi_snapshot_preview1";
+/// The provider of the Shopify Functions WASM API, whose functions read their input and
+/// write their output as MessagePack rather than JSON
+const MSGPACK_PROVIDER_MODULE: &str = "shopify_function_v1";
+
+/// Options for [`create_function_runner`]
+#[derive(Debug, Clone, Default)]
+pub struct CreateFunctionRunnerOptions {
+    /// Directory of the provider modules the function imports from, named after the module
+    /// they provide, e.g. `shopify_function_v1.wasm` for functions built on the Shopify
+    /// Functions WASM API or `javy_quickjs_provider_v3.wasm` for Javy
+    pub providers_dir: Option<PathBuf>,
+}
+
+/// A function compiled once, whose exports run in-process
+pub struct FunctionRunner {
+    wasm_path: PathBuf,
+    engine: Engine,
+    linker: Linker<WasiP1Ctx>,
+    module: Module,
+    /// The provider modules, by the import module name they are linked under
+    providers: Vec<(String, Module)>,
+    /// Whether the input and output are MessagePack, as for the Shopify Functions WASM API
+    uses_msgpack: bool,
+}
+
+/// Create a runner that compiles the WASM module once and runs its exports in-process
+///
+/// The module runs on wasmtime, as it does in function-runner, so the instructions it
+/// reports are the fuel the export consumes and its memory usage is the size of the
+/// function's and providers' memories after the run, in kilobytes. Imports other than WASI
+/// are linked to the provider module of the same name in `providers_dir`.
+///
+/// Like function-runner, the input and output are MessagePack for a module that imports
+/// from `shopify_function_v1`, the Shopify Functions WASM API provider, and JSON otherwise.
+///
+/// Returns an error if the module or a provider cannot be compiled, or an import has no
+/// provider.
+pub fn create_function_runner(
+    wasm_path: impl AsRef<Path>,
+    options: CreateFunctionRunnerOptions,
+) -> Result<FunctionRunner, String> {
+    let wasm_path = wasm_path.as_ref();
+    let mut config = Config::new();
+    // Functions built on the Shopify Functions WASM API also import the provider's memory
+    config.consume_fuel(true).wasm_multi_memory(true);
+    let engine = Engine::new(&config).map_err(|e| format!("Failed to create the engine: {e}"))?;
+    let module = compile_module(&engine, wasm_path)?;
+
+    let mut providers: Vec<(String, Module)> = vec![];
+    for import in module.imports() {
+        let name = import.module();
+        if name == WASI_MODULE || providers.iter().any(|(provider, _)| provider == name) {
+            continue;
+        }
+        let Some(providers_dir) = &options.providers_dir else {
+            return Err(format!(
+                "WASM module {} imports from {name}, which needs a provider module. Set providers_dir to a directory containing {name}.wasm, or use run_function to run this function with function-runner.",
+                wasm_path.display()
+            ));
+        };
+        let provider = compile_module(&engine, &providers_dir.join(format!("{name}.wasm")))?;
+        providers.push((name.to_string(), provider));
+    }
+
+    let mut linker = Linker::new(&engine);
+    preview1::add_to_linker_sync(&mut linker, |wasi| wasi)
+        .map_err(|e| format!("Failed to link WASI: {e}"))?;
+
+    let uses_msgpack = providers
+        .iter()
+        .any(|(name, _)| name == MSGPACK_PROVIDER_MODULE);
+    Ok(FunctionRunner {
+        wasm_path: wasm_path.to_path_buf(),
+        engine,
+        linker,
+        module,
+        providers,
+        uses_msgpack,
+    })
+}
+
+fn compile_module(engine: &Engine, wasm_path: &Path) -> Result<Module, String> {
+    Module::from_file(engine, wasm_path)
+        .map_err(|e| format!("Failed to compile WASM module {}: {e}", wasm_path.display()))
+}
+
+impl FunctionRunner {
+    /// Run the export named in the fixture with the fixture input on stdin
+    ///
+    /// Each run gets a fresh instance (and memory) of the compiled module and its
+    /// providers, so runs are isolated from each other just like separate function-runner
+    /// processes. The function's stdout is decoded as the output.
+    pub fn run(&self, fixture: &FixtureData) -> RunFunctionResult {
+        match self.run_export(fixture) {
+            Ok(output) => RunFunctionResult {
+                result: Some(output),
+                error: None,
+            },
+            Err(error) => RunFunctionResult::failure(error),
+        }
+    }
+
+    fn run_export(&self, fixture: &FixtureData) -> Result<RunFunctionOutput, String> {
+        if self.module.get_export(&fixture.export).is_none() {
+            return Err(format!(
+                "Export '{}' not found in {}",
+                fixture.export,
+                self.wasm_path.display()
+            ));
+        }
+
+        let input_json = fixture.input.to_string();
+        let input = if self.uses_msgpack {
+            msgpack::encode(&fixture.input)
+        } else {
+            input_json.clone().into_bytes()
+        };
+        let stdout = MemoryOutputPipe::new(usize::MAX);
+        let stderr = MemoryOutputPipe::new(usize::MAX);
+        let wasi = WasiCtxBuilder::new()
+            .arg(
+                self.wasm_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+            )
+            .stdin(MemoryInputPipe::new(input))
+            .stdout(stdout.clone())
+            .stderr(stderr.clone())
+            .build_p1();
+        let mut store = Store::new(&self.engine, wasi);
+        store.set_fuel(u64::MAX).map_err(|e| e.to_string())?;
+        let mut linker = self.linker.clone();
+
+        let mut instances = vec![];
+        for (name, provider) in &self.providers {
+            let instance = linker
+                .instantiate(&mut store, provider)
+                .map_err(|e| format!("Failed to instantiate provider {name}: {e}"))?;
+            if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+                initialize.call(&mut store, ()).map_err(|e| {
+                    format!("Failed to initialize provider {name}: {}", e.root_cause())
+                })?;
+            }
+            linker
+                .instance(&mut store, name, instance)
+                .map_err(|e| format!("Failed to link provider {name}: {e}"))?;
+            instances.push(instance);
+        }
+        let instance = linker
+            .instantiate(&mut store, &self.module)
+            .map_err(|e| e.to_string())?;
+        instances.push(instance);
+        let function = instance
+            .get_typed_func::<(), ()>(&mut store, &fixture.export)
+            .map_err(|e| e.to_string())?;
+
+        let fuel = store.get_fuel().map_err(|e| e.to_string())?;
+        let call = function.call(&mut store, ());
+        let instructions = fuel - store.get_fuel().map_err(|e| e.to_string())?;
+        let logs = String::from_utf8_lossy(&stderr.contents()).to_string();
+        if let Err(error) = call {
+            // WASI modules may exit explicitly, and only a non-zero exit code is a failure
+            if !matches!(error.downcast_ref::<I32Exit>(), Some(I32Exit(0))) {
+                let stderr_output = if logs.is_empty() {
+                    String::new()
+                } else {
+                    format!("\n{logs}")
+                };
+                return Err(format!(
+                    "Function export '{}' failed: {}{stderr_output}",
+                    fixture.export,
+                    error.root_cause()
+                ));
+            }
+        }
+
+        let output: Value = if self.uses_msgpack {
+            msgpack::decode(&stdout.contents())
+                .map_err(|e| format!("Failed to decode function output as MessagePack: {e}"))?
+        } else {
+            serde_json::from_slice(&stdout.contents())
+                .map_err(|e| format!("Failed to parse function output: {e}"))?
+        };
+        Ok(RunFunctionOutput {
+            output_size: output.to_string().len(),
+            output,
+            instructions: Some(instructions),
+            memory_usage: Some(memory_usage(&mut store, &instances)),
+            input_size: input_json.len(),
+            logs,
+        })
+    }
+}
+
+/// The size of the memories the instances export, in kilobytes
+fn memory_usage(store: &mut Store<WasiP1Ctx>, instances: &[Instance]) -> u64 {
+    let memories: Vec<_> = instances
+        .iter()
+        .filter_map(|instance| instance.get_memory(&mut *store, "memory"))
+        .collect();
+    memories
+        .iter()
+        .map(|memory| memory.data_size(&*store) as u64 / 1024)
+        .sum()
+}
@@ -0,0 +1,305 @@
+//! Compare a function's actual output with the expected output of a fixture
+
+use graphql_parser::schema::{InputObjectType, Type, TypeDefinition};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::schema::Schema;
+use crate::utils::determine_mutation_from_target::determine_mutation_from_target;
+
+/// Diff function output options
+#[derive(Debug, Clone, Copy)]
+pub struct DiffFunctionOutputOptions<'a> {
+    pub schema: &'a Schema,
+    /// The output the function produced (`result.output` from `run_function`)
+    pub actual: &'a Value,
+    /// The expected output (`expected_output` from `load_fixture`)
+    pub expected: &'a Value,
+    pub target: Option<&'a str>,
+    pub mutation_name: Option<&'a str>,
+    pub result_parameter_name: Option<&'a str>,
+}
+
+/// A difference between the actual and expected output
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputDifference {
+    /// GraphQL path of the difference, e.g. `operations[1].productDiscountsAdd.candidates[0]`
+    pub path: String,
+    pub message: String,
+    /// The expected value, or `None` if the actual output has an unexpected value
+    pub expected: Option<Value>,
+    /// The actual value, or `None` if the actual output is missing a value
+    pub actual: Option<Value>,
+}
+
+/// The diff function output result
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct DiffFunctionOutputResult {
+    pub differences: Vec<OutputDifference>,
+}
+
+/// Compares a function's actual output with a fixture's expected output
+///
+/// Differences are reported by GraphQL path, e.g.
+/// `operations[1].productDiscountsAdd.candidates[0].value.percentage.value`, rather than as
+/// a diff of the whole output. Object key order is ignored, while list items are compared
+/// in order. When both outputs set a different field of the same `@oneOf` input object
+/// (such as a different kind of operation), a single difference is reported for the
+/// object instead of one per field.
+///
+/// `Decimal`, `Float` and `ID` scalars are compared by value rather than by their JSON
+/// representation, as Shopify does when it parses the output: `"10.0"`, `"10"` and `10`
+/// are the same `Decimal`, and `"1"` and `1` are the same `ID`.
+///
+/// The result type is determined from the target the same way as in
+/// `validate_test_assets`, unless `mutation_name` and `result_parameter_name` are provided.
+pub fn diff_function_output(
+    options: DiffFunctionOutputOptions,
+) -> Result<DiffFunctionOutputResult, String> {
+    let DiffFunctionOutputOptions {
+        schema,
+        actual,
+        expected,
+        target,
+        mutation_name,
+        result_parameter_name,
+    } = options;
+
+    let (mutation_name, result_parameter_name) = match (mutation_name, result_parameter_name) {
+        (Some(mutation_name), Some(result_parameter_name)) => {
+            (mutation_name.to_string(), result_parameter_name.to_string())
+        }
+        _ => {
+            let target = target.ok_or(
+                "target is required when mutation_name and result_parameter_name are not provided",
+            )?;
+            let determined = determine_mutation_from_target(target, schema)?;
+            (determined.mutation_name, determined.result_parameter_name)
+        }
+    };
+
+    let result_type = schema
+        .mutation_type()
+        .and_then(|mutation| mutation.fields.iter().find(|f| f.name == mutation_name))
+        .and_then(|field| {
+            field
+                .arguments
+                .iter()
+                .find(|argument| argument.name == result_parameter_name)
+        })
+        .map(|argument| &argument.value_type);
+
+    let mut differences = vec![];
+    diff_values(schema, actual, expected, result_type, "", &mut differences);
+    Ok(DiffFunctionOutputResult { differences })
+}
+
+fn diff_values(
+    schema: &Schema,
+    actual: &Value,
+    expected: &Value,
+    value_type: Option<&Type<'static, String>>,
+    path: &str,
+    differences: &mut Vec<OutputDifference>,
+) {
+    let nullable_type = value_type.map(|value_type| match value_type {
+        Type::NonNullType(inner) => inner.as_ref(),
+        _ => value_type,
+    });
+
+    if let Some(Type::NamedType(name)) = nullable_type {
+        if matches!(schema.get_type(name), Some(TypeDefinition::Scalar(_)))
+            && scalar_values_equal(name, actual, expected)
+        {
+            return;
+        }
+    }
+
+    match (actual, expected) {
+        (Value::Array(actual_items), Value::Array(expected_items)) => {
+            let item_type = match nullable_type {
+                Some(Type::ListType(item_type)) => Some(item_type.as_ref()),
+                _ => None,
+            };
+            for index in 0..actual_items.len().max(expected_items.len()) {
+                let item_path = format!("{path}[{index}]");
+                match (actual_items.get(index), expected_items.get(index)) {
+                    (Some(actual_item), Some(expected_item)) => diff_values(
+                        schema,
+                        actual_item,
+                        expected_item,
+                        item_type,
+                        &item_path,
+                        differences,
+                    ),
+                    (None, Some(expected_item)) => differences.push(OutputDifference {
+                        path: item_path,
+                        message: format!("Missing item, expected {expected_item}"),
+                        expected: Some(expected_item.clone()),
+                        actual: None,
+                    }),
+                    (Some(actual_item), None) => differences.push(OutputDifference {
+                        path: item_path,
+                        message: format!("Unexpected item {actual_item}"),
+                        expected: None,
+                        actual: Some(actual_item.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Object(actual_fields), Value::Object(expected_fields)) => {
+            let input_object = match nullable_type {
+                Some(Type::NamedType(name)) => match schema.get_type(name) {
+                    Some(TypeDefinition::InputObject(input_object)) => Some(input_object),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            // A different field of a @oneOf object is a different kind of value altogether,
+            // so its contents are not compared field by field
+            if let Some(input_object) = input_object.filter(|input| is_one_of(input)) {
+                if actual_fields.len() == 1
+                    && expected_fields.len() == 1
+                    && actual_fields.keys().next() != expected_fields.keys().next()
+                {
+                    differences.push(OutputDifference {
+                        path: path.to_string(),
+                        message: format!(
+                            "Expected {} \"{}\", received \"{}\"",
+                            input_object.name,
+                            expected_fields.keys().next().unwrap(),
+                            actual_fields.keys().next().unwrap()
+                        ),
+                        expected: Some(expected.clone()),
+                        actual: Some(actual.clone()),
+                    });
+                    return;
+                }
+            }
+
+            let keys = expected_fields.keys().chain(
+                actual_fields
+                    .keys()
+                    .filter(|key| !expected_fields.contains_key(*key)),
+            );
+            for key in keys {
+                let field_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match (actual_fields.get(key), expected_fields.get(key)) {
+                    (Some(actual_value), Some(expected_value)) => {
+                        let field_type = input_object
+                            .and_then(|input| input.fields.iter().find(|f| &f.name == key))
+                            .map(|field| &field.value_type);
+                        diff_values(
+                            schema,
+                            actual_value,
+                            expected_value,
+                            field_type,
+                            &field_path,
+                            differences,
+                        );
+                    }
+                    (None, Some(expected_value)) => differences.push(OutputDifference {
+                        path: field_path,
+                        message: format!("Missing field, expected {expected_value}"),
+                        expected: Some(expected_value.clone()),
+                        actual: None,
+                    }),
+                    (Some(actual_value), None) => differences.push(OutputDifference {
+                        path: field_path,
+                        message: format!("Unexpected field with value {actual_value}"),
+                        expected: None,
+                        actual: Some(actual_value.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => {
+            if actual != expected {
+                differences.push(OutputDifference {
+                    path: path.to_string(),
+                    message: format!("Expected {expected}, received {actual}"),
+                    expected: Some(expected.clone()),
+                    actual: Some(actual.clone()),
+                });
+            }
+        }
+    }
+}
+
+/// Whether two values of a scalar are equal by value. Only `Decimal`, `Float` and `ID`
+/// have representations that differ for the same value.
+fn scalar_values_equal(scalar_name: &str, actual: &Value, expected: &Value) -> bool {
+    match scalar_name {
+        "Decimal" => normalize_decimal(actual)
+            .is_some_and(|actual| Some(actual) == normalize_decimal(expected)),
+        "Float" => match (actual.as_f64(), expected.as_f64()) {
+            (Some(actual), Some(expected)) => actual == expected,
+            _ => false,
+        },
+        "ID" => id_value(actual).is_some_and(|actual| Some(actual) == id_value(expected)),
+        _ => actual == expected,
+    }
+}
+
+/// A canonical form of a decimal string or number, e.g. `"10.0"`, `"10"` and `10` all
+/// become `1e1`, or `None` if the value is not a decimal. Digits are compared exactly
+/// rather than as floating point numbers.
+fn normalize_decimal(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        _ => return None,
+    };
+
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", text.strip_prefix('+').unwrap_or(&text)),
+    };
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (integer_digits, fraction_digits) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let all_digits = |digits: &str| digits.bytes().all(|byte| byte.is_ascii_digit());
+    if (integer_digits.is_empty() && fraction_digits.is_empty())
+        || !all_digits(integer_digits)
+        || !all_digits(fraction_digits)
+    {
+        return None;
+    }
+
+    let digits = format!("{integer_digits}{fraction_digits}");
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Some("0".to_string());
+    }
+
+    let significant_digits = digits.trim_end_matches('0');
+    let scale =
+        exponent - fraction_digits.len() as i64 + (digits.len() - significant_digits.len()) as i64;
+    Some(format!("{sign}{significant_digits}e{scale}"))
+}
+
+/// The value of an ID, which may be written as a string or an integer
+fn id_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(number) if number.is_i64() || number.is_u64() => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn is_one_of(input_object: &InputObjectType<'static, String>) -> bool {
+    input_object
+        .directives
+        .iter()
+        .any(|directive| directive.name == "oneOf")
+}
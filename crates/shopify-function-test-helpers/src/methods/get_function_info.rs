@@ -0,0 +1,121 @@
+//! Retrieve function information from the Shopify CLI
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::methods::load_function_info::{load_function_info, load_input_variables};
+
+/// Information about a Shopify function
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionInfo {
+    pub schema_path: PathBuf,
+    pub function_runner_path: PathBuf,
+    pub wasm_path: PathBuf,
+    /// Targeting details keyed by target, e.g. `cart.lines.discounts.generate.run`
+    pub targeting: HashMap<String, TargetingInfo>,
+    /// The metafield bound to the input queries' variables by
+    /// `[extensions.input.variables]`, if the function declares one
+    #[serde(default)]
+    pub input_variables: Option<InputVariables>,
+}
+
+/// The namespace and key of the metafield whose JSON value Shopify binds to the input
+/// queries' variables
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InputVariables {
+    pub namespace: String,
+    pub key: String,
+}
+
+/// The targeting details of a single function target
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetingInfo {
+    pub input_query_path: PathBuf,
+    #[serde(default)]
+    pub export: Option<String>,
+}
+
+/// Retrieves function information from the Shopify CLI
+///
+/// Runs `shopify app function info --json` from the app root directory. When the Shopify
+/// CLI or its `app function info` command is unavailable, such as in CI containers, falls
+/// back to reading the function's `shopify.extension.toml` with
+/// [`load_function_info`](crate::load_function_info).
+pub fn get_function_info(function_dir: impl AsRef<Path>) -> Result<FunctionInfo> {
+    let function_dir = function_dir.as_ref();
+    let resolved_function_dir = std::path::absolute(function_dir)
+        .map_err(|e| Error::FunctionInfo(format!("Failed to resolve function directory: {e}")))?;
+    let app_root_dir = resolved_function_dir
+        .parent()
+        .unwrap_or(&resolved_function_dir);
+    let function_name = resolved_function_dir
+        .file_name()
+        .unwrap_or(resolved_function_dir.as_os_str());
+
+    // Without the CLI, read the extension's configuration directly if there is one
+    let fall_back_to_extension_toml = |error: Error| {
+        if resolved_function_dir
+            .join("shopify.extension.toml")
+            .is_file()
+        {
+            load_function_info(&resolved_function_dir)
+        } else {
+            Err(error)
+        }
+    };
+
+    let output = match Command::new("shopify")
+        .args(["app", "function", "info", "--json", "--path"])
+        .arg(function_name)
+        .current_dir(app_root_dir)
+        .env("SHOPIFY_INVOKED_BY", "shopify-function-test-helpers")
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            return fall_back_to_extension_toml(Error::FunctionInfo(format!(
+                "Failed to start shopify function info command: {e}"
+            )))
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if !output.status.success() {
+        // Check if the error is due to the command not being found
+        if stderr.contains("Command app function info not found")
+            || stderr.contains("command not found")
+        {
+            return fall_back_to_extension_toml(Error::FunctionInfoUnavailable);
+        }
+        let code = output
+            .status
+            .code()
+            .map_or_else(|| "null".to_string(), |code| code.to_string());
+        return Err(Error::FunctionInfo(format!(
+            "Function info command failed with exit code {code}: {stderr}"
+        )));
+    }
+
+    let mut function_info: FunctionInfo = serde_json::from_str(stdout.trim()).map_err(|e| {
+        Error::FunctionInfo(format!(
+            "Failed to parse function info JSON: {e}\nOutput: {stdout}"
+        ))
+    })?;
+    // Take `[extensions.input.variables]` from the extension's configuration
+    if function_info.input_variables.is_none()
+        && resolved_function_dir
+            .join("shopify.extension.toml")
+            .is_file()
+    {
+        function_info.input_variables = load_input_variables(&resolved_function_dir)?;
+    }
+    Ok(function_info)
+}
@@ -0,0 +1,169 @@
+//! Replay a fixture's canned HTTP response as the run input's `fetchResult`
+
+use graphql_parser::query::{Definition, Field, OperationDefinition, Selection, SelectionSet};
+use serde_json::{Map, Value};
+
+use crate::methods::load_fixture::{FixtureData, HttpHeaderFixture, HttpResponseFixture};
+use crate::schema::QueryDocument;
+use crate::utils::inline_named_fragment_spreads::inline_named_fragment_spreads;
+
+const FETCH_RESULT_FIELD: &str = "fetchResult";
+
+/// Returns the run fixture with the fetch stage's response injected as `fetchResult`
+///
+/// The response from `payload.fetch.response` is shaped by the `fetchResult` selection of
+/// the run target's input query, the same way Shopify builds the input: `header(name:)`
+/// looks up a header case-insensitively, `jsonBody` is parsed from `body` (falling back
+/// to the raw string) and `body` is serialized from `jsonBody` when only one is given.
+///
+/// The returned fixture has no fetch stage and any `fetchResult` already in its input is
+/// replaced. Fixtures without a fetch stage, and queries that do not select
+/// `fetchResult`, are returned unchanged.
+pub fn inject_fetch_result(
+    fixture: &FixtureData,
+    input_query: &QueryDocument,
+) -> Result<FixtureData, String> {
+    let Some(fetch) = &fixture.fetch else {
+        return Ok(fixture.clone());
+    };
+    let mut run_fixture = FixtureData {
+        fetch: None,
+        ..fixture.clone()
+    };
+
+    let document = inline_named_fragment_spreads(input_query)?;
+    let Some(selection_set) = document
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Operation(operation) => Some(operation_selection_set(operation)),
+            Definition::Fragment(_) => None,
+        })
+    else {
+        return Ok(run_fixture);
+    };
+
+    for selection in &selection_set.items {
+        let Selection::Field(field) = selection else {
+            continue;
+        };
+        if field.name != FETCH_RESULT_FIELD {
+            continue;
+        }
+        let value = match &fetch.response {
+            Some(response) if !field.selection_set.items.is_empty() => {
+                http_response_value(response, &field.selection_set)
+            }
+            _ => Value::Null,
+        };
+        if let Value::Object(input) = &mut run_fixture.input {
+            input.insert(response_key(field).to_string(), value);
+        }
+    }
+
+    Ok(run_fixture)
+}
+
+fn operation_selection_set<'a>(
+    operation: &'a OperationDefinition<'static, String>,
+) -> &'a SelectionSet<'static, String> {
+    match operation {
+        OperationDefinition::SelectionSet(selection_set) => selection_set,
+        OperationDefinition::Query(query) => &query.selection_set,
+        OperationDefinition::Mutation(mutation) => &mutation.selection_set,
+        OperationDefinition::Subscription(subscription) => &subscription.selection_set,
+    }
+}
+
+/// The value of an `HttpResponse` for a selection set
+fn http_response_value(
+    response: &HttpResponseFixture,
+    selection_set: &SelectionSet<'static, String>,
+) -> Value {
+    let mut value = Map::new();
+
+    for field in collect_fields(selection_set) {
+        let field_value = match field.name.as_str() {
+            "__typename" => Value::from("HttpResponse"),
+            "status" => Value::from(response.status),
+            "body" => match (&response.body, &response.json_body) {
+                (Some(body), _) => Value::from(body.as_str()),
+                (None, Some(json_body)) => Value::from(json_body.to_string()),
+                (None, None) => Value::Null,
+            },
+            "jsonBody" => match (&response.json_body, &response.body) {
+                (Some(json_body), _) => json_body.clone(),
+                (None, Some(body)) => {
+                    serde_json::from_str(body).unwrap_or_else(|_| Value::from(body.as_str()))
+                }
+                (None, None) => Value::Null,
+            },
+            "headers" => Value::Array(
+                response
+                    .headers
+                    .iter()
+                    .map(|header| header_value(header, &field.selection_set))
+                    .collect(),
+            ),
+            "header" => {
+                let name = field
+                    .arguments
+                    .iter()
+                    .find(|(argument, _)| argument == "name")
+                    .and_then(|(_, value)| match value {
+                        graphql_parser::query::Value::String(name) => Some(name),
+                        _ => None,
+                    });
+                response
+                    .headers
+                    .iter()
+                    .find(|header| name.is_some_and(|name| header.name.eq_ignore_ascii_case(name)))
+                    .map_or(Value::Null, |header| {
+                        header_value(header, &field.selection_set)
+                    })
+            }
+            _ => continue,
+        };
+        value.insert(response_key(field).to_string(), field_value);
+    }
+
+    Value::Object(value)
+}
+
+fn header_value(
+    header: &HttpHeaderFixture,
+    selection_set: &SelectionSet<'static, String>,
+) -> Value {
+    let mut value = Map::new();
+    for field in collect_fields(selection_set) {
+        let field_value = match field.name.as_str() {
+            "__typename" => Value::from("HttpResponseHeader"),
+            "name" => Value::from(header.name.as_str()),
+            "value" => Value::from(header.value.as_str()),
+            _ => continue,
+        };
+        value.insert(response_key(field).to_string(), field_value);
+    }
+    Value::Object(value)
+}
+
+/// The fields of a selection set, including those of inline fragments
+fn collect_fields<'a>(
+    selection_set: &'a SelectionSet<'static, String>,
+) -> Vec<&'a Field<'static, String>> {
+    selection_set
+        .items
+        .iter()
+        .flat_map(|selection| match selection {
+            Selection::Field(field) => vec![field],
+            Selection::InlineFragment(inline_fragment) => {
+                collect_fields(&inline_fragment.selection_set)
+            }
+            Selection::FragmentSpread(_) => vec![],
+        })
+        .collect()
+}
+
+fn response_key<'a>(field: &'a Field<'static, String>) -> &'a str {
+    field.alias.as_deref().unwrap_or(&field.name)
+}
@@ -0,0 +1,110 @@
+//! Load and parse a fixture file
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+/// The parsed fixture data structure
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FixtureData {
+    /// The export data from `payload.export`
+    pub export: String,
+    /// The input data from `payload.input`
+    pub input: Value,
+    /// The output data from `payload.output` (null if the fixture has none yet)
+    #[serde(rename = "output", default)]
+    pub expected_output: Value,
+    /// The target string from `payload.target`
+    #[serde(default)]
+    pub target: String,
+    /// The values of the input query's variables, from `payload.variables`. Shopify reads
+    /// them from the metafield bound by `[extensions.input.variables]` in shopify.extension.toml.
+    #[serde(default)]
+    pub variables: Option<Map<String, Value>>,
+    /// The fetch stage that runs before this fixture's target, from `payload.fetch`
+    #[serde(default)]
+    pub fetch: Option<FetchFixtureData>,
+}
+
+/// The fetch stage of a fixture for a target with network access
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FetchFixtureData {
+    pub export: String,
+    pub input: Value,
+    /// The expected fetch result, e.g. `{ "request": { "method": ..., "url": ... } }`
+    #[serde(rename = "output", default)]
+    pub expected_output: Value,
+    pub target: String,
+    /// The response to the request, injected into the run input as `fetchResult`
+    #[serde(default)]
+    pub response: Option<HttpResponseFixture>,
+}
+
+impl FetchFixtureData {
+    /// The fetch stage as a fixture of its own, to validate and run like any other
+    pub fn fixture(&self) -> FixtureData {
+        FixtureData {
+            export: self.export.clone(),
+            input: self.input.clone(),
+            expected_output: self.expected_output.clone(),
+            target: self.target.clone(),
+            variables: None,
+            fetch: None,
+        }
+    }
+}
+
+/// The canned HTTP response replayed as `Input.fetchResult`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpResponseFixture {
+    pub status: i64,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub json_body: Option<Value>,
+    #[serde(default)]
+    pub headers: Vec<HttpHeaderFixture>,
+}
+
+/// A header of a canned HTTP response
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HttpHeaderFixture {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Deserialize)]
+struct FixtureFile {
+    payload: FixtureData,
+}
+
+/// Load and parse a fixture file, extracting the payload data
+pub fn load_fixture(filename: impl AsRef<Path>) -> Result<FixtureData> {
+    let filename = filename.as_ref();
+
+    let fixture_content = fs::read_to_string(filename).map_err(|e| Error::LoadFixture {
+        path: filename.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    serde_json::from_str::<FixtureFile>(&fixture_content)
+        .map(|fixture| fixture.payload)
+        .map_err(|e| {
+            if e.is_syntax() || e.is_eof() {
+                Error::InvalidFixtureJson {
+                    path: filename.to_path_buf(),
+                    message: e.to_string(),
+                }
+            } else {
+                Error::LoadFixture {
+                    path: filename.to_path_buf(),
+                    message: e.to_string(),
+                }
+            }
+        })
+}
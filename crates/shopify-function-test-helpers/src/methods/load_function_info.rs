@@ -0,0 +1,199 @@
+//! Load function information from a function's shopify.extension.toml
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::methods::get_function_info::{FunctionInfo, InputVariables, TargetingInfo};
+
+/// The environment variable that overrides where function-runner is found
+pub const FUNCTION_RUNNER_PATH_ENV: &str = "FUNCTION_RUNNER_PATH";
+
+/// The wasm path the Shopify CLI builds to when `[extensions.build].path` is omitted
+const DEFAULT_WASM_PATH: &str = "dist/index.wasm";
+
+#[derive(Deserialize)]
+struct ExtensionToml {
+    #[serde(default)]
+    extensions: Vec<ExtensionConfig>,
+}
+
+#[derive(Deserialize)]
+struct ExtensionConfig {
+    #[serde(rename = "type")]
+    extension_type: Option<String>,
+    #[serde(default)]
+    targeting: Vec<TargetingConfig>,
+    build: Option<BuildConfig>,
+    input: Option<InputConfig>,
+}
+
+#[derive(Deserialize)]
+struct TargetingConfig {
+    target: Option<String>,
+    input_query: Option<String>,
+    export: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BuildConfig {
+    path: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct InputConfig {
+    variables: Option<InputVariablesConfig>,
+}
+
+#[derive(Deserialize)]
+struct InputVariablesConfig {
+    namespace: Option<String>,
+    key: Option<String>,
+}
+
+/// Loads function information from a function's `shopify.extension.toml`, without the
+/// Shopify CLI
+///
+/// Returns the same information as [`get_function_info`](crate::get_function_info):
+/// `schema_path` is the function's `schema.graphql`, `wasm_path` comes from
+/// `[extensions.build].path` and `targeting` has the `input_query` and `export` of each
+/// `[[extensions.targeting]]` with an `input_query`. `input_variables` is the
+/// `[extensions.input.variables]` metafield, if any. `function_runner_path` is the
+/// `FUNCTION_RUNNER_PATH` environment variable if set, otherwise the first
+/// `function-runner` on `PATH`. All paths are absolute.
+pub fn load_function_info(function_dir: impl AsRef<Path>) -> Result<FunctionInfo> {
+    let resolved_function_dir = std::path::absolute(function_dir.as_ref())
+        .map_err(|e| Error::FunctionInfo(format!("Failed to resolve function directory: {e}")))?;
+    let path = resolved_function_dir.join("shopify.extension.toml");
+    let error = |message: String| Error::LoadFunctionInfo {
+        path: path.clone(),
+        message,
+    };
+
+    let extension = read_extension_toml(&path)?;
+
+    let mut targeting = HashMap::new();
+    for (index, target) in extension.targeting.iter().enumerate() {
+        let Some(name) = &target.target else {
+            return Err(error(format!(
+                "Targeting {index} must have a string target"
+            )));
+        };
+        let Some(input_query) = &target.input_query else {
+            continue;
+        };
+        targeting.insert(
+            name.clone(),
+            TargetingInfo {
+                input_query_path: resolved_function_dir.join(input_query),
+                export: target.export.clone(),
+            },
+        );
+    }
+
+    let wasm_path = extension
+        .build
+        .as_ref()
+        .and_then(|build| build.path.as_deref())
+        .unwrap_or(DEFAULT_WASM_PATH);
+    let input_variables = input_variables(&extension).map_err(error)?;
+
+    Ok(FunctionInfo {
+        schema_path: resolved_function_dir.join("schema.graphql"),
+        function_runner_path: find_function_runner().map_err(error)?,
+        wasm_path: resolved_function_dir.join(wasm_path),
+        targeting,
+        input_variables,
+    })
+}
+
+/// Reads the `[extensions.input.variables]` metafield of a function's
+/// `shopify.extension.toml`, for function info from the Shopify CLI
+pub(crate) fn load_input_variables(function_dir: &Path) -> Result<Option<InputVariables>> {
+    let path = function_dir.join("shopify.extension.toml");
+    let extension = read_extension_toml(&path)?;
+    input_variables(&extension).map_err(|message| Error::LoadFunctionInfo { path, message })
+}
+
+/// Reads the function entry of a `shopify.extension.toml`: the first `[[extensions]]` of
+/// type `function`, or the first entry if none has that type
+fn read_extension_toml(path: &Path) -> Result<ExtensionConfig> {
+    let error = |message: String| Error::LoadFunctionInfo {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let content = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let config: ExtensionToml = toml::from_str(&content).map_err(|e| error(e.to_string()))?;
+
+    let mut extensions = config.extensions;
+    let index = extensions
+        .iter()
+        .position(|extension| extension.extension_type.as_deref() == Some("function"))
+        .unwrap_or(0);
+    if index >= extensions.len() {
+        return Err(error("Expected an [[extensions]] entry".to_string()));
+    }
+    Ok(extensions.swap_remove(index))
+}
+
+fn input_variables(
+    extension: &ExtensionConfig,
+) -> std::result::Result<Option<InputVariables>, String> {
+    let Some(variables) = extension
+        .input
+        .as_ref()
+        .and_then(|input| input.variables.as_ref())
+    else {
+        return Ok(None);
+    };
+    match (&variables.namespace, &variables.key) {
+        (Some(namespace), Some(key)) => Ok(Some(InputVariables {
+            namespace: namespace.clone(),
+            key: key.clone(),
+        })),
+        _ => Err("[extensions.input.variables] must have a string namespace and key".to_string()),
+    }
+}
+
+/// Finds function-runner from the `FUNCTION_RUNNER_PATH` environment variable or `PATH`
+fn find_function_runner() -> std::result::Result<PathBuf, String> {
+    if let Some(configured_path) = std::env::var_os(FUNCTION_RUNNER_PATH_ENV)
+        .filter(|configured_path| !configured_path.is_empty())
+    {
+        return std::path::absolute(configured_path).map_err(|e| e.to_string());
+    }
+
+    let executable = if cfg!(windows) {
+        "function-runner.exe"
+    } else {
+        "function-runner"
+    };
+    std::env::var_os("PATH")
+        .iter()
+        .flat_map(std::env::split_paths)
+        .filter(|directory| !directory.as_os_str().is_empty())
+        .map(|directory| directory.join(executable))
+        .find(|candidate| is_executable(candidate))
+        .ok_or_else(|| {
+            format!(
+                "Could not find function-runner on PATH; install it or set {FUNCTION_RUNNER_PATH_ENV}"
+            )
+        })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,22 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::schema::QueryDocument;
+
+/// Load and parse a GraphQL query from a file path
+///
+/// Reads a GraphQL query file and parses it into a document AST.
+/// This is useful for loading input queries for validation and testing.
+pub fn load_input_query(query_path: impl AsRef<Path>) -> Result<QueryDocument> {
+    let query_path = query_path.as_ref();
+    let error = |message: String| Error::LoadInputQuery {
+        path: query_path.to_path_buf(),
+        message,
+    };
+
+    let query_string = fs::read_to_string(query_path).map_err(|e| error(e.to_string()))?;
+    graphql_parser::parse_query::<String>(&query_string)
+        .map(|document| document.into_static())
+        .map_err(|e| error(e.to_string()))
+}
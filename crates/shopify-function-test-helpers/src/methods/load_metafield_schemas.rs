@@ -0,0 +1,62 @@
+//! Load a registry of metafield schemas to validate fixture metafields against
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::utils::validate_json_schema::draft_07_problem;
+
+/// The declared type of a metafield, identified by namespace and key
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetafieldSchema {
+    /// The namespace passed to `metafield(namespace:)`, `None` for the app-reserved namespace
+    pub namespace: Option<String>,
+    /// The key passed to `metafield(key:)`
+    pub key: String,
+    /// The type that owns the metafield, e.g. `Discount`, `Product` or `Shop` (any owner if `None`)
+    pub owner_type: Option<String>,
+    /// A metafield type such as `number_integer`, `json` or `list.single_line_text_field`
+    /// (defaults to `json`)
+    #[serde(rename = "type")]
+    pub metafield_type: Option<String>,
+    /// A JSON Schema the metafield's value must also match
+    pub schema: Option<Value>,
+}
+
+/// Load a metafield schema registry from a JSON file
+///
+/// The file holds an array of metafield schemas, e.g.
+/// `[{ "ownerType": "Discount", "key": "function-configuration", "type": "json", "schema": { ... } }]`.
+/// Schemas are JSON Schema draft-07, so one that uses a keyword of a later draft, such as
+/// `prefixItems`, is rejected rather than having the keyword ignored.
+pub fn load_metafield_schemas(path: impl AsRef<Path>) -> Result<Vec<MetafieldSchema>> {
+    let path = path.as_ref();
+    let error = |message: String| Error::LoadMetafieldSchemas {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let content = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let metafield_schemas: Value =
+        serde_json::from_str(&content).map_err(|e| error(e.to_string()))?;
+
+    let Value::Array(entries) = &metafield_schemas else {
+        return Err(error("Expected an array of metafield schemas".to_string()));
+    };
+    for (index, entry) in entries.iter().enumerate() {
+        if !entry.get("key").is_some_and(Value::is_string) {
+            return Err(error(format!(
+                "Metafield schema {index} must have a string key"
+            )));
+        }
+        if let Some(problem) = entry.get("schema").and_then(draft_07_problem) {
+            return Err(error(format!("Metafield schema {index} {problem}")));
+        }
+    }
+
+    serde_json::from_value(metafield_schemas).map_err(|e| error(e.to_string()))
+}
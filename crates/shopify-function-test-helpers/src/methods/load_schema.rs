@@ -0,0 +1,19 @@
+//! Load and build a GraphQL schema from a schema file path
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::schema::Schema;
+
+/// Load and build a GraphQL schema object from a schema file path
+pub fn load_schema(schema_path: impl AsRef<Path>) -> Result<Schema> {
+    let schema_path = schema_path.as_ref();
+    let error = |message: String| Error::LoadSchema {
+        path: schema_path.to_path_buf(),
+        message,
+    };
+
+    let schema_string = fs::read_to_string(schema_path).map_err(|e| error(e.to_string()))?;
+    Schema::parse(&schema_string).map_err(error)
+}
@@ -0,0 +1,17 @@
+pub mod assert_fixture;
+pub mod create_function_runner;
+pub mod diff_function_output;
+pub mod get_function_info;
+pub mod inject_fetch_result;
+pub mod load_fixture;
+pub mod load_function_info;
+pub mod load_input_query;
+pub mod load_metafield_schemas;
+pub mod load_schema;
+pub mod record_fixture;
+pub mod resolve_input_variables;
+pub mod run_function;
+pub mod validate_fixture_input;
+pub mod validate_fixture_output;
+pub mod validate_input_query;
+pub mod validate_test_assets;
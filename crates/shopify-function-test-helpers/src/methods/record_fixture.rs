@@ -0,0 +1,249 @@
+//! Record a function's actual output as a fixture's expected output
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::methods::diff_function_output::{diff_function_output, DiffFunctionOutputOptions};
+use crate::methods::get_function_info::InputVariables;
+use crate::methods::inject_fetch_result::inject_fetch_result;
+use crate::methods::load_fixture::{load_fixture, FixtureData};
+use crate::methods::load_input_query::load_input_query;
+use crate::methods::load_schema::load_schema;
+use crate::methods::resolve_input_variables::resolve_input_variables;
+use crate::methods::run_function::run_function;
+use crate::schema::Schema;
+use crate::utils::replace_json_value::replace_json_value;
+
+/// The record fixture result
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordFixtureResult {
+    /// The output the function produced, if the run succeeded
+    pub output: Option<Value>,
+    /// For a fixture with a fetch stage, the output of the fetch export
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_output: Option<Value>,
+    /// Whether the fixture file was rewritten (false if the outputs already matched)
+    pub updated: bool,
+    pub error: Option<String>,
+}
+
+/// Run a fixture through function-runner and write the actual output to its `payload.output`
+///
+/// This is the fixture equivalent of updating a snapshot. Only the `payload.output` value
+/// (and `payload.fetch.output` for a fixture with a fetch stage) is rewritten: every other
+/// field of the fixture, and the formatting of the file, is kept as it was. Outputs are
+/// compared with [`diff_function_output`], so an output that only differs in how a value
+/// is written, such as the Decimal `"10"` instead of `"10.0"`, is kept. The file is left
+/// untouched when the outputs already match, or when the function fails to run.
+///
+/// A fixture with a fetch stage runs the fetch export with the input query at
+/// `fetch_query_path` first and records the request it produces, then runs the run export
+/// with the canned response as `fetchResult`.
+///
+/// With the metafield bound by `[extensions.input.variables]` (see
+/// [`FunctionInfo::input_variables`](crate::FunctionInfo::input_variables)), each stage
+/// runs with its variables taken from that metafield in its input, as
+/// [`assert_fixture`](crate::assert_fixture) does.
+pub fn record_fixture(
+    fixture_path: impl AsRef<Path>,
+    function_runner_path: impl AsRef<Path>,
+    wasm_path: impl AsRef<Path>,
+    query_path: impl AsRef<Path>,
+    schema_path: impl AsRef<Path>,
+    fetch_query_path: Option<&Path>,
+    input_variables: Option<&InputVariables>,
+) -> RecordFixtureResult {
+    let fixture_path = fixture_path.as_ref();
+    let failure = |error: String| RecordFixtureResult {
+        output: None,
+        fetch_output: None,
+        updated: false,
+        error: Some(format!(
+            "Failed to record fixture {}: {error}",
+            fixture_path.display()
+        )),
+    };
+
+    let query_path = query_path.as_ref();
+    let fixture = match load_fixture(fixture_path) {
+        Ok(fixture) => fixture,
+        Err(error) => return failure(error.to_string()),
+    };
+    let schema = match load_schema(&schema_path) {
+        Ok(schema) => schema,
+        Err(error) => return failure(error.to_string()),
+    };
+    let runner = StageRunner {
+        fixture_path,
+        schema: &schema,
+        function_runner_path: function_runner_path.as_ref(),
+        wasm_path: wasm_path.as_ref(),
+        schema_path: schema_path.as_ref(),
+        input_variables,
+    };
+    let mut updates: Vec<(&[&str], Value)> = vec![];
+
+    let mut fetch_output = None;
+    if let Some(fetch) = &fixture.fetch {
+        let Some(fetch_query_path) = fetch_query_path else {
+            return failure(format!(
+                "The fixture has a fetch stage, so the input query of {} is needed to record it",
+                fetch.target
+            ));
+        };
+        let (output, changed) = match runner.run(&fetch.fixture(), fetch_query_path) {
+            Ok(stage) => stage,
+            Err(error) => return run_failure(error),
+        };
+        if changed {
+            updates.push((&["payload", "fetch", "output"], output.clone()));
+        }
+        fetch_output = Some(output);
+    }
+
+    let fixture = if fixture.fetch.is_some() {
+        let injected = load_input_query(query_path)
+            .map_err(|error| error.to_string())
+            .and_then(|input_query| inject_fetch_result(&fixture, &input_query));
+        match injected {
+            Ok(fixture) => fixture,
+            Err(error) => return failure(error),
+        }
+    } else {
+        fixture
+    };
+
+    let (output, changed) = match runner.run(&fixture, query_path) {
+        Ok(stage) => stage,
+        Err(error) => return run_failure(error),
+    };
+    if changed {
+        updates.push((&["payload", "output"], output.clone()));
+    }
+
+    let result = RecordFixtureResult {
+        output: Some(output),
+        fetch_output,
+        updated: !updates.is_empty(),
+        error: None,
+    };
+    if updates.is_empty() {
+        return result;
+    }
+
+    let updated_content = fs::read_to_string(fixture_path)
+        .map_err(|error| error.to_string())
+        .and_then(|fixture_content| apply_updates(&fixture_content, &updates));
+    match updated_content {
+        Ok(updated_content) => match fs::write(fixture_path, updated_content) {
+            Ok(()) => result,
+            Err(error) => failure(error.to_string()),
+        },
+        Err(error) => failure(error),
+    }
+}
+
+/// What each stage of a fixture runs with
+struct StageRunner<'a> {
+    fixture_path: &'a Path,
+    schema: &'a Schema,
+    function_runner_path: &'a Path,
+    wasm_path: &'a Path,
+    schema_path: &'a Path,
+    input_variables: Option<&'a InputVariables>,
+}
+
+impl StageRunner<'_> {
+    /// Run one stage of a fixture and compare its output with the stage's expected
+    /// output, returning the output and whether it changed
+    fn run(&self, stage: &FixtureData, query_path: &Path) -> Result<(Value, bool), Option<String>> {
+        let stage = &self
+            .with_input_variables(stage, query_path)
+            .map_err(|error| {
+                Some(format!(
+                    "Failed to record fixture {}: {error}",
+                    self.fixture_path.display()
+                ))
+            })?;
+        let run_result = run_function(
+            stage,
+            self.function_runner_path,
+            self.wasm_path,
+            query_path,
+            self.schema_path,
+        );
+        let output = match run_result.result {
+            Some(result) if run_result.error.is_none() => result.output,
+            _ => return Err(run_result.error),
+        };
+
+        let diff = diff_function_output(DiffFunctionOutputOptions {
+            schema: self.schema,
+            actual: &output,
+            expected: &stage.expected_output,
+            target: Some(&stage.target),
+            mutation_name: None,
+            result_parameter_name: None,
+        })
+        .map_err(|error| {
+            Some(format!(
+                "Failed to record fixture {}: {error}",
+                self.fixture_path.display()
+            ))
+        })?;
+        let changed = !diff.differences.is_empty();
+        Ok((output, changed))
+    }
+
+    /// The stage with its variables taken from the input variables metafield, if any
+    fn with_input_variables(
+        &self,
+        stage: &FixtureData,
+        query_path: &Path,
+    ) -> Result<FixtureData, String> {
+        let Some(input_variables) = self.input_variables else {
+            return Ok(stage.clone());
+        };
+        let input_query = load_input_query(query_path).map_err(|error| error.to_string())?;
+        resolve_input_variables(stage, &input_query, Some(input_variables))
+    }
+}
+
+/// The result of a fixture whose stage failed to run, with the run error as is
+fn run_failure(error: Option<String>) -> RecordFixtureResult {
+    RecordFixtureResult {
+        output: None,
+        fetch_output: None,
+        updated: false,
+        error,
+    }
+}
+
+/// Write each value to its path in the fixture's JSON text, keeping the formatting of
+/// the rest of the file
+fn apply_updates(fixture_content: &str, updates: &[(&[&str], Value)]) -> Result<String, String> {
+    let mut updated_content = Some(fixture_content.to_string());
+    for (path, value) in updates {
+        updated_content = updated_content
+            .and_then(|updated_content| replace_json_value(&updated_content, path, value));
+    }
+    if let Some(updated_content) = updated_content {
+        return Ok(updated_content);
+    }
+
+    // An output the fixture has no value for yet is added after the other fields
+    let mut fixture_json: Value =
+        serde_json::from_str(fixture_content).map_err(|error| error.to_string())?;
+    for (path, value) in updates {
+        let pointer: String = path.iter().map(|key| format!("/{key}")).collect();
+        let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+        if let Some(Value::Object(object)) = fixture_json.pointer_mut(parent) {
+            object.insert(key.to_string(), value.clone());
+        }
+    }
+    Ok(format!("{fixture_json:#}\n"))
+}
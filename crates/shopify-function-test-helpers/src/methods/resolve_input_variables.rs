@@ -0,0 +1,142 @@
+//! Take a fixture's input query variables from the metafield Shopify reads them from
+
+use graphql_parser::query::{Definition, Field, Selection, SelectionSet, Value as QueryValue};
+use serde_json::Value;
+
+use crate::methods::get_function_info::InputVariables;
+use crate::methods::load_fixture::FixtureData;
+use crate::schema::{operation_selection_set, QueryDocument};
+use crate::utils::inline_named_fragment_spreads::inline_named_fragment_spreads;
+
+/// Returns the fixture with its input query variables taken from the metafield bound by
+/// `[extensions.input.variables]`, when its input has that metafield
+///
+/// Shopify binds the variables to the JSON value of that metafield, so an input query that
+/// selects it, e.g. `metafield(namespace: "$app:discount", key: "input-variables")
+/// { jsonValue }`, carries the values in the fixture input. The metafield is found by its
+/// literal `namespace` and `key` arguments, and its `jsonValue` (or its `value`, parsed as
+/// JSON) becomes `variables`. Fixtures whose input does not have the metafield keep the
+/// values from `payload.variables`, and are returned unchanged.
+///
+/// Returns an error if the metafield's value is not a JSON object.
+pub fn resolve_input_variables(
+    fixture: &FixtureData,
+    input_query: &QueryDocument,
+    input_variables: Option<&InputVariables>,
+) -> Result<FixtureData, String> {
+    let Some(input_variables) = input_variables else {
+        return Ok(fixture.clone());
+    };
+
+    let document = inline_named_fragment_spreads(input_query)?;
+    let mut values = vec![];
+    for definition in &document.definitions {
+        if let Definition::Operation(operation) = definition {
+            find_metafield_values(
+                operation_selection_set(operation),
+                &[&fixture.input],
+                input_variables,
+                &mut values,
+            );
+        }
+    }
+    let Some(variables) = values.into_iter().next() else {
+        return Ok(fixture.clone());
+    };
+
+    let Value::Object(variables) = variables else {
+        return Err(format!(
+            "The `{}.{}` metafield bound by [extensions.input.variables] must hold a JSON object, but got {variables}",
+            input_variables.namespace, input_variables.key
+        ));
+    };
+    Ok(FixtureData {
+        variables: Some(variables),
+        ..fixture.clone()
+    })
+}
+
+/// Collects the values of the matching metafield fields in a selection set, walking the
+/// fixture objects it applies to
+fn find_metafield_values(
+    selection_set: &SelectionSet<'static, String>,
+    objects: &[&Value],
+    input_variables: &InputVariables,
+    values: &mut Vec<Value>,
+) {
+    for selection in &selection_set.items {
+        match selection {
+            Selection::Field(field) => {
+                let response_key = field.alias.as_ref().unwrap_or(&field.name);
+                let field_values: Vec<&Value> = objects
+                    .iter()
+                    .filter_map(|object| object.get(response_key))
+                    .flat_map(flatten_lists)
+                    .collect();
+
+                if field.name == "metafield"
+                    && string_literal(field, "namespace") == Some(&input_variables.namespace)
+                    && string_literal(field, "key") == Some(&input_variables.key)
+                {
+                    values.extend(
+                        field_values
+                            .iter()
+                            .filter_map(|metafield| metafield_value(field, metafield)),
+                    );
+                } else {
+                    find_metafield_values(
+                        &field.selection_set,
+                        &field_values,
+                        input_variables,
+                        values,
+                    );
+                }
+            }
+            Selection::InlineFragment(inline_fragment) => find_metafield_values(
+                &inline_fragment.selection_set,
+                objects,
+                input_variables,
+                values,
+            ),
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+}
+
+/// The value of a metafield in the fixture input, from its `jsonValue` or `value`
+fn metafield_value(field: &Field<'static, String>, metafield: &Value) -> Option<Value> {
+    for selection in &field.selection_set.items {
+        let Selection::Field(selection) = selection else {
+            continue;
+        };
+        let value = metafield.get(selection.alias.as_ref().unwrap_or(&selection.name));
+        match (selection.name.as_str(), value) {
+            ("jsonValue", Some(value)) => return Some(value.clone()),
+            ("value", Some(Value::String(value))) => {
+                return Some(
+                    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.clone())),
+                )
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn string_literal<'a>(field: &'a Field<'static, String>, name: &str) -> Option<&'a String> {
+    field
+        .arguments
+        .iter()
+        .find_map(|(argument, value)| match value {
+            QueryValue::String(value) if argument == name => Some(value),
+            _ => None,
+        })
+}
+
+fn flatten_lists(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(flatten_lists).collect(),
+        Value::Null => vec![],
+        _ => vec![value],
+    }
+}
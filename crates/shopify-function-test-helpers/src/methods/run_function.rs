@@ -0,0 +1,180 @@
+//! Run a function with the given payload and return the result
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use graphql_parser::query::{Definition, OperationDefinition};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tempfile::TempDir;
+
+use crate::methods::load_fixture::FixtureData;
+use crate::methods::load_input_query::load_input_query;
+use crate::methods::load_schema::load_schema;
+use crate::utils::inline_query_variables::inline_query_variables;
+
+/// The output and resource usage of a successful run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunFunctionOutput {
+    pub output: Value,
+    /// Number of instructions executed, if the runner reported it
+    pub instructions: Option<u64>,
+    /// Linear memory used by the module in kilobytes, if the runner reported it
+    pub memory_usage: Option<u64>,
+    /// Size of the JSON input passed to the function, in bytes
+    pub input_size: usize,
+    /// Size of the JSON output returned by the function, in bytes
+    pub output_size: usize,
+    /// Anything the function logged to stderr
+    pub logs: String,
+}
+
+/// The run function result
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunFunctionResult {
+    pub result: Option<RunFunctionOutput>,
+    pub error: Option<String>,
+}
+
+impl RunFunctionResult {
+    pub(crate) fn failure(error: String) -> Self {
+        RunFunctionResult {
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Run a function using the function-runner binary directly
+///
+/// The fixture input is written to the runner's stdin and its `--json` output is parsed,
+/// including the instructions, memory usage and logs it reports. function-runner does not
+/// take variables, so the fixture's `variables` are inlined into the input query it gets.
+pub fn run_function(
+    fixture: &FixtureData,
+    function_runner_path: impl AsRef<Path>,
+    wasm_path: impl AsRef<Path>,
+    query_path: impl AsRef<Path>,
+    schema_path: impl AsRef<Path>,
+) -> RunFunctionResult {
+    let input_json = fixture.input.to_string();
+
+    let inlined_query = match &fixture.variables {
+        Some(variables) => {
+            match query_with_variables(query_path.as_ref(), schema_path.as_ref(), variables) {
+                Ok(inlined_query) => inlined_query,
+                Err(error) => return RunFunctionResult::failure(error),
+            }
+        }
+        None => None,
+    };
+    let query_path = inlined_query
+        .as_ref()
+        .map_or(query_path.as_ref(), |(_, path)| path.as_path());
+
+    let runner_process = Command::new(function_runner_path.as_ref())
+        .arg("-f")
+        .arg(wasm_path.as_ref())
+        .arg("--export")
+        .arg(&fixture.export)
+        .arg("--query-path")
+        .arg(query_path)
+        .arg("--schema-path")
+        .arg(schema_path.as_ref())
+        .arg("--json")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+
+    let mut runner_process = match runner_process {
+        Ok(runner_process) => runner_process,
+        Err(error) => {
+            return RunFunctionResult::failure(format!("Failed to start function-runner: {error}"))
+        }
+    };
+
+    if let Some(mut stdin) = runner_process.stdin.take() {
+        // A runner that exits before reading its input reports the problem through its exit code
+        let _ = stdin.write_all(input_json.as_bytes());
+    }
+
+    let output = match runner_process.wait_with_output() {
+        Ok(output) => output,
+        Err(error) => return RunFunctionResult::failure(error.to_string()),
+    };
+
+    if !output.status.success() {
+        let code = output
+            .status
+            .code()
+            .map_or_else(|| "null".to_string(), |code| code.to_string());
+        return RunFunctionResult::failure(format!(
+            "function-runner failed with exit code {code}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let result: Value = match serde_json::from_slice(&output.stdout) {
+        Ok(result) => result,
+        Err(error) => {
+            return RunFunctionResult::failure(format!(
+                "Failed to parse function-runner output: {error}"
+            ))
+        }
+    };
+
+    // function-runner output format: { output: {...} }
+    match result.get("output") {
+        Some(output) if !output.is_null() => RunFunctionResult {
+            result: Some(RunFunctionOutput {
+                output: output.clone(),
+                instructions: result.get("instructions").and_then(Value::as_u64),
+                memory_usage: result.get("memory_usage").and_then(Value::as_u64),
+                input_size: input_json.len(),
+                output_size: output.to_string().len(),
+                logs: result
+                    .get("logs")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            }),
+            error: None,
+        },
+        _ => RunFunctionResult::failure(format!(
+            "function-runner returned unexpected format - missing 'output' field. Received: {result}"
+        )),
+    }
+}
+
+/// The input query with the variables inlined, written to a temporary directory that is
+/// removed when it is dropped, or `None` if the query declares no variables
+fn query_with_variables(
+    query_path: &Path,
+    schema_path: &Path,
+    variables: &Map<String, Value>,
+) -> Result<Option<(TempDir, PathBuf)>, String> {
+    let query = load_input_query(query_path).map_err(|e| e.to_string())?;
+    let declares_variables = query.definitions.iter().any(|definition| {
+        matches!(
+            definition,
+            Definition::Operation(OperationDefinition::Query(query))
+                if !query.variable_definitions.is_empty()
+        )
+    });
+    if !declares_variables {
+        return Ok(None);
+    }
+
+    let schema = load_schema(schema_path).map_err(|e| e.to_string())?;
+    let inlined_query = inline_query_variables(&query, &schema, variables)?;
+    let dir = TempDir::new().map_err(|e| format!("Failed to write the input query: {e}"))?;
+    let path = dir
+        .path()
+        .join(query_path.file_name().unwrap_or("query.graphql".as_ref()));
+    fs::write(&path, inlined_query.to_string())
+        .map_err(|e| format!("Failed to write the input query: {e}"))?;
+    Ok(Some((dir, path)))
+}
@@ -0,0 +1,636 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use graphql_parser::query::{Definition, Field, Selection, SelectionSet, TypeCondition};
+use graphql_parser::schema::Type;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::methods::load_metafield_schemas::MetafieldSchema;
+use crate::schema::{
+    is_non_null, named_type, nullable_type, operation_selection_set, QueryDocument, Schema,
+};
+use crate::utils::coerce_input_value::coerce_input_value;
+use crate::utils::inline_named_fragment_spreads::inline_named_fragment_spreads;
+use crate::utils::validate_argument_responses::validate_argument_responses;
+use crate::utils::validate_metafield::{find_metafield_schema, validate_metafield};
+
+/// A segment of the path to a value in fixture data
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Key(key) => f.write_str(key),
+            PathSegment::Index(index) => write!(f, "{index}"),
+        }
+    }
+}
+
+impl From<&str> for PathSegment {
+    fn from(key: &str) -> Self {
+        PathSegment::Key(key.to_string())
+    }
+}
+
+impl From<String> for PathSegment {
+    fn from(key: String) -> Self {
+        PathSegment::Key(key)
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        PathSegment::Index(index)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FixtureInputValidationError {
+    pub message: String,
+    pub path: Vec<PathSegment>,
+}
+
+/// Validate fixture input options
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidateFixtureInputOptions<'a> {
+    /// Schemas to check the fixture's metafields against (see `load_metafield_schemas`)
+    pub metafield_schemas: &'a [MetafieldSchema],
+    /// The input query's variable values (see `FixtureData::variables`)
+    pub variables: Option<&'a Map<String, Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ValidateFixtureInputResult {
+    pub errors: Vec<FixtureInputValidationError>,
+}
+
+struct NestedValue<'v> {
+    value: &'v Value,
+    path: Vec<PathSegment>,
+}
+
+/// Response keys selected for each concrete type within a field's selection set
+type ExpectedFields = HashMap<String, HashSet<String>>;
+
+/// Signals that traversal stopped early, like returning `BREAK` from a graphql-js visitor
+struct Break;
+
+/// Validates that fixture input data matches the structure and types defined in a GraphQL query.
+///
+/// The validator traverses the query AST alongside the fixture data and validates the
+/// corresponding fixture data at each field. It mirrors the TypeScript `validateFixtureInput`,
+/// reporting the same messages and paths.
+pub fn validate_fixture_input(
+    query: &QueryDocument,
+    schema: &Schema,
+    value: &Value,
+) -> ValidateFixtureInputResult {
+    validate_fixture_input_with_options(query, schema, value, Default::default())
+}
+
+/// Validates fixture input data like [`validate_fixture_input`], with options
+///
+/// `Metafield.value` and `Metafield.jsonValue` are opaque to the schema. When a schema is
+/// registered for a metafield's namespace and key, its value is also checked against the
+/// declared metafield type and JSON Schema.
+///
+/// Fields that answer a list argument one value at a time, such as `hasTags(tags:)` and
+/// `inCollections(ids:)`, must have one response per requested value, in order. Arguments
+/// can refer to variables, whose values are taken from `options.variables`.
+pub fn validate_fixture_input_with_options(
+    query: &QueryDocument,
+    schema: &Schema,
+    value: &Value,
+    options: ValidateFixtureInputOptions,
+) -> ValidateFixtureInputResult {
+    let document = match inline_named_fragment_spreads(query) {
+        Ok(document) => document,
+        Err(message) => {
+            return ValidateFixtureInputResult {
+                errors: vec![FixtureInputValidationError {
+                    message,
+                    path: vec![],
+                }],
+            }
+        }
+    };
+
+    let mut validator = FixtureInputValidator {
+        schema,
+        metafield_schemas: options.metafield_schemas,
+        variables: options.variables,
+        errors: vec![],
+    };
+
+    for definition in &document.definitions {
+        let Definition::Operation(operation) = definition else {
+            continue;
+        };
+
+        let root_type = schema.query_type();
+        let root_values = [NestedValue {
+            value,
+            path: vec![],
+        }];
+        let possible_types = HashSet::from([root_type.to_string()]);
+
+        if validator
+            .visit_field_selection_set(
+                operation_selection_set(operation),
+                root_type,
+                &root_values,
+                &possible_types,
+                &[],
+            )
+            .is_err()
+        {
+            break;
+        }
+    }
+
+    ValidateFixtureInputResult {
+        errors: validator.errors,
+    }
+}
+
+struct FixtureInputValidator<'a> {
+    schema: &'a Schema,
+    metafield_schemas: &'a [MetafieldSchema],
+    variables: Option<&'a Map<String, Value>>,
+    errors: Vec<FixtureInputValidationError>,
+}
+
+impl FixtureInputValidator<'_> {
+    /// Visits the selection set of a field (or of the operation root) and, once
+    /// all selections are validated, checks the fixture objects for extra fields.
+    fn visit_field_selection_set(
+        &mut self,
+        selection_set: &SelectionSet<'static, String>,
+        type_name: &str,
+        values: &[NestedValue],
+        possible_types: &HashSet<String>,
+        field_path: &[String],
+    ) -> Result<(), Break> {
+        let mut expected_fields = ExpectedFields::new();
+        // A field selection set starts a new object context, so the __typename key is not inherited
+        let typename_response_key = typename_response_key_in(selection_set);
+
+        self.visit_selections(
+            selection_set,
+            type_name,
+            type_name,
+            values,
+            possible_types,
+            typename_response_key,
+            &mut expected_fields,
+            field_path,
+        )?;
+
+        self.check_for_extra_fields(values, &expected_fields, typename_response_key);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_selections(
+        &mut self,
+        selection_set: &SelectionSet<'static, String>,
+        selection_type: &str,
+        parent_field_type: &str,
+        values: &[NestedValue],
+        possible_types: &HashSet<String>,
+        typename_response_key: Option<&str>,
+        expected_fields: &mut ExpectedFields,
+        field_path: &[String],
+    ) -> Result<(), Break> {
+        if self.schema.is_abstract(selection_type) {
+            let has_typename = typename_response_key_in(selection_set).is_some();
+            let fragment_count = selection_set
+                .items
+                .iter()
+                .filter(|selection| !matches!(selection, Selection::Field(_)))
+                .count();
+
+            if !has_typename && fragment_count > 1 {
+                self.errors.push(FixtureInputValidationError {
+                    message: format!(
+                        "Missing `__typename` field for abstract type `{selection_type}`"
+                    ),
+                    path: field_path.iter().map(|key| key.as_str().into()).collect(),
+                });
+                return Err(Break);
+            }
+        }
+
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => self.visit_field(
+                    field,
+                    selection_type,
+                    parent_field_type,
+                    values,
+                    possible_types,
+                    typename_response_key,
+                    expected_fields,
+                    field_path,
+                )?,
+                Selection::InlineFragment(inline_fragment) => {
+                    let mut fragment_type = selection_type;
+                    let mut fragment_possible_types = possible_types.clone();
+
+                    if let Some(TypeCondition::On(type_condition)) = &inline_fragment.type_condition
+                    {
+                        fragment_type = type_condition;
+                        if self.schema.is_abstract(type_condition) {
+                            let condition_possible_types: HashSet<&str> = self
+                                .schema
+                                .possible_types(type_condition)
+                                .into_iter()
+                                .collect();
+                            fragment_possible_types
+                                .retain(|name| condition_possible_types.contains(name.as_str()));
+                        } else if self.schema.is_composite(type_condition) {
+                            fragment_possible_types = HashSet::from([type_condition.clone()]);
+                        }
+                    }
+
+                    // Inside an inline fragment without __typename - inherit from parent selection set
+                    let fragment_typename_response_key =
+                        typename_response_key_in(&inline_fragment.selection_set)
+                            .or(typename_response_key);
+
+                    self.visit_selections(
+                        &inline_fragment.selection_set,
+                        fragment_type,
+                        parent_field_type,
+                        values,
+                        &fragment_possible_types,
+                        fragment_typename_response_key,
+                        expected_fields,
+                        field_path,
+                    )?;
+                }
+                // Named fragment spreads were inlined before traversal
+                Selection::FragmentSpread(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_field(
+        &mut self,
+        field: &Field<'static, String>,
+        selection_type: &str,
+        parent_field_type: &str,
+        values: &[NestedValue],
+        possible_types: &HashSet<String>,
+        typename_response_key: Option<&str>,
+        expected_fields: &mut ExpectedFields,
+        field_path: &[String],
+    ) -> Result<(), Break> {
+        let response_key = field.alias.as_ref().unwrap_or(&field.name);
+
+        // Track this field for every concrete type the current selection applies to
+        for concrete_type_name in possible_types {
+            expected_fields
+                .entry(concrete_type_name.clone())
+                .or_default()
+                .insert(response_key.clone());
+        }
+
+        let Some(field_definition) = self.schema.get_field(selection_type, &field.name) else {
+            let mut path: Vec<PathSegment> =
+                field_path.iter().map(|key| key.as_str().into()).collect();
+            path.push(response_key.as_str().into());
+            self.errors.push(FixtureInputValidationError {
+                message: format!("Cannot validate `{response_key}`: missing field definition"),
+                path,
+            });
+            return Err(Break);
+        };
+        let field_type = &field_definition.field_type;
+        let field_named_type = named_type(field_type);
+
+        let mut nested_values = vec![];
+
+        for NestedValue {
+            value: current_value,
+            path: current_path,
+        } in values
+        {
+            let mut path = current_path.clone();
+            path.push(response_key.as_str().into());
+
+            let Some(value_for_response_key) = current_value.get(response_key) else {
+                // Field is missing from fixture
+                if is_value_expected_for_type(
+                    current_value,
+                    parent_field_type,
+                    possible_types,
+                    self.schema,
+                    typename_response_key,
+                ) {
+                    self.errors.push(FixtureInputValidationError {
+                        message: format!("Missing expected fixture data for `{response_key}`"),
+                        path,
+                    });
+                }
+                continue;
+            };
+
+            // Scalars and Enums (including wrapped types)
+            if self.schema.is_leaf(field_named_type) {
+                // Although we are validating output values (fixture data), we can use input coercion
+                // because the only output types that are also input types are built-in scalars,
+                // custom scalars, enums, and list/nullable wrappers of these.
+                let errors = &mut self.errors;
+                coerce_input_value(
+                    value_for_response_key,
+                    field_type,
+                    self.schema,
+                    &mut |_, message| {
+                        errors.push(FixtureInputValidationError {
+                            message,
+                            path: path.clone(),
+                        });
+                    },
+                );
+            }
+            // Nullable fields with null value
+            else if !is_non_null(field_type) && value_for_response_key.is_null() {
+                // null is valid for nullable types, nothing to do
+            }
+            // Lists - process recursively
+            else if let Type::ListType(element_type) = nullable_type(field_type) {
+                if let Value::Array(elements) = value_for_response_key {
+                    let argument_errors = validate_argument_responses(
+                        field_named_type,
+                        field,
+                        elements,
+                        &path,
+                        self.variables,
+                    );
+                    self.process_nested_arrays(elements, element_type, path, &mut nested_values);
+                    self.errors.extend(argument_errors);
+                } else {
+                    self.errors.push(FixtureInputValidationError {
+                        message: format!(
+                            "Expected array, but got {}",
+                            js_typeof(value_for_response_key)
+                        ),
+                        path,
+                    });
+                }
+            }
+            // Objects - validate and add to traversal stack
+            else {
+                match value_for_response_key {
+                    Value::Null => self.errors.push(FixtureInputValidationError {
+                        message: "Expected object, but got null".to_string(),
+                        path,
+                    }),
+                    Value::Object(_) | Value::Array(_) => nested_values.push(NestedValue {
+                        value: value_for_response_key,
+                        path,
+                    }),
+                    _ => self.errors.push(FixtureInputValidationError {
+                        message: format!(
+                            "Expected object, but got {}",
+                            js_typeof(value_for_response_key)
+                        ),
+                        path,
+                    }),
+                }
+            }
+        }
+
+        // Metafields with a registered schema
+        if field_named_type == "Metafield" {
+            if let Some(metafield_schema) = find_metafield_schema(
+                self.metafield_schemas,
+                field,
+                possible_types,
+                self.variables,
+            ) {
+                for NestedValue { value, path } in &nested_values {
+                    for error in validate_metafield(metafield_schema, field, value) {
+                        let mut path = path.clone();
+                        path.push(error.response_key.into());
+                        self.errors.push(FixtureInputValidationError {
+                            message: error.message,
+                            path,
+                        });
+                    }
+                }
+            }
+        }
+
+        if field.selection_set.items.is_empty() {
+            return Ok(());
+        }
+
+        let nested_possible_types: HashSet<String> = self
+            .schema
+            .possible_types(field_named_type)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let mut nested_field_path = field_path.to_vec();
+        nested_field_path.push(response_key.clone());
+
+        self.visit_field_selection_set(
+            &field.selection_set,
+            field_named_type,
+            &nested_values,
+            &nested_possible_types,
+            &nested_field_path,
+        )
+    }
+
+    /// Recursively processes nested arrays by flattening them.
+    ///
+    /// Validates nullability constraints as it goes and filters out nulls, since they
+    /// have no field values to validate. This is necessary because the traversal follows
+    /// the query structure, not the data structure: when visiting a field inside a
+    /// `[[T]]` type, the nested values must be T objects, not arrays.
+    fn process_nested_arrays<'v>(
+        &mut self,
+        elements: &'v [Value],
+        element_type: &Type<'static, String>,
+        path: Vec<PathSegment>,
+        nested_values: &mut Vec<NestedValue<'v>>,
+    ) {
+        for (index, element) in elements.iter().enumerate() {
+            let mut element_path = path.clone();
+            element_path.push(PathSegment::Index(index));
+
+            if element.is_null() {
+                if is_non_null(element_type) {
+                    self.errors.push(FixtureInputValidationError {
+                        message: "Null value found in non-nullable array".to_string(),
+                        path: element_path,
+                    });
+                }
+            } else if let Type::ListType(inner_element_type) = nullable_type(element_type) {
+                // Element type is a list - expect nested array and recurse
+                if let Value::Array(inner_elements) = element {
+                    self.process_nested_arrays(
+                        inner_elements,
+                        inner_element_type,
+                        element_path,
+                        nested_values,
+                    );
+                } else {
+                    // Fixture structure doesn't match schema nesting
+                    self.errors.push(FixtureInputValidationError {
+                        message: format!("Expected array, but got {}", js_typeof(element)),
+                        path: element_path,
+                    });
+                }
+            } else {
+                // Non-list type - add directly
+                nested_values.push(NestedValue {
+                    value: element,
+                    path: element_path,
+                });
+            }
+        }
+    }
+
+    /// Checks fixture objects for fields that are not present in the GraphQL query.
+    ///
+    /// Uses `__typename` to determine which inline fragment fields apply to each object.
+    /// Without `__typename`, the union of all fragment fields is allowed.
+    fn check_for_extra_fields(
+        &mut self,
+        fixture_objects: &[NestedValue],
+        expected_fields: &ExpectedFields,
+        typename_response_key: Option<&str>,
+    ) {
+        for NestedValue { value, path } in fixture_objects {
+            let Value::Object(fixture_object) = value else {
+                continue;
+            };
+
+            let object_typename = fixture_object
+                .get(typename_response_key.unwrap_or("__typename"))
+                .filter(|typename| is_truthy(typename));
+
+            let expected_for_this_object: HashSet<&String> = match object_typename {
+                // Object has __typename - direct lookup by concrete type name
+                Some(typename) => typename
+                    .as_str()
+                    .and_then(|typename| expected_fields.get(typename))
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+                // No __typename - we can't discriminate which fragment applies,
+                // so allow the union of all fragment fields
+                None => expected_fields.values().flatten().collect(),
+            };
+
+            for fixture_field in fixture_object.keys() {
+                if !expected_for_this_object.contains(fixture_field) {
+                    let mut field_path = path.clone();
+                    field_path.push(fixture_field.as_str().into());
+                    self.errors.push(FixtureInputValidationError {
+                        message: format!(
+                            "Extra field `{fixture_field}` found in fixture data not in query"
+                        ),
+                        path: field_path,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Determines if a fixture value is expected to contain the fields of the current selection
+///
+/// When `__typename` is selected, checks if the value's `__typename` is in the possible types.
+/// Otherwise, an empty object is accepted when an inline fragment narrowed the possible types
+/// of the parent field; non-empty objects conservatively expect all fields.
+fn is_value_expected_for_type(
+    fixture_value: &Value,
+    parent_field_type: &str,
+    possible_types: &HashSet<String>,
+    schema: &Schema,
+    typename_response_key: Option<&str>,
+) -> bool {
+    let Some(typename_response_key) = typename_response_key else {
+        let parent_field_possible_types: HashSet<&str> = if schema.is_abstract(parent_field_type) {
+            schema
+                .possible_types(parent_field_type)
+                .into_iter()
+                .collect()
+        } else {
+            HashSet::from([parent_field_type])
+        };
+        let narrowed = parent_field_possible_types.len() != possible_types.len()
+            || possible_types
+                .iter()
+                .any(|name| !parent_field_possible_types.contains(name.as_str()));
+
+        return !(narrowed && is_empty_object(fixture_value));
+    };
+
+    match fixture_value.get(typename_response_key) {
+        Some(Value::String(typename)) if !typename.is_empty() => possible_types.contains(typename),
+        // A non-string __typename can never match a type name
+        Some(typename) if is_truthy(typename) => false,
+        // No __typename in value - can't discriminate, so expect it
+        _ => true,
+    }
+}
+
+/// The response key of a `__typename` selection made directly in this selection set
+fn typename_response_key_in<'a>(
+    selection_set: &'a SelectionSet<'static, String>,
+) -> Option<&'a str> {
+    selection_set
+        .items
+        .iter()
+        .find_map(|selection| match selection {
+            Selection::Field(field) if field.name == "__typename" => {
+                Some(field.alias.as_deref().unwrap_or("__typename"))
+            }
+            _ => None,
+        })
+}
+
+/// Equivalent of `Object.keys(value).length === 0`
+fn is_empty_object(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => object.is_empty(),
+        Value::Array(array) => array.is_empty(),
+        Value::String(string) => string.is_empty(),
+        Value::Null | Value::Bool(_) | Value::Number(_) => true,
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+/// The JavaScript `typeof` of a JSON value, used in error messages
+fn js_typeof(value: &Value) -> &'static str {
+    match value {
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Null | Value::Array(_) | Value::Object(_) => "object",
+    }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::schema::Schema;
+use crate::utils::coerce_input_value::coerce_input_value;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputValidationError {
+    pub message: String,
+}
+
+/// Output fixture validation result
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputValidationResult {
+    /// GraphQL coercion errors (empty if valid)
+    pub errors: Vec<OutputValidationError>,
+    /// The mutation name that was validated
+    pub mutation_name: String,
+    /// The GraphQL type of the result parameter
+    pub result_parameter_type: Option<String>,
+}
+
+/// Validate output fixture by checking if it can be used as input to the corresponding mutation
+///
+/// Function output fixtures are designed to be used as input parameters to GraphQL mutations,
+/// so they are validated by finding the mutation field and its parameter type in the schema,
+/// then coercing the fixture data against that input type.
+pub fn validate_fixture_output(
+    output_fixture_data: &Value,
+    schema: &Schema,
+    mutation_name: &str,
+    result_parameter_name: &str,
+) -> OutputValidationResult {
+    let failure = |message: String| OutputValidationResult {
+        errors: vec![OutputValidationError { message }],
+        mutation_name: mutation_name.to_string(),
+        result_parameter_type: None,
+    };
+
+    // Get the mutation type from schema
+    let Some(mutation_type) = schema.mutation_type() else {
+        return failure("Schema does not have a mutation type".to_string());
+    };
+
+    // Get the specific mutation field
+    let Some(mutation_field) = mutation_type
+        .fields
+        .iter()
+        .find(|field| field.name == mutation_name)
+    else {
+        return failure(format!("Mutation '{mutation_name}' not found in schema"));
+    };
+
+    // Get the result parameter type
+    let Some(result_arg) = mutation_field
+        .arguments
+        .iter()
+        .find(|arg| arg.name == result_parameter_name)
+    else {
+        return failure(format!(
+            "Parameter '{result_parameter_name}' not found in mutation '{mutation_name}'"
+        ));
+    };
+
+    let mut errors = vec![];
+    coerce_input_value(
+        output_fixture_data,
+        &result_arg.value_type,
+        schema,
+        &mut |path, message| {
+            let path: Vec<String> = path.iter().map(ToString::to_string).collect();
+            errors.push(OutputValidationError {
+                message: format!("{message} At \"{}\"", path.join(".")),
+            });
+        },
+    );
+
+    OutputValidationResult {
+        errors,
+        mutation_name: mutation_name.to_string(),
+        result_parameter_type: Some(result_arg.value_type.to_string()),
+    }
+}
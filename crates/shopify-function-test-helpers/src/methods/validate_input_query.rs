@@ -0,0 +1,476 @@
+use std::collections::HashSet;
+
+use graphql_parser::query::{
+    Definition, Field, FragmentDefinition, OperationDefinition, Selection, SelectionSet,
+    TypeCondition, Value as QueryValue, VariableDefinition,
+};
+use graphql_parser::schema::{Field as SchemaField, TypeDefinition, Value as SchemaValue};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::methods::validate_fixture_input::PathSegment;
+use crate::schema::{is_non_null, named_type, operation_selection_set, QueryDocument, Schema};
+use crate::utils::coerce_input_value::{coerce_input_value, inspect};
+
+/// A GraphQL validation error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphQLError {
+    pub message: String,
+}
+
+/// Validate a GraphQL input query AST against a schema
+///
+/// Returns GraphQL validation errors (empty if valid). This implements the subset of the
+/// graphql-js validation rules that apply to function input queries: fields, arguments,
+/// fragments and variables must all be known, leaf fields must not have selections and
+/// composite fields must have them.
+///
+/// When a `target` is given, fields whose `@restrictTarget(only: [...])` excludes it are
+/// reported as well.
+pub fn validate_input_query(
+    query: &QueryDocument,
+    schema: &Schema,
+    target: Option<&str>,
+) -> Vec<GraphQLError> {
+    let mut validator = InputQueryValidator {
+        schema,
+        target,
+        fragments: query
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Fragment(fragment) => Some(fragment),
+                Definition::Operation(_) => None,
+            })
+            .collect(),
+        used_fragments: HashSet::new(),
+        errors: vec![],
+    };
+
+    for definition in &query.definitions {
+        match definition {
+            Definition::Operation(operation) => validator.validate_operation(operation),
+            Definition::Fragment(fragment) => validator.validate_fragment(fragment),
+        }
+    }
+
+    // Fragments are reachable from any operation, so unused ones are reported last
+    for fragment in &validator.fragments {
+        if !validator.used_fragments.contains(fragment.name.as_str()) {
+            validator.errors.push(GraphQLError {
+                message: format!("Fragment \"{}\" is never used.", fragment.name),
+            });
+        }
+    }
+
+    validator.errors
+}
+
+/// Validate the values a fixture provides for the input query's variables
+///
+/// Shopify binds input query variables to the metafield named by
+/// `[extensions.input.variables]` in shopify.extension.toml, and fixtures carry their
+/// values in `payload.variables`. Each variable the query declares must have a value of
+/// its type unless it is nullable or has a default, and each value must be for a
+/// variable the query declares. Mirrors the TypeScript `validateInputQueryVariables`,
+/// reporting the same messages as graphql-js `getVariableValues`.
+pub fn validate_input_query_variables(
+    query: &QueryDocument,
+    schema: &Schema,
+    variables: &Map<String, Value>,
+) -> Vec<GraphQLError> {
+    let Some(variable_definitions) =
+        query
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                Definition::Operation(operation) => Some(operation_variable_definitions(operation)),
+                Definition::Fragment(_) => None,
+            })
+    else {
+        return vec![];
+    };
+
+    let mut errors = vec![];
+    let mut error = |message: String| errors.push(GraphQLError { message });
+
+    for VariableDefinition {
+        name,
+        var_type,
+        default_value,
+        ..
+    } in variable_definitions
+    {
+        if !matches!(
+            schema.get_type(named_type(var_type)),
+            Some(
+                TypeDefinition::Scalar(_)
+                    | TypeDefinition::Enum(_)
+                    | TypeDefinition::InputObject(_)
+            )
+        ) {
+            error(format!(
+                "Variable \"${name}\" expected value of type \"{var_type}\" which cannot be used as an input type."
+            ));
+            continue;
+        }
+
+        match variables.get(name) {
+            None => {
+                if default_value.is_none() && is_non_null(var_type) {
+                    error(format!(
+                        "Variable \"${name}\" of required type \"{var_type}\" was not provided."
+                    ));
+                }
+            }
+            Some(Value::Null) if is_non_null(var_type) => error(format!(
+                "Variable \"${name}\" of non-null type \"{var_type}\" must not be null."
+            )),
+            Some(value) => coerce_input_value(value, var_type, schema, &mut |path, message| {
+                let invalid_value = path.iter().fold(value, |value, segment| match segment {
+                    PathSegment::Key(key) => &value[key.as_str()],
+                    PathSegment::Index(index) => &value[*index],
+                });
+                let location = if path.is_empty() {
+                    String::new()
+                } else {
+                    let path: String = path
+                        .iter()
+                        .map(|segment| match segment {
+                            PathSegment::Key(key) => format!(".{key}"),
+                            PathSegment::Index(index) => format!("[{index}]"),
+                        })
+                        .collect();
+                    format!(" at \"{name}{path}\"")
+                };
+                error(format!(
+                    "Variable \"${name}\" got invalid value {}{location}; {message}",
+                    inspect(invalid_value)
+                ));
+            }),
+        }
+    }
+
+    for name in variables.keys() {
+        if !variable_definitions
+            .iter()
+            .any(|definition| &definition.name == name)
+        {
+            error(format!(
+                "Variable \"${name}\" is not declared by the input query."
+            ));
+        }
+    }
+
+    errors
+}
+
+struct InputQueryValidator<'a> {
+    schema: &'a Schema,
+    target: Option<&'a str>,
+    fragments: Vec<&'a FragmentDefinition<'static, String>>,
+    used_fragments: HashSet<&'a str>,
+    errors: Vec<GraphQLError>,
+}
+
+impl<'a> InputQueryValidator<'a> {
+    fn error(&mut self, message: String) {
+        self.errors.push(GraphQLError { message });
+    }
+
+    fn validate_operation(&mut self, operation: &'a OperationDefinition<'static, String>) {
+        let (name, variable_definitions, root_type) = match operation {
+            OperationDefinition::SelectionSet(_) => (None, &[][..], Some(self.schema.query_type())),
+            OperationDefinition::Query(query) => (
+                query.name.as_deref(),
+                &query.variable_definitions[..],
+                Some(self.schema.query_type()),
+            ),
+            OperationDefinition::Mutation(mutation) => (
+                mutation.name.as_deref(),
+                &mutation.variable_definitions[..],
+                self.schema
+                    .mutation_type()
+                    .map(|mutation| mutation.name.as_str()),
+            ),
+            OperationDefinition::Subscription(subscription) => (
+                subscription.name.as_deref(),
+                &subscription.variable_definitions[..],
+                None,
+            ),
+        };
+
+        let Some(root_type) = root_type else {
+            self.error("Schema is not configured to execute this operation.".to_string());
+            return;
+        };
+
+        let selection_set = operation_selection_set(operation);
+        self.validate_selection_set(selection_set, root_type);
+
+        let mut used_variables = vec![];
+        collect_selection_set_variables(
+            selection_set,
+            &self.fragments,
+            &mut HashSet::new(),
+            &mut used_variables,
+        );
+
+        let defined_variables: HashSet<&str> = variable_definitions
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect();
+
+        for &variable in &used_variables {
+            if !defined_variables.contains(variable) {
+                self.error(match name {
+                    Some(name) => {
+                        format!("Variable \"${variable}\" is not defined by operation \"{name}\".")
+                    }
+                    None => format!("Variable \"${variable}\" is not defined."),
+                });
+            }
+        }
+
+        for VariableDefinition { name: variable, .. } in variable_definitions {
+            if !used_variables.contains(&variable.as_str()) {
+                self.error(match name {
+                    Some(name) => {
+                        format!("Variable \"${variable}\" is never used in operation \"{name}\".")
+                    }
+                    None => format!("Variable \"${variable}\" is never used."),
+                });
+            }
+        }
+    }
+
+    fn validate_fragment(&mut self, fragment: &'a FragmentDefinition<'static, String>) {
+        let TypeCondition::On(type_condition) = &fragment.type_condition;
+
+        if self.schema.get_type(type_condition).is_none() {
+            self.error(format!("Unknown type \"{type_condition}\"."));
+            return;
+        }
+        if !self.schema.is_composite(type_condition) {
+            self.error(format!(
+                "Fragment \"{}\" cannot condition on non composite type \"{type_condition}\".",
+                fragment.name
+            ));
+            return;
+        }
+
+        self.validate_selection_set(&fragment.selection_set, type_condition);
+    }
+
+    fn validate_selection_set(
+        &mut self,
+        selection_set: &'a SelectionSet<'static, String>,
+        parent_type: &str,
+    ) {
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => self.validate_field(field, parent_type),
+                Selection::InlineFragment(inline_fragment) => {
+                    let fragment_type = match &inline_fragment.type_condition {
+                        Some(TypeCondition::On(type_condition)) => {
+                            if self.schema.get_type(type_condition).is_none() {
+                                self.error(format!("Unknown type \"{type_condition}\"."));
+                                continue;
+                            }
+                            if !self.schema.is_composite(type_condition) {
+                                self.error(format!(
+                                    "Fragment cannot condition on non composite type \"{type_condition}\"."
+                                ));
+                                continue;
+                            }
+                            type_condition.as_str()
+                        }
+                        None => parent_type,
+                    };
+                    self.validate_selection_set(&inline_fragment.selection_set, fragment_type);
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.fragment_name.as_str();
+                    if self.fragments.iter().any(|fragment| fragment.name == name) {
+                        self.used_fragments.insert(name);
+                    } else {
+                        self.error(format!("Unknown fragment \"{name}\"."));
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_field(&mut self, field: &'a Field<'static, String>, parent_type: &str) {
+        let schema = self.schema;
+        let Some(field_definition) = schema.get_field(parent_type, &field.name) else {
+            self.error(format!(
+                "Cannot query field \"{}\" on type \"{parent_type}\".",
+                field.name
+            ));
+            return;
+        };
+
+        if let Some(target) = self.target {
+            if let Some(allowed_targets) = restricted_targets(field_definition) {
+                if !allowed_targets.contains(&target) {
+                    let allowed_targets: Vec<String> = allowed_targets
+                        .iter()
+                        .map(|allowed| format!("\"{allowed}\""))
+                        .collect();
+                    self.error(format!(
+                        "Cannot query field \"{}\" on type \"{parent_type}\" for target \"{target}\". \
+                         It is restricted to targets: {}.",
+                        field_definition.name,
+                        allowed_targets.join(", ")
+                    ));
+                }
+            }
+        }
+
+        for (argument_name, _) in &field.arguments {
+            if !field_definition
+                .arguments
+                .iter()
+                .any(|argument| &argument.name == argument_name)
+            {
+                self.error(format!(
+                    "Unknown argument \"{argument_name}\" on field \"{parent_type}.{}\".",
+                    field.name
+                ));
+            }
+        }
+
+        for argument in &field_definition.arguments {
+            let provided = field
+                .arguments
+                .iter()
+                .any(|(name, _)| name == &argument.name);
+            if !provided && argument.default_value.is_none() && is_non_null(&argument.value_type) {
+                self.error(format!(
+                    "Field \"{}\" argument \"{}\" of type \"{}\" is required, but it was not provided.",
+                    field.name, argument.name, argument.value_type
+                ));
+            }
+        }
+
+        let field_type = named_type(&field_definition.field_type);
+        let has_selection = !field.selection_set.items.is_empty();
+
+        if schema.is_leaf(field_type) {
+            if has_selection {
+                self.error(format!(
+                    "Field \"{}\" must not have a selection since type \"{}\" has no subfields.",
+                    field.name, field_definition.field_type
+                ));
+            }
+        } else if !has_selection {
+            self.error(format!(
+                "Field \"{0}\" of type \"{1}\" must have a selection of subfields. Did you mean \"{0} {{ ... }}\"?",
+                field.name, field_definition.field_type
+            ));
+        } else {
+            self.validate_selection_set(&field.selection_set, field_type);
+        }
+    }
+}
+
+fn operation_variable_definitions<'a>(
+    operation: &'a OperationDefinition<'static, String>,
+) -> &'a [VariableDefinition<'static, String>] {
+    match operation {
+        OperationDefinition::SelectionSet(_) => &[],
+        OperationDefinition::Query(query) => &query.variable_definitions,
+        OperationDefinition::Mutation(mutation) => &mutation.variable_definitions,
+        OperationDefinition::Subscription(subscription) => &subscription.variable_definitions,
+    }
+}
+
+/// The targets listed by a field's `@restrictTarget(only: [...])` directive, if it has one
+fn restricted_targets<'a>(
+    field_definition: &'a SchemaField<'static, String>,
+) -> Option<Vec<&'a str>> {
+    let directive = field_definition
+        .directives
+        .iter()
+        .find(|directive| directive.name == "restrictTarget")?;
+    let (_, only) = directive
+        .arguments
+        .iter()
+        .find(|(name, _)| name == "only")?;
+
+    match only {
+        SchemaValue::List(targets) => Some(
+            targets
+                .iter()
+                .filter_map(|target| match target {
+                    SchemaValue::String(target) => Some(target.as_str()),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        SchemaValue::String(target) => Some(vec![target.as_str()]),
+        _ => None,
+    }
+}
+
+/// Collects the variables referenced by an operation, in order of first use.
+/// Variables used inside fragments count as used by the operations that spread them.
+fn collect_selection_set_variables<'a>(
+    selection_set: &'a SelectionSet<'static, String>,
+    fragments: &[&'a FragmentDefinition<'static, String>],
+    visited_fragments: &mut HashSet<&'a str>,
+    variables: &mut Vec<&'a str>,
+) {
+    for selection in &selection_set.items {
+        match selection {
+            Selection::Field(field) => {
+                for (_, value) in &field.arguments {
+                    collect_variables(value, variables);
+                }
+                collect_selection_set_variables(
+                    &field.selection_set,
+                    fragments,
+                    visited_fragments,
+                    variables,
+                );
+            }
+            Selection::InlineFragment(inline_fragment) => collect_selection_set_variables(
+                &inline_fragment.selection_set,
+                fragments,
+                visited_fragments,
+                variables,
+            ),
+            Selection::FragmentSpread(spread) => {
+                let fragment = fragments
+                    .iter()
+                    .find(|fragment| fragment.name == spread.fragment_name);
+                if let Some(fragment) = fragment {
+                    if visited_fragments.insert(fragment.name.as_str()) {
+                        collect_selection_set_variables(
+                            &fragment.selection_set,
+                            fragments,
+                            visited_fragments,
+                            variables,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn collect_variables<'a>(value: &'a QueryValue<'static, String>, variables: &mut Vec<&'a str>) {
+    match value {
+        QueryValue::Variable(name) if !variables.contains(&name.as_str()) => {
+            variables.push(name);
+        }
+        QueryValue::List(items) => items
+            .iter()
+            .for_each(|item| collect_variables(item, variables)),
+        QueryValue::Object(fields) => fields
+            .values()
+            .for_each(|field| collect_variables(field, variables)),
+        _ => {}
+    }
+}
@@ -0,0 +1,158 @@
+use serde::Serialize;
+use serde_json::Map;
+
+use crate::methods::load_fixture::FixtureData;
+use crate::methods::load_metafield_schemas::MetafieldSchema;
+use crate::methods::validate_fixture_input::{
+    validate_fixture_input_with_options, FixtureInputValidationError, ValidateFixtureInputOptions,
+};
+use crate::methods::validate_fixture_output::{validate_fixture_output, OutputValidationError};
+use crate::methods::validate_input_query::{
+    validate_input_query, validate_input_query_variables, GraphQLError,
+};
+use crate::schema::{QueryDocument, Schema};
+use crate::utils::determine_mutation_from_target::determine_mutation_from_target;
+
+/// Validate test assets options
+#[derive(Debug, Clone, Copy)]
+pub struct ValidateTestAssetsOptions<'a> {
+    pub schema: &'a Schema,
+    pub fixture: &'a FixtureData,
+    pub input_query: &'a QueryDocument,
+    /// The function target, used to check `@restrictTarget` fields in the input query
+    /// (defaults to the fixture target)
+    pub target: Option<&'a str>,
+    /// The mutation name for output validation (determined from the target if not provided)
+    pub mutation_name: Option<&'a str>,
+    /// The mutation parameter name (determined from the target if not provided)
+    pub result_parameter_name: Option<&'a str>,
+    /// Schemas to check the fixture's metafields against (see `load_metafield_schemas`)
+    pub metafield_schemas: &'a [MetafieldSchema],
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct InputQueryValidation {
+    pub errors: Vec<GraphQLError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct InputFixtureValidation {
+    pub errors: Vec<FixtureInputValidationError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct OutputFixtureValidation {
+    pub errors: Vec<OutputValidationError>,
+    pub mutation_name: Option<String>,
+    pub result_parameter_type: Option<String>,
+}
+
+/// Complete validation results
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct CompleteValidationResult {
+    pub mutation_name: Option<String>,
+    pub result_parameter_name: Option<String>,
+    pub input_query: InputQueryValidation,
+    pub input_fixture: InputFixtureValidation,
+    pub output_fixture: OutputFixtureValidation,
+    pub error: Option<String>,
+}
+
+/// Validates test assets (input query and fixture) before function execution
+///
+/// This function provides a one-stop validation solution that:
+/// 1. Validates the input query against the schema, and the fixture's variable values
+///    against the variables it declares
+/// 2. Validates the input fixture data against the schema and query structure
+/// 3. Validates the output fixture data against the specified mutation
+pub fn validate_test_assets(options: ValidateTestAssetsOptions) -> CompleteValidationResult {
+    let ValidateTestAssetsOptions {
+        schema,
+        fixture,
+        input_query,
+        target,
+        mutation_name,
+        result_parameter_name,
+        metafield_schemas,
+    } = options;
+    let target = target.unwrap_or(&fixture.target);
+
+    let mut results = CompleteValidationResult {
+        mutation_name: mutation_name.map(str::to_string),
+        result_parameter_name: result_parameter_name.map(str::to_string),
+        ..Default::default()
+    };
+
+    // Step 1: Validate input query and its variables
+    results.input_query.errors =
+        validate_input_query(input_query, schema, Some(target).filter(|t| !t.is_empty()));
+    results
+        .input_query
+        .errors
+        .extend(validate_input_query_variables(
+            input_query,
+            schema,
+            fixture.variables.as_ref().unwrap_or(&Map::new()),
+        ));
+
+    // Step 2: Validate input fixture (which also validates query-fixture match)
+    results.input_fixture.errors = validate_fixture_input_with_options(
+        input_query,
+        schema,
+        &fixture.input,
+        ValidateFixtureInputOptions {
+            metafield_schemas,
+            variables: fixture.variables.as_ref(),
+        },
+    )
+    .errors;
+
+    // Step 3: Determine mutation details for output validation
+    let (mutation_name, result_parameter_name) = match (mutation_name, result_parameter_name) {
+        (Some(mutation_name), Some(result_parameter_name)) => {
+            (mutation_name.to_string(), result_parameter_name.to_string())
+        }
+        _ => {
+            if target.is_empty() {
+                results.error = Some(
+                    "Fixture must contain target when mutationName and resultParameterName are not provided"
+                        .to_string(),
+                );
+                return results;
+            }
+
+            match determine_mutation_from_target(target, schema) {
+                Ok(determined) => (
+                    mutation_name
+                        .map(str::to_string)
+                        .unwrap_or(determined.mutation_name),
+                    result_parameter_name
+                        .map(str::to_string)
+                        .unwrap_or(determined.result_parameter_name),
+                ),
+                Err(error) => {
+                    results.error = Some(error);
+                    return results;
+                }
+            }
+        }
+    };
+
+    results.mutation_name = Some(mutation_name.clone());
+    results.result_parameter_name = Some(result_parameter_name.clone());
+
+    // Step 4: Validate output fixture
+    let output_fixture_result = validate_fixture_output(
+        &fixture.expected_output,
+        schema,
+        &mutation_name,
+        &result_parameter_name,
+    );
+    results.output_fixture = OutputFixtureValidation {
+        errors: output_fixture_result.errors,
+        mutation_name: Some(output_fixture_result.mutation_name),
+        result_parameter_type: output_fixture_result.result_parameter_type,
+    };
+
+    results
+}
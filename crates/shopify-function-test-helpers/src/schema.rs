@@ -0,0 +1,286 @@
+//! An indexed view over a parsed GraphQL SDL document
+
+use std::collections::HashMap;
+
+use graphql_parser::query;
+use graphql_parser::schema::{
+    Definition, Document, Field, InputValue, ObjectType, ScalarType, Type, TypeDefinition,
+};
+use graphql_parser::Pos;
+
+/// A parsed GraphQL query document (as returned by `load_input_query`)
+pub type QueryDocument = query::Document<'static, String>;
+
+const BUILT_IN_SCALARS: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+/// A GraphQL schema built from SDL, with type lookups used by the validators
+#[derive(Debug, Clone)]
+pub struct Schema {
+    types: HashMap<String, TypeDefinition<'static, String>>,
+    query_type: String,
+    mutation_type: Option<String>,
+    possible_types: HashMap<String, Vec<String>>,
+    typename_field: Field<'static, String>,
+}
+
+impl Schema {
+    /// Build a schema from an SDL string
+    ///
+    /// Mirrors graphql-js `buildSchema`: built-in scalars are added when not
+    /// declared, and every referenced type must be defined.
+    pub fn parse(sdl: &str) -> Result<Self, String> {
+        let document: Document<'static, String> = graphql_parser::parse_schema::<String>(sdl)
+            .map_err(|error| error.to_string())?
+            .into_static();
+
+        let mut types = HashMap::new();
+        let mut type_names = vec![];
+        let mut query_type = None;
+        let mut mutation_type = None;
+
+        for definition in document.definitions {
+            match definition {
+                Definition::SchemaDefinition(schema_definition) => {
+                    query_type = schema_definition.query;
+                    mutation_type = schema_definition.mutation;
+                }
+                Definition::TypeDefinition(type_definition) => {
+                    let name = type_definition_name(&type_definition).to_string();
+                    if types.insert(name.clone(), type_definition).is_some() {
+                        return Err(format!("There can be only one type named \"{name}\"."));
+                    }
+                    type_names.push(name);
+                }
+                Definition::TypeExtension(_) | Definition::DirectiveDefinition(_) => {}
+            }
+        }
+
+        for scalar in BUILT_IN_SCALARS {
+            types
+                .entry(scalar.to_string())
+                .or_insert_with(|| TypeDefinition::Scalar(ScalarType::new(scalar.to_string())));
+        }
+
+        let query_type = query_type.unwrap_or_else(|| "Query".to_string());
+        let mutation_type =
+            mutation_type.or_else(|| types.contains_key("Mutation").then(|| "Mutation".into()));
+
+        let mut schema = Schema {
+            types,
+            query_type,
+            mutation_type,
+            possible_types: HashMap::new(),
+            typename_field: Field {
+                position: Pos::default(),
+                description: None,
+                name: "__typename".to_string(),
+                arguments: vec![],
+                field_type: Type::NonNullType(Box::new(Type::NamedType("String".to_string()))),
+                directives: vec![],
+            },
+        };
+        schema.check_type_references()?;
+        schema.possible_types = schema.collect_possible_types(&type_names);
+
+        Ok(schema)
+    }
+
+    /// Look up a named type definition
+    pub fn get_type(&self, name: &str) -> Option<&TypeDefinition<'static, String>> {
+        self.types.get(name)
+    }
+
+    /// The name of the query root type
+    pub fn query_type(&self) -> &str {
+        &self.query_type
+    }
+
+    /// The mutation root type, if the schema defines one
+    pub fn mutation_type(&self) -> Option<&ObjectType<'static, String>> {
+        match self.get_type(self.mutation_type.as_deref()?) {
+            Some(TypeDefinition::Object(object)) => Some(object),
+            _ => None,
+        }
+    }
+
+    /// Look up a field on an object or interface type, including `__typename`
+    /// on every composite type
+    pub fn get_field(&self, type_name: &str, field_name: &str) -> Option<&Field<'static, String>> {
+        if field_name == "__typename" && self.is_composite(type_name) {
+            return Some(&self.typename_field);
+        }
+
+        match self.get_type(type_name)? {
+            TypeDefinition::Object(object) => object.fields.iter().find(|f| f.name == field_name),
+            TypeDefinition::Interface(interface) => {
+                interface.fields.iter().find(|f| f.name == field_name)
+            }
+            _ => None,
+        }
+    }
+
+    /// The fields of an input object type
+    pub fn input_fields(&self, type_name: &str) -> Option<&[InputValue<'static, String>]> {
+        match self.get_type(type_name)? {
+            TypeDefinition::InputObject(input) => Some(&input.fields),
+            _ => None,
+        }
+    }
+
+    /// Concrete object types that may be returned for a named type
+    ///
+    /// Abstract types resolve to their members or implementers, object types
+    /// resolve to themselves and leaf types have no possible types.
+    pub fn possible_types(&self, type_name: &str) -> Vec<&str> {
+        match self.get_type(type_name) {
+            Some(TypeDefinition::Object(object)) => vec![object.name.as_str()],
+            Some(TypeDefinition::Union(_) | TypeDefinition::Interface(_)) => self
+                .possible_types
+                .get(type_name)
+                .map(|names| names.iter().map(String::as_str).collect())
+                .unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
+    pub fn is_abstract(&self, type_name: &str) -> bool {
+        matches!(
+            self.get_type(type_name),
+            Some(TypeDefinition::Union(_) | TypeDefinition::Interface(_))
+        )
+    }
+
+    pub fn is_composite(&self, type_name: &str) -> bool {
+        matches!(
+            self.get_type(type_name),
+            Some(
+                TypeDefinition::Object(_) | TypeDefinition::Union(_) | TypeDefinition::Interface(_)
+            )
+        )
+    }
+
+    pub fn is_leaf(&self, type_name: &str) -> bool {
+        matches!(
+            self.get_type(type_name),
+            Some(TypeDefinition::Scalar(_) | TypeDefinition::Enum(_))
+        )
+    }
+
+    fn collect_possible_types(&self, type_names: &[String]) -> HashMap<String, Vec<String>> {
+        let mut possible_types: HashMap<String, Vec<String>> = HashMap::new();
+
+        // Walk types in definition order so implementers are listed the way
+        // graphql-js lists them
+        for name in type_names {
+            match &self.types[name] {
+                TypeDefinition::Union(union) => {
+                    possible_types.insert(name.clone(), union.types.clone());
+                }
+                TypeDefinition::Object(object) => {
+                    for interface in &object.implements_interfaces {
+                        possible_types
+                            .entry(interface.clone())
+                            .or_default()
+                            .push(name.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        possible_types
+    }
+
+    fn check_type_references(&self) -> Result<(), String> {
+        let check = |name: &str| {
+            if self.types.contains_key(name) {
+                Ok(())
+            } else {
+                Err(format!("Unknown type: \"{name}\"."))
+            }
+        };
+
+        check(&self.query_type)?;
+        if let Some(mutation_type) = &self.mutation_type {
+            check(mutation_type)?;
+        }
+
+        for definition in self.types.values() {
+            match definition {
+                TypeDefinition::Object(object) => {
+                    object
+                        .implements_interfaces
+                        .iter()
+                        .try_for_each(|n| check(n))?;
+                    for field in &object.fields {
+                        check(named_type(&field.field_type))?;
+                        for argument in &field.arguments {
+                            check(named_type(&argument.value_type))?;
+                        }
+                    }
+                }
+                TypeDefinition::Interface(interface) => {
+                    interface
+                        .implements_interfaces
+                        .iter()
+                        .try_for_each(|n| check(n))?;
+                    for field in &interface.fields {
+                        check(named_type(&field.field_type))?;
+                    }
+                }
+                TypeDefinition::Union(union) => union.types.iter().try_for_each(|n| check(n))?,
+                TypeDefinition::InputObject(input) => {
+                    for field in &input.fields {
+                        check(named_type(&field.value_type))?;
+                    }
+                }
+                TypeDefinition::Scalar(_) | TypeDefinition::Enum(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The root selection set of an operation
+pub(crate) fn operation_selection_set<'a>(
+    operation: &'a query::OperationDefinition<'static, String>,
+) -> &'a query::SelectionSet<'static, String> {
+    match operation {
+        query::OperationDefinition::SelectionSet(selection_set) => selection_set,
+        query::OperationDefinition::Query(query) => &query.selection_set,
+        query::OperationDefinition::Mutation(mutation) => &mutation.selection_set,
+        query::OperationDefinition::Subscription(subscription) => &subscription.selection_set,
+    }
+}
+
+/// The innermost named type of a possibly wrapped type
+pub(crate) fn named_type<'a>(ty: &'a Type<'static, String>) -> &'a str {
+    match ty {
+        Type::NamedType(name) => name,
+        Type::ListType(inner) | Type::NonNullType(inner) => named_type(inner),
+    }
+}
+
+/// The type with its outermost non-null wrapper removed
+pub(crate) fn nullable_type<'a>(ty: &'a Type<'static, String>) -> &'a Type<'static, String> {
+    match ty {
+        Type::NonNullType(inner) => inner,
+        _ => ty,
+    }
+}
+
+pub(crate) fn is_non_null(ty: &Type<'static, String>) -> bool {
+    matches!(ty, Type::NonNullType(_))
+}
+
+pub(crate) fn type_definition_name<'a>(definition: &'a TypeDefinition<'static, String>) -> &'a str {
+    match definition {
+        TypeDefinition::Scalar(t) => &t.name,
+        TypeDefinition::Object(t) => &t.name,
+        TypeDefinition::Interface(t) => &t.name,
+        TypeDefinition::Union(t) => &t.name,
+        TypeDefinition::Enum(t) => &t.name,
+        TypeDefinition::InputObject(t) => &t.name,
+    }
+}
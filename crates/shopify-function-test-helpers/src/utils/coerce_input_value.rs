@@ -0,0 +1,237 @@
+//! A port of graphql-js `coerceInputValue` over JSON values
+//!
+//! Only the validation side is implemented: values are checked against the
+//! input type and each problem is reported through `on_error` with the path
+//! at which it was found. Messages match the ones produced by graphql-js so
+//! fixtures report the same errors from Rust and from TypeScript.
+
+use graphql_parser::schema::{Type, TypeDefinition};
+use serde_json::{Map, Value};
+
+use crate::methods::validate_fixture_input::PathSegment;
+use crate::schema::Schema;
+
+/// Validate `value` against the input type `ty`, reporting every error found
+pub fn coerce_input_value(
+    value: &Value,
+    ty: &Type<'static, String>,
+    schema: &Schema,
+    on_error: &mut dyn FnMut(&[PathSegment], String),
+) {
+    coerce(value, ty, schema, &mut vec![], on_error);
+}
+
+fn coerce(
+    value: &Value,
+    ty: &Type<'static, String>,
+    schema: &Schema,
+    path: &mut Vec<PathSegment>,
+    on_error: &mut dyn FnMut(&[PathSegment], String),
+) {
+    match ty {
+        Type::NonNullType(inner) => {
+            if value.is_null() {
+                on_error(
+                    path,
+                    format!("Expected non-nullable type \"{ty}\" not to be null."),
+                );
+                return;
+            }
+            coerce(value, inner, schema, path, on_error);
+        }
+        _ if value.is_null() => {}
+        Type::ListType(item_type) => match value {
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    path.push(PathSegment::Index(index));
+                    coerce(item, item_type, schema, path, on_error);
+                    path.pop();
+                }
+            }
+            // Lists accept "one or many" input values
+            _ => coerce(value, item_type, schema, path, on_error),
+        },
+        Type::NamedType(name) => match schema.get_type(name) {
+            Some(TypeDefinition::Scalar(_)) => {
+                if let Err(message) = parse_scalar_value(name, value) {
+                    on_error(path, message);
+                }
+            }
+            Some(TypeDefinition::Enum(enum_type)) => match value {
+                Value::String(enum_value) => {
+                    if !enum_type.values.iter().any(|v| &v.name == enum_value) {
+                        on_error(
+                            path,
+                            format!("Value \"{enum_value}\" does not exist in \"{name}\" enum."),
+                        );
+                    }
+                }
+                _ => on_error(
+                    path,
+                    format!(
+                        "Enum \"{name}\" cannot represent non-string value: {}.",
+                        inspect(value)
+                    ),
+                ),
+            },
+            Some(TypeDefinition::InputObject(input_type)) => {
+                let Value::Object(fields) = value else {
+                    on_error(path, format!("Expected type \"{name}\" to be an object."));
+                    return;
+                };
+
+                for field in &input_type.fields {
+                    match fields.get(&field.name) {
+                        None => {
+                            if field.default_value.is_none()
+                                && matches!(field.value_type, Type::NonNullType(_))
+                            {
+                                on_error(
+                                    path,
+                                    format!(
+                                        "Field \"{}\" of required type \"{}\" was not provided.",
+                                        field.name, field.value_type
+                                    ),
+                                );
+                            }
+                        }
+                        Some(field_value) => {
+                            path.push(PathSegment::Key(field.name.clone()));
+                            coerce(field_value, &field.value_type, schema, path, on_error);
+                            path.pop();
+                        }
+                    }
+                }
+
+                // Ensure every provided field is defined
+                for field_name in fields.keys() {
+                    if !input_type.fields.iter().any(|f| &f.name == field_name) {
+                        on_error(
+                            path,
+                            format!("Field \"{field_name}\" is not defined by type \"{name}\"."),
+                        );
+                    }
+                }
+
+                // @oneOf input objects must set exactly one field, to a non-null value
+                if input_type.directives.iter().any(|d| d.name == "oneOf") {
+                    let keys: Vec<&String> = fields
+                        .keys()
+                        .filter(|key| input_type.fields.iter().any(|f| &f.name == *key))
+                        .collect();
+                    match keys[..] {
+                        [key] => {
+                            if fields[key].is_null() {
+                                path.push(PathSegment::Key(key.clone()));
+                                on_error(path, format!("Field \"{key}\" must be non-null."));
+                                path.pop();
+                            }
+                        }
+                        _ => on_error(
+                            path,
+                            format!("Exactly one key must be specified for OneOf type \"{name}\"."),
+                        ),
+                    }
+                }
+            }
+            _ => on_error(path, format!("Expected \"{name}\" to be an input type.")),
+        },
+    }
+}
+
+/// Mirrors the `parseValue` implementations of the built-in scalars.
+/// Custom scalars accept any value.
+fn parse_scalar_value(name: &str, value: &Value) -> Result<(), String> {
+    match name {
+        "Int" => match value.as_f64() {
+            Some(number) if number.fract() == 0.0 => {
+                if number > i32::MAX as f64 || number < i32::MIN as f64 {
+                    Err(format!(
+                        "Int cannot represent non 32-bit signed integer value: {}",
+                        inspect(value)
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(format!(
+                "Int cannot represent non-integer value: {}",
+                inspect(value)
+            )),
+        },
+        "Float" if !value.is_number() => Err(format!(
+            "Float cannot represent non numeric value: {}",
+            inspect(value)
+        )),
+        "String" if !value.is_string() => Err(format!(
+            "String cannot represent a non string value: {}",
+            inspect(value)
+        )),
+        "Boolean" if !value.is_boolean() => Err(format!(
+            "Boolean cannot represent a non boolean value: {}",
+            inspect(value)
+        )),
+        "ID" if !value.is_string() && !value.as_f64().is_some_and(|n| n.fract() == 0.0) => {
+            Err(format!("ID cannot represent value: {}", inspect(value)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Format a value the way graphql-js `inspect` does in error messages
+pub fn inspect(value: &Value) -> String {
+    inspect_at_depth(value, 0)
+}
+
+const MAX_ARRAY_LENGTH: usize = 10;
+const MAX_RECURSIVE_DEPTH: usize = 2;
+
+fn inspect_at_depth(value: &Value, depth: usize) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => match n.as_f64() {
+            // JavaScript prints integral floats without a fractional part
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 1e21 => format!("{f:.0}"),
+            _ => n.to_string(),
+        },
+        Value::String(s) => Value::String(s.clone()).to_string(),
+        Value::Array(items) => {
+            if items.is_empty() {
+                return "[]".to_string();
+            }
+            if depth >= MAX_RECURSIVE_DEPTH {
+                return "[Array]".to_string();
+            }
+            let mut parts: Vec<String> = items
+                .iter()
+                .take(MAX_ARRAY_LENGTH)
+                .map(|item| inspect_at_depth(item, depth + 1))
+                .collect();
+            if items.len() > MAX_ARRAY_LENGTH {
+                let remaining = items.len() - MAX_ARRAY_LENGTH;
+                parts.push(if remaining == 1 {
+                    "... 1 more item".to_string()
+                } else {
+                    format!("... {remaining} more items")
+                });
+            }
+            format!("[{}]", parts.join(", "))
+        }
+        Value::Object(map) => inspect_object(map, depth),
+    }
+}
+
+fn inspect_object(map: &Map<String, Value>, depth: usize) -> String {
+    if map.is_empty() {
+        return "{}".to_string();
+    }
+    if depth >= MAX_RECURSIVE_DEPTH {
+        return "[Object]".to_string();
+    }
+    let properties: Vec<String> = map
+        .iter()
+        .map(|(key, value)| format!("{key}: {}", inspect_at_depth(value, depth + 1)))
+        .collect();
+    format!("{{ {} }}", properties.join(", "))
+}
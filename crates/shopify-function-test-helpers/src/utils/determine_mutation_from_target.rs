@@ -0,0 +1,54 @@
+use serde::Serialize;
+
+use crate::schema::Schema;
+
+/// Mutation determination result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MutationTarget {
+    pub mutation_name: String,
+    pub result_parameter_name: String,
+}
+
+/// Determines the mutation name and result parameter name from a target string and GraphQL schema
+/// by analyzing the schema's mutation descriptions and field definitions.
+///
+/// For example, `cart.validations.generate.run` matches the mutation whose description reads
+/// "Handles the Function result for the cart.validations.generate.run target.", and the mutation's
+/// first argument is used as the result parameter.
+pub fn determine_mutation_from_target(
+    target: &str,
+    schema: &Schema,
+) -> Result<MutationTarget, String> {
+    find_mutation(target, schema).map_err(|message| {
+        format!("Failed to determine mutation from target '{target}': {message}")
+    })
+}
+
+fn find_mutation(target: &str, schema: &Schema) -> Result<MutationTarget, String> {
+    let mutation_type = schema
+        .mutation_type()
+        .ok_or("Schema does not define a Mutation type")?;
+
+    for mutation_field in &mutation_type.fields {
+        let description = mutation_field.description.as_deref().unwrap_or_default();
+
+        if description.contains(target) {
+            // Use the first argument's name as the result parameter name
+            let Some(result_arg) = mutation_field.arguments.first() else {
+                return Err(format!(
+                    "Mutation '{}' has no arguments",
+                    mutation_field.name
+                ));
+            };
+
+            return Ok(MutationTarget {
+                mutation_name: mutation_field.name.clone(),
+                result_parameter_name: result_arg.name.clone(),
+            });
+        }
+    }
+
+    Err(format!(
+        "No mutation found for target '{target}'. Make sure the schema contains a mutation with a description that includes this target."
+    ))
+}
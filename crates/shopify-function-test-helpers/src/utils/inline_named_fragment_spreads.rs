@@ -0,0 +1,88 @@
+use graphql_parser::query::{
+    Definition, FragmentDefinition, InlineFragment, OperationDefinition, Selection, SelectionSet,
+};
+
+use crate::schema::QueryDocument;
+
+/// Transforms a GraphQL document by replacing all named fragment spreads with inline fragments.
+///
+/// Returns a new document with all fragment spreads inlined and fragment definitions removed,
+/// or an error if a fragment spread references a fragment definition that doesn't exist.
+pub fn inline_named_fragment_spreads(document: &QueryDocument) -> Result<QueryDocument, String> {
+    let fragments: Vec<&FragmentDefinition<'static, String>> = document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Fragment(fragment) => Some(fragment),
+            Definition::Operation(_) => None,
+        })
+        .collect();
+
+    let mut definitions = vec![];
+    for definition in &document.definitions {
+        // Remove fragment definitions since we have inlined them
+        if let Definition::Operation(operation) = definition {
+            let mut operation = operation.clone();
+            inline_selection_set(
+                operation_selection_set(&mut operation),
+                &fragments,
+                &mut vec![],
+            )?;
+            definitions.push(Definition::Operation(operation));
+        }
+    }
+
+    Ok(QueryDocument { definitions })
+}
+
+fn operation_selection_set<'a>(
+    operation: &'a mut OperationDefinition<'static, String>,
+) -> &'a mut SelectionSet<'static, String> {
+    match operation {
+        OperationDefinition::SelectionSet(selection_set) => selection_set,
+        OperationDefinition::Query(query) => &mut query.selection_set,
+        OperationDefinition::Mutation(mutation) => &mut mutation.selection_set,
+        OperationDefinition::Subscription(subscription) => &mut subscription.selection_set,
+    }
+}
+
+fn inline_selection_set(
+    selection_set: &mut SelectionSet<'static, String>,
+    fragments: &[&FragmentDefinition<'static, String>],
+    visiting: &mut Vec<String>,
+) -> Result<(), String> {
+    for selection in &mut selection_set.items {
+        match selection {
+            Selection::Field(field) => {
+                inline_selection_set(&mut field.selection_set, fragments, visiting)?;
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                inline_selection_set(&mut inline_fragment.selection_set, fragments, visiting)?;
+            }
+            Selection::FragmentSpread(spread) => {
+                let name = &spread.fragment_name;
+                let fragment_definition = fragments
+                    .iter()
+                    .find(|fragment| &fragment.name == name)
+                    .ok_or_else(|| format!("Fragment definition not found: {name}"))?;
+                if visiting.contains(name) {
+                    return Err(format!("Cannot spread fragment \"{name}\" within itself."));
+                }
+
+                visiting.push(name.clone());
+                let mut inline_fragment = InlineFragment {
+                    position: spread.position,
+                    type_condition: Some(fragment_definition.type_condition.clone()),
+                    directives: spread.directives.clone(),
+                    selection_set: fragment_definition.selection_set.clone(),
+                };
+                inline_selection_set(&mut inline_fragment.selection_set, fragments, visiting)?;
+                visiting.pop();
+
+                *selection = Selection::InlineFragment(inline_fragment);
+            }
+        }
+    }
+
+    Ok(())
+}
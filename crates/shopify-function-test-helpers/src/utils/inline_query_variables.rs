@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, HashMap};
+
+use graphql_parser::query::{
+    Definition, Directive, OperationDefinition, Selection, SelectionSet, Type, Value as QueryValue,
+};
+use graphql_parser::schema::TypeDefinition;
+use serde_json::{Map, Value};
+
+use crate::schema::{QueryDocument, Schema};
+
+/// Replaces the variables of a query with their values, and removes their definitions.
+///
+/// function-runner reads the input query from a file and has no way to pass variables,
+/// so the query it gets is self-contained, e.g. `hasAnyTag(tags: $tags)` with
+/// `tags: ["VIP"]` becomes `hasAnyTag(tags: ["VIP"])`.
+///
+/// Returns an error if a variable has no value of its type.
+pub fn inline_query_variables(
+    document: &QueryDocument,
+    schema: &Schema,
+    variables: &Map<String, Value>,
+) -> Result<QueryDocument, String> {
+    let mut document = document.clone();
+
+    let mut values = HashMap::new();
+    for definition in &mut document.definitions {
+        if let Definition::Operation(OperationDefinition::Query(query)) = definition {
+            for variable in query.variable_definitions.drain(..) {
+                let value = variables.get(&variable.name).unwrap_or(&Value::Null);
+                let value = query_value(value, &variable.var_type, schema).ok_or_else(|| {
+                    format!("Variable \"${}\" has no value to inline", variable.name)
+                })?;
+                values.insert(variable.name, value);
+            }
+        }
+    }
+
+    for definition in &mut document.definitions {
+        let selection_set = match definition {
+            Definition::Operation(OperationDefinition::SelectionSet(selection_set)) => {
+                selection_set
+            }
+            Definition::Operation(OperationDefinition::Query(query)) => {
+                inline_directives(&mut query.directives, &values)?;
+                &mut query.selection_set
+            }
+            Definition::Operation(OperationDefinition::Mutation(mutation)) => {
+                &mut mutation.selection_set
+            }
+            Definition::Operation(OperationDefinition::Subscription(subscription)) => {
+                &mut subscription.selection_set
+            }
+            Definition::Fragment(fragment) => &mut fragment.selection_set,
+        };
+        inline_selection_set(selection_set, &values)?;
+    }
+
+    Ok(document)
+}
+
+fn inline_selection_set(
+    selection_set: &mut SelectionSet<'static, String>,
+    values: &HashMap<String, QueryValue<'static, String>>,
+) -> Result<(), String> {
+    for selection in &mut selection_set.items {
+        match selection {
+            Selection::Field(field) => {
+                for (_, argument) in &mut field.arguments {
+                    inline_value(argument, values)?;
+                }
+                inline_directives(&mut field.directives, values)?;
+                inline_selection_set(&mut field.selection_set, values)?;
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                inline_directives(&mut inline_fragment.directives, values)?;
+                inline_selection_set(&mut inline_fragment.selection_set, values)?;
+            }
+            Selection::FragmentSpread(fragment_spread) => {
+                inline_directives(&mut fragment_spread.directives, values)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn inline_directives(
+    directives: &mut [Directive<'static, String>],
+    values: &HashMap<String, QueryValue<'static, String>>,
+) -> Result<(), String> {
+    for directive in directives {
+        for (_, argument) in &mut directive.arguments {
+            inline_value(argument, values)?;
+        }
+    }
+    Ok(())
+}
+
+fn inline_value(
+    value: &mut QueryValue<'static, String>,
+    values: &HashMap<String, QueryValue<'static, String>>,
+) -> Result<(), String> {
+    match value {
+        QueryValue::Variable(name) => {
+            *value = values
+                .get(name.as_str())
+                .cloned()
+                .ok_or_else(|| format!("Variable \"${name}\" is not defined"))?;
+        }
+        QueryValue::List(items) => {
+            for item in items {
+                inline_value(item, values)?;
+            }
+        }
+        QueryValue::Object(fields) => {
+            for field in fields.values_mut() {
+                inline_value(field, values)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The literal for a JSON value of an input type, like graphql-js `astFromValue`, or
+/// `None` if a non-null value is null
+fn query_value(
+    value: &Value,
+    ty: &Type<'static, String>,
+    schema: &Schema,
+) -> Option<QueryValue<'static, String>> {
+    match ty {
+        Type::NonNullType(inner) => {
+            if value.is_null() {
+                return None;
+            }
+            query_value(value, inner, schema)
+        }
+        _ if value.is_null() => Some(QueryValue::Null),
+        Type::ListType(item_type) => match value {
+            Value::Array(items) => items
+                .iter()
+                .map(|item| query_value(item, item_type, schema))
+                .collect::<Option<_>>()
+                .map(QueryValue::List),
+            _ => query_value(value, item_type, schema),
+        },
+        Type::NamedType(name) => {
+            if let (Some(TypeDefinition::Enum(_)), Value::String(value)) =
+                (schema.get_type(name), value)
+            {
+                return Some(QueryValue::Enum(value.clone()));
+            }
+            if let (Some(input_fields), Value::Object(object)) = (schema.input_fields(name), value)
+            {
+                let mut fields = BTreeMap::new();
+                for input_field in input_fields {
+                    if let Some(field_value) = object.get(&input_field.name) {
+                        fields.insert(
+                            input_field.name.clone(),
+                            query_value(field_value, &input_field.value_type, schema)?,
+                        );
+                    }
+                }
+                return Some(QueryValue::Object(fields));
+            }
+            Some(json_query_value(value))
+        }
+    }
+}
+
+/// The literal for a JSON value of a scalar type
+fn json_query_value(value: &Value) -> QueryValue<'static, String> {
+    match value {
+        Value::Null => QueryValue::Null,
+        Value::Bool(value) => QueryValue::Boolean(*value),
+        Value::Number(number) => match number.as_i64().and_then(|n| i32::try_from(n).ok()) {
+            Some(n) => QueryValue::Int(n.into()),
+            None => QueryValue::Float(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) => QueryValue::String(value.clone()),
+        Value::Array(items) => QueryValue::List(items.iter().map(json_query_value).collect()),
+        Value::Object(object) => QueryValue::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), json_query_value(value)))
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,9 @@
+pub mod coerce_input_value;
+pub mod determine_mutation_from_target;
+pub mod inline_named_fragment_spreads;
+pub mod inline_query_variables;
+pub mod msgpack;
+pub mod replace_json_value;
+pub mod validate_argument_responses;
+pub mod validate_json_schema;
+pub mod validate_metafield;
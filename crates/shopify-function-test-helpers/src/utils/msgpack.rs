@@ -0,0 +1,246 @@
+//! Encode and decode JSON values as MessagePack, the format functions built on the
+//! Shopify Functions WASM API read their input and write their output in
+
+use serde_json::{Map, Number, Value};
+
+/// Encodes a JSON value as MessagePack, with each integer, string, array and map in its
+/// smallest encoding and other numbers as 64-bit floats, as function-runner does
+pub fn encode(value: &Value) -> Vec<u8> {
+    let mut bytes = vec![];
+    encode_value(value, &mut bytes);
+    bytes
+}
+
+fn encode_value(value: &Value, bytes: &mut Vec<u8>) {
+    match value {
+        Value::Null => bytes.push(0xc0),
+        Value::Bool(false) => bytes.push(0xc2),
+        Value::Bool(true) => bytes.push(0xc3),
+        Value::Number(number) => {
+            if let Some(number) = number.as_u64() {
+                encode_uint(number, bytes);
+            } else if let Some(number) = number.as_i64() {
+                encode_negative_int(number, bytes);
+            } else {
+                bytes.push(0xcb);
+                bytes.extend(number.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        }
+        Value::String(string) => {
+            let len = string.len();
+            match len {
+                0..=31 => bytes.push(0xa0 | len as u8),
+                32..=0xff => bytes.extend([0xd9, len as u8]),
+                0x100..=0xffff => {
+                    bytes.push(0xda);
+                    bytes.extend((len as u16).to_be_bytes());
+                }
+                _ => {
+                    bytes.push(0xdb);
+                    bytes.extend((len as u32).to_be_bytes());
+                }
+            }
+            bytes.extend(string.as_bytes());
+        }
+        Value::Array(items) => {
+            encode_len(items.len(), [0x90, 0xdc, 0xdd], bytes);
+            for item in items {
+                encode_value(item, bytes);
+            }
+        }
+        Value::Object(fields) => {
+            encode_len(fields.len(), [0x80, 0xde, 0xdf], bytes);
+            for (key, value) in fields {
+                encode_value(&Value::String(key.clone()), bytes);
+                encode_value(value, bytes);
+            }
+        }
+    }
+}
+
+fn encode_uint(number: u64, bytes: &mut Vec<u8>) {
+    match number {
+        0..=0x7f => bytes.push(number as u8),
+        0x80..=0xff => bytes.extend([0xcc, number as u8]),
+        0x100..=0xffff => {
+            bytes.push(0xcd);
+            bytes.extend((number as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            bytes.push(0xce);
+            bytes.extend((number as u32).to_be_bytes());
+        }
+        _ => {
+            bytes.push(0xcf);
+            bytes.extend(number.to_be_bytes());
+        }
+    }
+}
+
+fn encode_negative_int(number: i64, bytes: &mut Vec<u8>) {
+    if number >= -32 {
+        bytes.push(number as i8 as u8);
+    } else if number >= i64::from(i8::MIN) {
+        bytes.extend([0xd0, number as i8 as u8]);
+    } else if number >= i64::from(i16::MIN) {
+        bytes.push(0xd1);
+        bytes.extend((number as i16).to_be_bytes());
+    } else if number >= i64::from(i32::MIN) {
+        bytes.push(0xd2);
+        bytes.extend((number as i32).to_be_bytes());
+    } else {
+        bytes.push(0xd3);
+        bytes.extend(number.to_be_bytes());
+    }
+}
+
+/// Writes the length of an array or map, given its fix, 16-bit and 32-bit markers
+fn encode_len(len: usize, [fix, len16, len32]: [u8; 3], bytes: &mut Vec<u8>) {
+    match len {
+        0..=15 => bytes.push(fix | len as u8),
+        16..=0xffff => {
+            bytes.push(len16);
+            bytes.extend((len as u16).to_be_bytes());
+        }
+        _ => {
+            bytes.push(len32);
+            bytes.extend((len as u32).to_be_bytes());
+        }
+    }
+}
+
+/// Decodes a MessagePack value as JSON
+///
+/// Returns an error if the bytes are not a single MessagePack value, or the value has no
+/// JSON equivalent: binary data, an extension type, a map key that is not a string or a
+/// float that is not finite.
+pub fn decode(bytes: &[u8]) -> Result<Value, String> {
+    let mut decoder = Decoder { bytes, offset: 0 };
+    let value = decoder.value()?;
+    if decoder.offset != bytes.len() {
+        return Err(format!(
+            "unexpected data after the value at byte {}",
+            decoder.offset
+        ));
+    }
+    Ok(value)
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Decoder<'_> {
+    fn value(&mut self) -> Result<Value, String> {
+        let offset = self.offset;
+        let marker = self.take::<1>()?[0];
+        match marker {
+            0x00..=0x7f => Ok(Value::from(marker)),
+            0x80..=0x8f => self.map(usize::from(marker & 0x0f)),
+            0x90..=0x9f => self.array(usize::from(marker & 0x0f)),
+            0xa0..=0xbf => self.string(usize::from(marker & 0x1f)),
+            0xc0 => Ok(Value::Null),
+            0xc2 => Ok(Value::Bool(false)),
+            0xc3 => Ok(Value::Bool(true)),
+            0xca => float(f64::from(f32::from_be_bytes(self.take()?)), offset),
+            0xcb => float(f64::from_be_bytes(self.take()?), offset),
+            0xcc => Ok(Value::from(u8::from_be_bytes(self.take()?))),
+            0xcd => Ok(Value::from(u16::from_be_bytes(self.take()?))),
+            0xce => Ok(Value::from(u32::from_be_bytes(self.take()?))),
+            0xcf => Ok(Value::from(u64::from_be_bytes(self.take()?))),
+            0xd0 => Ok(Value::from(i8::from_be_bytes(self.take()?))),
+            0xd1 => Ok(Value::from(i16::from_be_bytes(self.take()?))),
+            0xd2 => Ok(Value::from(i32::from_be_bytes(self.take()?))),
+            0xd3 => Ok(Value::from(i64::from_be_bytes(self.take()?))),
+            0xd9 => {
+                let len = u8::from_be_bytes(self.take()?);
+                self.string(usize::from(len))
+            }
+            0xda => {
+                let len = u16::from_be_bytes(self.take()?);
+                self.string(usize::from(len))
+            }
+            0xdb => {
+                let len = u32::from_be_bytes(self.take()?);
+                self.string(len as usize)
+            }
+            0xdc => {
+                let len = u16::from_be_bytes(self.take()?);
+                self.array(usize::from(len))
+            }
+            0xdd => {
+                let len = u32::from_be_bytes(self.take()?);
+                self.array(len as usize)
+            }
+            0xde => {
+                let len = u16::from_be_bytes(self.take()?);
+                self.map(usize::from(len))
+            }
+            0xdf => {
+                let len = u32::from_be_bytes(self.take()?);
+                self.map(len as usize)
+            }
+            0xe0..=0xff => Ok(Value::from(marker as i8)),
+            0xc4..=0xc6 => Err(format!("unsupported binary data at byte {offset}")),
+            0xc7..=0xc9 | 0xd4..=0xd8 => {
+                Err(format!("unsupported extension type at byte {offset}"))
+            }
+            0xc1 => Err(format!("invalid marker 0xc1 at byte {offset}")),
+        }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.take_slice(N)?);
+        Ok(bytes)
+    }
+
+    fn take_slice(&mut self, len: usize) -> Result<&[u8], String> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("unexpected end of data at byte {}", self.bytes.len()))?;
+        let bytes = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn string(&mut self, len: usize) -> Result<Value, String> {
+        let offset = self.offset;
+        let bytes = self.take_slice(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map(Value::String)
+            .map_err(|_| format!("invalid UTF-8 in the string at byte {offset}"))
+    }
+
+    fn array(&mut self, len: usize) -> Result<Value, String> {
+        // Each item takes at least a byte, so a length past the end is not preallocated
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.offset));
+        for _ in 0..len {
+            items.push(self.value()?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn map(&mut self, len: usize) -> Result<Value, String> {
+        let mut fields = Map::new();
+        for _ in 0..len {
+            let offset = self.offset;
+            let Value::String(key) = self.value()? else {
+                return Err(format!("map key at byte {offset} is not a string"));
+            };
+            let value = self.value()?;
+            fields.insert(key, value);
+        }
+        Ok(Value::Object(fields))
+    }
+}
+
+/// A float as a JSON number, which cannot be NaN or infinite
+fn float(number: f64, offset: usize) -> Result<Value, String> {
+    Number::from_f64(number)
+        .map(Value::Number)
+        .ok_or_else(|| format!("unsupported float {number} at byte {offset}"))
+}
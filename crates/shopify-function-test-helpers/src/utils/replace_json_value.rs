@@ -0,0 +1,142 @@
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Serializer, Value};
+
+/// Replaces the value at an object key path in JSON text
+///
+/// Only the text of the value is rewritten, so the rest of the document keeps its key
+/// order, whitespace and line endings. The new value is serialized with the document's
+/// indentation unit and aligned with the line holding its key. Returns `None` if the
+/// path does not exist or the text ends inside a string. The text must be valid JSON.
+pub fn replace_json_value(text: &str, path: &[&str], value: &Value) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut position = skip_whitespace(bytes, 0);
+
+    for key in path {
+        position = find_object_value(bytes, position, key)?;
+    }
+
+    let start = position;
+    let end = skip_value(bytes, start)?;
+
+    let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_indent: String = text[line_start..start]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+    let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+
+    let serialized = serialize(value, &indent_unit(text))
+        .split('\n')
+        .collect::<Vec<_>>()
+        .join(&format!("{newline}{line_indent}"));
+
+    Some(format!("{}{serialized}{}", &text[..start], &text[end..]))
+}
+
+/// The indentation of the first indented line. Documents without indented lines are
+/// minified, so the value is too.
+fn indent_unit(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let indent: String = line
+                .chars()
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .collect();
+            (indent, line.trim().is_empty())
+        })
+        .find(|(indent, blank)| !indent.is_empty() && !blank)
+        .map(|(indent, _)| indent)
+        .unwrap_or_default()
+}
+
+fn serialize(value: &Value, indent: &str) -> String {
+    if indent.is_empty() {
+        return value.to_string();
+    }
+
+    let mut buffer = vec![];
+    let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = Serializer::with_formatter(&mut buffer, formatter);
+    value
+        .serialize(&mut serializer)
+        .expect("serializing a JSON value cannot fail");
+    String::from_utf8(buffer).expect("serde_json writes UTF-8")
+}
+
+/// The start of the value for `key` in the object starting at `position`, or `None`
+/// if the value there is not an object or has no such key
+fn find_object_value(bytes: &[u8], position: usize, key: &str) -> Option<usize> {
+    if bytes.get(position) != Some(&b'{') {
+        return None;
+    }
+
+    let mut position = skip_whitespace(bytes, position + 1);
+    while bytes.get(position) == Some(&b'"') {
+        let key_end = skip_value(bytes, position)?;
+        let current_key: String = serde_json::from_slice(&bytes[position..key_end]).ok()?;
+
+        // Skip the colon separating the key from its value
+        let value_start = skip_whitespace(bytes, skip_whitespace(bytes, key_end) + 1);
+        if current_key == key {
+            return Some(value_start);
+        }
+
+        position = skip_whitespace(bytes, skip_value(bytes, value_start)?);
+        if bytes.get(position) == Some(&b',') {
+            position = skip_whitespace(bytes, position + 1);
+        }
+    }
+    None
+}
+
+/// The end of the JSON value starting at `position`, or `None` if a string in it is
+/// unterminated
+fn skip_value(bytes: &[u8], mut position: usize) -> Option<usize> {
+    let mut depth = 0usize;
+
+    loop {
+        match bytes.get(position) {
+            None => return Some(position),
+            Some(b'"') => {
+                position += 1;
+                while position < bytes.len() && bytes[position] != b'"' {
+                    position += if bytes[position] == b'\\' { 2 } else { 1 };
+                }
+                if position >= bytes.len() {
+                    return None;
+                }
+                position += 1;
+            }
+            Some(b'{' | b'[') => {
+                depth += 1;
+                position += 1;
+            }
+            Some(b'}' | b']') => {
+                depth = depth.saturating_sub(1);
+                position += 1;
+            }
+            Some(_) if depth > 0 => position += 1,
+            Some(_) => {
+                // Numbers, booleans and null end at the next delimiter
+                while position < bytes.len()
+                    && !matches!(bytes[position], b',' | b'}' | b']')
+                    && !bytes[position].is_ascii_whitespace()
+                {
+                    position += 1;
+                }
+            }
+        }
+
+        if depth == 0 {
+            return Some(position);
+        }
+    }
+}
+
+fn skip_whitespace(bytes: &[u8], mut position: usize) -> usize {
+    while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+        position += 1;
+    }
+    position
+}
@@ -0,0 +1,121 @@
+use graphql_parser::query::{Field, Selection, Value as QueryValue};
+use serde_json::{Map, Number, Value};
+
+use crate::methods::validate_fixture_input::{FixtureInputValidationError, PathSegment};
+
+/// Types that answer a list argument with one object per requested value, e.g.
+/// `hasTags(tags: ["VIP"])` returns `[{ "tag": "VIP", "hasTag": true }]`.
+/// Each entry is the type, the argument and the field that echoes each value.
+const ARGUMENT_RESPONSES: [(&str, &str, &str); 2] = [
+    ("CollectionMembership", "ids", "collectionId"),
+    ("HasTagResponse", "tags", "tag"),
+];
+
+/// Checks that a list of argument responses answers each requested value, in order
+///
+/// For example, the `hasTags(tags: $tags)` responses must have one `HasTagResponse` per
+/// tag in `$tags`, and each selected `tag` must be the tag it answers. Nothing is checked
+/// when the argument is omitted or refers to a variable without a value.
+pub fn validate_argument_responses(
+    type_name: &str,
+    field: &Field<'static, String>,
+    responses: &[Value],
+    path: &[PathSegment],
+    variables: Option<&Map<String, Value>>,
+) -> Vec<FixtureInputValidationError> {
+    let Some(&(_, argument, echo_field)) = ARGUMENT_RESPONSES
+        .iter()
+        .find(|(argument_type, _, _)| *argument_type == type_name)
+    else {
+        return vec![];
+    };
+    let Some(values) = field
+        .arguments
+        .iter()
+        .find(|(name, _)| name == argument)
+        .and_then(|(_, value)| argument_value(value, variables))
+    else {
+        return vec![];
+    };
+    // A single value stands for a list of one, as in GraphQL input coercion
+    let values = match values {
+        Value::Null => return vec![],
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    if responses.len() != values.len() {
+        return vec![FixtureInputValidationError {
+            message: format!(
+                "Expected a `{type_name}` for each value of the `{argument}` argument ({}), but got {}",
+                values.len(),
+                responses.len()
+            ),
+            path: path.to_vec(),
+        }];
+    }
+
+    let Some(echo_response_key) =
+        field
+            .selection_set
+            .items
+            .iter()
+            .find_map(|selection| match selection {
+                Selection::Field(selection) if selection.name == echo_field => {
+                    Some(selection.alias.as_ref().unwrap_or(&selection.name))
+                }
+                _ => None,
+            })
+    else {
+        return vec![];
+    };
+
+    let mut errors = vec![];
+    for (index, (response, value)) in responses.iter().zip(&values).enumerate() {
+        // Missing and mistyped fields are reported by validate_fixture_input
+        let Some(echoed) = response
+            .get(echo_response_key)
+            .filter(|echoed| echoed.is_string())
+        else {
+            continue;
+        };
+        if echoed == value {
+            continue;
+        }
+        let mut echoed_path = path.to_vec();
+        echoed_path.push(index.into());
+        echoed_path.push(echo_response_key.as_str().into());
+        errors.push(FixtureInputValidationError {
+            message: format!("Expected {value} from the `{argument}` argument, but got {echoed}"),
+            path: echoed_path,
+        });
+    }
+    errors
+}
+
+/// The JSON value of an argument, or `None` if it refers to a variable without a value
+fn argument_value(
+    value: &QueryValue<'static, String>,
+    variables: Option<&Map<String, Value>>,
+) -> Option<Value> {
+    Some(match value {
+        QueryValue::Variable(name) => variables?.get(name)?.clone(),
+        QueryValue::Int(number) => Value::from(number.as_i64()?),
+        QueryValue::Float(number) => Value::Number(Number::from_f64(*number)?),
+        QueryValue::String(string) | QueryValue::Enum(string) => Value::from(string.as_str()),
+        QueryValue::Boolean(boolean) => Value::Bool(*boolean),
+        QueryValue::Null => Value::Null,
+        QueryValue::List(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| argument_value(item, variables))
+                .collect::<Option<_>>()?,
+        ),
+        QueryValue::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, field)| Some((name.clone(), argument_value(field, variables)?)))
+                .collect::<Option<_>>()?,
+        ),
+    })
+}
@@ -0,0 +1,187 @@
+use jsonschema::error::{TypeKind, ValidationErrorKind};
+use jsonschema::{JsonType, ValidationError};
+use serde_json::Value;
+
+use crate::methods::validate_fixture_input::PathSegment;
+
+/// The keywords of JSON Schema 2019-09 and 2020-12 that draft-07 would silently ignore
+const LATER_DRAFT_KEYWORDS: [&str; 12] = [
+    "$dynamicAnchor",
+    "$dynamicRef",
+    "$recursiveAnchor",
+    "$recursiveRef",
+    "$vocabulary",
+    "dependentRequired",
+    "dependentSchemas",
+    "maxContains",
+    "minContains",
+    "prefixItems",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+];
+
+/// A value that does not match a JSON Schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonSchemaError {
+    pub message: String,
+    /// The path to the value within the validated JSON
+    pub path: Vec<PathSegment>,
+}
+
+/// Validates a JSON value against a JSON Schema with the `jsonschema` crate, as draft-07
+///
+/// Mismatched types, enums and constants, missing required properties and unexpected
+/// additional properties are described the same way as the TypeScript
+/// `validateJsonSchema`, e.g. ``expected a number, but got "15"`` or
+/// ``missing required property `code` ``. Other keywords keep the validator's message.
+/// A schema that cannot be compiled, e.g. because of an unresolvable `$ref`, is reported
+/// as a single error.
+pub fn validate_json_schema(schema: &Value, value: &Value) -> Vec<JsonSchemaError> {
+    // ajv 6, which the TypeScript `validateJsonSchema` uses, only supports draft-07
+    let validator = match jsonschema::draft7::new(schema) {
+        Ok(validator) => validator,
+        Err(error) => {
+            return vec![JsonSchemaError {
+                message: format!("invalid JSON Schema: {error}"),
+                path: vec![],
+            }]
+        }
+    };
+
+    validator
+        .iter_errors(value)
+        .flat_map(|error| {
+            let path = instance_path(value, error.instance_path.as_str());
+            messages(&error)
+                .into_iter()
+                .map(move |message| JsonSchemaError {
+                    message,
+                    path: path.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Describes why a schema cannot be validated as draft-07, the draft both the Rust and
+/// TypeScript validators use: it declares another `$schema`, or uses a keyword of a later
+/// draft that draft-07 would ignore. `$defs` is allowed, as a `$ref` to it is a JSON
+/// Pointer like any other.
+pub fn draft_07_problem(schema: &Value) -> Option<String> {
+    if let Some(declared) = schema.get("$schema") {
+        let declared_draft_07 = declared.as_str().is_some_and(|uri| {
+            uri.trim_end_matches('#') == "http://json-schema.org/draft-07/schema"
+        });
+        if !declared_draft_07 {
+            return Some(format!(
+                "declares `$schema` {declared}, but only JSON Schema draft-07 is supported"
+            ));
+        }
+    }
+    find_later_draft_keyword(schema)
+        .map(|keyword| format!("uses `{keyword}`, which JSON Schema draft-07 does not support"))
+}
+
+/// The first keyword of a later draft in a schema and its subschemas
+fn find_later_draft_keyword(schema: &Value) -> Option<&'static str> {
+    let keywords = match schema {
+        Value::Object(keywords) => keywords,
+        Value::Array(schemas) => return schemas.iter().find_map(find_later_draft_keyword),
+        _ => return None,
+    };
+    keywords.iter().find_map(|(keyword, value)| {
+        if let Some(later_draft_keyword) = LATER_DRAFT_KEYWORDS
+            .iter()
+            .find(|later_draft_keyword| **later_draft_keyword == keyword)
+        {
+            return Some(*later_draft_keyword);
+        }
+        match keyword.as_str() {
+            // Values, not schemas
+            "const" | "default" | "enum" | "examples" => None,
+            // Schemas by property or definition name, whose names are not keywords
+            "$defs" | "definitions" | "dependencies" | "patternProperties" | "properties" => value
+                .as_object()
+                .and_then(|schemas| schemas.values().find_map(find_later_draft_keyword)),
+            _ => find_later_draft_keyword(value),
+        }
+    })
+}
+
+/// Describes an error, once per unexpected property
+fn messages(error: &ValidationError) -> Vec<String> {
+    let instance = error.instance.as_ref();
+    match &error.kind {
+        ValidationErrorKind::Type { kind } => {
+            let types: Vec<&str> = match kind {
+                TypeKind::Single(json_type) => vec![describe_type(*json_type)],
+                TypeKind::Multiple(json_types) => json_types.iter().map(describe_type).collect(),
+            };
+            vec![mismatch(&types.join(" or "), instance)]
+        }
+        ValidationErrorKind::Enum { options } => {
+            let options: Vec<String> = options
+                .as_array()
+                .map(|options| options.iter().map(ToString::to_string).collect())
+                .unwrap_or_default();
+            vec![mismatch(
+                &format!("one of {}", options.join(", ")),
+                instance,
+            )]
+        }
+        ValidationErrorKind::Constant { expected_value } => {
+            vec![mismatch(&expected_value.to_string(), instance)]
+        }
+        ValidationErrorKind::Required { property } => {
+            let property = property
+                .as_str()
+                .map_or(property.to_string(), str::to_string);
+            vec![format!("missing required property `{property}`")]
+        }
+        ValidationErrorKind::AdditionalProperties { unexpected } => unexpected
+            .iter()
+            .map(|property| format!("unexpected property `{property}`"))
+            .collect(),
+        _ => vec![error.to_string()],
+    }
+}
+
+/// The path segments of a JSON Pointer into `value`, with array indices as numbers
+fn instance_path(value: &Value, pointer: &str) -> Vec<PathSegment> {
+    let mut path = vec![];
+    let mut current = Some(value);
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        match (current, segment.parse::<usize>()) {
+            (Some(Value::Array(items)), Ok(index)) => {
+                current = items.get(index);
+                path.push(PathSegment::from(index));
+            }
+            _ => {
+                current = current.and_then(|current| current.get(&segment));
+                path.push(PathSegment::from(segment));
+            }
+        }
+    }
+    path
+}
+
+fn describe_type(json_type: JsonType) -> &'static str {
+    match json_type {
+        JsonType::Null => "null",
+        JsonType::Boolean => "a boolean",
+        JsonType::Object => "an object",
+        JsonType::Array => "an array",
+        JsonType::Number => "a number",
+        JsonType::Integer => "an integer",
+        JsonType::String => "a string",
+    }
+}
+
+/// Whether a value is an integer, including numbers such as `10.0` like JavaScript
+pub fn is_integer(value: &Value) -> bool {
+    value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+}
+
+fn mismatch(expected: &str, value: &Value) -> String {
+    format!("expected {expected}, but got {value}")
+}
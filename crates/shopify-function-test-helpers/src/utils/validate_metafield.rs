@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+
+use graphql_parser::query::{Field, Selection};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::methods::load_metafield_schemas::MetafieldSchema;
+use crate::methods::validate_fixture_input::PathSegment;
+use crate::utils::validate_json_schema::{is_integer, validate_json_schema, JsonSchemaError};
+
+/// A problem with a metafield's value
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetafieldError {
+    pub message: String,
+    /// The response key of the metafield field that holds the value, e.g. `jsonValue`
+    pub response_key: String,
+}
+
+/// Types whose `value` is the string itself rather than serialized JSON
+const STRING_VALUE_TYPES: [&str; 6] = [
+    "color",
+    "date",
+    "date_time",
+    "multi_line_text_field",
+    "single_line_text_field",
+    "url",
+];
+
+/// Finds the schema registered for a `metafield` field of the input query
+///
+/// `owner_types` are the possible types of the object the field is selected on, and
+/// `variables` the input query's variable values for arguments such as `key: $key`.
+/// Returns `None` if no schema matches or the `namespace` and `key` arguments are not
+/// strings.
+pub fn find_metafield_schema<'a>(
+    registry: &'a [MetafieldSchema],
+    field: &Field<'static, String>,
+    owner_types: &HashSet<String>,
+    variables: Option<&Map<String, Value>>,
+) -> Option<&'a MetafieldSchema> {
+    let namespace = match string_argument(field, "namespace", variables) {
+        None => None,
+        Some(Some(namespace)) => Some(namespace),
+        Some(None) => return None,
+    };
+    let Some(Some(key)) = string_argument(field, "key", variables) else {
+        return None;
+    };
+
+    registry.iter().find(|metafield_schema| {
+        metafield_schema.key == key
+            && metafield_schema.namespace.as_deref() == namespace
+            && metafield_schema
+                .owner_type
+                .as_ref()
+                .is_none_or(|owner_type| owner_types.contains(owner_type))
+    })
+}
+
+/// Checks the `type`, `value` and `jsonValue` of a fixture metafield against its schema
+///
+/// `value` is parsed the way Shopify serializes it for the metafield type: text, date,
+/// color and URL types are stored as is, and other types as JSON. The parsed value and
+/// `jsonValue` must match the metafield type and, if given, the JSON Schema.
+pub fn validate_metafield(
+    metafield_schema: &MetafieldSchema,
+    field: &Field<'static, String>,
+    metafield: &Value,
+) -> Vec<MetafieldError> {
+    let name = match &metafield_schema.namespace {
+        Some(namespace) => format!("{namespace}.{}", metafield_schema.key),
+        None => metafield_schema.key.clone(),
+    };
+    let metafield_type = metafield_schema.metafield_type.as_deref().unwrap_or("json");
+    let mut errors = vec![];
+
+    for selection in &field.selection_set.items {
+        let Selection::Field(selection) = selection else {
+            continue;
+        };
+        let response_key = selection.alias.as_ref().unwrap_or(&selection.name);
+        // Missing and mistyped fields are reported by validate_fixture_input
+        let Some(field_value) = metafield.get(response_key).filter(|value| !value.is_null()) else {
+            continue;
+        };
+
+        let problems = match selection.name.as_str() {
+            "type" => match &metafield_schema.metafield_type {
+                Some(expected) if field_value.as_str() != Some(expected) => {
+                    vec![JsonSchemaError {
+                        message: format!("expected type `{expected}`, but got {field_value}"),
+                        path: vec![],
+                    }]
+                }
+                _ => vec![],
+            },
+            "value" => match field_value {
+                Value::String(value) => validate_value(
+                    metafield_type,
+                    metafield_schema.schema.as_ref(),
+                    &parse_value(metafield_type, value),
+                ),
+                _ => vec![],
+            },
+            "jsonValue" => validate_value(
+                metafield_type,
+                metafield_schema.schema.as_ref(),
+                field_value,
+            ),
+            _ => vec![],
+        };
+
+        for JsonSchemaError { message, path } in problems {
+            let location = if path.is_empty() {
+                String::new()
+            } else {
+                let path: Vec<String> = path.iter().map(PathSegment::to_string).collect();
+                format!(" at `{}`", path.join("."))
+            };
+            errors.push(MetafieldError {
+                message: format!("Invalid `{name}` metafield{location}: {message}"),
+                response_key: response_key.clone(),
+            });
+        }
+    }
+
+    errors
+}
+
+fn validate_value(
+    metafield_type: &str,
+    schema: Option<&Value>,
+    value: &Value,
+) -> Vec<JsonSchemaError> {
+    let type_errors = validate_metafield_type(metafield_type, value, &[]);
+    match schema {
+        Some(schema) if type_errors.is_empty() => validate_json_schema(schema, value),
+        _ => type_errors,
+    }
+}
+
+fn validate_metafield_type(
+    metafield_type: &str,
+    value: &Value,
+    path: &[PathSegment],
+) -> Vec<JsonSchemaError> {
+    let error = |message: String| JsonSchemaError {
+        message,
+        path: path.to_vec(),
+    };
+
+    if let Some(element_type) = metafield_type.strip_prefix("list.") {
+        let Value::Array(elements) = value else {
+            return vec![error(mismatch("an array", value))];
+        };
+        return elements
+            .iter()
+            .enumerate()
+            .flat_map(|(index, element)| {
+                let mut element_path = path.to_vec();
+                element_path.push(index.into());
+                validate_metafield_type(element_type, element, &element_path)
+            })
+            .collect();
+    }
+
+    let Some((expected, matches)) = metafield_type_check(metafield_type, value) else {
+        return vec![error(format!(
+            "unsupported metafield type `{metafield_type}`"
+        ))];
+    };
+    if matches {
+        vec![]
+    } else {
+        vec![error(mismatch(expected, value))]
+    }
+}
+
+/// What a metafield type expects and whether the value matches, or `None` for an
+/// unsupported type
+fn metafield_type_check(metafield_type: &str, value: &Value) -> Option<(&'static str, bool)> {
+    let string_matches = |pattern: &str| {
+        value
+            .as_str()
+            .is_some_and(|string| matches(pattern, string))
+    };
+    let check = match metafield_type {
+        "boolean" => ("a boolean", value.is_boolean()),
+        "color" => (
+            "a hex color such as \"#FF0000\"",
+            string_matches("^#[0-9A-Fa-f]{6}$"),
+        ),
+        "date" => (
+            "a date such as \"2025-01-31\"",
+            string_matches("^[0-9]{4}-[0-9]{2}-[0-9]{2}$"),
+        ),
+        "date_time" => (
+            "a date and time such as \"2025-01-31T12:00:00Z\"",
+            string_matches(
+                "^[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}(\\.[0-9]+)?(Z|[+-][0-9]{2}:[0-9]{2})?$",
+            ),
+        ),
+        "dimension" | "volume" | "weight" => (
+            "an object with a numeric `value` and a `unit`",
+            value.get("value").is_some_and(Value::is_number)
+                && value.get("unit").is_some_and(Value::is_string),
+        ),
+        "json" => ("any JSON value", true),
+        "money" => (
+            "an object with a decimal `amount` and a `currency_code`",
+            value.get("amount").is_some_and(is_decimal_string)
+                && value.get("currency_code").is_some_and(Value::is_string),
+        ),
+        "multi_line_text_field" => ("a string", value.is_string()),
+        "number_decimal" => (
+            "a decimal number",
+            value.is_number() || is_decimal_string(value),
+        ),
+        "number_integer" => ("an integer", is_integer(value)),
+        "rating" => (
+            "an object with a decimal `value`, `scale_min` and `scale_max`",
+            value.get("value").is_some_and(is_decimal_string)
+                && value.get("scale_min").is_some_and(is_decimal_string)
+                && value.get("scale_max").is_some_and(is_decimal_string),
+        ),
+        "rich_text_field" => ("an object", value.is_object()),
+        "single_line_text_field" => (
+            "a single line of text",
+            value.as_str().is_some_and(|string| !string.contains('\n')),
+        ),
+        "url" => (
+            "a URL such as \"https://example.com\"",
+            string_matches("^(https?://|mailto:|sms:|tel:)\\S+$"),
+        ),
+        _ if metafield_type.ends_with("_reference") => (
+            "a GID such as \"gid://shopify/Product/1\"",
+            string_matches("^gid://shopify/[A-Za-z0-9_]+/\\S+$"),
+        ),
+        _ => return None,
+    };
+    Some(check)
+}
+
+fn parse_value(metafield_type: &str, value: &str) -> Value {
+    if STRING_VALUE_TYPES.contains(&metafield_type) || metafield_type.ends_with("_reference") {
+        return Value::from(value);
+    }
+    // Invalid JSON is reported as a mismatch with the metafield type
+    serde_json::from_str(value).unwrap_or_else(|_| Value::from(value))
+}
+
+/// The value of a string argument, given as a literal or a variable: `None` if it is
+/// omitted, `Some(None)` if it is not a string
+fn string_argument<'a>(
+    field: &'a Field<'static, String>,
+    name: &str,
+    variables: Option<&'a Map<String, Value>>,
+) -> Option<Option<&'a str>> {
+    field
+        .arguments
+        .iter()
+        .find(|(argument, _)| argument == name)
+        .map(|(_, value)| match value {
+            graphql_parser::query::Value::String(value) => Some(value.as_str()),
+            graphql_parser::query::Value::Variable(variable) => variables?.get(variable)?.as_str(),
+            _ => None,
+        })
+}
+
+fn is_decimal_string(value: &Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|string| matches("^-?[0-9]+(\\.[0-9]+)?$", string))
+}
+
+fn matches(pattern: &str, string: &str) -> bool {
+    Regex::new(pattern).is_ok_and(|regex| regex.is_match(string))
+}
+
+fn mismatch(expected: &str, value: &Value) -> String {
+    format!("expected {expected}, but got {value}")
+}
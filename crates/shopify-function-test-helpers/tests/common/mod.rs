@@ -0,0 +1,53 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use shopify_function_test_helpers::{load_schema, QueryDocument, Schema};
+
+/// Path to a file in the repository's shared `test/fixtures` directory
+pub fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../test/fixtures")
+        .join(name)
+}
+
+/// Path to a file in one of the `test-app` example extensions
+pub fn test_app_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../test-app/extensions")
+        .join(path)
+}
+
+/// Write an executable script to `dir` that behaves like function-runner:
+/// it consumes the input from stdin, then runs `script`
+#[cfg(unix)]
+pub fn fake_runner(dir: &Path, script: &str) -> PathBuf {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join("function-runner");
+    fs::write(&path, format!("#!/bin/sh\ncat > /dev/null\n{script}\n")).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+pub fn test_schema() -> Schema {
+    load_schema(fixture_path("test-schema.graphql")).unwrap()
+}
+
+pub fn parse(query: &str) -> QueryDocument {
+    graphql_parser::parse_query::<String>(query)
+        .unwrap()
+        .into_static()
+}
+
+/// Build a fixture validation error from a message and path segments
+#[macro_export]
+macro_rules! fixture_error {
+    ($message:expr, [$($segment:expr),* $(,)?]) => {
+        shopify_function_test_helpers::FixtureInputValidationError {
+            message: $message.to_string(),
+            path: vec![$(shopify_function_test_helpers::PathSegment::from($segment)),*],
+        }
+    };
+}
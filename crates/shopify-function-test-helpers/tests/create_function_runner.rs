@@ -0,0 +1,221 @@
+mod common;
+
+use common::fixture_path;
+use serde_json::json;
+use shopify_function_test_helpers::{
+    create_function_runner, CreateFunctionRunnerOptions, FixtureData, FunctionRunner,
+    RunFunctionOutput,
+};
+
+// See echo-function.wat, provider-function.wat, echo-provider-function.wat and the
+// providers directory for the module sources
+fn echo_runner() -> FunctionRunner {
+    create_function_runner(
+        fixture_path("echo-function.wasm"),
+        CreateFunctionRunnerOptions::default(),
+    )
+    .unwrap()
+}
+
+fn fixture_for(export: &str) -> FixtureData {
+    FixtureData {
+        export: export.to_string(),
+        input: json!({ "cart": { "lines": [{ "quantity": 1 }] } }),
+        expected_output: json!({}),
+        target: "cart.validations.generate.run".to_string(),
+        variables: None,
+        fetch: None,
+    }
+}
+
+#[test]
+fn runs_an_export_with_the_fixture_input_on_stdin() {
+    let fixture = fixture_for("echo");
+
+    let result = echo_runner().run(&fixture);
+
+    assert_eq!(result.error, None);
+    let input_size = fixture.input.to_string().len();
+    assert_eq!(
+        result.result,
+        Some(RunFunctionOutput {
+            output: fixture.input,
+            // One for the call, five per WASI call (its `drop` is free) and four to copy
+            // the length
+            instructions: Some(15),
+            // The echo module declares two 64 KiB pages
+            memory_usage: Some(128),
+            input_size,
+            output_size: input_size,
+            logs: String::new(),
+        })
+    );
+}
+
+#[test]
+fn reports_the_memory_the_export_grows() {
+    let result = echo_runner().run(&fixture_for("grow"));
+
+    let output = result.result.unwrap();
+    assert_eq!(output.instructions, Some(19));
+    assert_eq!(output.memory_usage, Some(192));
+}
+
+#[test]
+fn reuses_the_compiled_module_across_runs() {
+    let runner = echo_runner();
+
+    let outputs: Vec<_> = (1..=3)
+        .map(|quantity| {
+            let fixture = FixtureData {
+                input: json!({ "cart": { "lines": [{ "quantity": quantity }] } }),
+                ..fixture_for("echo")
+            };
+            runner.run(&fixture).result.unwrap().output
+        })
+        .collect();
+
+    assert_eq!(
+        outputs,
+        vec![
+            json!({ "cart": { "lines": [{ "quantity": 1 }] } }),
+            json!({ "cart": { "lines": [{ "quantity": 2 }] } }),
+            json!({ "cart": { "lines": [{ "quantity": 3 }] } }),
+        ]
+    );
+}
+
+#[test]
+fn returns_an_error_when_the_export_traps() {
+    let result = echo_runner().run(&fixture_for("fail"));
+
+    assert_eq!(result.result, None);
+    assert_eq!(
+        result.error.as_deref(),
+        Some("Function export 'fail' failed: wasm trap: wasm `unreachable` instruction executed")
+    );
+}
+
+#[test]
+fn returns_an_error_when_the_export_does_not_exist() {
+    let result = echo_runner().run(&fixture_for("missing-export"));
+
+    assert_eq!(result.result, None);
+    assert_eq!(
+        result.error,
+        Some(format!(
+            "Export 'missing-export' not found in {}",
+            fixture_path("echo-function.wasm").display()
+        ))
+    );
+}
+
+#[test]
+fn links_imports_to_provider_modules() {
+    let runner = create_function_runner(
+        fixture_path("echo-provider-function.wasm"),
+        CreateFunctionRunnerOptions {
+            providers_dir: Some(fixture_path("providers")),
+        },
+    )
+    .unwrap();
+    let fixture = fixture_for("run");
+
+    let output = runner.run(&fixture).result.unwrap();
+
+    assert_eq!(output.output, fixture.input);
+    // The function's two, then the provider's echo, but not its initialization
+    assert_eq!(output.instructions, Some(17));
+    // Only the provider has a memory, of one page
+    assert_eq!(output.memory_usage, Some(64));
+}
+
+// The provider traps unless its input is exactly the MessagePack encoding of the fixture
+// input, and writes a MessagePack output with one value of each kind
+#[test]
+fn runs_shopify_function_wasm_api_modules_with_messagepack() {
+    let runner = create_function_runner(
+        fixture_path("provider-function.wasm"),
+        CreateFunctionRunnerOptions {
+            providers_dir: Some(fixture_path("providers")),
+        },
+    )
+    .unwrap();
+    let fixture = fixture_for("run");
+
+    let result = runner.run(&fixture);
+
+    assert_eq!(result.error, None);
+    let output = result.result.unwrap();
+    assert_eq!(
+        output.output,
+        json!({
+            "operations": [{
+                "validationAdd": {
+                    "errors": [{
+                        "message": "Each line can have at most 300 items",
+                        "target": "$.cart",
+                    }],
+                },
+            }],
+            "stats": [-3, 1.5, null, true, false],
+        })
+    );
+    assert_eq!(output.input_size, fixture.input.to_string().len());
+    assert_eq!(output.output_size, output.output.to_string().len());
+}
+
+#[test]
+fn fails_when_the_provider_rejects_the_messagepack_input() {
+    let runner = create_function_runner(
+        fixture_path("provider-function.wasm"),
+        CreateFunctionRunnerOptions {
+            providers_dir: Some(fixture_path("providers")),
+        },
+    )
+    .unwrap();
+    // Any other input makes the provider trap, so the test above proves the encoding
+    let fixture = FixtureData {
+        input: json!({ "cart": { "lines": [{ "quantity": 2 }] } }),
+        ..fixture_for("run")
+    };
+
+    let result = runner.run(&fixture);
+
+    assert_eq!(result.result, None);
+    assert_eq!(
+        result.error.as_deref(),
+        Some("Function export 'run' failed: wasm trap: wasm `unreachable` instruction executed")
+    );
+}
+
+#[test]
+fn rejects_modules_whose_imports_have_no_provider() {
+    let path = fixture_path("provider-function.wasm");
+
+    let error = create_function_runner(&path, CreateFunctionRunnerOptions::default())
+        .err()
+        .unwrap();
+
+    assert!(
+        error.starts_with(&format!(
+            "WASM module {} imports from shopify_function_v1, which needs a provider module.",
+            path.display()
+        )),
+        "{error}"
+    );
+}
+
+#[test]
+fn rejects_files_that_are_not_wasm_modules() {
+    let path = fixture_path("valid-fixture.json");
+
+    let error = create_function_runner(&path, CreateFunctionRunnerOptions::default())
+        .err()
+        .unwrap();
+
+    assert!(
+        error.starts_with(&format!("Failed to compile WASM module {}", path.display())),
+        "{error}"
+    );
+}
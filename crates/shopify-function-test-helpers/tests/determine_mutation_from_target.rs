@@ -0,0 +1,37 @@
+mod common;
+
+use common::test_schema;
+use shopify_function_test_helpers::{determine_mutation_from_target, MutationTarget};
+
+#[test]
+fn finds_mutation_by_target_in_description() {
+    let result = determine_mutation_from_target("data.processing.generate.run", &test_schema());
+
+    assert_eq!(
+        result,
+        Ok(MutationTarget {
+            mutation_name: "processData".to_string(),
+            result_parameter_name: "result".to_string(),
+        })
+    );
+}
+
+#[test]
+fn finds_mutation_and_returns_actual_parameter_name_from_schema() {
+    let result = determine_mutation_from_target("data.fetching.generate.run", &test_schema());
+
+    assert_eq!(
+        result,
+        Ok(MutationTarget {
+            mutation_name: "fetchData".to_string(),
+            result_parameter_name: "input".to_string(),
+        })
+    );
+}
+
+#[test]
+fn returns_an_error_when_target_not_found() {
+    let error = determine_mutation_from_target("nonexistent.target", &test_schema()).unwrap_err();
+
+    assert!(error.contains("No mutation found for target 'nonexistent.target'"));
+}
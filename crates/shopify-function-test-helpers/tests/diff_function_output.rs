@@ -0,0 +1,334 @@
+mod common;
+
+use common::test_app_path;
+use serde_json::{json, Value};
+use shopify_function_test_helpers::{
+    diff_function_output, load_fixture, load_schema, DiffFunctionOutputOptions, OutputDifference,
+    Schema,
+};
+
+const TARGET: &str = "cart.lines.discounts.generate.run";
+
+fn discount_schema() -> Schema {
+    load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap()
+}
+
+fn expected_output() -> Value {
+    load_fixture(test_app_path(
+        "discount-function-rs/tests/fixtures/cart-lines-valid-fixture.json",
+    ))
+    .unwrap()
+    .expected_output
+}
+
+fn diff(actual: &Value) -> Vec<OutputDifference> {
+    diff_function_output(DiffFunctionOutputOptions {
+        schema: &discount_schema(),
+        actual,
+        expected: &expected_output(),
+        target: Some(TARGET),
+        mutation_name: None,
+        result_parameter_name: None,
+    })
+    .unwrap()
+    .differences
+}
+
+#[test]
+fn reports_no_differences_for_equal_outputs() {
+    assert_eq!(diff(&expected_output()), vec![]);
+}
+
+#[test]
+fn ignores_object_key_order() {
+    let mut actual = expected_output();
+    let candidate = &mut actual["operations"][1]["productDiscountsAdd"]["candidates"][0];
+    let message = candidate.as_object_mut().unwrap().shift_remove("message");
+    candidate["message"] = message.unwrap();
+
+    assert_eq!(diff(&actual), vec![]);
+}
+
+#[test]
+fn reports_changed_values_by_graphql_path() {
+    let mut actual = expected_output();
+    actual["operations"][1]["productDiscountsAdd"]["candidates"][0]["value"]["percentage"]
+        ["value"] = json!("15.0");
+
+    assert_eq!(
+        diff(&actual),
+        vec![OutputDifference {
+            path: "operations[1].productDiscountsAdd.candidates[0].value.percentage.value"
+                .to_string(),
+            message: "Expected \"20.0\", received \"15.0\"".to_string(),
+            expected: Some(json!("20.0")),
+            actual: Some(json!("15.0")),
+        }]
+    );
+}
+
+#[test]
+fn reports_missing_and_unexpected_fields() {
+    let mut actual = expected_output();
+    let order_discounts_add = &mut actual["operations"][0]["orderDiscountsAdd"];
+    order_discounts_add
+        .as_object_mut()
+        .unwrap()
+        .shift_remove("selectionStrategy");
+    order_discounts_add["candidates"][0]["targets"][0]["orderSubtotal"]["extra"] = json!(1);
+
+    let paths_and_messages: Vec<(String, String)> = diff(&actual)
+        .into_iter()
+        .map(|difference| (difference.path, difference.message))
+        .collect();
+    assert_eq!(
+        paths_and_messages,
+        vec![
+            (
+                "operations[0].orderDiscountsAdd.candidates[0].targets[0].orderSubtotal.extra"
+                    .to_string(),
+                "Unexpected field with value 1".to_string()
+            ),
+            (
+                "operations[0].orderDiscountsAdd.selectionStrategy".to_string(),
+                "Missing field, expected \"FIRST\"".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn reports_missing_and_unexpected_list_items() {
+    let expected = expected_output();
+    let mut missing = expected.clone();
+    missing["operations"].as_array_mut().unwrap().pop();
+    let mut unexpected = expected.clone();
+    unexpected["operations"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({ "enteredDiscountCodesAccept": { "codes": [] } }));
+
+    assert_eq!(
+        diff(&missing),
+        vec![OutputDifference {
+            path: "operations[1]".to_string(),
+            message: format!("Missing item, expected {}", expected["operations"][1]),
+            expected: Some(expected["operations"][1].clone()),
+            actual: None,
+        }]
+    );
+    assert_eq!(
+        diff(&unexpected),
+        vec![OutputDifference {
+            path: "operations[2]".to_string(),
+            message: r#"Unexpected item {"enteredDiscountCodesAccept":{"codes":[]}}"#.to_string(),
+            expected: None,
+            actual: Some(json!({ "enteredDiscountCodesAccept": { "codes": [] } })),
+        }]
+    );
+}
+
+#[test]
+fn reports_a_different_one_of_field_once_for_the_object() {
+    let mut actual = expected_output();
+    actual["operations"].as_array_mut().unwrap().reverse();
+
+    let messages: Vec<(String, String)> = diff(&actual)
+        .into_iter()
+        .map(|difference| (difference.path, difference.message))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (
+                "operations[0]".to_string(),
+                "Expected CartOperation \"orderDiscountsAdd\", received \"productDiscountsAdd\""
+                    .to_string()
+            ),
+            (
+                "operations[1]".to_string(),
+                "Expected CartOperation \"productDiscountsAdd\", received \"orderDiscountsAdd\""
+                    .to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn reports_a_different_nested_one_of_field() {
+    let mut actual = expected_output();
+    actual["operations"][0]["orderDiscountsAdd"]["candidates"][0]["value"] =
+        json!({ "fixedAmount": { "amount": "10.0" } });
+
+    assert_eq!(
+        diff(&actual),
+        vec![OutputDifference {
+            path: "operations[0].orderDiscountsAdd.candidates[0].value".to_string(),
+            message:
+                "Expected OrderDiscountCandidateValue \"percentage\", received \"fixedAmount\""
+                    .to_string(),
+            expected: Some(json!({ "percentage": { "value": "10.0" } })),
+            actual: Some(json!({ "fixedAmount": { "amount": "10.0" } })),
+        }]
+    );
+}
+
+#[test]
+fn accepts_an_explicit_mutation_instead_of_a_target() {
+    let mut actual = expected_output();
+    actual["operations"].as_array_mut().unwrap().reverse();
+
+    let result = diff_function_output(DiffFunctionOutputOptions {
+        schema: &discount_schema(),
+        actual: &actual,
+        expected: &expected_output(),
+        target: None,
+        mutation_name: Some("cartLinesDiscountsGenerateRun"),
+        result_parameter_name: Some("result"),
+    })
+    .unwrap();
+
+    let paths: Vec<&str> = result
+        .differences
+        .iter()
+        .map(|difference| difference.path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["operations[0]", "operations[1]"]);
+}
+
+#[test]
+fn requires_a_target_or_mutation() {
+    let expected = expected_output();
+
+    let result = diff_function_output(DiffFunctionOutputOptions {
+        schema: &discount_schema(),
+        actual: &expected,
+        expected: &expected,
+        target: None,
+        mutation_name: None,
+        result_parameter_name: None,
+    });
+
+    assert_eq!(
+        result,
+        Err(
+            "target is required when mutation_name and result_parameter_name are not provided"
+                .to_string()
+        )
+    );
+}
+
+fn diff_result(actual: Value, expected: Value) -> Vec<OutputDifference> {
+    let schema = Schema::parse(
+        r#"
+        scalar Decimal
+
+        type Query {
+          version: String
+        }
+
+        type Mutation {
+          run(result: Result!): Boolean!
+        }
+
+        input Result {
+          amount: Decimal
+          ratio: Float
+          id: ID
+          name: String
+        }
+        "#,
+    )
+    .unwrap();
+
+    diff_function_output(DiffFunctionOutputOptions {
+        schema: &schema,
+        actual: &actual,
+        expected: &expected,
+        target: None,
+        mutation_name: Some("run"),
+        result_parameter_name: Some("result"),
+    })
+    .unwrap()
+    .differences
+}
+
+#[test]
+fn compares_decimal_values_by_value() {
+    let mut actual = expected_output();
+    actual["operations"][1]["productDiscountsAdd"]["candidates"][0]["value"]["percentage"]
+        ["value"] = json!("20");
+    actual["operations"][0]["orderDiscountsAdd"]["candidates"][0]["value"]["percentage"]["value"] =
+        json!(10.0);
+
+    assert_eq!(diff(&actual), vec![]);
+    assert_eq!(
+        diff_result(json!({ "amount": "0.50" }), json!({ "amount": ".5" })),
+        vec![]
+    );
+    assert_eq!(
+        diff_result(json!({ "amount": "1e2" }), json!({ "amount": "100.00" })),
+        vec![]
+    );
+}
+
+#[test]
+fn reports_decimal_values_that_differ() {
+    assert_eq!(
+        diff_result(json!({ "amount": "10.5" }), json!({ "amount": "10.0" })),
+        vec![OutputDifference {
+            path: "amount".to_string(),
+            message: "Expected \"10.0\", received \"10.5\"".to_string(),
+            expected: Some(json!("10.0")),
+            actual: Some(json!("10.5")),
+        }]
+    );
+    assert_eq!(
+        diff_result(json!({ "amount": "abc" }), json!({ "amount": "abc" })),
+        vec![]
+    );
+    assert_eq!(
+        diff_result(json!({ "amount": null }), json!({ "amount": "0" })).len(),
+        1
+    );
+}
+
+#[test]
+fn compares_float_values_by_value() {
+    assert_eq!(
+        diff_result(json!({ "ratio": 1.0 }), json!({ "ratio": 1 })),
+        vec![]
+    );
+    assert_eq!(
+        diff_result(json!({ "ratio": "1" }), json!({ "ratio": 1 })).len(),
+        1
+    );
+}
+
+#[test]
+fn compares_id_values_by_value() {
+    assert_eq!(
+        diff_result(json!({ "id": 12345 }), json!({ "id": "12345" })),
+        vec![]
+    );
+    assert_eq!(
+        diff_result(
+            json!({ "id": 1 }),
+            json!({ "id": "gid://shopify/CartLine/1" })
+        ),
+        vec![OutputDifference {
+            path: "id".to_string(),
+            message: "Expected \"gid://shopify/CartLine/1\", received 1".to_string(),
+            expected: Some(json!("gid://shopify/CartLine/1")),
+            actual: Some(json!(1)),
+        }]
+    );
+}
+
+#[test]
+fn compares_other_scalars_by_their_json_representation() {
+    assert_eq!(
+        diff_result(json!({ "name": "10" }), json!({ "name": "10.0" })).len(),
+        1
+    );
+}
@@ -0,0 +1,264 @@
+//! These tests stand in for function-runner with a shell script that
+//! echoes the expected output of the shared test fixture
+#![cfg(unix)]
+
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use common::{fake_runner, fixture_path, test_app_path};
+use shopify_function_test_helpers::{
+    assert_fixture, assert_fixture_with_metafield_schemas, fixture_tests, load_fixture,
+    load_metafield_schemas, FunctionInfo, InputVariables, TargetingInfo,
+};
+use tempfile::TempDir;
+
+fn function_info_with_runner(dir: &Path, script: &str) -> FunctionInfo {
+    FunctionInfo {
+        schema_path: fixture_path("test-schema.graphql"),
+        function_runner_path: fake_runner(dir, script),
+        wasm_path: dir.join("function.wasm"),
+        targeting: HashMap::from([(
+            "data.processing.generate.run".to_string(),
+            TargetingInfo {
+                input_query_path: fixture_path("valid-query.graphql"),
+                export: Some("test-data-processing".to_string()),
+            },
+        )]),
+        input_variables: None,
+    }
+}
+
+fn echo_output(output: &serde_json::Value) -> String {
+    format!("echo '{}'", serde_json::json!({ "output": output }))
+}
+
+// Generates `shared_fixtures::valid_fixture`. The function info outlives the
+// tests, so its runner goes in Cargo's scratch directory rather than a TempDir.
+#[fixture_tests("../../test/fixtures")]
+fn shared_fixtures() -> FunctionInfo {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("shared_fixtures");
+    fs::create_dir_all(&dir).unwrap();
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+    function_info_with_runner(&dir, &echo_output(&fixture.expected_output))
+}
+
+#[fixture_tests(
+    "../../test/fixtures",
+    metafield_schemas = "../../test/fixtures/metafields/metafield-schemas.json"
+)]
+fn shared_fixtures_with_metafield_schemas() -> FunctionInfo {
+    shared_fixtures()
+}
+
+#[test]
+#[should_panic(expected = "Function output does not match the expected output")]
+fn fails_when_output_differs() {
+    let dir = TempDir::new().unwrap();
+    let function_info = function_info_with_runner(dir.path(), &echo_output(&serde_json::json!({})));
+
+    assert_fixture(fixture_path("valid-fixture.json"), &function_info);
+}
+
+#[test]
+#[should_panic(expected = "function-runner failed with exit code 1: Error: Export not found")]
+fn fails_when_function_errors() {
+    let dir = TempDir::new().unwrap();
+    let function_info =
+        function_info_with_runner(dir.path(), "echo 'Error: Export not found' >&2\nexit 1");
+
+    assert_fixture(fixture_path("valid-fixture.json"), &function_info);
+}
+
+#[test]
+#[should_panic(
+    expected = "targets 'data.processing.generate.run', which is not a target of this function"
+)]
+fn fails_for_unknown_targets() {
+    let dir = TempDir::new().unwrap();
+    let mut function_info = function_info_with_runner(dir.path(), "exit 1");
+    function_info.targeting.clear();
+
+    assert_fixture(fixture_path("valid-fixture.json"), &function_info);
+}
+
+/// A discount function with a fetch target, whose runner echoes `request` for the fetch
+/// export and `output` for the run export, checking that the run input has a fetchResult
+fn fetch_function_info(
+    dir: &Path,
+    request: &serde_json::Value,
+    output: &serde_json::Value,
+) -> FunctionInfo {
+    fs::write(
+        dir.join("fetch.graphql"),
+        "query Input { enteredDiscountCodes }",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("run.graphql"),
+        "query Input { cart { lines { id } } fetchResult { status jsonBody } }",
+    )
+    .unwrap();
+
+    let script = format!(
+        "case \"$*\" in\n*generate_fetch*) echo '{}' ;;\n*) echo '{}' ;;\nesac",
+        serde_json::json!({ "output": request }),
+        serde_json::json!({ "output": output }),
+    );
+    FunctionInfo {
+        schema_path: test_app_path("discount-function-rs/schema.graphql"),
+        function_runner_path: fake_runner(dir, &script),
+        wasm_path: dir.join("function.wasm"),
+        targeting: HashMap::from([
+            (
+                "cart.lines.discounts.generate.fetch".to_string(),
+                TargetingInfo {
+                    input_query_path: dir.join("fetch.graphql"),
+                    export: Some("cart_lines_discounts_generate_fetch".to_string()),
+                },
+            ),
+            (
+                "cart.lines.discounts.generate.run".to_string(),
+                TargetingInfo {
+                    input_query_path: dir.join("run.graphql"),
+                    export: Some("cart_lines_discounts_generate_run".to_string()),
+                },
+            ),
+        ]),
+        input_variables: None,
+    }
+}
+
+#[test]
+fn runs_both_stages_of_a_fetch_fixture() {
+    let path = fixture_path("fetch/cart-lines-fetch-fixture.json");
+    let fixture = load_fixture(&path).unwrap();
+    let dir = TempDir::new().unwrap();
+    let function_info = fetch_function_info(
+        dir.path(),
+        &fixture.fetch.as_ref().unwrap().expected_output,
+        &fixture.expected_output,
+    );
+
+    assert_fixture(&path, &function_info);
+}
+
+#[test]
+#[should_panic(expected = "Fetch request does not match the expected output")]
+fn fails_when_the_fetch_request_differs() {
+    let path = fixture_path("fetch/cart-lines-fetch-fixture.json");
+    let fixture = load_fixture(&path).unwrap();
+    let mut request = fixture.fetch.unwrap().expected_output;
+    request["request"]["url"] = serde_json::json!("https://other.example.com");
+    let dir = TempDir::new().unwrap();
+    let function_info = fetch_function_info(dir.path(), &request, &fixture.expected_output);
+
+    assert_fixture(&path, &function_info);
+}
+
+/// The discount function's lines target, whose runner echoes `output`
+fn discount_function_info(dir: &Path, output: &serde_json::Value) -> FunctionInfo {
+    FunctionInfo {
+        schema_path: test_app_path("discount-function-rs/schema.graphql"),
+        function_runner_path: fake_runner(dir, &echo_output(output)),
+        wasm_path: dir.join("function.wasm"),
+        targeting: HashMap::from([(
+            "cart.lines.discounts.generate.run".to_string(),
+            TargetingInfo {
+                input_query_path: test_app_path(
+                    "discount-function-rs/src/cart_lines_discounts_generate_run.graphql",
+                ),
+                export: Some("cart_lines_discounts_generate_run".to_string()),
+            },
+        )]),
+        input_variables: None,
+    }
+}
+
+fn configured_fixture() -> serde_json::Value {
+    let path =
+        test_app_path("discount-function-rs/tests/fixtures/cart-lines-configured-fixture.json");
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn compares_output_values_by_their_type_for_the_fixture_target() {
+    let dir = TempDir::new().unwrap();
+    let fixture = configured_fixture();
+    // "15" and "15.0" are the same Decimal percentage
+    let mut output = fixture["payload"]["output"].clone();
+    output["operations"][0]["orderDiscountsAdd"]["candidates"][0]["value"]["percentage"]["value"] =
+        serde_json::json!("15");
+    let function_info = discount_function_info(dir.path(), &output);
+
+    assert_fixture(
+        test_app_path("discount-function-rs/tests/fixtures/cart-lines-configured-fixture.json"),
+        &function_info,
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "Invalid `function-configuration` metafield at `order.percentage`: expected a number, but got \"15\""
+)]
+fn fails_when_a_metafield_does_not_match_its_schema() {
+    let dir = TempDir::new().unwrap();
+    let mut fixture = configured_fixture();
+    fixture["payload"]["input"]["discount"]["metafield"]["jsonValue"]["order"]["percentage"] =
+        serde_json::json!("15");
+    let fixture_path = dir.path().join("fixture.json");
+    fs::write(&fixture_path, fixture.to_string()).unwrap();
+    let function_info = discount_function_info(dir.path(), &fixture["payload"]["output"]);
+    let metafield_schemas = load_metafield_schemas(test_app_path(
+        "discount-function-rs/tests/metafield-schemas.json",
+    ))
+    .unwrap();
+
+    assert_fixture_with_metafield_schemas(&fixture_path, &function_info, &metafield_schemas);
+}
+
+/// The tagged discount function, whose runner echoes the expected output only when its
+/// input query has the tags from the `[extensions.input.variables]` metafield inlined
+fn tagged_function_info(dir: &Path) -> FunctionInfo {
+    let script = format!(
+        "query_path=$(echo \"$*\" | sed 's/.*--query-path \\([^ ]*\\).*/\\1/')\n\
+         grep -q 'hasTags(tags: \\[\"VIP\", \"Wholesale\"\\])' \"$query_path\" || exit 1\n{}",
+        echo_output(&serde_json::json!({ "operations": [] }))
+    );
+    FunctionInfo {
+        schema_path: test_app_path("discount-function-rs/schema.graphql"),
+        function_runner_path: fake_runner(dir, &script),
+        wasm_path: dir.join("function.wasm"),
+        targeting: HashMap::from([(
+            "cart.lines.discounts.generate.run".to_string(),
+            TargetingInfo {
+                input_query_path: fixture_path("input-variables/query.graphql"),
+                export: Some("cart_lines_discounts_generate_run".to_string()),
+            },
+        )]),
+        input_variables: Some(InputVariables {
+            namespace: "$app:tagged-discount".to_string(),
+            key: "input-variables".to_string(),
+        }),
+    }
+}
+
+#[test]
+fn takes_input_query_variables_from_the_extension_metafield() {
+    let dir = TempDir::new().unwrap();
+    let function_info = tagged_function_info(dir.path());
+
+    assert_fixture(fixture_path("input-variables/fixture.json"), &function_info);
+}
+
+#[test]
+#[should_panic(expected = "Variable \"$tags\" of required type \"[String!]!\" was not provided.")]
+fn fails_without_the_input_variables_table() {
+    let dir = TempDir::new().unwrap();
+    let mut function_info = tagged_function_info(dir.path());
+    function_info.input_variables = None;
+
+    assert_fixture(fixture_path("input-variables/fixture.json"), &function_info);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use common::parse;
+use serde_json::{json, Value};
+use shopify_function_test_helpers::{
+    inject_fetch_result, FetchFixtureData, FixtureData, HttpHeaderFixture, HttpResponseFixture,
+    QueryDocument,
+};
+
+fn run_query() -> QueryDocument {
+    parse(
+        r#"
+        query Input {
+          cart {
+            lines {
+              id
+            }
+          }
+          fetchResult {
+            status
+            body
+            jsonBody
+            contentType: header(name: "Content-Type") {
+              value
+            }
+            missing: header(name: "X-Missing") {
+              value
+            }
+          }
+        }
+        "#,
+    )
+}
+
+fn response(body: Option<&str>, json_body: Option<Value>) -> HttpResponseFixture {
+    HttpResponseFixture {
+        status: 200,
+        body: body.map(str::to_string),
+        json_body,
+        headers: vec![HttpHeaderFixture {
+            name: "content-type".to_string(),
+            value: "application/json".to_string(),
+        }],
+    }
+}
+
+fn fixture_with(response: Option<HttpResponseFixture>) -> FixtureData {
+    FixtureData {
+        export: "cart_lines_discounts_generate_run".to_string(),
+        target: "cart.lines.discounts.generate.run".to_string(),
+        input: json!({ "cart": { "lines": [{ "id": "gid://shopify/CartLine/1" }] } }),
+        expected_output: json!({ "operations": [] }),
+        variables: None,
+        fetch: Some(FetchFixtureData {
+            export: "cart_lines_discounts_generate_fetch".to_string(),
+            target: "cart.lines.discounts.generate.fetch".to_string(),
+            input: json!({ "enteredDiscountCodes": ["10OFF"] }),
+            expected_output: json!({ "request": null }),
+            response,
+        }),
+    }
+}
+
+#[test]
+fn injects_the_response_shaped_by_the_run_query() {
+    let run_fixture = inject_fetch_result(
+        &fixture_with(Some(response(Some(r#"{"discount":10}"#), None))),
+        &run_query(),
+    )
+    .unwrap();
+
+    assert_eq!(run_fixture.fetch, None);
+    assert_eq!(
+        run_fixture.input,
+        json!({
+            "cart": { "lines": [{ "id": "gid://shopify/CartLine/1" }] },
+            "fetchResult": {
+                "status": 200,
+                "body": r#"{"discount":10}"#,
+                "jsonBody": { "discount": 10 },
+                "contentType": { "value": "application/json" },
+                "missing": null
+            }
+        })
+    );
+}
+
+#[test]
+fn serializes_body_from_json_body() {
+    let run_fixture = inject_fetch_result(
+        &fixture_with(Some(response(None, Some(json!({ "discount": 10 }))))),
+        &run_query(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        run_fixture.input["fetchResult"]["body"],
+        r#"{"discount":10}"#
+    );
+    assert_eq!(
+        run_fixture.input["fetchResult"]["jsonBody"],
+        json!({ "discount": 10 })
+    );
+}
+
+#[test]
+fn keeps_a_body_that_is_not_json_as_json_body() {
+    let run_fixture = inject_fetch_result(
+        &fixture_with(Some(response(Some("Internal Server Error"), None))),
+        &run_query(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        run_fixture.input["fetchResult"]["jsonBody"],
+        "Internal Server Error"
+    );
+}
+
+#[test]
+fn injects_null_for_a_missing_response() {
+    let run_fixture = inject_fetch_result(&fixture_with(None), &run_query()).unwrap();
+
+    assert_eq!(run_fixture.input["fetchResult"], Value::Null);
+}
+
+#[test]
+fn follows_aliases_fragments_and_typename() {
+    let run_fixture = inject_fetch_result(
+        &fixture_with(Some(response(None, None))),
+        &parse(
+            r#"
+            query Input {
+              response: fetchResult {
+                ...Response
+              }
+            }
+
+            fragment Response on HttpResponse {
+              __typename
+              code: status
+              headers {
+                name
+                value
+              }
+            }
+            "#,
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        run_fixture.input["response"],
+        json!({
+            "__typename": "HttpResponse",
+            "code": 200,
+            "headers": [{ "name": "content-type", "value": "application/json" }]
+        })
+    );
+}
+
+#[test]
+fn returns_a_fixture_without_a_fetch_stage_unchanged() {
+    let fixture = FixtureData {
+        fetch: None,
+        ..fixture_with(None)
+    };
+
+    assert_eq!(inject_fetch_result(&fixture, &run_query()), Ok(fixture));
+}
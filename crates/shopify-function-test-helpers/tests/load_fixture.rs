@@ -0,0 +1,102 @@
+mod common;
+
+use common::{fixture_path, test_app_path};
+use serde_json::json;
+use shopify_function_test_helpers::{load_fixture, Error, HttpResponseFixture};
+
+#[test]
+fn loads_fixture_from_a_valid_json_file() {
+    let fixture = load_fixture(test_app_path(
+        "cart-validation-js/tests/fixtures/checkout-validation-valid-fixture.json",
+    ))
+    .unwrap();
+
+    assert_eq!(fixture.export, "cart-validations-generate-run");
+    assert_eq!(fixture.target, "cart.validations.generate.run");
+    assert!(fixture.input.get("cart").is_some());
+}
+
+#[test]
+fn loads_test_fixture_with_input_and_output() {
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+
+    assert_eq!(fixture.export, "test-data-processing");
+    assert_eq!(fixture.target, "data.processing.generate.run");
+    assert_eq!(fixture.input["data"]["items"].as_array().unwrap().len(), 1);
+    assert!(fixture.input["data"]["metadata"].is_object());
+    assert_eq!(fixture.expected_output["title"], "Test Processing Result");
+    assert_eq!(fixture.expected_output["count"], 42);
+    assert_eq!(
+        fixture.expected_output["items"].as_array().unwrap().len(),
+        2
+    );
+}
+
+#[test]
+fn loads_the_fetch_stage_of_a_fixture() {
+    let fixture = load_fixture(fixture_path("fetch/cart-lines-fetch-fixture.json")).unwrap();
+    let fetch = fixture.fetch.unwrap();
+
+    assert_eq!(fixture.target, "cart.lines.discounts.generate.run");
+    assert_eq!(fetch.export, "cart_lines_discounts_generate_fetch");
+    assert_eq!(fetch.target, "cart.lines.discounts.generate.fetch");
+    assert_eq!(fetch.input["enteredDiscountCodes"], json!(["10OFF"]));
+    assert_eq!(fetch.expected_output["request"]["method"], "POST");
+    assert_eq!(
+        fetch.response,
+        Some(HttpResponseFixture {
+            status: 200,
+            body: None,
+            json_body: Some(json!({ "valid": true })),
+            headers: vec![],
+        })
+    );
+}
+
+#[test]
+fn loads_the_input_query_variables_of_a_fixture() {
+    let fixture = load_fixture(fixture_path("variables/tagged-cart-fixture.json")).unwrap();
+
+    assert_eq!(
+        fixture.variables.map(serde_json::Value::Object),
+        Some(json!({
+            "tags": ["VIP", "Wholesale"],
+            "collectionIds": ["gid://shopify/Collection/1"],
+            "configurationKey": "function-configuration"
+        }))
+    );
+}
+
+#[test]
+fn leaves_out_the_variables_of_a_fixture_without_them() {
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+
+    assert_eq!(fixture.variables, None);
+}
+
+#[test]
+fn leaves_out_the_fetch_stage_of_a_fixture_without_one() {
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+
+    assert_eq!(fixture.fetch, None);
+}
+
+#[test]
+fn returns_an_error_for_non_existent_file() {
+    let error = load_fixture("non-existent-file.json").unwrap_err();
+
+    assert!(matches!(error, Error::LoadFixture { .. }));
+    assert!(error
+        .to_string()
+        .starts_with("Failed to load fixture file non-existent-file.json"));
+}
+
+#[test]
+fn returns_an_error_for_invalid_json() {
+    let error = load_fixture(fixture_path("valid-query.graphql")).unwrap_err();
+
+    assert!(matches!(error, Error::InvalidFixtureJson { .. }));
+    assert!(error
+        .to_string()
+        .starts_with("Invalid JSON in fixture file"));
+}
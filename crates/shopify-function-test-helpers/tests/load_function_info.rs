@@ -0,0 +1,174 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::{fixture_path, test_app_path};
+use shopify_function_test_helpers::{load_function_info, InputVariables, FUNCTION_RUNNER_PATH_ENV};
+
+fn absolute(path: impl AsRef<Path>) -> PathBuf {
+    std::path::absolute(path).unwrap()
+}
+
+// The environment is shared by the whole test binary, so every test that depends on how
+// function-runner is found lives in this one test
+#[test]
+fn loads_function_info_from_the_extension_toml() {
+    let function_dir = absolute(test_app_path("discount-function-rs"));
+    std::env::set_var(FUNCTION_RUNNER_PATH_ENV, "/opt/bin/function-runner");
+
+    let function_info = load_function_info(&function_dir).unwrap();
+    assert_eq!(
+        function_info.schema_path,
+        function_dir.join("schema.graphql")
+    );
+    assert_eq!(
+        function_info.function_runner_path,
+        PathBuf::from("/opt/bin/function-runner")
+    );
+    assert_eq!(
+        function_info.wasm_path,
+        function_dir.join("target/wasm32-wasip1/release/discount-function-rs.wasm")
+    );
+    assert_eq!(function_info.targeting.len(), 4);
+    let run = &function_info.targeting["cart.lines.discounts.generate.run"];
+    assert_eq!(
+        run.input_query_path,
+        function_dir.join("src/cart_lines_discounts_generate_run.graphql")
+    );
+    assert_eq!(
+        run.export.as_deref(),
+        Some("cart_lines_discounts_generate_run")
+    );
+    assert_eq!(function_info.input_variables, None);
+
+    // [extensions.input.variables] names the metafield the input query variables come from
+    let input_variables_dir = absolute(fixture_path("input-variables"));
+    let function_info = load_function_info(&input_variables_dir).unwrap();
+    assert_eq!(
+        function_info.input_variables,
+        Some(InputVariables {
+            namespace: "$app:tagged-discount".to_string(),
+            key: "input-variables".to_string(),
+        })
+    );
+    assert_eq!(
+        function_info.wasm_path,
+        input_variables_dir.join("function.wasm")
+    );
+
+    // Without a build path, the wasm is where the Shopify CLI builds it by default, and
+    // targets without an input query are skipped
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("shopify.extension.toml"),
+        r#"
+api_version = "2025-07"
+
+[[extensions]]
+handle = "minimal-function"
+type = "function"
+
+  [[extensions.targeting]]
+  target = "cart.validations.generate.run"
+  input_query = "src/run.graphql"
+
+  [[extensions.targeting]]
+  target = "cart.validations.generate.fetch"
+"#,
+    )
+    .unwrap();
+    let function_info = load_function_info(dir.path()).unwrap();
+    assert_eq!(function_info.wasm_path, dir.path().join("dist/index.wasm"));
+    assert_eq!(
+        function_info
+            .targeting
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        vec!["cart.validations.generate.run"]
+    );
+    assert_eq!(
+        function_info.targeting["cart.validations.generate.run"].export,
+        None
+    );
+
+    std::env::remove_var(FUNCTION_RUNNER_PATH_ENV);
+    let original_path = std::env::var_os("PATH");
+
+    #[cfg(unix)]
+    {
+        let bin_dir = tempfile::tempdir().unwrap();
+        let runner = common::fake_runner(bin_dir.path(), "");
+        std::env::set_var(
+            "PATH",
+            std::env::join_paths([dir.path(), bin_dir.path()]).unwrap(),
+        );
+        assert_eq!(
+            load_function_info(&function_dir)
+                .unwrap()
+                .function_runner_path,
+            runner
+        );
+    }
+
+    std::env::set_var("PATH", dir.path());
+    let error = load_function_info(&function_dir).unwrap_err().to_string();
+    if let Some(original_path) = original_path {
+        std::env::set_var("PATH", original_path);
+    }
+    assert_eq!(
+        error,
+        format!(
+            "Failed to load function info from {}: Could not find function-runner on PATH; install it or set FUNCTION_RUNNER_PATH",
+            function_dir.join("shopify.extension.toml").display()
+        )
+    );
+}
+
+#[test]
+fn rejects_a_missing_or_invalid_extension_toml() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shopify.extension.toml");
+
+    let missing = load_function_info(dir.path()).unwrap_err().to_string();
+    assert!(missing.starts_with(&format!(
+        "Failed to load function info from {}: ",
+        path.display()
+    )));
+
+    fs::write(&path, "api_version = \"2025-07\"\n").unwrap();
+    assert_eq!(
+        load_function_info(dir.path()).unwrap_err().to_string(),
+        format!(
+            "Failed to load function info from {}: Expected an [[extensions]] entry",
+            path.display()
+        )
+    );
+
+    fs::write(
+        &path,
+        "[[extensions]]\ntype = \"function\"\n\n[[extensions.targeting]]\ninput_query = \"run.graphql\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        load_function_info(dir.path()).unwrap_err().to_string(),
+        format!(
+            "Failed to load function info from {}: Targeting 0 must have a string target",
+            path.display()
+        )
+    );
+
+    fs::write(
+        &path,
+        "[[extensions]]\ntype = \"function\"\n\n[extensions.input.variables]\nkey = \"input-variables\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        load_function_info(dir.path()).unwrap_err().to_string(),
+        format!(
+            "Failed to load function info from {}: [extensions.input.variables] must have a string namespace and key",
+            path.display()
+        )
+    );
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::fixture_path;
+use graphql_parser::query::{Definition, OperationDefinition};
+use shopify_function_test_helpers::load_input_query;
+
+#[test]
+fn loads_and_parses_a_graphql_query_file() {
+    let document = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+
+    assert_eq!(document.definitions.len(), 1);
+    assert!(matches!(
+        document.definitions[0],
+        Definition::Operation(OperationDefinition::Query(_))
+    ));
+}
+
+#[test]
+fn returns_an_error_for_non_existent_file() {
+    let error = load_input_query(fixture_path("nonexistent.graphql")).unwrap_err();
+
+    assert!(error
+        .to_string()
+        .starts_with("Failed to load input query from"));
+}
+
+#[test]
+fn returns_an_error_for_invalid_graphql() {
+    let error = load_input_query(fixture_path("malformed-query.graphql")).unwrap_err();
+
+    assert!(error
+        .to_string()
+        .starts_with("Failed to load input query from"));
+}
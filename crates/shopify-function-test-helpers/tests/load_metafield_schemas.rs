@@ -0,0 +1,99 @@
+mod common;
+
+use std::fs;
+
+use common::fixture_path;
+use serde_json::json;
+use shopify_function_test_helpers::{load_metafield_schemas, MetafieldSchema};
+
+#[test]
+fn loads_metafield_schemas_from_a_json_file() {
+    let metafield_schemas =
+        load_metafield_schemas(fixture_path("metafields/metafield-schemas.json")).unwrap();
+
+    assert_eq!(metafield_schemas.len(), 3);
+    assert_eq!(metafield_schemas[0].owner_type.as_deref(), Some("Discount"));
+    assert_eq!(metafield_schemas[0].key, "function-configuration");
+    assert_eq!(
+        metafield_schemas[0].schema.as_ref().unwrap()["additionalProperties"],
+        json!(false)
+    );
+    assert_eq!(
+        metafield_schemas[1],
+        MetafieldSchema {
+            namespace: Some("settings".to_string()),
+            key: "max-discounts".to_string(),
+            owner_type: Some("Shop".to_string()),
+            metafield_type: Some("number_integer".to_string()),
+            schema: None,
+        }
+    );
+}
+
+#[test]
+fn rejects_files_that_are_not_an_array_of_metafield_schemas() {
+    let dir = tempfile::tempdir().unwrap();
+    let not_an_array = dir.path().join("not-an-array.json");
+    let missing_key = dir.path().join("missing-key.json");
+    fs::write(&not_an_array, r#"{ "key": "config" }"#).unwrap();
+    fs::write(&missing_key, r#"[{ "type": "json" }]"#).unwrap();
+
+    assert_eq!(
+        load_metafield_schemas(&not_an_array)
+            .unwrap_err()
+            .to_string(),
+        format!(
+            "Failed to load metafield schemas from {}: Expected an array of metafield schemas",
+            not_an_array.display()
+        )
+    );
+    assert_eq!(
+        load_metafield_schemas(&missing_key)
+            .unwrap_err()
+            .to_string(),
+        format!(
+            "Failed to load metafield schemas from {}: Metafield schema 0 must have a string key",
+            missing_key.display()
+        )
+    );
+}
+
+#[test]
+fn rejects_schemas_of_later_json_schema_drafts() {
+    let dir = tempfile::tempdir().unwrap();
+    let later_draft = fixture_path("metafields/draft-2020-12-metafield-schemas.json");
+    let declared_draft = dir.path().join("declared-draft.json");
+    fs::write(
+        &declared_draft,
+        r#"[{ "key": "config", "schema": { "$schema": "https://json-schema.org/draft/2020-12/schema" } }]"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        load_metafield_schemas(&later_draft)
+            .unwrap_err()
+            .to_string(),
+        format!(
+            "Failed to load metafield schemas from {}: Metafield schema 0 uses `prefixItems`, which JSON Schema draft-07 does not support",
+            later_draft.display()
+        )
+    );
+    assert_eq!(
+        load_metafield_schemas(&declared_draft)
+            .unwrap_err()
+            .to_string(),
+        format!(
+            r#"Failed to load metafield schemas from {}: Metafield schema 0 declares `$schema` "https://json-schema.org/draft/2020-12/schema", but only JSON Schema draft-07 is supported"#,
+            declared_draft.display()
+        )
+    );
+}
+
+#[test]
+fn fails_for_a_non_existent_file() {
+    let error = load_metafield_schemas(fixture_path("metafields/missing.json")).unwrap_err();
+
+    assert!(error
+        .to_string()
+        .starts_with("Failed to load metafield schemas from"));
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{fixture_path, test_app_path};
+use shopify_function_test_helpers::{load_schema, Schema};
+
+#[test]
+fn loads_and_builds_a_schema_file() {
+    let schema = load_schema(fixture_path("test-schema.graphql")).unwrap();
+
+    assert_eq!(schema.query_type(), "Query");
+    assert_eq!(schema.mutation_type().unwrap().name, "Mutation");
+    assert_eq!(
+        schema.possible_types("Purchasable"),
+        vec!["PhysicalProduct", "DigitalProduct"]
+    );
+}
+
+#[test]
+fn loads_a_function_api_schema_with_a_custom_mutation_root() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+
+    assert_eq!(schema.query_type(), "Input");
+    assert_eq!(schema.mutation_type().unwrap().name, "MutationRoot");
+}
+
+#[test]
+fn returns_an_error_for_non_existent_file() {
+    let error = load_schema(fixture_path("nonexistent.graphql")).unwrap_err();
+
+    assert!(error.to_string().starts_with("Failed to build schema from"));
+}
+
+#[test]
+fn rejects_references_to_unknown_types() {
+    let error = Schema::parse("type Query { item: Item }").unwrap_err();
+
+    assert_eq!(error, "Unknown type: \"Item\".");
+}
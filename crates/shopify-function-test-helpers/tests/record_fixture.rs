@@ -0,0 +1,350 @@
+//! These tests stand in for function-runner with small shell scripts
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::{fake_runner, fixture_path, test_app_path};
+use serde_json::json;
+use shopify_function_test_helpers::{record_fixture, InputVariables, RecordFixtureResult};
+use tempfile::TempDir;
+
+const FIXTURE: &str = r#"{
+    "shopId": 1,
+    "payload": {
+        "export": "run",
+        "target": "cart.validations.generate.run",
+        "input": { "cart": { "lines": [] } },
+        "output": {
+            "operations": []
+        }
+    },
+    "logs": []
+}
+"#;
+
+fn write_fixture(dir: &Path, content: &str) -> PathBuf {
+    let path = dir.join("fixture.json");
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn record_with(dir: &Path, fixture: &Path, script: &str) -> RecordFixtureResult {
+    record_fixture(
+        fixture,
+        fake_runner(dir, script),
+        "function.wasm",
+        fixture_path("valid-query.graphql"),
+        test_app_path("cart-validation-js/schema.graphql"),
+        None,
+        None,
+    )
+}
+
+#[test]
+fn writes_the_actual_output_and_keeps_the_rest_of_the_file() {
+    let dir = TempDir::new().unwrap();
+    let fixture = write_fixture(dir.path(), FIXTURE);
+
+    let result = record_with(
+        dir.path(),
+        &fixture,
+        r#"echo '{"output":{"operations":[{"validationAdd":{"errors":[]}}]}}'"#,
+    );
+
+    assert_eq!(
+        result,
+        RecordFixtureResult {
+            output: Some(json!({ "operations": [{ "validationAdd": { "errors": [] } }] })),
+            fetch_output: None,
+            updated: true,
+            error: None,
+        }
+    );
+    assert_eq!(
+        fs::read_to_string(&fixture).unwrap(),
+        r#"{
+    "shopId": 1,
+    "payload": {
+        "export": "run",
+        "target": "cart.validations.generate.run",
+        "input": { "cart": { "lines": [] } },
+        "output": {
+            "operations": [
+                {
+                    "validationAdd": {
+                        "errors": []
+                    }
+                }
+            ]
+        }
+    },
+    "logs": []
+}
+"#
+    );
+}
+
+#[test]
+fn keeps_minified_fixtures_minified() {
+    let dir = TempDir::new().unwrap();
+    let fixture = write_fixture(
+        dir.path(),
+        r#"{"payload":{"export":"run","input":{},"output":null,"target":"cart.validations.generate.run"}}"#,
+    );
+
+    record_with(
+        dir.path(),
+        &fixture,
+        r#"echo '{"output":{"operations":[]}}'"#,
+    );
+
+    assert_eq!(
+        fs::read_to_string(&fixture).unwrap(),
+        r#"{"payload":{"export":"run","input":{},"output":{"operations":[]},"target":"cart.validations.generate.run"}}"#
+    );
+}
+
+#[test]
+fn leaves_the_file_untouched_when_the_output_already_matches() {
+    let dir = TempDir::new().unwrap();
+    let fixture = write_fixture(dir.path(), FIXTURE);
+
+    let result = record_with(
+        dir.path(),
+        &fixture,
+        r#"echo '{"output":{"operations":[]}}'"#,
+    );
+
+    assert_eq!(
+        result,
+        RecordFixtureResult {
+            output: Some(json!({ "operations": [] })),
+            fetch_output: None,
+            updated: false,
+            error: None,
+        }
+    );
+    assert_eq!(fs::read_to_string(&fixture).unwrap(), FIXTURE);
+}
+
+#[test]
+fn adds_the_output_when_the_fixture_has_none() {
+    let dir = TempDir::new().unwrap();
+    let fixture = write_fixture(
+        dir.path(),
+        r#"{"payload":{"export":"run","target":"cart.validations.generate.run","input":{}}}"#,
+    );
+
+    let result = record_with(
+        dir.path(),
+        &fixture,
+        r#"echo '{"output":{"operations":[]}}'"#,
+    );
+
+    assert!(result.updated);
+    let content: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&fixture).unwrap()).unwrap();
+    assert_eq!(
+        content,
+        json!({
+            "payload": {
+                "export": "run",
+                "target": "cart.validations.generate.run",
+                "input": {},
+                "output": { "operations": [] }
+            }
+        })
+    );
+}
+
+#[test]
+fn returns_the_run_error_and_leaves_the_file_untouched() {
+    let dir = TempDir::new().unwrap();
+    let fixture = write_fixture(dir.path(), FIXTURE);
+
+    let result = record_with(dir.path(), &fixture, "echo boom >&2; exit 1");
+
+    assert_eq!(
+        result,
+        RecordFixtureResult {
+            output: None,
+            fetch_output: None,
+            updated: false,
+            error: Some("function-runner failed with exit code 1: boom\n".to_string()),
+        }
+    );
+    assert_eq!(fs::read_to_string(&fixture).unwrap(), FIXTURE);
+}
+
+#[test]
+fn returns_an_error_when_the_fixture_cannot_be_loaded() {
+    let dir = TempDir::new().unwrap();
+    let missing = dir.path().join("missing.json");
+
+    let result = record_with(
+        dir.path(),
+        &missing,
+        r#"echo '{"output":{"operations":[]}}'"#,
+    );
+
+    assert!(!result.updated);
+    assert!(result
+        .error
+        .unwrap()
+        .starts_with(&format!("Failed to record fixture {}", missing.display())));
+}
+
+#[test]
+fn runs_with_the_variables_of_the_input_variables_metafield() {
+    let dir = TempDir::new().unwrap();
+    let fixture = write_fixture(
+        dir.path(),
+        &fs::read_to_string(fixture_path("input-variables/fixture.json")).unwrap(),
+    );
+    let query_copy = dir.path().join("query.graphql");
+    let runner = fake_runner(
+        dir.path(),
+        &format!(
+            "query_path=$(echo \"$*\" | sed 's/.*--query-path \\([^ ]*\\).*/\\1/')\n\
+             cp \"$query_path\" {}\necho '{{\"output\":{{\"operations\":[]}}}}'",
+            query_copy.display()
+        ),
+    );
+
+    let result = record_fixture(
+        &fixture,
+        runner,
+        "function.wasm",
+        fixture_path("input-variables/query.graphql"),
+        test_app_path("discount-function-rs/schema.graphql"),
+        None,
+        Some(&InputVariables {
+            namespace: "$app:tagged-discount".to_string(),
+            key: "input-variables".to_string(),
+        }),
+    );
+
+    assert_eq!(result.error, None);
+    let query = fs::read_to_string(query_copy).unwrap();
+    assert!(query.contains(r#"hasTags(tags: ["VIP", "Wholesale"])"#));
+}
+
+/// Record a fetch fixture with a runner that echoes `request` for the fetch export and
+/// `output` for the run export
+fn record_fetch_fixture(
+    dir: &Path,
+    fixture: &serde_json::Value,
+    request: &serde_json::Value,
+    output: &serde_json::Value,
+) -> (PathBuf, RecordFixtureResult) {
+    let fixture = write_fixture(dir, &format!("{fixture:#}\n"));
+    fs::write(
+        dir.join("fetch.graphql"),
+        "query Input { enteredDiscountCodes }",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("run.graphql"),
+        "query Input { cart { lines { id } } fetchResult { status jsonBody } }",
+    )
+    .unwrap();
+    let script = format!(
+        "case \"$*\" in\n*generate_fetch*) echo '{}' ;;\n*) echo '{}' ;;\nesac",
+        json!({ "output": request }),
+        json!({ "output": output }),
+    );
+
+    let result = record_fixture(
+        &fixture,
+        fake_runner(dir, &script),
+        "function.wasm",
+        dir.join("run.graphql"),
+        test_app_path("discount-function-rs/schema.graphql"),
+        Some(&dir.join("fetch.graphql")),
+        None,
+    );
+    (fixture, result)
+}
+
+fn fetch_fixture() -> serde_json::Value {
+    serde_json::from_str(
+        &fs::read_to_string(fixture_path("fetch/cart-lines-fetch-fixture.json")).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn records_the_request_of_the_fetch_stage() {
+    let dir = TempDir::new().unwrap();
+    let mut request = fetch_fixture()["payload"]["fetch"]["output"].clone();
+    request["request"]["url"] = json!("https://other.example.com");
+    let output = json!({ "operations": [] });
+
+    let (fixture, result) = record_fetch_fixture(dir.path(), &fetch_fixture(), &request, &output);
+
+    assert_eq!(
+        result,
+        RecordFixtureResult {
+            output: Some(output.clone()),
+            fetch_output: Some(request.clone()),
+            updated: true,
+            error: None,
+        }
+    );
+    let content: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&fixture).unwrap()).unwrap();
+    assert_eq!(content["payload"]["fetch"]["output"], request);
+    assert_eq!(content["payload"]["output"], output);
+}
+
+#[test]
+fn keeps_outputs_that_only_differ_in_how_values_are_written() {
+    let dir = TempDir::new().unwrap();
+    let mut fixture = fetch_fixture();
+    let request = fixture["payload"]["fetch"]["output"].clone();
+    let output = json!({
+        "operations": [{
+            "orderDiscountsAdd": {
+                "candidates": [{
+                    "targets": [{ "orderSubtotal": { "excludedCartLineIds": [] } }],
+                    "value": { "fixedAmount": { "amount": "10" } }
+                }],
+                "selectionStrategy": "FIRST"
+            }
+        }]
+    });
+    // "10" and "10.0" are the same Decimal
+    fixture["payload"]["output"] = output.clone();
+    fixture["payload"]["output"]["operations"][0]["orderDiscountsAdd"]["candidates"][0]["value"]
+        ["fixedAmount"]["amount"] = json!("10.0");
+
+    let (path, result) = record_fetch_fixture(dir.path(), &fixture, &request, &output);
+
+    assert_eq!(result.error, None);
+    assert!(!result.updated);
+    assert_eq!(fs::read_to_string(&path).unwrap(), format!("{fixture:#}\n"));
+}
+
+#[test]
+fn needs_the_fetch_query_to_record_a_fetch_stage() {
+    let dir = TempDir::new().unwrap();
+    let fixture = write_fixture(dir.path(), &format!("{:#}", fetch_fixture()));
+
+    let result = record_fixture(
+        &fixture,
+        fake_runner(dir.path(), "exit 1"),
+        "function.wasm",
+        fixture_path("valid-query.graphql"),
+        test_app_path("discount-function-rs/schema.graphql"),
+        None,
+        None,
+    );
+
+    assert!(!result.updated);
+    assert!(result.error.unwrap().ends_with(
+        "The fixture has a fetch stage, so the input query of cart.lines.discounts.generate.fetch is needed to record it"
+    ));
+}
@@ -0,0 +1,185 @@
+//! These tests stand in for function-runner with small shell scripts
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+use common::{fake_runner, fixture_path};
+use serde_json::json;
+use shopify_function_test_helpers::{
+    load_fixture, run_function, RunFunctionOutput, RunFunctionResult,
+};
+use tempfile::TempDir;
+
+fn run_with(script: &str) -> RunFunctionResult {
+    let dir = TempDir::new().unwrap();
+    let runner = fake_runner(dir.path(), script);
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+
+    run_function(
+        &fixture,
+        runner,
+        "function.wasm",
+        fixture_path("valid-query.graphql"),
+        fixture_path("test-schema.graphql"),
+    )
+}
+
+#[test]
+fn runs_a_function_and_returns_its_output() {
+    let result = run_with(r#"echo '{"output":{"operations":[]},"logs":""}'"#);
+
+    assert_eq!(result.error, None);
+    assert_eq!(result.result.unwrap().output, json!({ "operations": [] }));
+}
+
+#[test]
+fn reports_resource_usage_and_logs() {
+    let result = run_with(
+        r#"echo '{"name":"function.wasm","size":143,"memory_usage":1088,"instructions":50920,"logs":"Applying discount","output":{"operations":[]},"success":true}'"#,
+    );
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+
+    assert_eq!(
+        result.result,
+        Some(RunFunctionOutput {
+            output: json!({ "operations": [] }),
+            instructions: Some(50920),
+            memory_usage: Some(1088),
+            input_size: fixture.input.to_string().len(),
+            output_size: r#"{"operations":[]}"#.len(),
+            logs: "Applying discount".to_string(),
+        })
+    );
+}
+
+#[test]
+fn passes_the_fixture_input_and_export_to_the_runner() {
+    let dir = TempDir::new().unwrap();
+    let args_path = dir.path().join("args");
+    let stdin_path = dir.path().join("stdin");
+    let runner = dir.path().join("function-runner");
+    fs::write(
+        &runner,
+        format!(
+            "#!/bin/sh\necho \"$@\" > {}\ncat > {}\necho '{{\"output\":{{}}}}'\n",
+            args_path.display(),
+            stdin_path.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&runner, fs::Permissions::from_mode(0o755)).unwrap();
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+
+    let result = run_function(
+        &fixture,
+        &runner,
+        "function.wasm",
+        "query.graphql",
+        "schema.graphql",
+    );
+
+    assert_eq!(result.error, None);
+    assert_eq!(
+        fs::read_to_string(args_path).unwrap().trim(),
+        "-f function.wasm --export test-data-processing --query-path query.graphql --schema-path schema.graphql --json"
+    );
+    let stdin: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(stdin_path).unwrap()).unwrap();
+    assert_eq!(stdin, fixture.input);
+}
+
+#[test]
+fn reports_non_zero_exit_codes_with_stderr() {
+    let result = run_with("echo 'Error: Export not found' >&2\nexit 1");
+
+    assert_eq!(result.result, None);
+    assert_eq!(
+        result.error.as_deref(),
+        Some("function-runner failed with exit code 1: Error: Export not found\n")
+    );
+}
+
+#[test]
+fn reports_runner_spawn_errors() {
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+
+    let result = run_function(
+        &fixture,
+        "/nonexistent/function-runner",
+        "function.wasm",
+        "query.graphql",
+        "schema.graphql",
+    );
+
+    assert_eq!(result.result, None);
+    assert!(result
+        .error
+        .unwrap()
+        .starts_with("Failed to start function-runner: "));
+}
+
+#[test]
+fn reports_invalid_json_output() {
+    let result = run_with("echo 'invalid json {{{'");
+
+    assert_eq!(result.result, None);
+    assert!(result
+        .error
+        .unwrap()
+        .starts_with("Failed to parse function-runner output: "));
+}
+
+#[test]
+fn rejects_output_without_output_wrapper() {
+    let result = run_with(r#"echo '{"operations":[]}'"#);
+
+    assert_eq!(result.result, None);
+    assert_eq!(
+        result.error.as_deref(),
+        Some(
+            r#"function-runner returned unexpected format - missing 'output' field. Received: {"operations":[]}"#
+        )
+    );
+}
+
+#[test]
+fn inlines_the_fixture_variables_into_the_input_query() {
+    let dir = TempDir::new().unwrap();
+    let query_copy = dir.path().join("query.graphql");
+    let query_path_copy = dir.path().join("query-path");
+    let runner = fake_runner(
+        dir.path(),
+        &format!(
+            "query_path=$(echo \"$*\" | sed 's/.*--query-path \\([^ ]*\\).*/\\1/')\n\
+             echo \"$query_path\" > {}\ncp \"$query_path\" {}\necho '{{\"output\":{{}}}}'",
+            query_path_copy.display(),
+            query_copy.display()
+        ),
+    );
+    let mut fixture = load_fixture(fixture_path("input-variables/fixture.json")).unwrap();
+    fixture.variables = Some(
+        json!({ "tags": ["VIP", "Wholesale"] })
+            .as_object()
+            .unwrap()
+            .clone(),
+    );
+
+    let result = run_function(
+        &fixture,
+        runner,
+        "function.wasm",
+        fixture_path("input-variables/query.graphql"),
+        common::test_app_path("discount-function-rs/schema.graphql"),
+    );
+
+    assert_eq!(result.error, None);
+    let query = fs::read_to_string(query_copy).unwrap();
+    assert!(query.contains(r#"hasTags(tags: ["VIP", "Wholesale"])"#));
+    assert!(!query.contains("$tags"));
+    // The inlined copy of the query is removed once the runner is done
+    let query_path = fs::read_to_string(query_path_copy).unwrap();
+    assert!(!std::path::Path::new(query_path.trim()).exists());
+}
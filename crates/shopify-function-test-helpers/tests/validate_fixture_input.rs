@@ -0,0 +1,917 @@
+mod common;
+
+use common::{fixture_path, parse, test_app_path, test_schema};
+use serde_json::{json, Value};
+use shopify_function_test_helpers::{
+    load_fixture, load_input_query, load_metafield_schemas, load_schema, validate_fixture_input,
+    validate_fixture_input_with_options, FixtureInputValidationError, MetafieldSchema,
+    ValidateFixtureInputOptions,
+};
+
+#[test]
+fn validates_default_fixture() {
+    let query = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture.input);
+
+    assert_eq!(result.errors, vec![]);
+}
+
+#[test]
+fn handles_multiple_aliases_for_the_same_field() {
+    let query = parse(
+        r#"
+        query Query {
+          data {
+            firstItems: items { id count }
+            secondItems: items { id details { name } }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": {
+            "firstItems": [{ "id": "gid://test/Item/1", "count": 5 }],
+            "secondItems": [{ "id": "gid://test/Item/1", "details": { "name": "First Item" } }]
+        }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(result.errors, vec![]);
+}
+
+#[test]
+fn handles_named_fragments() {
+    let query = parse(
+        r#"
+        query {
+          data { ...ItemFields }
+        }
+
+        fragment ItemFields on DataContainer {
+          items { id count }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": { "items": [{ "id": "gid://test/Item/1", "count": 5 }] }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(result.errors, vec![]);
+}
+
+#[test]
+fn handles_aliased_typename_in_inline_fragments() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            searchResults {
+              type: __typename
+              ... on Item { id count }
+              ... on Metadata { email phone }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": {
+            "searchResults": [
+                { "type": "Item", "id": "gid://test/Item/1", "count": 5 },
+                { "type": "Metadata", "email": "test@example.com", "phone": "555-0001" }
+            ]
+        }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(result.errors, vec![]);
+}
+
+#[test]
+fn does_not_inherit_typename_key_across_field_boundaries() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            nested {
+              outerType: __typename
+              ... on NestedOuterA {
+                id
+                inner {
+                  ... on NestedInnerA { name }
+                  ... on NestedInnerB { value }
+                }
+              }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": {
+            "nested": [
+                { "outerType": "NestedOuterA", "id": "1", "inner": [{ "name": "Inner name" }] }
+            ]
+        }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Missing `__typename` field for abstract type `NestedInner`",
+            ["data", "nested", "inner"]
+        )]
+    );
+}
+
+#[test]
+fn handles_inline_fragment_on_interface_type() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            products {
+              __typename
+              ... on Purchasable { price currency }
+              ... on GiftCard { code balance }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": {
+            "products": [
+                { "__typename": "PhysicalProduct", "price": 1000, "currency": "USD" },
+                { "__typename": "DigitalProduct", "price": 500, "currency": "USD" },
+                { "__typename": "GiftCard", "code": "GIFT123", "balance": 5000 }
+            ]
+        }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(result.errors, vec![]);
+}
+
+#[test]
+fn handles_empty_objects_when_narrowing_from_union_to_interface_subset() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            products {
+              ... on Purchasable { price currency }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": { "products": [{ "price": 1000, "currency": "USD" }, {}] }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(result.errors, vec![]);
+}
+
+#[test]
+fn requires_typename_for_nested_interface_fragments_with_partial_field_sets() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            interfaceImplementers {
+              ...on HasId {
+                id
+                ...on HasName {
+                  name
+                  ...on HasDescription { description }
+                }
+              }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": {
+            "interfaceImplementers": [
+                { "id": "1", "name": "Implementer1", "description": "Implements all three" },
+                { "id": "2", "name": "Implementer2" },
+                { "id": "3" },
+                {}
+            ]
+        }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![
+            fixture_error!(
+                "Missing expected fixture data for `name`",
+                ["data", "interfaceImplementers", 2, "name"]
+            ),
+            fixture_error!(
+                "Missing expected fixture data for `description`",
+                ["data", "interfaceImplementers", 1, "description"]
+            ),
+            fixture_error!(
+                "Missing expected fixture data for `description`",
+                ["data", "interfaceImplementers", 2, "description"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn handles_deeply_nested_lists() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            metadataCube { email }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": {
+            "metadataCube": [[[{ "email": "a@example.com" }, null], []], null]
+        }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(result.errors, vec![]);
+}
+
+#[test]
+fn detects_null_in_non_nullable_scalar_field() {
+    let query = parse("query { data { items { id count } } }");
+    let fixture_input = json!({ "data": { "items": [{ "id": "1", "count": null }] } });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Expected non-nullable type \"Int!\" not to be null.",
+            ["data", "items", 0, "count"]
+        )]
+    );
+}
+
+#[test]
+fn detects_null_in_non_nullable_array() {
+    let query = parse("query { data { items { id count } } }");
+    let fixture_input = json!({
+        "data": { "items": [{ "id": "1", "count": 10 }, null, { "id": "2", "count": 20 }] }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Null value found in non-nullable array",
+            ["data", "items", 1]
+        )]
+    );
+}
+
+#[test]
+fn detects_null_in_non_nullable_object_field() {
+    let query = parse("query { data { requiredMetadata { email phone } } }");
+    let fixture_input = json!({ "data": { "requiredMetadata": null } });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Expected object, but got null",
+            ["data", "requiredMetadata"]
+        )]
+    );
+}
+
+#[test]
+fn detects_missing_fields_in_fixture_data() {
+    let query = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+    let fixture_input = json!({ "data": { "items": [{ "id": "gid://test/Item/1" }] } });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![
+            fixture_error!(
+                "Missing expected fixture data for `count`",
+                ["data", "items", 0, "count"]
+            ),
+            fixture_error!(
+                "Missing expected fixture data for `details`",
+                ["data", "items", 0, "details"]
+            ),
+            fixture_error!(
+                "Missing expected fixture data for `metadata`",
+                ["data", "metadata"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn detects_extra_fields_at_root_level() {
+    let query = parse("query { version }");
+    let fixture_input = json!({ "version": "1.0", "data": {} });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Extra field `data` found in fixture data not in query",
+            ["data"]
+        )]
+    );
+}
+
+#[test]
+fn detects_fields_from_wrong_fragment_type_in_unions() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            searchResults {
+              __typename
+              ... on Item { id count }
+              ... on Metadata { email phone }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": {
+            "searchResults": [
+                {
+                    "__typename": "Item",
+                    "id": "gid://test/Item/1",
+                    "count": 5,
+                    "email": "item@example.com",
+                    "phone": "555-1234"
+                },
+                {
+                    "__typename": "Metadata",
+                    "email": "metadata@example.com",
+                    "phone": "555-5678",
+                    "id": "wrong-id",
+                    "count": 10
+                }
+            ]
+        }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![
+            fixture_error!(
+                "Extra field `email` found in fixture data not in query",
+                ["data", "searchResults", 0, "email"]
+            ),
+            fixture_error!(
+                "Extra field `phone` found in fixture data not in query",
+                ["data", "searchResults", 0, "phone"]
+            ),
+            fixture_error!(
+                "Extra field `id` found in fixture data not in query",
+                ["data", "searchResults", 1, "id"]
+            ),
+            fixture_error!(
+                "Extra field `count` found in fixture data not in query",
+                ["data", "searchResults", 1, "count"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn detects_type_mismatches_between_object_and_scalar() {
+    let query = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+    let fixture_input = json!({ "data": "this should be an object, not a string" });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!("Expected object, but got string", ["data"])]
+    );
+}
+
+#[test]
+fn detects_invalid_scalar_values() {
+    let query = parse("query Query { data { items { id count } } }");
+    let fixture_input = json!({ "data": { "items": [{ "id": 123, "count": "not a number" }] } });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Int cannot represent non-integer value: \"not a number\"",
+            ["data", "items", 0, "count"]
+        )]
+    );
+}
+
+#[test]
+fn detects_incorrect_array_nesting_depth() {
+    let query = parse("query { data { itemMatrix { id count } } }");
+    let fixture_input = json!({
+        "data": { "itemMatrix": [{ "id": "1", "count": 10 }, { "id": "2", "count": 20 }] }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![
+            fixture_error!("Expected array, but got object", ["data", "itemMatrix", 0]),
+            fixture_error!("Expected array, but got object", ["data", "itemMatrix", 1]),
+        ]
+    );
+}
+
+#[test]
+fn detects_fields_with_missing_type_information() {
+    let query = parse("query { data { items { id nonExistentField } } }");
+    let fixture_input = json!({
+        "data": { "items": [{ "id": "gid://test/Item/1", "nonExistentField": "some value" }] }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Cannot validate `nonExistentField`: missing field definition",
+            ["data", "items", "nonExistentField"]
+        )]
+    );
+}
+
+#[test]
+fn detects_empty_objects_when_inline_fragment_is_on_same_type_as_field() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            purchasable {
+              ... on Purchasable { price }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({ "data": { "purchasable": {} } });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Missing expected fixture data for `price`",
+            ["data", "purchasable", "price"]
+        )]
+    );
+}
+
+#[test]
+fn detects_missing_typename_in_union_with_multiple_inline_fragments() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            searchResults {
+              ... on Item { id count }
+              ... on Metadata { email phone }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "data": {
+            "searchResults": [
+                { "id": "gid://test/Item/1", "count": 5 },
+                { "email": "test@example.com", "phone": "555-0001" }
+            ]
+        }
+    });
+
+    let result = validate_fixture_input(&query, &test_schema(), &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            "Missing `__typename` field for abstract type `SearchResult`",
+            ["data", "searchResults"]
+        )]
+    );
+}
+
+const METAFIELD_QUERY: &str = r#"
+    query {
+      discount {
+        metafield(key: "function-configuration") {
+          jsonValue
+        }
+      }
+      shop {
+        maxDiscounts: metafield(namespace: "settings", key: "max-discounts") {
+          type
+          value
+        }
+        excludedTags: metafield(namespace: "settings", key: "excluded-tags") {
+          jsonValue
+        }
+      }
+    }
+"#;
+
+fn metafield_fixture_input(
+    configuration: Value,
+    max_discounts: Value,
+    excluded_tags: Value,
+) -> Value {
+    json!({
+        "discount": { "metafield": { "jsonValue": configuration } },
+        "shop": { "maxDiscounts": max_discounts, "excludedTags": excluded_tags },
+    })
+}
+
+fn validate_metafields(
+    query: &str,
+    fixture_input: &Value,
+    metafield_schemas: &[MetafieldSchema],
+) -> Vec<FixtureInputValidationError> {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    validate_fixture_input_with_options(
+        &parse(query),
+        &schema,
+        fixture_input,
+        ValidateFixtureInputOptions {
+            metafield_schemas,
+            ..Default::default()
+        },
+    )
+    .errors
+}
+
+fn test_metafield_schemas() -> Vec<MetafieldSchema> {
+    load_metafield_schemas(fixture_path("metafields/metafield-schemas.json")).unwrap()
+}
+
+#[test]
+fn accepts_metafields_matching_their_schemas() {
+    let fixture_input = metafield_fixture_input(
+        json!({ "order": { "percentage": 15, "selectionStrategy": "MAXIMUM" } }),
+        json!({ "type": "number_integer", "value": "3" }),
+        json!({ "jsonValue": ["sale", "clearance"] }),
+    );
+
+    let errors = validate_metafields(METAFIELD_QUERY, &fixture_input, &test_metafield_schemas());
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn treats_metafield_values_as_opaque_without_metafield_schemas() {
+    let fixture_input = metafield_fixture_input(
+        json!({ "order": { "percentage": 150 } }),
+        json!({ "type": "number_integer", "value": "three" }),
+        json!({ "jsonValue": ["sale", "clearance"] }),
+    );
+
+    let errors = validate_metafields(METAFIELD_QUERY, &fixture_input, &[]);
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn checks_metafield_json_value_against_the_json_schema() {
+    let fixture_input = metafield_fixture_input(
+        json!({
+            "order": { "percentage": 150, "selectionStrategy": "LAST" },
+            "shipping": {},
+        }),
+        json!({ "type": "number_integer", "value": "3" }),
+        json!({ "jsonValue": ["sale", "clearance"] }),
+    );
+
+    let errors = validate_metafields(METAFIELD_QUERY, &fixture_input, &test_metafield_schemas());
+
+    assert_eq!(
+        errors,
+        vec![
+            fixture_error!(
+                "Invalid `function-configuration` metafield at `order.percentage`: 150 is greater than the maximum of 100",
+                ["discount", "metafield", "jsonValue"]
+            ),
+            fixture_error!(
+                r#"Invalid `function-configuration` metafield at `order.selectionStrategy`: expected one of "ALL", "FIRST", "MAXIMUM", but got "LAST""#,
+                ["discount", "metafield", "jsonValue"]
+            ),
+            fixture_error!(
+                "Invalid `function-configuration` metafield: unexpected property `shipping`",
+                ["discount", "metafield", "jsonValue"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn checks_metafield_value_and_type_against_the_metafield_type() {
+    let fixture_input = metafield_fixture_input(
+        json!({}),
+        json!({ "type": "json", "value": "2.5" }),
+        json!({ "jsonValue": ["sale", 42] }),
+    );
+
+    let errors = validate_metafields(METAFIELD_QUERY, &fixture_input, &test_metafield_schemas());
+
+    assert_eq!(
+        errors,
+        vec![
+            fixture_error!(
+                r#"Invalid `settings.max-discounts` metafield: expected type `number_integer`, but got "json""#,
+                ["shop", "maxDiscounts", "type"]
+            ),
+            fixture_error!(
+                "Invalid `settings.max-discounts` metafield: expected an integer, but got 2.5",
+                ["shop", "maxDiscounts", "value"]
+            ),
+            fixture_error!(
+                "Invalid `settings.excluded-tags` metafield at `1`: expected a single line of text, but got 42",
+                ["shop", "excludedTags", "jsonValue"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn only_applies_metafield_schemas_to_their_owner_type() {
+    let query = r#"
+        query {
+          discount {
+            metafield(namespace: "settings", key: "max-discounts") {
+              value
+            }
+          }
+        }
+    "#;
+    let fixture_input = json!({ "discount": { "metafield": { "value": "three" } } });
+
+    let errors = validate_metafields(query, &fixture_input, &test_metafield_schemas());
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn skips_null_metafields() {
+    let fixture_input = json!({
+        "discount": { "metafield": null },
+        "shop": { "maxDiscounts": null, "excludedTags": null },
+    });
+
+    let errors = validate_metafields(METAFIELD_QUERY, &fixture_input, &test_metafield_schemas());
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn reports_each_json_schema_mismatch_with_its_location() {
+    let metafield_schemas = [MetafieldSchema {
+        key: "function-configuration".to_string(),
+        schema: Some(json!({
+            "type": "object",
+            "required": ["code"],
+            "properties": {
+                "code": { "type": "string", "pattern": "^[A-Z0-9]+$", "maxLength": 8 },
+                "percentage": { "type": "number", "exclusiveMinimum": 0, "maximum": 100 },
+                "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 },
+                "target": { "$ref": "#/$defs/target" },
+                "limit": { "type": ["integer", "null"] },
+            },
+            "additionalProperties": false,
+            "$defs": {
+                "target": { "oneOf": [{ "const": "ORDER" }, { "const": "PRODUCT" }] },
+            },
+        })),
+        ..Default::default()
+    }];
+    let query = r#"
+        query {
+          discount {
+            metafield(key: "function-configuration") {
+              jsonValue
+            }
+          }
+        }
+    "#;
+    let errors = |configuration: Value| {
+        validate_metafields(
+            query,
+            &json!({ "discount": { "metafield": { "jsonValue": configuration } } }),
+            &metafield_schemas,
+        )
+        .into_iter()
+        .map(|error| error.message)
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        errors(json!({
+            "code": "SAVE10",
+            "percentage": 10,
+            "tags": ["sale"],
+            "target": "ORDER",
+            "limit": 1,
+        })),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        errors(json!({
+            "code": "save10",
+            "percentage": 0,
+            "tags": ["sale", 1, "clearance"],
+            "target": "SHIPPING",
+            "limit": 1.5,
+            "extra": true,
+        })),
+        [
+            r#"Invalid `function-configuration` metafield at `code`: "save10" does not match "^[A-Z0-9]+$""#,
+            "Invalid `function-configuration` metafield at `percentage`: 0 is less than or equal to the minimum of 0",
+            "Invalid `function-configuration` metafield at `tags.1`: expected a string, but got 1",
+            r#"Invalid `function-configuration` metafield at `tags`: ["sale",1,"clearance"] has more than 2 items"#,
+            r#"Invalid `function-configuration` metafield at `target`: "SHIPPING" is not valid under any of the schemas listed in the 'oneOf' keyword"#,
+            "Invalid `function-configuration` metafield at `limit`: expected an integer or null, but got 1.5",
+            "Invalid `function-configuration` metafield: unexpected property `extra`",
+        ]
+    );
+    assert_eq!(
+        errors(json!({})),
+        ["Invalid `function-configuration` metafield: missing required property `code`"]
+    );
+}
+
+#[test]
+fn reports_json_schemas_that_cannot_be_compiled() {
+    let metafield_schemas = [MetafieldSchema {
+        key: "function-configuration".to_string(),
+        schema: Some(json!({ "$ref": "#/$defs/missing" })),
+        ..Default::default()
+    }];
+
+    let errors = validate_metafields(
+        METAFIELD_QUERY,
+        &metafield_fixture_input(json!({}), Value::Null, Value::Null),
+        &metafield_schemas,
+    );
+
+    assert_eq!(
+        errors.into_iter().map(|error| error.message).collect::<Vec<_>>(),
+        ["Invalid `function-configuration` metafield: invalid JSON Schema: Pointer '/$defs/missing' does not exist"]
+    );
+}
+
+fn validate_tagged_cart(
+    fixture_input: &Value,
+    metafield_schemas: &[MetafieldSchema],
+    variables: Option<Value>,
+) -> Vec<FixtureInputValidationError> {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let query = load_input_query(fixture_path("variables/tagged-cart-query.graphql")).unwrap();
+    validate_fixture_input_with_options(
+        &query,
+        &schema,
+        fixture_input,
+        ValidateFixtureInputOptions {
+            metafield_schemas,
+            variables: variables.as_ref().and_then(Value::as_object),
+        },
+    )
+    .errors
+}
+
+fn tagged_cart_fixture() -> (Value, Value) {
+    let fixture = load_fixture(fixture_path("variables/tagged-cart-fixture.json")).unwrap();
+    (fixture.input, Value::Object(fixture.variables.unwrap()))
+}
+
+#[test]
+fn accepts_responses_matching_the_variable_values() {
+    let (fixture_input, variables) = tagged_cart_fixture();
+
+    let errors = validate_tagged_cart(&fixture_input, &test_metafield_schemas(), Some(variables));
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn reports_responses_that_do_not_answer_each_requested_value() {
+    let (fixture_input, mut variables) = tagged_cart_fixture();
+    variables["tags"] = json!(["VIP", "Gold"]);
+    variables["collectionIds"] =
+        json!(["gid://shopify/Collection/1", "gid://shopify/Collection/2"]);
+
+    let errors = validate_tagged_cart(&fixture_input, &[], Some(variables));
+
+    assert_eq!(
+        errors,
+        vec![
+            fixture_error!(
+                r#"Expected "Gold" from the `tags` argument, but got "Wholesale""#,
+                ["cart", "buyerIdentity", "customer", "hasTags", 1usize, "tag"]
+            ),
+            fixture_error!(
+                "Expected a `CollectionMembership` for each value of the `ids` argument (2), but got 1",
+                ["cart", "lines", 0usize, "merchandise", "product", "inCollections"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn checks_literal_arguments() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let query = parse(
+        r#"
+        query {
+          cart {
+            buyerIdentity {
+              customer {
+                vip: hasTags(tags: "VIP") {
+                  name: tag
+                  hasTag
+                }
+              }
+            }
+          }
+        }
+        "#,
+    );
+    let fixture_input = json!({
+        "cart": { "buyerIdentity": { "customer": { "vip": [{ "name": "Gold", "hasTag": true }] } } }
+    });
+
+    let result = validate_fixture_input(&query, &schema, &fixture_input);
+
+    assert_eq!(
+        result.errors,
+        vec![fixture_error!(
+            r#"Expected "VIP" from the `tags` argument, but got "Gold""#,
+            ["cart", "buyerIdentity", "customer", "vip", 0usize, "name"]
+        )]
+    );
+}
+
+#[test]
+fn skips_arguments_whose_variables_have_no_value() {
+    let (fixture_input, _) = tagged_cart_fixture();
+
+    let errors = validate_tagged_cart(&fixture_input, &[], None);
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn finds_metafield_schemas_by_variable_namespace_and_key() {
+    let (mut fixture_input, variables) = tagged_cart_fixture();
+    fixture_input["discount"]["metafield"]["jsonValue"]["order"]["percentage"] = json!(150);
+
+    let errors = validate_tagged_cart(&fixture_input, &test_metafield_schemas(), Some(variables));
+
+    assert_eq!(
+        errors,
+        vec![fixture_error!(
+            "Invalid `function-configuration` metafield at `order.percentage`: 150 is greater than the maximum of 100",
+            ["discount", "metafield", "jsonValue"]
+        )]
+    );
+}
@@ -0,0 +1,232 @@
+mod common;
+
+use common::{test_app_path, test_schema};
+use serde_json::{json, Value};
+use shopify_function_test_helpers::{load_fixture, load_schema, validate_fixture_output, Schema};
+
+fn fixture_output() -> Value {
+    json!({
+        "title": "Test Processing Result",
+        "count": 42,
+        "items": [
+            { "name": "Item 1", "value": 100 },
+            { "name": "Item 2", "value": 200 }
+        ]
+    })
+}
+
+fn messages(errors: &[shopify_function_test_helpers::OutputValidationError]) -> Vec<&str> {
+    errors.iter().map(|e| e.message.as_str()).collect()
+}
+
+#[test]
+fn validates_a_valid_fixture() {
+    let result =
+        validate_fixture_output(&fixture_output(), &test_schema(), "processData", "result");
+
+    assert_eq!(result.errors, vec![]);
+    assert_eq!(result.mutation_name, "processData");
+    assert_eq!(
+        result.result_parameter_type.as_deref(),
+        Some("ProcessDataResult!")
+    );
+}
+
+#[test]
+fn handles_invalid_mutation_name() {
+    let result = validate_fixture_output(
+        &fixture_output(),
+        &test_schema(),
+        "nonExistentMutation",
+        "result",
+    );
+
+    assert_eq!(
+        messages(&result.errors),
+        vec!["Mutation 'nonExistentMutation' not found in schema"]
+    );
+    assert_eq!(result.result_parameter_type, None);
+}
+
+#[test]
+fn handles_invalid_parameter_name() {
+    let result = validate_fixture_output(
+        &fixture_output(),
+        &test_schema(),
+        "processData",
+        "nonExistentParam",
+    );
+
+    assert_eq!(
+        messages(&result.errors),
+        vec!["Parameter 'nonExistentParam' not found in mutation 'processData'"]
+    );
+}
+
+#[test]
+fn validates_against_other_mutations_in_the_schema() {
+    let fetch_output_data = json!({
+        "request": {
+            "url": "https://example.com/api",
+            "method": "POST",
+            "headers": "Content-Type: application/json",
+            "body": "test body"
+        }
+    });
+
+    let result = validate_fixture_output(&fetch_output_data, &test_schema(), "fetchData", "input");
+
+    assert_eq!(result.errors, vec![]);
+    assert_eq!(
+        result.result_parameter_type.as_deref(),
+        Some("FetchDataResult!")
+    );
+}
+
+#[test]
+fn detects_type_mismatches_in_fixture_data() {
+    let mut output = fixture_output();
+    output["count"] = json!("this should be a number");
+
+    let result = validate_fixture_output(&output, &test_schema(), "processData", "result");
+
+    assert_eq!(
+        messages(&result.errors),
+        vec!["Int cannot represent non-integer value: \"this should be a number\" At \"count\""]
+    );
+}
+
+#[test]
+fn detects_extra_fields_in_process_data_result() {
+    let mut output = fixture_output();
+    output["extraField1"] = json!("this should not be allowed");
+    output["extraField2"] = json!(123);
+    output["nestedExtra"] = json!({ "invalidNested": "also invalid" });
+
+    let result = validate_fixture_output(&output, &test_schema(), "processData", "result");
+
+    assert_eq!(
+        messages(&result.errors),
+        vec![
+            "Field \"extraField1\" is not defined by type \"ProcessDataResult\". At \"\"",
+            "Field \"extraField2\" is not defined by type \"ProcessDataResult\". At \"\"",
+            "Field \"nestedExtra\" is not defined by type \"ProcessDataResult\". At \"\"",
+        ]
+    );
+}
+
+#[test]
+fn detects_missing_required_fields_and_nulls_with_their_path() {
+    let output = json!({
+        "title": "Test Processing Result",
+        "items": [{ "name": null, "value": 1 }]
+    });
+
+    let result = validate_fixture_output(&output, &test_schema(), "processData", "result");
+
+    assert_eq!(
+        messages(&result.errors),
+        vec![
+            "Field \"count\" of required type \"Int!\" was not provided. At \"\"",
+            "Expected non-nullable type \"String!\" not to be null. At \"items.0.name\"",
+        ]
+    );
+}
+
+fn discount_schema() -> Schema {
+    load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap()
+}
+
+fn discount_output() -> Value {
+    load_fixture(test_app_path(
+        "discount-function-rs/tests/fixtures/cart-lines-valid-fixture.json",
+    ))
+    .unwrap()
+    .expected_output
+}
+
+#[test]
+fn accepts_one_of_operations_that_set_exactly_one_field() {
+    let result = validate_fixture_output(
+        &discount_output(),
+        &discount_schema(),
+        "cartLinesDiscountsGenerateRun",
+        "result",
+    );
+
+    assert_eq!(result.errors, vec![]);
+}
+
+#[test]
+fn reports_one_of_operations_that_set_more_than_one_field() {
+    let mut output = discount_output();
+    let product_discounts_add = output["operations"][1]["productDiscountsAdd"].take();
+    output["operations"][1] = output["operations"][0].clone();
+    output["operations"][1]["productDiscountsAdd"] = product_discounts_add;
+
+    let result = validate_fixture_output(
+        &output,
+        &discount_schema(),
+        "cartLinesDiscountsGenerateRun",
+        "result",
+    );
+
+    assert_eq!(
+        messages(&result.errors),
+        vec!["Exactly one key must be specified for OneOf type \"CartOperation\". At \"operations.1\""]
+    );
+}
+
+#[test]
+fn reports_one_of_operations_that_set_no_fields() {
+    let mut output = discount_output();
+    output["operations"][1] = json!({});
+
+    let result = validate_fixture_output(
+        &output,
+        &discount_schema(),
+        "cartLinesDiscountsGenerateRun",
+        "result",
+    );
+
+    assert_eq!(
+        messages(&result.errors),
+        vec!["Exactly one key must be specified for OneOf type \"CartOperation\". At \"operations.1\""]
+    );
+}
+
+#[test]
+fn reports_one_of_fields_set_to_null() {
+    let output = json!({ "operations": [{ "productDiscountsAdd": null }] });
+
+    let result = validate_fixture_output(
+        &output,
+        &discount_schema(),
+        "cartLinesDiscountsGenerateRun",
+        "result",
+    );
+
+    assert_eq!(
+        messages(&result.errors),
+        vec!["Field \"productDiscountsAdd\" must be non-null. At \"operations.0.productDiscountsAdd\""]
+    );
+}
+
+#[test]
+fn reports_nested_one_of_input_objects() {
+    let mut output = discount_output();
+    output["operations"][0]["orderDiscountsAdd"]["candidates"][0]["value"]["fixedAmount"] =
+        json!({ "amount": "5.0" });
+
+    let result = validate_fixture_output(
+        &output,
+        &discount_schema(),
+        "cartLinesDiscountsGenerateRun",
+        "result",
+    );
+
+    assert_eq!(
+        messages(&result.errors),
+        vec!["Exactly one key must be specified for OneOf type \"OrderDiscountCandidateValue\". At \"operations.0.orderDiscountsAdd.candidates.0.value\""]
+    );
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use common::{fixture_path, parse, test_app_path, test_schema};
+use serde_json::json;
+use shopify_function_test_helpers::{
+    load_input_query, load_schema, validate_input_query, validate_input_query_variables,
+};
+
+#[test]
+fn validates_a_valid_graphql_query_against_schema() {
+    let query = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+
+    let errors = validate_input_query(&query, &test_schema(), None);
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn returns_validation_errors_for_invalid_graphql_query() {
+    let query = load_input_query(fixture_path("wrong-fields-query.graphql")).unwrap();
+
+    let errors = validate_input_query(&query, &test_schema(), None);
+
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Cannot query field \"nonExistentField\" on type \"Item\".",
+            "Cannot query field \"anotherInvalidField\" on type \"ItemDetails\".",
+            "Cannot query field \"invalidMetadataField\" on type \"Metadata\".",
+        ]
+    );
+}
+
+#[test]
+fn reports_selections_on_leaf_fields_and_missing_selections_on_objects() {
+    let query = parse(
+        r#"
+        query {
+          version { length }
+          data
+        }
+        "#,
+    );
+
+    let errors = validate_input_query(&query, &test_schema(), None);
+
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Field \"version\" must not have a selection since type \"String\" has no subfields.",
+            "Field \"data\" of type \"DataContainer\" must have a selection of subfields. Did you mean \"data { ... }\"?",
+        ]
+    );
+}
+
+#[test]
+fn reports_unknown_and_missing_required_arguments() {
+    let query = parse(
+        r#"
+        query {
+          data {
+            items(last: 1) { id }
+            metafield(namespace: "app") { value }
+          }
+        }
+        "#,
+    );
+
+    let errors = validate_input_query(&query, &test_schema(), None);
+
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Unknown argument \"last\" on field \"DataContainer.items\".",
+            "Field \"metafield\" argument \"key\" of type \"String!\" is required, but it was not provided.",
+        ]
+    );
+}
+
+#[test]
+fn reports_unknown_and_unused_fragments() {
+    let query = parse(
+        r#"
+        query {
+          data { ...Missing }
+        }
+
+        fragment Unused on Item { id }
+        "#,
+    );
+
+    let errors = validate_input_query(&query, &test_schema(), None);
+
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Unknown fragment \"Missing\".",
+            "Fragment \"Unused\" is never used.",
+        ]
+    );
+}
+
+#[test]
+fn reports_undefined_and_unused_variables() {
+    let query = parse(
+        r#"
+        query Input($unused: Int) {
+          data {
+            items(first: $first) { id }
+          }
+        }
+        "#,
+    );
+
+    let errors = validate_input_query(&query, &test_schema(), None);
+
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Variable \"$first\" is not defined by operation \"Input\".",
+            "Variable \"$unused\" is never used in operation \"Input\".",
+        ]
+    );
+}
+
+#[test]
+fn reports_fields_restricted_to_other_targets() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let query = parse(
+        r#"
+        query Input {
+          enteredDiscountCodes
+          discount { discountClasses }
+        }
+        "#,
+    );
+
+    let errors = validate_input_query(&query, &schema, Some("cart.lines.discounts.generate.run"));
+
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Cannot query field \"enteredDiscountCodes\" on type \"Input\" for target \"cart.lines.discounts.generate.run\". \
+             It is restricted to targets: \"cart.lines.discounts.generate.fetch\", \"cart.delivery-options.discounts.generate.fetch\"."
+        ]
+    );
+}
+
+#[test]
+fn allows_restricted_fields_for_their_targets() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let query = parse("query Input { enteredDiscountCodes }");
+
+    let errors = validate_input_query(&query, &schema, Some("cart.lines.discounts.generate.fetch"));
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn does_not_check_targets_when_no_target_is_provided() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let query = parse("query Input { enteredDiscountCodes }");
+
+    let errors = validate_input_query(&query, &schema, None);
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn accepts_values_for_the_declared_variables() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let query = load_input_query(fixture_path("variables/tagged-cart-query.graphql")).unwrap();
+    let variables = json!({ "tags": ["VIP"], "configurationKey": "function-configuration" });
+
+    let errors = validate_input_query_variables(&query, &schema, variables.as_object().unwrap());
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn reports_missing_invalid_and_undeclared_variables() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let query = load_input_query(fixture_path("variables/tagged-cart-query.graphql")).unwrap();
+    let variables = json!({
+        "tags": ["VIP", 1],
+        "collectionIds": null,
+        "minimumSubtotal": "100.0"
+    });
+
+    let errors = validate_input_query_variables(&query, &schema, variables.as_object().unwrap());
+
+    let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Variable \"$tags\" got invalid value 1 at \"tags[1]\"; String cannot represent a non string value: 1",
+            "Variable \"$configurationKey\" of required type \"String!\" was not provided.",
+            "Variable \"$minimumSubtotal\" is not declared by the input query.",
+        ]
+    );
+}
+
+#[test]
+fn accepts_a_query_without_variables_and_no_values() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let query = parse("query Input { discount { discountClasses } }");
+
+    let errors = validate_input_query_variables(&query, &schema, &Default::default());
+
+    assert_eq!(errors, vec![]);
+}
@@ -0,0 +1,384 @@
+mod common;
+
+use common::{fixture_path, parse, test_app_path, test_schema};
+use serde_json::json;
+use shopify_function_test_helpers::{
+    inject_fetch_result, load_fixture, load_input_query, load_metafield_schemas, load_schema,
+    validate_test_assets, ValidateTestAssetsOptions,
+};
+
+#[test]
+fn determines_mutation_details_from_target() {
+    let schema = test_schema();
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+    let input_query = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+
+    let result = validate_test_assets(ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture: &fixture,
+        input_query: &input_query,
+        target: None,
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas: &[],
+    });
+
+    assert_eq!(result.mutation_name.as_deref(), Some("processData"));
+    assert_eq!(result.result_parameter_name.as_deref(), Some("result"));
+    assert_eq!(result.input_query.errors, vec![]);
+    assert_eq!(result.input_fixture.errors, vec![]);
+    assert_eq!(result.output_fixture.errors, vec![]);
+    assert_eq!(result.error, None);
+}
+
+#[test]
+fn detects_invalid_output_fixture_with_extra_fields() {
+    let schema = test_schema();
+    let mut fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+    let input_query = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+    fixture.expected_output["extraField"] = json!("should not exist");
+
+    let result = validate_test_assets(ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture: &fixture,
+        input_query: &input_query,
+        target: None,
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas: &[],
+    });
+
+    assert_eq!(result.input_query.errors, vec![]);
+    assert_eq!(result.input_fixture.errors, vec![]);
+    assert_eq!(result.output_fixture.errors.len(), 1);
+    assert!(result.output_fixture.errors[0]
+        .message
+        .contains("Field \"extraField\" is not defined by type \"ProcessDataResult\""));
+}
+
+#[test]
+fn detects_schema_mismatches_in_input_query() {
+    let schema = test_schema();
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+    let input_query = load_input_query(fixture_path("wrong-fields-query.graphql")).unwrap();
+
+    let result = validate_test_assets(ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture: &fixture,
+        input_query: &input_query,
+        target: None,
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas: &[],
+    });
+
+    let messages: Vec<&str> = result
+        .input_query
+        .errors
+        .iter()
+        .map(|error| error.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Cannot query field \"nonExistentField\" on type \"Item\".",
+            "Cannot query field \"anotherInvalidField\" on type \"ItemDetails\".",
+            "Cannot query field \"invalidMetadataField\" on type \"Metadata\".",
+        ]
+    );
+}
+
+#[test]
+fn handles_invalid_mutation_name() {
+    let schema = test_schema();
+    let fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+    let input_query = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+
+    let result = validate_test_assets(ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture: &fixture,
+        input_query: &input_query,
+        target: None,
+        mutation_name: Some("nonExistentMutation"),
+        result_parameter_name: Some("result"),
+        metafield_schemas: &[],
+    });
+
+    assert_eq!(result.output_fixture.errors.len(), 1);
+    assert!(result.output_fixture.errors[0]
+        .message
+        .contains("Mutation 'nonExistentMutation' not found"));
+}
+
+#[test]
+fn requires_target_when_mutation_details_are_not_provided() {
+    let schema = test_schema();
+    let mut fixture = load_fixture(fixture_path("valid-fixture.json")).unwrap();
+    let input_query = load_input_query(fixture_path("valid-query.graphql")).unwrap();
+    fixture.target = String::new();
+
+    let result = validate_test_assets(ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture: &fixture,
+        input_query: &input_query,
+        target: None,
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas: &[],
+    });
+
+    assert_eq!(
+        result.error.as_deref(),
+        Some(
+            "Fixture must contain target when mutationName and resultParameterName are not provided"
+        )
+    );
+}
+
+#[test]
+fn validates_discount_function_fixtures() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let metafield_schemas = load_metafield_schemas(test_app_path(
+        "discount-function-rs/tests/metafield-schemas.json",
+    ))
+    .unwrap();
+
+    for (fixture_name, query_name) in [
+        (
+            "cart-lines-valid-fixture.json",
+            "cart_lines_discounts_generate_run.graphql",
+        ),
+        (
+            "cart-lines-configured-fixture.json",
+            "cart_lines_discounts_generate_run.graphql",
+        ),
+        (
+            "cart-lines-partially-configured-fixture.json",
+            "cart_lines_discounts_generate_run.graphql",
+        ),
+        (
+            "delivery-valid-fixture.json",
+            "cart_delivery_options_discounts_generate_run.graphql",
+        ),
+        (
+            "delivery-configured-fixture.json",
+            "cart_delivery_options_discounts_generate_run.graphql",
+        ),
+    ] {
+        let fixture = load_fixture(test_app_path(&format!(
+            "discount-function-rs/tests/fixtures/{fixture_name}"
+        )))
+        .unwrap();
+        let input_query = load_input_query(test_app_path(&format!(
+            "discount-function-rs/src/{query_name}"
+        )))
+        .unwrap();
+
+        let result = validate_test_assets(ValidateTestAssetsOptions {
+            schema: &schema,
+            fixture: &fixture,
+            input_query: &input_query,
+            target: None,
+            mutation_name: None,
+            result_parameter_name: None,
+            metafield_schemas: &metafield_schemas,
+        });
+
+        assert_eq!(result.error, None, "{fixture_name}");
+        assert_eq!(result.input_query.errors, vec![], "{fixture_name}");
+        assert_eq!(result.input_fixture.errors, vec![], "{fixture_name}");
+        assert_eq!(result.output_fixture.errors, vec![], "{fixture_name}");
+    }
+}
+
+#[test]
+fn validates_both_stages_of_discount_function_fetch_fixtures() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+
+    for (fixture_name, query_prefix) in [
+        (
+            "cart-lines-fetch-fixture.json",
+            "cart_lines_discounts_generate",
+        ),
+        (
+            "delivery-fetch-fixture.json",
+            "cart_delivery_options_discounts_generate",
+        ),
+    ] {
+        let fixture = load_fixture(test_app_path(&format!(
+            "discount-function-rs/tests/fixtures/{fixture_name}"
+        )))
+        .unwrap();
+        let fetch_query = load_input_query(test_app_path(&format!(
+            "discount-function-rs/src/{query_prefix}_fetch.graphql"
+        )))
+        .unwrap();
+        let run_query = load_input_query(test_app_path(&format!(
+            "discount-function-rs/src/{query_prefix}_run.graphql"
+        )))
+        .unwrap();
+        let run_fixture = inject_fetch_result(&fixture, &run_query).unwrap();
+
+        for (fixture, input_query) in [
+            (&fixture.fetch.as_ref().unwrap().fixture(), &fetch_query),
+            (&run_fixture, &run_query),
+        ] {
+            let result = validate_test_assets(ValidateTestAssetsOptions {
+                schema: &schema,
+                fixture,
+                input_query,
+                target: None,
+                mutation_name: None,
+                result_parameter_name: None,
+                metafield_schemas: &[],
+            });
+
+            let stage = &fixture.target;
+            assert_eq!(result.error, None, "{fixture_name} {stage}");
+            assert_eq!(result.input_query.errors, vec![], "{fixture_name} {stage}");
+            assert_eq!(
+                result.input_fixture.errors,
+                vec![],
+                "{fixture_name} {stage}"
+            );
+            assert_eq!(
+                result.output_fixture.errors,
+                vec![],
+                "{fixture_name} {stage}"
+            );
+        }
+    }
+}
+
+#[test]
+fn reports_fields_restricted_to_other_targets_than_the_fixture_target() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let fixture = load_fixture(test_app_path(
+        "discount-function-rs/tests/fixtures/cart-lines-valid-fixture.json",
+    ))
+    .unwrap();
+    let input_query = parse(
+        r#"
+        query Input {
+          enteredDiscountCodes
+          cart { lines { id cost { subtotalAmount { amount } } } }
+          discount { discountClasses }
+        }
+        "#,
+    );
+
+    let result = validate_test_assets(ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture: &fixture,
+        input_query: &input_query,
+        target: None,
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas: &[],
+    });
+
+    assert_eq!(result.input_query.errors.len(), 1);
+    assert!(result.input_query.errors[0].message.contains(
+        "Cannot query field \"enteredDiscountCodes\" on type \"Input\" for target \"cart.lines.discounts.generate.run\""
+    ));
+}
+
+#[test]
+fn checks_restrictions_against_an_explicit_target() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let fixture = load_fixture(test_app_path(
+        "discount-function-rs/tests/fixtures/cart-lines-valid-fixture.json",
+    ))
+    .unwrap();
+    let input_query = parse("query Input { enteredDiscountCodes }");
+
+    let result = validate_test_assets(ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture: &fixture,
+        input_query: &input_query,
+        target: Some("cart.lines.discounts.generate.fetch"),
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas: &[],
+    });
+
+    assert_eq!(result.input_query.errors, vec![]);
+}
+
+#[test]
+fn checks_fixture_metafields_against_their_schemas() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let mut fixture = load_fixture(test_app_path(
+        "discount-function-rs/tests/fixtures/cart-lines-configured-fixture.json",
+    ))
+    .unwrap();
+    let input_query = load_input_query(test_app_path(
+        "discount-function-rs/src/cart_lines_discounts_generate_run.graphql",
+    ))
+    .unwrap();
+    let metafield_schemas = load_metafield_schemas(test_app_path(
+        "discount-function-rs/tests/metafield-schemas.json",
+    ))
+    .unwrap();
+    fixture.input["discount"]["metafield"]["jsonValue"]["order"]["percentage"] = json!("15");
+
+    let result = validate_test_assets(ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture: &fixture,
+        input_query: &input_query,
+        target: None,
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas: &metafield_schemas,
+    });
+
+    assert_eq!(
+        result.input_fixture.errors,
+        vec![fixture_error!(
+            r#"Invalid `function-configuration` metafield at `order.percentage`: expected a number, but got "15""#,
+            ["discount", "metafield", "jsonValue"]
+        )]
+    );
+}
+
+#[test]
+fn checks_the_fixture_variables_against_the_input_query() {
+    let schema = load_schema(test_app_path("discount-function-rs/schema.graphql")).unwrap();
+    let fixture = load_fixture(fixture_path("variables/tagged-cart-fixture.json")).unwrap();
+    let input_query =
+        load_input_query(fixture_path("variables/tagged-cart-query.graphql")).unwrap();
+    let options = |fixture| ValidateTestAssetsOptions {
+        schema: &schema,
+        fixture,
+        input_query: &input_query,
+        target: None,
+        mutation_name: None,
+        result_parameter_name: None,
+        metafield_schemas: &[],
+    };
+
+    let valid = validate_test_assets(options(&fixture));
+    assert_eq!(valid.input_query.errors, vec![]);
+    assert_eq!(valid.input_fixture.errors, vec![]);
+
+    let mut missing_variables = fixture.clone();
+    missing_variables.variables = json!({ "tags": ["VIP"] }).as_object().cloned();
+    let invalid = validate_test_assets(options(&missing_variables));
+    let messages: Vec<&str> = invalid
+        .input_query
+        .errors
+        .iter()
+        .map(|e| e.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["Variable \"$configurationKey\" of required type \"String!\" was not provided."]
+    );
+    assert_eq!(
+        invalid.input_fixture.errors,
+        vec![fixture_error!(
+            "Expected a `HasTagResponse` for each value of the `tags` argument (1), but got 2",
+            ["cart", "buyerIdentity", "customer", "hasTags"]
+        )]
+    );
+}
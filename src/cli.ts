@@ -0,0 +1,152 @@
+/**
+ * Command line interface that builds a function and runs its fixtures
+ */
+
+import fs from "fs";
+import path from "path";
+import { parseArgs } from "util";
+
+import {
+  createFixtureReport,
+  formatJsonReport,
+  formatJUnitReport,
+} from "./methods/create-fixture-report.js";
+import { loadMetafieldSchemas } from "./methods/load-metafield-schemas.js";
+import { formatFixtureResult } from "./methods/run-fixture.js";
+import {
+  runFunctionTests,
+  RunFunctionTestsResult,
+} from "./methods/run-function-tests.js";
+
+const USAGE = `Usage: shopify-function-test [function-dir] [options]
+
+Builds a Shopify function and runs every fixture in its tests/fixtures directory.
+
+Options:
+  --fixtures <dir>             The fixtures directory, relative to the function directory
+  --metafield-schemas <file>   A metafield schemas file to check fixture metafields against,
+                               relative to the function directory
+  --direct                     Run [extensions.build].command instead of the Shopify CLI
+  --cache                      Skip the build if the function's sources are unchanged
+  --json <file>                Write a JSON report of the results to a file
+  --junit <file>               Write a JUnit XML report of the results to a file
+  -h, --help                   Show this help`;
+
+/**
+ * Interface for where the command line interface writes
+ */
+export interface CliOutput {
+  log: (message: string) => void;
+  error: (message: string) => void;
+}
+
+/**
+ * Runs the command line interface
+ * @param {string[]} args - The command line arguments, without the node executable and script
+ * @param {CliOutput} output - Where to write results and errors (defaults to the console)
+ * @returns {Promise<number>} The exit code: 0 if every fixture passed, 1 otherwise
+ */
+export async function main(
+  args: string[],
+  output: CliOutput = console,
+): Promise<number> {
+  let parsed: ReturnType<typeof parseCliArgs>;
+  try {
+    parsed = parseCliArgs(args);
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    output.error(`${errorMessage}\n\n${USAGE}`);
+    return 1;
+  }
+  const { values, positionals } = parsed;
+
+  if (values.help) {
+    output.log(USAGE);
+    return 0;
+  }
+  if (positionals.length > 1) {
+    output.error(`Expected one function directory\n\n${USAGE}`);
+    return 1;
+  }
+
+  const functionDir = positionals[0] ?? ".";
+  let metafieldSchemas;
+  if (values["metafield-schemas"]) {
+    try {
+      metafieldSchemas = await loadMetafieldSchemas(
+        path.resolve(functionDir, values["metafield-schemas"]),
+      );
+    } catch (error) {
+      const errorMessage =
+        error instanceof Error ? error.message : String(error);
+      output.error(errorMessage);
+      return 1;
+    }
+  }
+
+  const result = await runFunctionTests({
+    functionDir,
+    fixturesDir: values.fixtures,
+    metafieldSchemas,
+    buildOptions: { direct: values.direct, cache: values.cache },
+    onFixtureResult: (fixture) => output.log(formatFixtureResult(fixture)),
+  });
+
+  if (result.error !== null) {
+    output.error(result.error);
+  } else {
+    output.log(`\n${summarize(result)}`);
+  }
+
+  const report = createFixtureReport(result.results, {
+    functionName: path.basename(path.resolve(functionDir)),
+    error: result.error,
+  });
+  const reports = [
+    { file: values.json, contents: () => formatJsonReport(report) },
+    { file: values.junit, contents: () => formatJUnitReport(report) },
+  ];
+  for (const { file, contents } of reports) {
+    if (!file) {
+      continue;
+    }
+    try {
+      await fs.promises.mkdir(path.dirname(file), { recursive: true });
+      await fs.promises.writeFile(file, contents());
+    } catch (error) {
+      const errorMessage =
+        error instanceof Error ? error.message : String(error);
+      output.error(`Failed to write report ${file}: ${errorMessage}`);
+      return 1;
+    }
+  }
+
+  return result.passed ? 0 : 1;
+}
+
+function parseCliArgs(args: string[]) {
+  return parseArgs({
+    args,
+    allowPositionals: true,
+    options: {
+      fixtures: { type: "string" },
+      "metafield-schemas": { type: "string" },
+      direct: { type: "boolean" },
+      cache: { type: "boolean" },
+      json: { type: "string" },
+      junit: { type: "string" },
+      help: { type: "boolean", short: "h" },
+    },
+  });
+}
+
+/**
+ * Summarizes how many fixtures passed, e.g. `Fixtures: 1 failed, 6 passed, 7 total`
+ */
+function summarize({ results }: RunFunctionTestsResult): string {
+  const failed = results.filter((result) => !result.passed).length;
+  const passed = results.length - failed;
+  const counts = failed > 0 ? [`${failed} failed`] : [];
+  counts.push(`${passed} passed`, `${results.length} total`);
+  return `Fixtures: ${counts.join(", ")}`;
+}
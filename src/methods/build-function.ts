@@ -2,10 +2,22 @@
  * Build a function run payload from a cart and options
  */
 
-import { spawn } from "child_process";
+import { spawn, SpawnOptions } from "child_process";
+import fs from "fs";
 import path from "path";
 import { fileURLToPath } from "url";
 
+import {
+  findCachedBuild,
+  hashBuildInputs,
+  recordBuildHash,
+} from "../utils/build-cache.js";
+import {
+  DEFAULT_WASM_PATH,
+  extensionTomlPath,
+  readExtensionToml,
+} from "../utils/read-extension-toml.js";
+
 /**
  * Interface for the build function result
  */
@@ -13,6 +25,35 @@ export interface BuildFunctionResult {
   success: boolean;
   output: string | null;
   error: string | null;
+  /** The absolute path to the built wasm module, from `[extensions.build].path` */
+  wasmPath?: string;
+  /** The size of the built wasm module in bytes */
+  wasmSize?: number;
+  /** Whether the build was skipped because nothing it depends on changed since the last one */
+  cached?: boolean;
+}
+
+/**
+ * Interface for the build function options
+ */
+export interface BuildFunctionOptions {
+  /**
+   * Run the function's `[extensions.build].command`, e.g. `cargo build --target=wasm32-wasip1 --release`,
+   * directly instead of `shopify app function build`, so the build needs neither the
+   * Shopify CLI nor its login
+   */
+  direct?: boolean;
+  /**
+   * Called with each chunk of output as the build writes it, e.g. cargo diagnostics.
+   * When building directly, defaults to writing it to this process's stdout and stderr.
+   */
+  onOutput?: (chunk: string, stream: "stdout" | "stderr") => void;
+  /**
+   * Skip the build when the files matching `[extensions.build].watch`, the Cargo manifest
+   * and the input queries are unchanged since the last successful build and its wasm is
+   * still there. Defaults to false.
+   */
+  cache?: boolean;
 }
 
 const __filename = fileURLToPath(import.meta.url);
@@ -21,10 +62,12 @@ const __dirname = path.dirname(__filename);
 /**
  * Build a function run payload from a cart and options
  * @param {string} functionPath - Optional path to the function directory
+ * @param {BuildFunctionOptions} options - Optional build options, e.g. to build without the Shopify CLI
  * @returns {Object} A function run payload
  */
 export async function buildFunction(
   functionPath?: string,
+  options: BuildFunctionOptions = {},
 ): Promise<BuildFunctionResult> {
   try {
     let functionDir;
@@ -47,51 +90,83 @@ export async function buildFunction(
       functionName = path.basename(functionDir);
     }
 
-    return new Promise((resolve, reject) => {
-      const shopifyProcess = spawn(
-        "shopify",
-        ["app", "function", "build", "--path", functionName],
-        {
-          cwd: appRootDir,
-          stdio: ["pipe", "pipe", "pipe"],
-          env: {
-            ...process.env,
-            SHOPIFY_INVOKED_BY: "shopify-function-test-helpers",
+    let build: () => Promise<string>;
+    if (options.direct) {
+      const command = await readBuildCommand(functionDir);
+      build = () =>
+        runBuild(
+          command,
+          [],
+          { cwd: functionDir, stdio: ["pipe", "pipe", "pipe"], shell: true },
+          `build command \`${command}\``,
+          options.onOutput ??
+            ((chunk, stream) => process[stream].write(chunk)),
+        );
+    } else {
+      build = () =>
+        runBuild(
+          "shopify",
+          ["app", "function", "build", "--path", functionName],
+          {
+            cwd: appRootDir,
+            stdio: ["pipe", "pipe", "pipe"],
+            env: {
+              ...process.env,
+              SHOPIFY_INVOKED_BY: "shopify-function-test-helpers",
+            },
           },
-        },
-      );
-
-      let stdout = "";
-      let stderr = "";
-
-      shopifyProcess.stdout.on("data", (data) => {
-        stdout += data.toString();
-      });
-
-      shopifyProcess.stderr.on("data", (data) => {
-        stderr += data.toString();
-      });
+          "shopify build command",
+          options.onOutput,
+        );
+    }
 
-      shopifyProcess.on("close", (code) => {
-        if (code !== 0) {
-          reject(
-            new Error(`Build command failed with exit code ${code}: ${stderr}`),
-          );
-          return;
-        }
+    // Without a readable shopify.extension.toml, the function is always built
+    const extension = await readExtensionToml(functionDir).catch(
+      () => undefined,
+    );
+    const wasmPath =
+      extension &&
+      path.resolve(functionDir, extension.build?.path ?? DEFAULT_WASM_PATH);
+    const buildHash =
+      extension && options.cache === true
+        ? await hashBuildInputs(
+            functionDir,
+            extension,
+            options.direct ? "direct" : "cli",
+          )
+        : undefined;
 
-        resolve({
+    if (wasmPath && buildHash) {
+      const wasmSize = await findCachedBuild(wasmPath, buildHash);
+      if (wasmSize !== undefined) {
+        return {
           success: true,
-          output: stdout.trim(),
+          output: "",
           error: null,
-        });
-      });
+          cached: true,
+          wasmPath,
+          wasmSize,
+        };
+      }
+    }
 
-      shopifyProcess.on("error", (error) => {
-        reject(
-          new Error(`Failed to start shopify build command: ${error.message}`),
-        );
-      });
+    return build().then(async (stdout) => {
+      const wasmSize = wasmPath ? await builtWasmSize(wasmPath) : undefined;
+      // The CLI may build elsewhere, e.g. a JavaScript function without a build path,
+      // but the build command must produce the configured module
+      if (options.direct && wasmSize === undefined) {
+        throw new Error(`The build did not produce ${wasmPath}`);
+      }
+      if (wasmPath && wasmSize !== undefined && buildHash) {
+        await recordBuildHash(wasmPath, buildHash);
+      }
+      return {
+        success: true,
+        output: stdout.trim(),
+        error: null,
+        cached: false,
+        ...(wasmSize !== undefined && { wasmPath, wasmSize }),
+      };
     });
   } catch (error) {
     if (error instanceof Error) {
@@ -109,3 +184,78 @@ export async function buildFunction(
     }
   }
 }
+
+/**
+ * Reads the function's `[extensions.build].command` to run it without the Shopify CLI
+ */
+async function readBuildCommand(functionDir: string): Promise<string> {
+  const filename = extensionTomlPath(functionDir);
+  let command;
+  try {
+    command = (await readExtensionToml(functionDir)).build?.command?.trim();
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    throw new Error(
+      `Failed to read the build command from ${filename}: ${errorMessage}`,
+    );
+  }
+  if (!command) {
+    throw new Error(
+      `${filename} has no [extensions.build].command; build the function with the Shopify CLI instead`,
+    );
+  }
+  return command;
+}
+
+/**
+ * Spawns a build and resolves with its stdout, rejecting if it fails
+ */
+function runBuild(
+  command: string,
+  args: string[],
+  spawnOptions: SpawnOptions,
+  description: string,
+  onOutput: BuildFunctionOptions["onOutput"],
+): Promise<string> {
+  return new Promise((resolve, reject) => {
+    const buildProcess = spawn(command, args, spawnOptions);
+
+    let stdout = "";
+    let stderr = "";
+
+    buildProcess.stdout?.on("data", (data) => {
+      stdout += data.toString();
+      onOutput?.(data.toString(), "stdout");
+    });
+
+    buildProcess.stderr?.on("data", (data) => {
+      stderr += data.toString();
+      onOutput?.(data.toString(), "stderr");
+    });
+
+    buildProcess.on("close", (code) => {
+      if (code !== 0) {
+        reject(
+          new Error(`Build command failed with exit code ${code}: ${stderr}`),
+        );
+        return;
+      }
+      resolve(stdout);
+    });
+
+    buildProcess.on("error", (error) => {
+      reject(new Error(`Failed to start ${description}: ${error.message}`));
+    });
+  });
+}
+
+/**
+ * The size of the wasm module a build produced, or undefined if it is missing
+ */
+async function builtWasmSize(wasmPath: string): Promise<number | undefined> {
+  try {
+    return (await fs.promises.stat(wasmPath)).size;
+  } catch {
+    return undefined;
+  }
+}
@@ -0,0 +1,236 @@
+/**
+ * Report which branches of an input query a target's fixtures never exercise
+ */
+
+import {
+  DocumentNode,
+  FieldNode,
+  getNamedType,
+  getNullableType,
+  GraphQLSchema,
+  isAbstractType,
+  isListType,
+  isNullableType,
+  Kind,
+} from "graphql";
+
+import { injectFetchResult } from "./inject-fetch-result.js";
+import { FetchFixtureData, FixtureData } from "./load-fixture.js";
+import { visitFixtureInput } from "./validate-fixture-input.js";
+
+/**
+ * Interface for check input coverage options
+ */
+export interface InputCoverageOptions {
+  schema: GraphQLSchema;
+  /** The target's input query */
+  inputQueryAST: DocumentNode;
+  /** The target, e.g. `cart.lines.discounts.generate.run` */
+  target: string;
+  /**
+   * The fixtures to measure. Fixtures for other targets are skipped, and a fixture
+   * whose fetch stage is for the target counts with its fetch input.
+   */
+  fixtures: FixtureData[];
+}
+
+/**
+ * Interface for how often the fixtures exercised the branches of a field
+ */
+export interface InputFieldCoverage {
+  /** The field's path in the input query by response key, e.g. `cart.lines.merchandise` */
+  path: string;
+  /** For a nullable field, how many values were null and how many were not */
+  nullable?: { null: number; nonNull: number };
+  /** For a list field, how many lists were empty and how many were not */
+  list?: { empty: number; nonEmpty: number };
+  /**
+   * For a union or interface field whose `__typename` is selected, how many values had
+   * each possible type
+   */
+  types?: Record<string, number>;
+}
+
+/**
+ * Interface for a branch of the input query that no fixture exercised
+ */
+export interface InputCoverageGap {
+  /** The field's path in the input query, e.g. `cart.lines.merchandise` */
+  path: string;
+  /** The branch: a null or non-null value, an empty or non-empty list, or a possible type */
+  branch: "null" | "non-null" | "empty" | "non-empty" | "type";
+  /** For a `type` branch, the type no value had, e.g. `CustomProduct` */
+  typeName?: string;
+  message: string;
+}
+
+/**
+ * Interface for the input coverage result
+ */
+export interface InputCoverageResult {
+  target: string;
+  /** The number of fixtures measured */
+  fixtures: number;
+  /** Every field with branches, in query order */
+  fields: InputFieldCoverage[];
+  /** The branches no fixture exercised */
+  gaps: InputCoverageGap[];
+}
+
+/**
+ * Measures which branches of a target's input query its fixtures exercise
+ *
+ * Walks each fixture's input with the same visitor as validateFixtureInput, and
+ * aggregates over all of them:
+ * - Nullable fields, whose value should be null in some fixture and not in another
+ * - List fields, which should be empty in some fixture and not in another
+ * - Union and interface fields that select `__typename`, such as `merchandise`, which
+ *   should have each possible type in some fixture
+ *
+ * Fields that are missing from a fixture, e.g. because an inline fragment does not
+ * apply, do not count.
+ * @param {InputCoverageOptions} options - The schema, the input query, the target and its fixtures
+ * @returns {InputCoverageResult} The coverage of each field and the branches never exercised
+ */
+export function checkInputCoverage({
+  schema,
+  inputQueryAST,
+  target,
+  fixtures,
+}: InputCoverageOptions): InputCoverageResult {
+  const fields = new Map<string, InputFieldCoverage>();
+  const inputs = fixtures.flatMap((fixture) =>
+    targetInputs(fixture, target, inputQueryAST),
+  );
+
+  for (const input of inputs) {
+    visitFixtureInput(
+      inputQueryAST,
+      schema,
+      input,
+      {},
+      ({ node, queryPath, fieldType, values }) => {
+        const nullableType = getNullableType(fieldType);
+        const namedType = getNamedType(fieldType);
+        const typenameKey = typenameResponseKey(node);
+        const nullable = isNullableType(fieldType);
+        const list = isListType(nullableType);
+        const abstractType =
+          isAbstractType(namedType) && typenameKey !== undefined
+            ? namedType
+            : undefined;
+        if (!nullable && !list && !abstractType) {
+          return;
+        }
+
+        const path = queryPath.join(".");
+        let coverage = fields.get(path);
+        if (!coverage) {
+          coverage = { path };
+          if (nullable) {
+            coverage.nullable = { null: 0, nonNull: 0 };
+          }
+          if (list) {
+            coverage.list = { empty: 0, nonEmpty: 0 };
+          }
+          if (abstractType) {
+            coverage.types = Object.fromEntries(
+              schema
+                .getPossibleTypes(abstractType)
+                .map(({ name }) => [name, 0]),
+            );
+          }
+          fields.set(path, coverage);
+        }
+
+        for (const fieldValue of values) {
+          if (coverage.nullable) {
+            coverage.nullable[fieldValue === null ? "null" : "nonNull"]++;
+          }
+          if (coverage.list && Array.isArray(fieldValue)) {
+            coverage.list[fieldValue.length === 0 ? "empty" : "nonEmpty"]++;
+          }
+          if (coverage.types && typenameKey !== undefined) {
+            for (const object of flattenLists(fieldValue)) {
+              const typename = object?.[typenameKey];
+              if (typeof typename === "string" && typename in coverage.types) {
+                coverage.types[typename]++;
+              }
+            }
+          }
+        }
+      },
+    );
+  }
+
+  const gaps: InputCoverageGap[] = [];
+  for (const { path, nullable, list, types } of fields.values()) {
+    if (nullable?.null === 0) {
+      gaps.push({ path, branch: "null", message: "Never null" });
+    }
+    if (nullable?.nonNull === 0) {
+      gaps.push({ path, branch: "non-null", message: "Always null" });
+    }
+    if (list?.empty === 0) {
+      gaps.push({ path, branch: "empty", message: "Never an empty list" });
+    }
+    if (list?.nonEmpty === 0) {
+      gaps.push({
+        path,
+        branch: "non-empty",
+        message: "Never a non-empty list",
+      });
+    }
+    for (const [typeName, count] of Object.entries(types ?? {})) {
+      if (count === 0) {
+        gaps.push({
+          path,
+          branch: "type",
+          typeName,
+          message: `Never a \`${typeName}\``,
+        });
+      }
+    }
+  }
+
+  return {
+    target,
+    fixtures: inputs.length,
+    fields: [...fields.values()],
+    gaps,
+  };
+}
+
+/**
+ * The input of a fixture's stage for a target, if it has one
+ */
+function targetInputs(
+  fixture: FixtureData,
+  target: string,
+  inputQueryAST: DocumentNode,
+): Record<string, any>[] {
+  const stages: (FixtureData | FetchFixtureData)[] = [];
+  if (fixture.target === target) {
+    // A run stage gets its fetch stage's response as `fetchResult`
+    stages.push(injectFetchResult(fixture, inputQueryAST));
+  }
+  if (fixture.fetch?.target === target) {
+    stages.push(fixture.fetch);
+  }
+  return stages.map((stage) => stage.input);
+}
+
+/**
+ * The response key of the `__typename` selected directly on a field, if any
+ */
+function typenameResponseKey(node: FieldNode): string | undefined {
+  const typename = node.selectionSet?.selections.find(
+    (selection): selection is FieldNode =>
+      selection.kind === Kind.FIELD && selection.name.value === "__typename",
+  );
+  return typename && (typename.alias?.value ?? "__typename");
+}
+
+function flattenLists(value: any): any[] {
+  return Array.isArray(value) ? value.flatMap(flattenLists) : [value];
+}
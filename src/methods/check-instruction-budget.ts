@@ -0,0 +1,224 @@
+/**
+ * Check a function run against the instruction limit scaled by @scaleLimits
+ */
+
+import {
+  DocumentNode,
+  getDirectiveValues,
+  GraphQLSchema,
+  TypeInfo,
+  visit,
+  visitWithTypeInfo,
+} from "graphql";
+
+import { inlineNamedFragmentSpreads } from "../utils/inline-named-fragment-spreads.js";
+
+import { FixtureData } from "./load-fixture.js";
+
+/** Instruction limit for a function run before any scaling */
+export const DEFAULT_INSTRUCTION_LIMIT = 11_000_000;
+
+/** Limits never scale by more than this factor */
+export const MAX_SCALE_FACTOR = 10;
+
+/**
+ * Interface for a list field annotated with @scaleLimits, with the number of items found in the input
+ */
+export interface ScaledListField {
+  /** Schema coordinate of the field, e.g. `Cart.lines` */
+  coordinate: string;
+  rate: number;
+  count: number;
+}
+
+/**
+ * Interface for the scale factor of a fixture input and the fields it comes from
+ */
+export interface ScaleFactorResult {
+  scaleFactor: number;
+  scaledFields: ScaledListField[];
+}
+
+/**
+ * Interface for the instruction budget check options
+ */
+export interface InstructionBudgetOptions {
+  schema: GraphQLSchema;
+  fixture: FixtureData;
+  inputQueryAST: DocumentNode;
+  /** The instruction count of the run (`result.instructions` from runFunction) */
+  instructions: number | null;
+  /** The limit before scaling (defaults to DEFAULT_INSTRUCTION_LIMIT) */
+  instructionLimit?: number;
+}
+
+/**
+ * Interface for an instruction budget error
+ */
+export interface InstructionBudgetError {
+  message: string;
+}
+
+/**
+ * Interface for the instruction budget check result
+ */
+export interface InstructionBudgetResult {
+  scaleFactor: number;
+  scaledFields: ScaledListField[];
+  /** The instruction limit after scaling */
+  instructionLimit: number;
+  instructions: number | null;
+  errors: InstructionBudgetError[];
+}
+
+/**
+ * Computes how much a function's resource limits scale for an input
+ *
+ * Each list field annotated with `@scaleLimits(rate: ...)` in the schema contributes
+ * `rate * itemCount`, where the item count is the total number of items the field
+ * holds across the input. The scale factor is the largest contribution, clamped
+ * between 1 and MAX_SCALE_FACTOR.
+ * @param {DocumentNode} queryAST - The input query the fixture input was produced from
+ * @param {GraphQLSchema} schema - The schema containing the @scaleLimits annotations
+ * @param {any} input - The fixture input
+ * @returns {ScaleFactorResult} The scale factor and the annotated fields found in the input
+ */
+export function computeScaleFactor(
+  queryAST: DocumentNode,
+  schema: GraphQLSchema,
+  input: any,
+): ScaleFactorResult {
+  const scaleLimitsDirective = schema.getDirective("scaleLimits");
+  const typeInfo = new TypeInfo(schema);
+  const valueStack: any[][] = [[input]];
+  const countedParents = new Map<string, Set<any>>();
+  const scaledFields = new Map<string, ScaledListField>();
+
+  if (scaleLimitsDirective) {
+    visit(
+      inlineNamedFragmentSpreads(queryAST),
+      visitWithTypeInfo(typeInfo, {
+        Field: {
+          enter(node) {
+            const parentType = typeInfo.getParentType();
+            const fieldDefinition = typeInfo.getFieldDef();
+            const responseKey = node.alias?.value ?? node.name.value;
+            const parents = valueStack[valueStack.length - 1];
+
+            const rate = fieldDefinition?.astNode
+              ? getDirectiveValues(
+                  scaleLimitsDirective,
+                  fieldDefinition.astNode,
+                )?.rate
+              : undefined;
+
+            if (parentType && typeof rate === "number") {
+              const coordinate = `${parentType.name}.${fieldDefinition!.name}`;
+              const counted = countedParents.get(coordinate) ?? new Set<any>();
+              countedParents.set(coordinate, counted);
+
+              // A field selected more than once (or through several fragments) still
+              // holds the same items, so each parent object is only counted once
+              let count = 0;
+              for (const parent of parents) {
+                if (!counted.has(parent)) {
+                  counted.add(parent);
+                  count += countListItems(parent[responseKey]);
+                }
+              }
+
+              const scaledField = scaledFields.get(coordinate) ?? {
+                coordinate,
+                rate,
+                count: 0,
+              };
+              scaledField.count += count;
+              scaledFields.set(coordinate, scaledField);
+            }
+
+            valueStack.push(
+              parents.flatMap((parent) => flatten(parent[responseKey])),
+            );
+          },
+          leave() {
+            valueStack.pop();
+          },
+        },
+      }),
+    );
+  }
+
+  const fields = Array.from(scaledFields.values());
+  const largestFactor = Math.max(
+    1,
+    ...fields.map((field) => field.rate * field.count),
+  );
+
+  return {
+    scaleFactor: Math.min(largestFactor, MAX_SCALE_FACTOR),
+    scaledFields: fields,
+  };
+}
+
+/**
+ * Checks that a function run stays within the instruction limit for its input
+ *
+ * The limit is DEFAULT_INSTRUCTION_LIMIT (or `instructionLimit`) multiplied by the
+ * scale factor computed from the fixture input and the schema's @scaleLimits rates.
+ * @param {InstructionBudgetOptions} options - The schema, fixture, query and instruction count
+ * @returns {InstructionBudgetResult} The scaled limit and an error if the run exceeded it
+ */
+export function checkInstructionBudget(
+  options: InstructionBudgetOptions,
+): InstructionBudgetResult {
+  const { schema, fixture, inputQueryAST, instructions } = options;
+  const baseInstructionLimit =
+    options.instructionLimit ?? DEFAULT_INSTRUCTION_LIMIT;
+
+  const { scaleFactor, scaledFields } = computeScaleFactor(
+    inputQueryAST,
+    schema,
+    fixture.input,
+  );
+  const instructionLimit = Math.floor(baseInstructionLimit * scaleFactor);
+  const errors: InstructionBudgetError[] = [];
+
+  if (instructions === null) {
+    errors.push({
+      message:
+        "The run did not report an instruction count. Run the function with runFunction to measure instructions.",
+    });
+  } else if (instructions > instructionLimit) {
+    errors.push({
+      message: `Function used ${instructions} instructions, exceeding the limit of ${instructionLimit} (${baseInstructionLimit} scaled by ${scaleFactor})`,
+    });
+  }
+
+  return {
+    scaleFactor,
+    scaledFields,
+    instructionLimit,
+    instructions,
+    errors,
+  };
+}
+
+/**
+ * The number of non-null items in a (possibly nested) list value
+ */
+function countListItems(value: any): number {
+  if (Array.isArray(value)) {
+    return value.reduce((count, item) => count + countListItems(item), 0);
+  }
+  return value === null || value === undefined ? 0 : 1;
+}
+
+/**
+ * The objects held by a (possibly nested) list value, or the value itself if it is an object
+ */
+function flatten(value: any): any[] {
+  if (Array.isArray(value)) {
+    return value.flatMap(flatten);
+  }
+  return value !== null && typeof value === "object" ? [value] : [];
+}
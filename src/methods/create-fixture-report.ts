@@ -0,0 +1,325 @@
+/**
+ * Turn fixture results into a JSON report or JUnit XML for archiving
+ */
+
+import path from "path";
+
+import { OutputDifference } from "./diff-function-output.js";
+import {
+  FixtureRunResult,
+  FixtureStageResult,
+  formatFieldPath,
+  stageFailures,
+} from "./run-fixture.js";
+
+/**
+ * The version of the JSON report format, increased when it changes incompatibly
+ */
+export const FIXTURE_REPORT_VERSION = 1;
+
+/**
+ * Interface for an error found while validating a fixture
+ */
+export interface ReportedValidationError {
+  message: string;
+  /** The path of the fixture field with the error, e.g. `cart.lines[0].cost`, if any */
+  path: string | null;
+}
+
+/**
+ * Interface for the result of one fixture for one target
+ */
+export interface FixtureReportEntry {
+  /** The fixture's file name */
+  fixture: string;
+  passed: boolean;
+  /** The errors of each validation phase, or null if the fixture was not validated */
+  validation: {
+    error: string | null;
+    inputQuery: ReportedValidationError[];
+    inputFixture: ReportedValidationError[];
+    outputFixture: ReportedValidationError[];
+  } | null;
+  /** Differences between the output and the expected output */
+  differences: OutputDifference[];
+  /** The instructions the run executed, or null if it did not run or report them */
+  instructions: number | null;
+  /** The memory the run used in kilobytes, or null if it did not run or report it */
+  memoryUsage: number | null;
+  /** Why the fixture could not run, e.g. a failed run */
+  error: string | null;
+  /** Each problem on one line, as listed by stageFailures */
+  failures: string[];
+}
+
+/**
+ * Interface for the fixtures of one target
+ */
+export interface TargetReport {
+  target: string;
+  passed: number;
+  failed: number;
+  fixtures: FixtureReportEntry[];
+}
+
+/**
+ * Interface for the report of a function's fixtures
+ */
+export interface FixtureReport {
+  version: number;
+  /** The function's name, e.g. its directory name */
+  function: string;
+  summary: {
+    fixtures: number;
+    passed: number;
+    failed: number;
+  };
+  /** Why the fixtures could not run at all, e.g. a failed build */
+  error: string | null;
+  /** Fixtures that could not be loaded, so have no target */
+  fixtureErrors: { fixture: string; error: string }[];
+  /** The fixtures of each target, by target name */
+  targets: TargetReport[];
+}
+
+/**
+ * Interface for create fixture report options
+ */
+export interface CreateFixtureReportOptions {
+  /** The function's name, e.g. its directory name */
+  functionName: string;
+  /** Why the fixtures could not run at all, e.g. a failed build */
+  error?: string | null;
+}
+
+/**
+ * Creates a report of a function's fixture results, grouped by target
+ *
+ * A fixture with a fetch stage is reported under both of its targets. The report holds
+ * no timings, and its targets and fixtures are sorted by name, so the same results give
+ * the same report and reports can be archived and compared.
+ * @param {FixtureRunResult[]} results - The fixture results (from runFixture or runFunctionTests)
+ * @param {CreateFixtureReportOptions} options - The function's name and why it could not run
+ * @returns {FixtureReport} The report
+ */
+export function createFixtureReport(
+  results: FixtureRunResult[],
+  { functionName, error = null }: CreateFixtureReportOptions,
+): FixtureReport {
+  const targets = new Map<string, TargetReport>();
+  const fixtureErrors: FixtureReport["fixtureErrors"] = [];
+
+  for (const result of results) {
+    const fixture = path.basename(result.fixturePath);
+    if (result.error !== null) {
+      fixtureErrors.push({ fixture, error: result.error });
+    }
+    for (const stage of result.stages) {
+      let target = targets.get(stage.target);
+      if (!target) {
+        target = { target: stage.target, passed: 0, failed: 0, fixtures: [] };
+        targets.set(stage.target, target);
+      }
+      target[stage.passed ? "passed" : "failed"]++;
+      target.fixtures.push(reportEntry(fixture, stage));
+    }
+  }
+
+  const passed = results.filter((result) => result.passed).length;
+  return {
+    version: FIXTURE_REPORT_VERSION,
+    function: functionName,
+    summary: {
+      fixtures: results.length,
+      passed,
+      failed: results.length - passed,
+    },
+    error,
+    fixtureErrors: fixtureErrors.sort(byName((entry) => entry.fixture)),
+    targets: [...targets.values()]
+      .sort(byName((target) => target.target))
+      .map((target) => ({
+        ...target,
+        fixtures: target.fixtures.sort(byName((entry) => entry.fixture)),
+      })),
+  };
+}
+
+/**
+ * Formats a report as JSON, with a trailing newline
+ * @param {FixtureReport} report - The report (from createFixtureReport)
+ * @returns {string} The JSON
+ */
+export function formatJsonReport(report: FixtureReport): string {
+  return `${JSON.stringify(report, null, 2)}\n`;
+}
+
+/**
+ * Formats a report as JUnit XML
+ *
+ * Each target is a `<testsuite>` named `<function>.<target>`, with a `<testcase>` per
+ * fixture. A failing fixture has a `<failure>` listing each problem, and the
+ * instructions and memory a run used are `<property>` elements of its test case.
+ * Fixtures that could not be loaded, and a function that could not run at all, are
+ * `<error>` test cases of a test suite named after the function.
+ * @param {FixtureReport} report - The report (from createFixtureReport)
+ * @returns {string} The XML
+ */
+export function formatJUnitReport(report: FixtureReport): string {
+  const suites: string[] = [];
+
+  const errorCases = [
+    ...(report.error === null ? [] : [{ name: "setup", error: report.error }]),
+    ...report.fixtureErrors.map(({ fixture, error }) => ({
+      name: fixture,
+      error,
+    })),
+  ];
+  if (errorCases.length > 0) {
+    const cases = errorCases.map(({ name, error }) =>
+      [
+        `    <testcase classname="${xml(report.function)}" name="${xml(name)}">`,
+        `      <error message="${xml(firstLine(error))}">${xml(error)}</error>`,
+        `    </testcase>`,
+      ].join("\n"),
+    );
+    suites.push(
+      testSuite(
+        report.function,
+        errorCases.length,
+        0,
+        errorCases.length,
+        cases,
+      ),
+    );
+  }
+
+  for (const { target, failed, fixtures } of report.targets) {
+    const suiteName = `${report.function}.${target}`;
+    const cases = fixtures.map((entry) => {
+      const lines = [
+        `    <testcase classname="${xml(suiteName)}" name="${xml(entry.fixture)}">`,
+      ];
+      const properties = [
+        ["instructions", entry.instructions],
+        ["memoryUsage", entry.memoryUsage],
+      ].filter(([, value]) => value !== null);
+      if (properties.length > 0) {
+        lines.push("      <properties>");
+        for (const [name, value] of properties) {
+          lines.push(`        <property name="${name}" value="${value}"/>`);
+        }
+        lines.push("      </properties>");
+      }
+      if (!entry.passed) {
+        const message = firstLine(entry.failures[0] ?? "Fixture failed");
+        lines.push(
+          `      <failure message="${xml(message)}" type="${failureType(entry)}">${xml(entry.failures.join("\n"))}</failure>`,
+        );
+      }
+      lines.push("    </testcase>");
+      return lines.join("\n");
+    });
+    suites.push(testSuite(suiteName, fixtures.length, failed, 0, cases));
+  }
+
+  const tests =
+    errorCases.length +
+    report.targets.reduce((sum, target) => sum + target.fixtures.length, 0);
+  const failures = report.targets.reduce(
+    (sum, target) => sum + target.failed,
+    0,
+  );
+  return [
+    `<?xml version="1.0" encoding="UTF-8"?>`,
+    `<testsuites name="${xml(report.function)}" tests="${tests}" failures="${failures}" errors="${errorCases.length}">`,
+    ...suites,
+    `</testsuites>`,
+    "",
+  ].join("\n");
+}
+
+function reportEntry(
+  fixture: string,
+  stage: FixtureStageResult,
+): FixtureReportEntry {
+  const { validation, run } = stage;
+  return {
+    fixture,
+    passed: stage.passed,
+    validation: validation && {
+      error: validation.error ?? null,
+      inputQuery: validation.inputQuery.errors.map(({ message }) => ({
+        message,
+        path: null,
+      })),
+      inputFixture: validation.inputFixture.errors.map(
+        ({ message, path: fieldPath }) => ({
+          message,
+          path: fieldPath.length > 0 ? formatFieldPath(fieldPath) : null,
+        }),
+      ),
+      outputFixture: validation.outputFixture.errors.map(({ message }) => ({
+        message,
+        path: null,
+      })),
+    },
+    differences: stage.differences,
+    instructions: run?.instructions ?? null,
+    memoryUsage: run?.memoryUsage ?? null,
+    error: stage.error,
+    failures: stageFailures(stage),
+  };
+}
+
+/**
+ * The phase that failed first: `validation`, `run` or `output`
+ */
+function failureType(entry: FixtureReportEntry): string {
+  const { validation } = entry;
+  if (
+    validation &&
+    (validation.error !== null ||
+      validation.inputQuery.length > 0 ||
+      validation.inputFixture.length > 0 ||
+      validation.outputFixture.length > 0)
+  ) {
+    return "validation";
+  }
+  return entry.error === null ? "output" : "run";
+}
+
+function testSuite(
+  name: string,
+  tests: number,
+  failures: number,
+  errors: number,
+  cases: string[],
+): string {
+  return [
+    `  <testsuite name="${xml(name)}" tests="${tests}" failures="${failures}" errors="${errors}">`,
+    ...cases,
+    `  </testsuite>`,
+  ].join("\n");
+}
+
+function byName<T>(name: (item: T) => string) {
+  return (a: T, b: T) => (name(a) < name(b) ? -1 : name(a) > name(b) ? 1 : 0);
+}
+
+function firstLine(text: string): string {
+  return text.split("\n")[0];
+}
+
+/**
+ * Escapes text for an XML attribute or element, dropping characters XML cannot hold
+ */
+function xml(text: string): string {
+  return text
+    .replace(/[^\t\n\r\x20-\uD7FF\uE000-\uFFFD\u{10000}-\u{10FFFF}]/gu, "")
+    .replace(/&/g, "&amp;")
+    .replace(/</g, "&lt;")
+    .replace(/>/g, "&gt;")
+    .replace(/"/g, "&quot;")
+    .replace(/'/g, "&apos;");
+}
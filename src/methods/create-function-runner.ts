@@ -0,0 +1,277 @@
+/**
+ * Run function exports in-process with an embedded WASI runtime
+ */
+
+import fs from "fs";
+import os from "os";
+import path from "path";
+import { WASI } from "node:wasi";
+
+import { FixtureData } from "./load-fixture.js";
+import { RunFunctionResult } from "./run-function.js";
+
+const WASI_MODULE = "wasi_snapshot_preview1";
+/**
+ * The provider of the Shopify Functions WASM API, whose functions read their input
+ * and write their output as MessagePack rather than JSON
+ */
+const MSGPACK_PROVIDER_MODULE = "shopify_function_v1";
+
+/**
+ * Interface for an in-process function runner
+ */
+export interface FunctionRunner {
+  /**
+   * Run the export named in the fixture with the fixture input on stdin
+   * @param {FixtureData} fixture - The fixture data containing export, input, and target
+   * @returns {Promise<RunFunctionResult>} The function run result
+   */
+  run(fixture: FixtureData): Promise<RunFunctionResult>;
+  /**
+   * Remove the temporary files used to pass stdin/stdout to the module
+   */
+  close(): Promise<void>;
+}
+
+/**
+ * Interface for the in-process function runner options
+ */
+export interface CreateFunctionRunnerOptions {
+  /**
+   * Directory of the provider modules the function imports from, named after the
+   * module they provide, e.g. `javy_quickjs_provider_v3.wasm` for Javy
+   */
+  providersDir?: string;
+}
+
+/**
+ * A compiled provider module and the import module name it is linked under
+ */
+interface Provider {
+  name: string;
+  module: WebAssembly.Module;
+}
+
+/**
+ * Create a runner that compiles the WASM module once and runs its exports in-process
+ *
+ * Each run gets a fresh instance (and memory) of the compiled module, so runs are
+ * isolated from each other just like separate function-runner processes. The fixture
+ * input is written to the module's stdin and its stdout is parsed as the output.
+ *
+ * Imports other than WASI are linked to provider modules, as function-runner does:
+ * each run instantiates the provider named after the import module, initializes it,
+ * and shares the run's stdin, stdout and stderr with it. Functions built on the
+ * Shopify Functions WASM API, which import from `shopify_function_v1`, read and write
+ * MessagePack instead of JSON and are rejected; run them with runFunction or the Rust
+ * `create_function_runner`.
+ *
+ * The JavaScript engine does not count instructions, so they are reported as null.
+ * The memory usage is the size of the function's and providers' memories after the
+ * run, in kilobytes.
+ * @param {string} wasmPath - Path to the WASM file
+ * @param {CreateFunctionRunnerOptions} options - Optional runner options, e.g. where to find providers
+ * @returns {Promise<FunctionRunner>} A runner for the module's exports
+ * @throws {Error} If the module or a provider cannot be compiled, an import has no provider or the module imports from `shopify_function_v1`
+ */
+export async function createFunctionRunner(
+  wasmPath: string,
+  options: CreateFunctionRunnerOptions = {},
+): Promise<FunctionRunner> {
+  const module = await compileModule(wasmPath);
+
+  const providerNames = [
+    ...new Set(
+      WebAssembly.Module.imports(module)
+        .map((moduleImport) => moduleImport.module)
+        .filter((moduleName) => moduleName !== WASI_MODULE),
+    ),
+  ];
+  const providers: Provider[] = [];
+  if (providerNames.includes(MSGPACK_PROVIDER_MODULE)) {
+    throw new Error(
+      `WASM module ${wasmPath} imports from ${MSGPACK_PROVIDER_MODULE}, whose MessagePack input and output are not supported in-process. ` +
+        `Use runFunction to run this function with function-runner, or create_function_runner from the Rust test helpers.`,
+    );
+  }
+  for (const name of providerNames) {
+    if (!options.providersDir) {
+      throw new Error(
+        `WASM module ${wasmPath} imports from ${name}, which needs a provider module. ` +
+          `Set providersDir to a directory containing ${name}.wasm, or use runFunction to run this function with function-runner.`,
+      );
+    }
+    providers.push({
+      name,
+      module: await compileModule(
+        path.join(options.providersDir, `${name}.wasm`),
+      ),
+    });
+  }
+
+  const exportNames = new Set(
+    WebAssembly.Module.exports(module)
+      .filter((moduleExport) => moduleExport.kind === "function")
+      .map((moduleExport) => moduleExport.name),
+  );
+  const tempDir = await fs.promises.mkdtemp(
+    path.join(os.tmpdir(), "shopify-function-runner-"),
+  );
+  let runCount = 0;
+
+  return {
+    async run(fixture: FixtureData): Promise<RunFunctionResult> {
+      if (!exportNames.has(fixture.export)) {
+        return {
+          result: null,
+          error: `Export '${fixture.export}' not found in ${wasmPath}`,
+        };
+      }
+
+      runCount += 1;
+      const stdinPath = path.join(tempDir, `${runCount}.stdin`);
+      const stdoutPath = path.join(tempDir, `${runCount}.stdout`);
+      const stderrPath = path.join(tempDir, `${runCount}.stderr`);
+      const inputJson = JSON.stringify(fixture.input);
+      await fs.promises.writeFile(stdinPath, inputJson);
+
+      const stdin = fs.openSync(stdinPath, "r");
+      const stdout = fs.openSync(stdoutPath, "w+");
+      const stderr = fs.openSync(stderrPath, "w+");
+      const createWasi = () =>
+        new WASI({
+          version: "preview1",
+          args: [path.basename(wasmPath)],
+          env: {},
+          stdin,
+          stdout,
+          stderr,
+          returnOnExit: true,
+        });
+
+      try {
+        const wasi = createWasi();
+        const imports = wasi.getImportObject() as WebAssembly.Imports;
+        const instances: WebAssembly.Instance[] = [];
+        for (const provider of providers) {
+          const providerInstance = await instantiateProvider(
+            provider,
+            createWasi(),
+          );
+          imports[provider.name] =
+            providerInstance.exports as WebAssembly.ModuleImports;
+          instances.push(providerInstance);
+        }
+        const instance = await WebAssembly.instantiate(module, imports);
+        instances.push(instance);
+
+        // Named exports are called directly (as function-runner does), so WASI only
+        // needs to be bound to the instance memory rather than started via `_start`
+        if (instance.exports.memory instanceof WebAssembly.Memory) {
+          wasi.initialize({ exports: { memory: instance.exports.memory } });
+        }
+
+        try {
+          (instance.exports[fixture.export] as () => void)();
+        } catch (error) {
+          const errorMessage =
+            error instanceof Error ? error.message : String(error);
+          const stderrOutput = fs.readFileSync(stderrPath, "utf8");
+          return {
+            result: null,
+            error: `Function export '${fixture.export}' failed: ${errorMessage}${stderrOutput ? `\n${stderrOutput}` : ""}`,
+          };
+        }
+
+        const output = fs.readFileSync(stdoutPath, "utf8");
+        try {
+          return {
+            result: {
+              output: JSON.parse(output),
+              instructions: null,
+              memoryUsage: memoryUsage(instances),
+              inputSize: Buffer.byteLength(inputJson),
+              outputSize: Buffer.byteLength(output.trim()),
+              logs: fs.readFileSync(stderrPath, "utf8"),
+            },
+            error: null,
+          };
+        } catch (parseError) {
+          return {
+            result: null,
+            error: `Failed to parse function output: ${parseError instanceof Error ? parseError.message : "Unknown error"}`,
+          };
+        }
+      } catch (error) {
+        return {
+          result: null,
+          error: error instanceof Error ? error.message : String(error),
+        };
+      } finally {
+        fs.closeSync(stdin);
+        fs.closeSync(stdout);
+        fs.closeSync(stderr);
+        await Promise.all(
+          [stdinPath, stdoutPath, stderrPath].map((file) =>
+            fs.promises.rm(file, { force: true }),
+          ),
+        );
+      }
+    },
+
+    async close(): Promise<void> {
+      await fs.promises.rm(tempDir, { recursive: true, force: true });
+    },
+  };
+}
+
+/**
+ * Compiles a WASM module
+ */
+async function compileModule(wasmPath: string): Promise<WebAssembly.Module> {
+  try {
+    const wasm = await fs.promises.readFile(wasmPath);
+    return await WebAssembly.compile(wasm);
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    throw new Error(
+      `Failed to compile WASM module ${wasmPath}: ${errorMessage}`,
+    );
+  }
+}
+
+/**
+ * Instantiates a provider with its own WASI bindings and runs its initializer
+ */
+async function instantiateProvider(
+  provider: Provider,
+  wasi: WASI,
+): Promise<WebAssembly.Instance> {
+  const instance = await WebAssembly.instantiate(
+    provider.module,
+    wasi.getImportObject() as WebAssembly.Imports,
+  );
+  const { memory, _initialize } = instance.exports;
+  if (memory instanceof WebAssembly.Memory) {
+    // Binds WASI to the provider's memory and calls `_initialize`, if any
+    wasi.initialize(instance);
+  } else if (typeof _initialize === "function") {
+    _initialize();
+  }
+  return instance;
+}
+
+/**
+ * The size of the memories the instances export, in kilobytes
+ */
+function memoryUsage(instances: WebAssembly.Instance[]): number {
+  const memories = new Set(
+    instances
+      .map((instance) => instance.exports.memory)
+      .filter((memory) => memory instanceof WebAssembly.Memory),
+  );
+  return [...memories].reduce(
+    (size, memory) => size + memory.buffer.byteLength / 1024,
+    0,
+  );
+}
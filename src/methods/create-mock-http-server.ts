@@ -0,0 +1,329 @@
+/**
+ * Serve canned HTTP responses locally to the requests of fetch targets
+ */
+
+import http from "http";
+import { AddressInfo } from "net";
+import { isDeepStrictEqual } from "util";
+
+import { HttpResponseFixture } from "./load-fixture.js";
+
+const HTTP_METHODS = ["GET", "POST"];
+
+// Hop-by-hop and transport headers, which are added by the HTTP connection rather
+// than the route, so they are left out of the response a function receives
+const TRANSPORT_HEADERS = new Set([
+  "connection",
+  "content-length",
+  "date",
+  "keep-alive",
+  "proxy-authenticate",
+  "proxy-authorization",
+  "te",
+  "trailer",
+  "transfer-encoding",
+  "upgrade",
+]);
+
+/**
+ * Interface for the `HttpRequest` a fetch target returns
+ */
+export interface HttpRequestData {
+  method: string;
+  url: string;
+  headers?: { name: string; value: string }[];
+  body?: string | null;
+  jsonBody?: any;
+  policy: { readTimeoutMs: number };
+}
+
+/**
+ * Interface for a canned response of the mock HTTP server
+ */
+export interface MockHttpRoute {
+  method: string;
+  url: string;
+  /** The expected body: a string is compared as is, anything else as JSON (any body if omitted) */
+  body?: any;
+  response: HttpResponseFixture;
+  /** How long to wait before responding, e.g. to exceed the request's readTimeoutMs */
+  delayMs?: number;
+}
+
+/**
+ * Interface for a request received by the mock HTTP server
+ */
+export interface MockHttpServerRequest {
+  method: string;
+  url: string;
+  headers: { name: string; value: string }[];
+  body: string;
+  /** Whether a route matched the request (unmatched requests get a 404) */
+  matched: boolean;
+}
+
+/**
+ * Interface for the result of sending a request to the mock HTTP server
+ */
+export interface MockHttpFetchResult {
+  /** The response as received, or null if the request was not sent or timed out */
+  response: HttpResponseFixture | null;
+  /** Problems with the request's shape; a request with errors is not sent */
+  errors: { message: string }[];
+  /** Whether no response arrived within the request's readTimeoutMs */
+  timedOut: boolean;
+}
+
+/**
+ * Interface for a local mock HTTP server
+ */
+export interface MockHttpServer {
+  /** The server's base URL, e.g. `http://127.0.0.1:49152` */
+  url: string;
+  /** The requests received so far, in order */
+  requests: MockHttpServerRequest[];
+  /**
+   * Send a fetch target's request to the server, as Shopify would send it to its URL
+   * @param {HttpRequestData} request - The `request` from the fetch target's output
+   * @returns {Promise<MockHttpFetchResult>} The response, or the reason there is none
+   */
+  fetch(request: HttpRequestData): Promise<MockHttpFetchResult>;
+  /**
+   * Stop the server, dropping any responses still pending
+   */
+  close(): Promise<void>;
+}
+
+/**
+ * Start a local HTTP server that answers fetch target requests with canned responses
+ *
+ * Requests are matched to routes by method, URL and body, so a function's request
+ * is checked end to end without reaching the network: the request is sent to the
+ * local server over HTTP, with its original URL, headers and body, and the response
+ * is read back within the request's `policy.readTimeoutMs`, with repeated headers kept
+ * and the connection's transport headers (e.g. `Date`) dropped. Before it is sent, the
+ * request is checked the way Shopify checks it: only GET and POST are allowed, the
+ * URL must use HTTPS, a GET request has no body, `body` and `jsonBody` are not both
+ * set and the timeout is a positive integer.
+ * @param {MockHttpRoute[]} routes - The canned responses
+ * @returns {Promise<MockHttpServer>} The started server
+ */
+export async function createMockHttpServer(
+  routes: MockHttpRoute[],
+): Promise<MockHttpServer> {
+  const requests: MockHttpServerRequest[] = [];
+
+  const server = http.createServer((req, res) => {
+    const chunks: Buffer[] = [];
+    req.on("data", (chunk: Buffer) => chunks.push(chunk));
+    req.on("end", () => {
+      const body = Buffer.concat(chunks).toString("utf-8");
+      const method = req.method ?? "";
+      const url = req.url ?? "";
+      const route = routes.find((candidate) =>
+        routeMatches(candidate, method, url, body),
+      );
+      requests.push({
+        method,
+        url,
+        headers: headerList(req.rawHeaders),
+        body,
+        matched: route !== undefined,
+      });
+
+      res.sendDate = false;
+      if (!route) {
+        res.writeHead(404, { "Content-Type": "text/plain" });
+        res.end(`No mock response for ${method} ${url}`);
+        return;
+      }
+
+      const respond = () => {
+        const { status, body: responseBody, jsonBody, headers = [] } =
+          route.response;
+        res.statusCode = status;
+        for (const header of headers) {
+          res.appendHeader(header.name, header.value);
+        }
+        res.end(
+          responseBody ??
+            (jsonBody === undefined || jsonBody === null
+              ? ""
+              : JSON.stringify(jsonBody)),
+        );
+      };
+      if (route.delayMs) {
+        const timer = setTimeout(respond, route.delayMs);
+        res.on("close", () => clearTimeout(timer));
+      } else {
+        respond();
+      }
+    });
+  });
+
+  await new Promise<void>((resolve, reject) => {
+    server.once("error", reject);
+    server.listen(0, "127.0.0.1", () => resolve());
+  });
+  const { port } = server.address() as AddressInfo;
+
+  return {
+    url: `http://127.0.0.1:${port}`,
+    requests,
+
+    async fetch(request: HttpRequestData): Promise<MockHttpFetchResult> {
+      const errors = validateHttpRequest(request);
+      if (errors.length > 0) {
+        return { response: null, errors, timedOut: false };
+      }
+
+      const body =
+        request.body ??
+        (request.jsonBody === undefined || request.jsonBody === null
+          ? undefined
+          : JSON.stringify(request.jsonBody));
+
+      return new Promise((resolve) => {
+        const clientRequest = http.request({
+          host: "127.0.0.1",
+          port,
+          method: request.method,
+          // The absolute URL is sent as the request target, as to a proxy
+          path: request.url,
+          headers: {
+            Host: new URL(request.url).host,
+            ...Object.fromEntries(
+              (request.headers ?? []).map((header) => [
+                header.name,
+                header.value,
+              ]),
+            ),
+          },
+          agent: false,
+        });
+
+        clientRequest.setTimeout(request.policy.readTimeoutMs, () => {
+          clientRequest.destroy();
+          resolve({ response: null, errors: [], timedOut: true });
+        });
+        // Errors after a timeout are ignored, as the promise is already resolved
+        clientRequest.on("error", (error) => {
+          resolve({
+            response: null,
+            errors: [{ message: `Request failed: ${error.message}` }],
+            timedOut: false,
+          });
+        });
+        clientRequest.on("response", (res) => {
+          const chunks: Buffer[] = [];
+          res.on("data", (chunk: Buffer) => chunks.push(chunk));
+          res.on("end", () => {
+            resolve({
+              response: {
+                status: res.statusCode ?? 0,
+                body: Buffer.concat(chunks).toString("utf-8"),
+                headers: headerList(res.rawHeaders).filter(
+                  (header) => !TRANSPORT_HEADERS.has(header.name.toLowerCase()),
+                ),
+              },
+              errors: [],
+              timedOut: false,
+            });
+          });
+        });
+
+        clientRequest.end(body);
+      });
+    },
+
+    async close(): Promise<void> {
+      server.closeAllConnections();
+      await new Promise<void>((resolve) => server.close(() => resolve()));
+    },
+  };
+}
+
+/**
+ * Check an `HttpRequest` the way Shopify does before sending it
+ */
+function validateHttpRequest(
+  request: HttpRequestData,
+): { message: string }[] {
+  const errors: { message: string }[] = [];
+
+  if (!HTTP_METHODS.includes(request.method)) {
+    errors.push({
+      message: `Request method must be one of ${HTTP_METHODS.join(", ")}, received "${request.method}"`,
+    });
+  }
+
+  let protocol: string | undefined;
+  try {
+    protocol = new URL(request.url).protocol;
+  } catch {
+    errors.push({ message: `Request URL "${request.url}" is not a valid URL` });
+  }
+  if (protocol !== undefined && protocol !== "https:") {
+    errors.push({ message: `Request URL "${request.url}" must use HTTPS` });
+  }
+
+  const hasBody = request.body !== undefined && request.body !== null;
+  const hasJsonBody =
+    request.jsonBody !== undefined && request.jsonBody !== null;
+  if (hasBody && hasJsonBody) {
+    errors.push({ message: "Request must not set both body and jsonBody" });
+  }
+  if (request.method === "GET" && (hasBody || hasJsonBody)) {
+    errors.push({ message: "GET request must not have a body" });
+  }
+
+  const readTimeoutMs = request.policy?.readTimeoutMs;
+  if (!Number.isInteger(readTimeoutMs) || readTimeoutMs <= 0) {
+    errors.push({
+      message: `Request policy readTimeoutMs must be a positive integer, received ${JSON.stringify(readTimeoutMs)}`,
+    });
+  }
+
+  return errors;
+}
+
+function routeMatches(
+  route: MockHttpRoute,
+  method: string,
+  url: string,
+  body: string,
+): boolean {
+  if (
+    route.method !== method ||
+    normalizeUrl(route.url) !== normalizeUrl(url)
+  ) {
+    return false;
+  }
+  if (route.body === undefined) {
+    return true;
+  }
+  if (typeof route.body === "string") {
+    return route.body === body;
+  }
+  try {
+    return isDeepStrictEqual(route.body, JSON.parse(body));
+  } catch {
+    return false;
+  }
+}
+
+function normalizeUrl(url: string): string {
+  try {
+    return new URL(url).href;
+  } catch {
+    return url;
+  }
+}
+
+function headerList(rawHeaders: string[]): { name: string; value: string }[] {
+  const headers: { name: string; value: string }[] = [];
+  for (let index = 0; index + 1 < rawHeaders.length; index += 2) {
+    headers.push({ name: rawHeaders[index], value: rawHeaders[index + 1] });
+  }
+  return headers;
+}
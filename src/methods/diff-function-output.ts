@@ -0,0 +1,295 @@
+/**
+ * Compare a function's actual output with the expected output of a fixture
+ */
+
+import {
+  getNullableType,
+  GraphQLInputType,
+  GraphQLSchema,
+  isInputObjectType,
+  isListType,
+  isScalarType,
+} from "graphql";
+
+import { determineMutationFromTarget } from "../utils/determine-mutation-from-target.js";
+import { isOneOfInputObject } from "../utils/is-one-of-input-object.js";
+
+/**
+ * Interface for a difference between the actual and expected output
+ */
+export interface OutputDifference {
+  /** GraphQL path of the difference, e.g. `operations[1].productDiscountsAdd.candidates[0]` */
+  path: string;
+  message: string;
+  /** The expected value, or undefined if the actual output has an unexpected value */
+  expected: any;
+  /** The actual value, or undefined if the actual output is missing a value */
+  actual: any;
+}
+
+/**
+ * Interface for diff function output options
+ */
+export interface DiffFunctionOutputOptions {
+  /** The schema, to compare values by type; without it values are compared as JSON */
+  schema?: GraphQLSchema;
+  /** The output the function produced (`result.output` from runFunction) */
+  actual: any;
+  /** The expected output (`expectedOutput` from loadFixture) */
+  expected: any;
+  target?: string;
+  mutationName?: string;
+  resultParameterName?: string;
+}
+
+/**
+ * Interface for the diff function output result
+ */
+export interface DiffFunctionOutputResult {
+  differences: OutputDifference[];
+}
+
+/**
+ * Compares a function's actual output with a fixture's expected output
+ *
+ * Differences are reported by GraphQL path, e.g.
+ * `operations[1].productDiscountsAdd.candidates[0].value.percentage.value`, rather
+ * than as a diff of the whole output. Object key order is ignored, while list items
+ * are compared in order. When both outputs set a different field of the same
+ * `@oneOf` input object (such as a different kind of operation), a single difference
+ * is reported for the object instead of one per field.
+ *
+ * `Decimal`, `Float` and `ID` scalars are compared by value rather than by their JSON
+ * representation, as Shopify does when it parses the output: `"10.0"`, `"10"` and
+ * `10` are the same `Decimal`, and `"1"` and `1` are the same `ID`.
+ *
+ * The result type is determined from the target the same way as in validateTestAssets,
+ * unless mutationName and resultParameterName are provided. Without a schema, values
+ * are compared by their JSON representation and no target is needed.
+ * @param {DiffFunctionOutputOptions} options - The schema, the outputs and the target or mutation
+ * @returns {DiffFunctionOutputResult} The differences (empty if the outputs are equal)
+ * @throws {Error} If the mutation for the target cannot be determined
+ */
+export function diffFunctionOutput({
+  schema,
+  actual,
+  expected,
+  target,
+  mutationName,
+  resultParameterName,
+}: DiffFunctionOutputOptions): DiffFunctionOutputResult {
+  const differences: OutputDifference[] = [];
+  if (!schema) {
+    diffValues(actual, expected, undefined, "", differences);
+    return { differences };
+  }
+
+  if (!mutationName || !resultParameterName) {
+    if (!target) {
+      throw new Error(
+        "target is required when mutationName and resultParameterName are not provided",
+      );
+    }
+    ({ mutationName, resultParameterName } = determineMutationFromTarget(
+      target,
+      schema,
+    ));
+  }
+
+  const resultType = schema
+    .getMutationType()
+    ?.getFields()
+    [mutationName]?.args.find((arg) => arg.name === resultParameterName)?.type;
+
+  diffValues(actual, expected, resultType, "", differences);
+  return { differences };
+}
+
+function diffValues(
+  actual: any,
+  expected: any,
+  type: GraphQLInputType | undefined,
+  path: string,
+  differences: OutputDifference[],
+): void {
+  const nullableType = type && getNullableType(type);
+
+  if (
+    isScalarType(nullableType) &&
+    scalarValuesEqual(nullableType.name, actual, expected)
+  ) {
+    return;
+  }
+
+  if (Array.isArray(actual) && Array.isArray(expected)) {
+    const itemType = isListType(nullableType) ? nullableType.ofType : undefined;
+    const length = Math.max(actual.length, expected.length);
+    for (let index = 0; index < length; index++) {
+      const itemPath = `${path}[${index}]`;
+      if (index >= actual.length) {
+        differences.push({
+          path: itemPath,
+          message: `Missing item, expected ${inspect(expected[index])}`,
+          expected: expected[index],
+          actual: undefined,
+        });
+      } else if (index >= expected.length) {
+        differences.push({
+          path: itemPath,
+          message: `Unexpected item ${inspect(actual[index])}`,
+          expected: undefined,
+          actual: actual[index],
+        });
+      } else {
+        diffValues(
+          actual[index],
+          expected[index],
+          itemType,
+          itemPath,
+          differences,
+        );
+      }
+    }
+    return;
+  }
+
+  if (isObject(actual) && isObject(expected)) {
+    const inputObjectType = isInputObjectType(nullableType)
+      ? nullableType
+      : undefined;
+    const actualKeys = Object.keys(actual);
+    const expectedKeys = Object.keys(expected);
+
+    // A different field of a @oneOf object is a different kind of value altogether,
+    // so its contents are not compared field by field
+    if (
+      inputObjectType &&
+      isOneOfInputObject(inputObjectType) &&
+      actualKeys.length === 1 &&
+      expectedKeys.length === 1 &&
+      actualKeys[0] !== expectedKeys[0]
+    ) {
+      differences.push({
+        path,
+        message: `Expected ${inputObjectType.name} "${expectedKeys[0]}", received "${actualKeys[0]}"`,
+        expected,
+        actual,
+      });
+      return;
+    }
+
+    const fields = inputObjectType?.getFields();
+    for (const key of new Set([...expectedKeys, ...actualKeys])) {
+      const fieldPath = path ? `${path}.${key}` : key;
+      if (!(key in actual)) {
+        differences.push({
+          path: fieldPath,
+          message: `Missing field, expected ${inspect(expected[key])}`,
+          expected: expected[key],
+          actual: undefined,
+        });
+      } else if (!(key in expected)) {
+        differences.push({
+          path: fieldPath,
+          message: `Unexpected field with value ${inspect(actual[key])}`,
+          expected: undefined,
+          actual: actual[key],
+        });
+      } else {
+        diffValues(
+          actual[key],
+          expected[key],
+          fields?.[key]?.type,
+          fieldPath,
+          differences,
+        );
+      }
+    }
+    return;
+  }
+
+  if (actual !== expected) {
+    differences.push({
+      path,
+      message: `Expected ${inspect(expected)}, received ${inspect(actual)}`,
+      expected,
+      actual,
+    });
+  }
+}
+
+/**
+ * Whether two values of a scalar are equal by value. Only `Decimal`, `Float`
+ * and `ID` have representations that differ for the same value.
+ */
+function scalarValuesEqual(
+  scalarName: string,
+  actual: any,
+  expected: any,
+): boolean {
+  switch (scalarName) {
+    case "Decimal": {
+      const actualDecimal = normalizeDecimal(actual);
+      return (
+        actualDecimal !== null && actualDecimal === normalizeDecimal(expected)
+      );
+    }
+    case "Float":
+      return (
+        typeof actual === "number" &&
+        typeof expected === "number" &&
+        actual === expected
+      );
+    case "ID":
+      return (
+        isIdValue(actual) &&
+        isIdValue(expected) &&
+        String(actual) === String(expected)
+      );
+    default:
+      return actual === expected;
+  }
+}
+
+/**
+ * A canonical form of a decimal string or number, e.g. `"10.0"`, `"10"` and `10`
+ * all become `1e1`, or null if the value is not a decimal. Digits are compared
+ * exactly rather than as floating point numbers.
+ */
+function normalizeDecimal(value: any): string | null {
+  if (typeof value !== "string" && typeof value !== "number") {
+    return null;
+  }
+
+  const match = /^([+-]?)(\d*)(?:\.(\d*))?(?:[eE]([+-]?\d+))?$/.exec(
+    String(value).trim(),
+  );
+  if (!match || (match[2] === "" && !match[3])) {
+    return null;
+  }
+
+  const [, sign, integerDigits, fractionDigits = "", exponent = "0"] = match;
+  const digits = `${integerDigits}${fractionDigits}`.replace(/^0+/, "");
+  if (digits === "") {
+    return "0";
+  }
+
+  const significantDigits = digits.replace(/0+$/, "");
+  const scale =
+    Number(exponent) -
+    fractionDigits.length +
+    (digits.length - significantDigits.length);
+  return `${sign === "-" ? "-" : ""}${significantDigits}e${scale}`;
+}
+
+function isIdValue(value: any): boolean {
+  return typeof value === "string" || Number.isInteger(value);
+}
+
+function isObject(value: any): value is Record<string, any> {
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}
+
+function inspect(value: any): string {
+  return JSON.stringify(value);
+}
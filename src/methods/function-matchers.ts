@@ -0,0 +1,169 @@
+/**
+ * Vitest and Jest matchers for test assets, function output and instruction counts
+ */
+
+import { GraphQLSchema } from "graphql";
+
+import { diffFunctionOutput } from "./diff-function-output.js";
+import { RunFunctionOutput, RunFunctionResult } from "./run-function.js";
+import { validationFailures } from "./run-fixture.js";
+import { CompleteValidationResult } from "./validate-test-assets.js";
+
+/**
+ * Interface for the options of toProduceOutput
+ */
+export interface ProduceOutputOptions {
+  /** The schema, to compare values by type, e.g. `Decimal` values by value */
+  schema?: GraphQLSchema;
+  /** The fixture's target, to find its result type in the schema. Required with a schema. */
+  target?: string;
+}
+
+/**
+ * Interface for the state Vitest and Jest pass to a matcher as `this`
+ */
+export interface FunctionMatcherContext {
+  isNot: boolean;
+}
+
+/**
+ * Interface for the result of a matcher
+ */
+export interface FunctionMatcherResult {
+  pass: boolean;
+  message: () => string;
+}
+
+/**
+ * Interface for the matchers, to add to Vitest's `Assertion` or Jest's `Matchers`
+ */
+export interface FunctionMatchers<R = unknown> {
+  toHaveValidTestAssets: () => R;
+  toProduceOutput: (expected: any, options?: ProduceOutputOptions) => R;
+  toStayWithinInstructionBudget: (instructionLimit: number) => R;
+}
+
+/**
+ * Matchers to register with `expect.extend(functionMatchers)`
+ *
+ * - `toHaveValidTestAssets()` passes for a validateTestAssets result without errors
+ * - `toProduceOutput(expected)` passes for a runFunction result whose output has no
+ *   differences from the expected output (see diffFunctionOutput). It throws if given
+ *   a schema without a target.
+ * - `toStayWithinInstructionBudget(n)` passes for a runFunction result that executed
+ *   at most `n` instructions
+ *
+ * Failure messages list each error on its own line with the path of the fixture field
+ * or output value it concerns.
+ */
+export const functionMatchers = {
+  toHaveValidTestAssets(
+    this: FunctionMatcherContext,
+    received: CompleteValidationResult,
+  ): FunctionMatcherResult {
+    const failures = validationFailures(received);
+    return {
+      pass: failures.length === 0,
+      message: () =>
+        this.isNot
+          ? "Expected invalid test assets, but validateTestAssets found no errors"
+          : listFailures(
+              `Expected valid test assets, but validateTestAssets found ${count(failures.length, "error")}`,
+              failures,
+            ),
+    };
+  },
+
+  toProduceOutput(
+    this: FunctionMatcherContext,
+    received: RunFunctionResult | RunFunctionOutput,
+    expected: any,
+    { schema, target }: ProduceOutputOptions = {},
+  ): FunctionMatcherResult {
+    if (schema && !target) {
+      throw new Error(
+        "toProduceOutput needs a target with a schema, to find the target's result type",
+      );
+    }
+    const { output, error } = runOutput(received);
+    if (error !== null) {
+      return {
+        pass: false,
+        message: () => `Expected the function to run, but it failed: ${error}`,
+      };
+    }
+
+    const { differences } = diffFunctionOutput({
+      schema,
+      actual: output?.output,
+      expected,
+      target,
+    });
+    return {
+      pass: differences.length === 0,
+      message: () =>
+        this.isNot
+          ? "Expected the output to differ from the expected output, but it matches"
+          : listFailures(
+              `Expected the function to produce the expected output, but found ${count(differences.length, "difference")}`,
+              differences.map(
+                ({ path, message }) => `${path || "(output)"}: ${message}`,
+              ),
+            ),
+    };
+  },
+
+  toStayWithinInstructionBudget(
+    this: FunctionMatcherContext,
+    received: RunFunctionResult | RunFunctionOutput,
+    instructionLimit: number,
+  ): FunctionMatcherResult {
+    const { output, error } = runOutput(received);
+    const instructions = output?.instructions ?? null;
+    if (error !== null || instructions === null) {
+      return {
+        pass: false,
+        message: () =>
+          error === null
+            ? "Expected an instruction count, but the run did not report one"
+            : `Expected the function to run, but it failed: ${error}`,
+      };
+    }
+
+    const share = Math.round((instructions / instructionLimit) * 100);
+    const executed = `the run executed ${instructions.toLocaleString("en-US")} (${share}% of the budget)`;
+    const limit = `${instructionLimit.toLocaleString("en-US")} instructions`;
+    return {
+      pass: instructions <= instructionLimit,
+      message: () =>
+        this.isNot
+          ? `Expected more than ${limit}, but ${executed}`
+          : `Expected at most ${limit}, but ${executed}`,
+    };
+  },
+};
+
+/**
+ * The output of a runFunction result, or the output itself
+ */
+function runOutput(received: RunFunctionResult | RunFunctionOutput): {
+  output: RunFunctionOutput | null;
+  error: string | null;
+} {
+  if ("output" in received) {
+    return { output: received, error: null };
+  }
+  return {
+    output: received.result,
+    error: received.error ?? (received.result ? null : "no output"),
+  };
+}
+
+function listFailures(summary: string, failures: string[]): string {
+  const lines = failures.map((failure) => `  ${failure}`);
+  return [`${summary}:`, ...lines].join("\n");
+}
+
+function count(total: number, noun: string): string {
+  return `${total} ${noun}${total === 1 ? "" : "s"}`;
+}
@@ -0,0 +1,334 @@
+/**
+ * Generate a fixture skeleton from an input query and schema
+ */
+
+import {
+  DocumentNode,
+  FieldNode,
+  getNamedType,
+  getNullableType,
+  GraphQLCompositeType,
+  GraphQLEnumType,
+  GraphQLInputType,
+  GraphQLObjectType,
+  GraphQLOutputType,
+  GraphQLScalarType,
+  GraphQLSchema,
+  isAbstractType,
+  isEnumType,
+  isInputObjectType,
+  isInputType,
+  isListType,
+  isNonNullType,
+  isObjectType,
+  Kind,
+  OperationDefinitionNode,
+  SelectionSetNode,
+  typeFromAST,
+} from "graphql";
+
+import { determineMutationFromTarget } from "../utils/determine-mutation-from-target.js";
+import { inlineNamedFragmentSpreads } from "../utils/inline-named-fragment-spreads.js";
+import { isOneOfInputObject } from "../utils/is-one-of-input-object.js";
+import { requestedArgumentValues } from "../utils/validate-argument-responses.js";
+
+import { FixtureData } from "./load-fixture.js";
+import {
+  FixtureFieldVisit,
+  visitFixtureInput,
+} from "./validate-fixture-input.js";
+
+/**
+ * Placeholder values for the built-in scalars and the custom scalars of the
+ * Shopify Functions schemas. `ID` is handled separately so that each generated
+ * ID is a unique GID.
+ */
+const SCALAR_PLACEHOLDERS: Record<string, any> = {
+  Int: 1,
+  Float: 1.5,
+  Boolean: false,
+  Decimal: "10.0",
+  Date: "2025-01-01",
+  DateTime: "2025-01-01T00:00:00Z",
+  DateTimeWithoutTimezone: "2025-01-01T00:00:00",
+  TimeWithoutTimezone: "00:00:00",
+  URL: "https://example.com",
+  Handle: "example-handle",
+  JSON: {},
+  Void: null,
+};
+
+/**
+ * Generates a fixture whose input and output are valid for an input query
+ *
+ * The input query is walked against the schema to build `input`: every selected
+ * field gets a placeholder value, lists hold a single item, and abstract types
+ * resolve to the possible type matching the most selected fields. Placeholders
+ * are realistic for the scalar type, e.g. GIDs such as `gid://shopify/CartLine/1`
+ * for `ID`, decimal strings for `Decimal` and the first member of an enum.
+ *
+ * `expectedOutput` is the smallest valid value for the target's mutation result:
+ * only required fields are set and required lists are empty. Required variables of
+ * the query get the smallest valid value of their type in `variables`, and fields
+ * such as `hasTags(tags: $tags)` get one response for each requested value.
+ *
+ * The result passes `validateTestAssets` and can be written to a fixture file as
+ * `{ "payload": { export, target, input, output: expectedOutput } }`.
+ * @param {GraphQLSchema} schema - The schema of the function API
+ * @param {DocumentNode} inputQueryAST - The parsed input query AST (from loadInputQuery)
+ * @param {string} target - The function target, used to find the mutation for the output
+ * @param {string} exportName - The WASM export the fixture runs
+ * @returns {FixtureData} The generated fixture data
+ * @throws {Error} If the query has no operation or the target does not match a mutation
+ */
+export function generateFixture(
+  schema: GraphQLSchema,
+  inputQueryAST: DocumentNode,
+  target: string,
+  exportName: string,
+): FixtureData {
+  const operation = inlineNamedFragmentSpreads(inputQueryAST).definitions.find(
+    (definition): definition is OperationDefinitionNode =>
+      definition.kind === Kind.OPERATION_DEFINITION,
+  );
+  if (!operation) {
+    throw new Error("Input query does not contain an operation");
+  }
+
+  const { mutationName, resultParameterName } = determineMutationFromTarget(
+    target,
+    schema,
+  );
+  const resultArgument = schema
+    .getMutationType()!
+    .getFields()
+    [mutationName].args.find((arg) => arg.name === resultParameterName);
+  if (!resultArgument) {
+    throw new Error(
+      `Parameter '${resultParameterName}' not found in mutation '${mutationName}'`,
+    );
+  }
+
+  const variables: Record<string, any> = {};
+  const generator = new FixtureGenerator(schema, variables);
+  for (const definition of operation.variableDefinitions ?? []) {
+    const type = typeFromAST(schema, definition.type);
+    if (
+      isNonNullType(type) &&
+      isInputType(type) &&
+      definition.defaultValue === undefined
+    ) {
+      const name = definition.variable.name.value;
+      variables[name] = generator.resultValue(type, "Variable", name);
+    }
+  }
+
+  // The visitor enters each field of the query with the generated objects it is
+  // selected on, so fragments, aliases and abstract types are handled as in
+  // validateFixtureInput
+  const input = generator.objectValue(
+    schema.getQueryType()!,
+    operation.selectionSet,
+  );
+  visitFixtureInput(inputQueryAST, schema, input, {}, (field) =>
+    generator.addField(field),
+  );
+
+  const fixture: FixtureData = {
+    export: exportName,
+    input,
+    expectedOutput: generator.resultValue(
+      resultArgument.type,
+      mutationName,
+      resultParameterName,
+    ),
+    target,
+  };
+  if (operation.variableDefinitions?.length) {
+    fixture.variables = variables;
+  }
+  return fixture;
+}
+
+class FixtureGenerator {
+  private idCounts = new Map<string, number>();
+  private objectTypes = new WeakMap<object, GraphQLObjectType>();
+
+  constructor(
+    private schema: GraphQLSchema,
+    private variables: Record<string, any>,
+  ) {}
+
+  /**
+   * An empty object of a composite type, whose fields are added as the visitor
+   * enters them. Abstract types resolve to the possible type that matches the most
+   * fields of the selection set.
+   */
+  objectValue(
+    type: GraphQLCompositeType,
+    selectionSet: SelectionSetNode | undefined,
+  ): Record<string, any> {
+    let concreteType: GraphQLObjectType;
+    if (isAbstractType(type)) {
+      const candidates = this.schema
+        .getPossibleTypes(type)
+        .map((possibleType) => ({
+          possibleType,
+          fieldCount: this.countFields(selectionSet, possibleType),
+        }));
+      ({ possibleType: concreteType } = candidates.reduce(
+        (best, candidate) =>
+          candidate.fieldCount > best.fieldCount ? candidate : best,
+      ));
+    } else {
+      concreteType = type;
+    }
+
+    const value: Record<string, any> = {};
+    this.objectTypes.set(value, concreteType);
+    return value;
+  }
+
+  /**
+   * Adds a field of the input query to the generated objects it is selected on,
+   * skipping objects whose type its inline fragments exclude and objects that
+   * already have it, e.g. from another selection of the same response key
+   */
+  addField({ node, fieldType, possibleTypes, objects }: FixtureFieldVisit) {
+    const responseKey = node.alias?.value ?? node.name.value;
+    for (const object of objects) {
+      const objectType = this.objectTypes.get(object);
+      if (
+        !objectType ||
+        !possibleTypes.has(objectType.name) ||
+        object[responseKey] !== undefined
+      ) {
+        continue;
+      }
+      object[responseKey] =
+        node.name.value === "__typename"
+          ? objectType.name
+          : this.fieldValue(fieldType, node, objectType);
+    }
+  }
+
+  /**
+   * The smallest valid value of an input type: only required fields are set,
+   * lists are empty and @oneOf input objects set their first field
+   */
+  resultValue(
+    type: GraphQLInputType,
+    parentTypeName: string,
+    fieldName: string,
+  ): any {
+    const nullableType = getNullableType(type);
+
+    if (isListType(nullableType)) {
+      return [];
+    }
+    if (isInputObjectType(nullableType)) {
+      const fields = Object.values(nullableType.getFields());
+      const requiredFields = isOneOfInputObject(nullableType)
+        ? fields.slice(0, 1)
+        : fields.filter(
+            (field) =>
+              isNonNullType(field.type) && field.defaultValue === undefined,
+          );
+
+      return Object.fromEntries(
+        requiredFields.map((field) => [
+          field.name,
+          this.resultValue(field.type, nullableType.name, field.name),
+        ]),
+      );
+    }
+    return this.leafValue(nullableType, parentTypeName, fieldName);
+  }
+
+  private fieldValue(
+    type: GraphQLOutputType,
+    field: FieldNode,
+    parentType: GraphQLObjectType,
+  ): any {
+    const nullableType = getNullableType(type);
+
+    if (isListType(nullableType)) {
+      // One response for each value of an argument such as `hasTags(tags:)`
+      const requested = requestedArgumentValues(
+        getNamedType(nullableType).name,
+        field,
+        this.variables,
+      );
+      if (!requested) {
+        return [this.fieldValue(nullableType.ofType, field, parentType)];
+      }
+      return requested.values.map((requestedValue) => {
+        const response = this.fieldValue(
+          nullableType.ofType,
+          field,
+          parentType,
+        );
+        if (requested.echoResponseKey !== undefined) {
+          response[requested.echoResponseKey] = requestedValue;
+        }
+        return response;
+      });
+    }
+    if (isObjectType(nullableType) || isAbstractType(nullableType)) {
+      return this.objectValue(nullableType, field.selectionSet);
+    }
+    return this.leafValue(nullableType, parentType.name, field.name.value);
+  }
+
+  /**
+   * A placeholder for a scalar or enum value. IDs are GIDs of the parent type,
+   * numbered so that each generated ID is unique.
+   */
+  private leafValue(
+    type: GraphQLScalarType | GraphQLEnumType,
+    parentTypeName: string,
+    fieldName: string,
+  ): any {
+    if (isEnumType(type)) {
+      return type.getValues()[0]?.name ?? null;
+    }
+    if (type.name === "ID") {
+      const count = (this.idCounts.get(parentTypeName) ?? 0) + 1;
+      this.idCounts.set(parentTypeName, count);
+      return `gid://shopify/${parentTypeName}/${count}`;
+    }
+    if (type.name in SCALAR_PLACEHOLDERS) {
+      return SCALAR_PLACEHOLDERS[type.name];
+    }
+    // Strings and unknown custom scalars
+    return fieldName;
+  }
+
+  /**
+   * The number of response keys selected on an object type, following inline
+   * fragments whose type condition includes the object type
+   */
+  private countFields(
+    selectionSet: SelectionSetNode | undefined,
+    objectType: GraphQLObjectType,
+    responseKeys = new Set<string>(),
+  ): number {
+    for (const selection of selectionSet?.selections ?? []) {
+      if (selection.kind === Kind.FIELD) {
+        responseKeys.add(selection.alias?.value ?? selection.name.value);
+      } else if (selection.kind === Kind.INLINE_FRAGMENT) {
+        const conditionType = selection.typeCondition
+          ? this.schema.getType(selection.typeCondition.name.value)
+          : objectType;
+        const applies =
+          conditionType === objectType ||
+          (isAbstractType(conditionType) &&
+            this.schema.isSubType(conditionType, objectType));
+        if (applies) {
+          this.countFields(selection.selectionSet, objectType, responseKeys);
+        }
+      }
+    }
+    return responseKeys.size;
+  }
+}
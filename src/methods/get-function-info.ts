@@ -3,8 +3,17 @@
  */
 
 import { spawn } from "child_process";
+import fs from "fs";
 import path from "path";
 
+import {
+  ExtensionInputVariablesConfig,
+  extensionTomlPath,
+  readExtensionToml,
+} from "../utils/read-extension-toml.js";
+
+import { loadFunctionInfo } from "./load-function-info.js";
+
 /**
  * Information about a Shopify function
  */
@@ -13,13 +22,22 @@ export interface FunctionInfo {
   functionRunnerPath: string;
   wasmPath: string;
   targeting: Record<string, any>;
+  /**
+   * The metafield bound to the input queries' variables by
+   * `[extensions.input.variables]`, if the function declares one
+   */
+  inputVariables?: ExtensionInputVariablesConfig;
 }
 
 /**
  * Retrieves function information from the Shopify CLI
+ *
+ * When the Shopify CLI or its `app function info` command is unavailable, such as in CI
+ * containers, falls back to reading the function's `shopify.extension.toml` with
+ * `loadFunctionInfo`.
  * @param {string} functionDir - The directory path of the function
  * @returns {Promise<FunctionInfo>} Function information including schemaPath, functionRunnerPath, wasmPath, and targeting
- * @throws {Error} If the CLI command is not available or fails
+ * @throws {Error} If the CLI command fails, or is not available and there is no shopify.extension.toml to fall back to
  */
 export async function getFunctionInfo(
   functionDir: string,
@@ -27,8 +45,18 @@ export async function getFunctionInfo(
   const resolvedFunctionDir = path.resolve(functionDir);
   const appRootDir = path.dirname(resolvedFunctionDir);
   const functionName = path.basename(resolvedFunctionDir);
+  const extensionToml = extensionTomlPath(resolvedFunctionDir);
 
   return new Promise((resolve, reject) => {
+    // Without the CLI, read the extension's configuration directly if there is one
+    const fallBackToExtensionToml = (error: Error) => {
+      if (!fs.existsSync(extensionToml)) {
+        reject(error);
+        return;
+      }
+      loadFunctionInfo(resolvedFunctionDir).then(resolve, reject);
+    };
+
     const shopifyProcess = spawn(
       "shopify",
       ["app", "function", "info", "--json", "--path", functionName],
@@ -60,7 +88,7 @@ export async function getFunctionInfo(
           stderr.includes("Command app function info not found") ||
           stderr.includes("command not found")
         ) {
-          reject(
+          fallBackToExtensionToml(
             new Error(
               'The "shopify app function info" command is not available in your CLI version.\n' +
                 "Please upgrade to the latest version:\n" +
@@ -79,7 +107,17 @@ export async function getFunctionInfo(
 
       try {
         const functionInfo = JSON.parse(stdout.trim()) as FunctionInfo;
-        resolve(functionInfo);
+        if (functionInfo.inputVariables || !fs.existsSync(extensionToml)) {
+          resolve(functionInfo);
+          return;
+        }
+        // Take `[extensions.input.variables]` from the extension's configuration
+        readExtensionToml(resolvedFunctionDir).then(({ input }) => {
+          resolve({
+            ...functionInfo,
+            ...(input?.variables && { inputVariables: input.variables }),
+          });
+        }, reject);
       } catch (error) {
         const errorMessage =
           error instanceof Error ? error.message : String(error);
@@ -92,7 +130,7 @@ export async function getFunctionInfo(
     });
 
     shopifyProcess.on("error", (error) => {
-      reject(
+      fallBackToExtensionToml(
         new Error(
           `Failed to start shopify function info command: ${error.message}`,
         ),
@@ -0,0 +1,164 @@
+/**
+ * Replay a fixture's canned HTTP response as the run input's fetchResult
+ */
+
+import {
+  DocumentNode,
+  FieldNode,
+  Kind,
+  OperationDefinitionNode,
+  SelectionSetNode,
+} from "graphql";
+
+import { inlineNamedFragmentSpreads } from "../utils/inline-named-fragment-spreads.js";
+
+import { FixtureData, HttpResponseFixture } from "./load-fixture.js";
+
+const FETCH_RESULT_FIELD = "fetchResult";
+
+/**
+ * Returns the run fixture with the fetch stage's response injected as `fetchResult`
+ *
+ * The response from `payload.fetch.response` is shaped by the `fetchResult` selection
+ * of the run target's input query, the same way Shopify builds the input: `header(name:)`
+ * looks up a header case-insensitively, `jsonBody` is parsed from `body` (falling back to
+ * the raw string) and `body` is serialized from `jsonBody` when only one is given.
+ *
+ * The returned fixture has no fetch stage and any `fetchResult` already in its input is
+ * replaced, so it can be passed to validateTestAssets and runFunction like any other
+ * fixture.
+ * Fixtures without a fetch stage, and queries that do not select `fetchResult`, are
+ * returned unchanged.
+ * @param {FixtureData} fixture - The fixture with a fetch stage (from loadFixture)
+ * @param {DocumentNode} inputQueryAST - The run target's input query AST (from loadInputQuery)
+ * @returns {FixtureData} The run fixture with `fetchResult` in its input
+ */
+export function injectFetchResult(
+  fixture: FixtureData,
+  inputQueryAST: DocumentNode,
+): FixtureData {
+  const { fetch, ...runFixture } = fixture;
+  if (!fetch) {
+    return fixture;
+  }
+
+  const operation = inlineNamedFragmentSpreads(inputQueryAST).definitions.find(
+    (definition): definition is OperationDefinitionNode =>
+      definition.kind === Kind.OPERATION_DEFINITION,
+  );
+  const fetchResultFields = (operation?.selectionSet.selections ?? []).filter(
+    (selection): selection is FieldNode =>
+      selection.kind === Kind.FIELD &&
+      selection.name.value === FETCH_RESULT_FIELD,
+  );
+  if (fetchResultFields.length === 0) {
+    return runFixture;
+  }
+
+  const input = { ...runFixture.input };
+  for (const field of fetchResultFields) {
+    input[field.alias?.value ?? field.name.value] = field.selectionSet
+      ? httpResponseValue(fetch.response, field.selectionSet)
+      : null;
+  }
+  return { ...runFixture, input };
+}
+
+/**
+ * The value of an `HttpResponse` for a selection set
+ */
+function httpResponseValue(
+  response: HttpResponseFixture | null | undefined,
+  selectionSet: SelectionSetNode,
+): Record<string, any> | null {
+  if (response === null || response === undefined) {
+    return null;
+  }
+
+  const headers = response.headers ?? [];
+  const jsonBody = response.jsonBody ?? null;
+  const value: Record<string, any> = {};
+
+  for (const field of collectFields(selectionSet)) {
+    const responseKey = field.alias?.value ?? field.name.value;
+
+    switch (field.name.value) {
+      case "__typename":
+        value[responseKey] = "HttpResponse";
+        break;
+      case "status":
+        value[responseKey] = response.status;
+        break;
+      case "body":
+        value[responseKey] =
+          response.body ??
+          (jsonBody === null ? null : JSON.stringify(jsonBody));
+        break;
+      case "jsonBody":
+        value[responseKey] = jsonBody ?? parseJsonBody(response.body);
+        break;
+      case "headers":
+        value[responseKey] = headers.map((header) =>
+          headerValue(header, field.selectionSet),
+        );
+        break;
+      case "header": {
+        const nameArgument = field.arguments?.find(
+          (argument) => argument.name.value === "name",
+        )?.value;
+        const name =
+          nameArgument?.kind === Kind.STRING ? nameArgument.value : undefined;
+        const header = headers.find(
+          (candidate) => candidate.name.toLowerCase() === name?.toLowerCase(),
+        );
+        value[responseKey] = header
+          ? headerValue(header, field.selectionSet)
+          : null;
+        break;
+      }
+    }
+  }
+
+  return value;
+}
+
+function headerValue(
+  header: { name: string; value: string },
+  selectionSet: SelectionSetNode | undefined,
+): Record<string, any> {
+  const value: Record<string, any> = {};
+  for (const field of selectionSet ? collectFields(selectionSet) : []) {
+    const responseKey = field.alias?.value ?? field.name.value;
+    if (field.name.value === "__typename") {
+      value[responseKey] = "HttpResponseHeader";
+    } else if (field.name.value === "name" || field.name.value === "value") {
+      value[responseKey] = header[field.name.value];
+    }
+  }
+  return value;
+}
+
+/**
+ * The fields of a selection set, including those of inline fragments
+ */
+function collectFields(selectionSet: SelectionSetNode): FieldNode[] {
+  return selectionSet.selections.flatMap((selection) => {
+    if (selection.kind === Kind.FIELD) {
+      return [selection];
+    }
+    return selection.kind === Kind.INLINE_FRAGMENT
+      ? collectFields(selection.selectionSet)
+      : [];
+  });
+}
+
+function parseJsonBody(body: string | null | undefined): any {
+  if (body === null || body === undefined) {
+    return null;
+  }
+  try {
+    return JSON.parse(body);
+  } catch {
+    return body;
+  }
+}
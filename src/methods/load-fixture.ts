@@ -4,6 +4,29 @@
 
 import fs from "fs";
 
+/**
+ * Interface for the canned HTTP response replayed as `Input.fetchResult`
+ */
+export interface HttpResponseFixture {
+  status: number;
+  body?: string | null;
+  jsonBody?: any;
+  headers?: { name: string; value: string }[];
+}
+
+/**
+ * Interface for the fetch stage of a fixture for a target with network access
+ */
+export interface FetchFixtureData {
+  export: string;
+  input: Record<string, any>;
+  /** The expected fetch result, e.g. `{ request: { method, url, ... } }` */
+  expectedOutput: Record<string, any>;
+  target: string;
+  /** The response to the request, injected into the run input as `fetchResult` */
+  response: HttpResponseFixture | null;
+}
+
 /**
  * Interface for the parsed fixture data structure
  */
@@ -12,6 +35,13 @@ export interface FixtureData {
   input: Record<string, any>;
   expectedOutput: Record<string, any>;
   target: string;
+  /**
+   * The values of the input query's variables, from payload.variables. Shopify reads them
+   * from the metafield bound by `[extensions.input.variables]` in shopify.extension.toml.
+   */
+  variables?: Record<string, any>;
+  /** The fetch stage that runs before this fixture's target, from payload.fetch */
+  fetch?: FetchFixtureData;
 }
 
 /**
@@ -22,18 +52,38 @@ export interface FixtureData {
  *   - input: Object - The input data from payload.input
  *   - expectedOutput: Object - The output data from payload.output
  *   - target: string - The target string from payload.target
+ *   - variables: Object - The input query variable values from payload.variables (optional)
+ *   - fetch: Object - The fetch stage from payload.fetch, for targets with network access (optional)
  */
 export async function loadFixture(filename: string): Promise<FixtureData> {
   try {
     const fixtureContent = await fs.promises.readFile(filename, "utf-8");
     const fixture = JSON.parse(fixtureContent);
 
-    return {
+    const fixtureData: FixtureData = {
       export: fixture.payload.export,
       input: fixture.payload.input,
       expectedOutput: fixture.payload.output,
       target: fixture.payload.target,
     };
+
+    const variables = fixture.payload.variables;
+    if (variables) {
+      fixtureData.variables = variables;
+    }
+
+    const fetch = fixture.payload.fetch;
+    if (fetch) {
+      fixtureData.fetch = {
+        export: fetch.export,
+        input: fetch.input,
+        expectedOutput: fetch.output,
+        target: fetch.target,
+        response: fetch.response,
+      };
+    }
+
+    return fixtureData;
   } catch (error) {
     if (error instanceof SyntaxError) {
       throw new Error(
@@ -0,0 +1,103 @@
+/**
+ * Load function information from a function's shopify.extension.toml
+ */
+
+import fs from "fs";
+import path from "path";
+
+import {
+  DEFAULT_WASM_PATH,
+  extensionTomlPath,
+  readExtensionToml,
+} from "../utils/read-extension-toml.js";
+
+import type { FunctionInfo } from "./get-function-info.js";
+
+/**
+ * The environment variable that overrides where function-runner is found
+ */
+export const FUNCTION_RUNNER_PATH_ENV = "FUNCTION_RUNNER_PATH";
+
+/**
+ * Loads function information from a function's `shopify.extension.toml`, without the Shopify CLI
+ *
+ * Returns the same shape as `getFunctionInfo`: `schemaPath` is the function's
+ * `schema.graphql`, `wasmPath` comes from `[extensions.build].path` and `targeting` has
+ * the `inputQueryPath` and `export` of each `[[extensions.targeting]]` with an
+ * `input_query`. `inputVariables` is the `[extensions.input.variables]` metafield, if
+ * any. `functionRunnerPath` is the `FUNCTION_RUNNER_PATH` environment variable if set,
+ * otherwise the first `function-runner` on `PATH`. All paths are absolute.
+ * @param {string} functionDir - The directory path of the function
+ * @returns {Promise<FunctionInfo>} Function information including schemaPath, functionRunnerPath, wasmPath, and targeting
+ * @throws {Error} If the file cannot be read or parsed, or function-runner cannot be found
+ */
+export async function loadFunctionInfo(
+  functionDir: string,
+): Promise<FunctionInfo> {
+  const resolvedFunctionDir = path.resolve(functionDir);
+  const filename = extensionTomlPath(resolvedFunctionDir);
+
+  try {
+    const extension = await readExtensionToml(resolvedFunctionDir);
+
+    const targeting: Record<string, any> = {};
+    for (const target of extension.targeting) {
+      if (typeof target.input_query !== "string") {
+        continue;
+      }
+      targeting[target.target] = {
+        inputQueryPath: path.resolve(resolvedFunctionDir, target.input_query),
+        ...(typeof target.export === "string" && { export: target.export }),
+      };
+    }
+
+    return {
+      schemaPath: path.join(resolvedFunctionDir, "schema.graphql"),
+      functionRunnerPath: findFunctionRunner(),
+      wasmPath: path.resolve(
+        resolvedFunctionDir,
+        extension.build?.path ?? DEFAULT_WASM_PATH,
+      ),
+      targeting,
+      ...(extension.input?.variables && {
+        inputVariables: extension.input.variables,
+      }),
+    };
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    throw new Error(
+      `Failed to load function info from ${filename}: ${errorMessage}`,
+    );
+  }
+}
+
+/**
+ * Finds function-runner from the `FUNCTION_RUNNER_PATH` environment variable or `PATH`
+ */
+function findFunctionRunner(): string {
+  const configuredPath = process.env[FUNCTION_RUNNER_PATH_ENV];
+  if (configuredPath) {
+    return path.resolve(configuredPath);
+  }
+
+  const executable =
+    process.platform === "win32" ? "function-runner.exe" : "function-runner";
+  for (const directory of (process.env.PATH ?? "").split(path.delimiter)) {
+    if (!directory) {
+      continue;
+    }
+    const candidate = path.resolve(directory, executable);
+    try {
+      fs.accessSync(candidate, fs.constants.X_OK);
+      if (fs.statSync(candidate).isFile()) {
+        return candidate;
+      }
+    } catch {
+      // Not in this directory
+    }
+  }
+
+  throw new Error(
+    `Could not find function-runner on PATH; install it or set ${FUNCTION_RUNNER_PATH_ENV}`,
+  );
+}
@@ -0,0 +1,70 @@
+/**
+ * Load a registry of metafield schemas to validate fixture metafields against
+ */
+
+import fs from "fs";
+
+import { draft07Problem } from "../utils/validate-json-schema.js";
+
+/**
+ * Interface for the declared type of a metafield, identified by namespace and key
+ */
+export interface MetafieldSchema {
+  /** The namespace passed to `metafield(namespace:)`, omitted for the app-reserved namespace */
+  namespace?: string;
+  /** The key passed to `metafield(key:)` */
+  key: string;
+  /** The type that owns the metafield, e.g. `Discount`, `Product` or `Shop` (any owner if omitted) */
+  ownerType?: string;
+  /** A metafield type such as `number_integer`, `json` or `list.single_line_text_field` (defaults to `json`) */
+  type?: string;
+  /** A JSON Schema the metafield's value must also match */
+  schema?: any;
+}
+
+/**
+ * The metafield schemas that fixture metafields are checked against
+ */
+export type MetafieldSchemaRegistry = MetafieldSchema[];
+
+/**
+ * Load a metafield schema registry from a JSON file
+ *
+ * The file holds an array of metafield schemas, e.g.
+ * `[{ "ownerType": "Discount", "key": "function-configuration", "type": "json", "schema": { ... } }]`.
+ * Schemas are JSON Schema draft-07, so one that uses a keyword of a later draft, such
+ * as `prefixItems`, is rejected rather than having the keyword ignored.
+ * @param {string} filename - The path to the metafield schemas JSON file
+ * @returns {Promise<MetafieldSchemaRegistry>} The metafield schemas
+ */
+export async function loadMetafieldSchemas(
+  filename: string,
+): Promise<MetafieldSchemaRegistry> {
+  try {
+    const content = await fs.promises.readFile(filename, "utf-8");
+    const metafieldSchemas = JSON.parse(content);
+
+    if (!Array.isArray(metafieldSchemas)) {
+      throw new Error("Expected an array of metafield schemas");
+    }
+    for (const [index, metafieldSchema] of metafieldSchemas.entries()) {
+      if (typeof metafieldSchema?.key !== "string") {
+        throw new Error(`Metafield schema ${index} must have a string key`);
+      }
+      const problem =
+        metafieldSchema.schema === undefined
+          ? null
+          : draft07Problem(metafieldSchema.schema);
+      if (problem !== null) {
+        throw new Error(`Metafield schema ${index} ${problem}`);
+      }
+    }
+
+    return metafieldSchemas;
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    throw new Error(
+      `Failed to load metafield schemas from ${filename}: ${errorMessage}`,
+    );
+  }
+}
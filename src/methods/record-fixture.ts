@@ -0,0 +1,213 @@
+/**
+ * Record a function's actual output as a fixture's expected output
+ */
+
+import fs from "fs";
+
+import { GraphQLSchema } from "graphql";
+
+import type { ExtensionInputVariablesConfig } from "../utils/read-extension-toml.js";
+import { replaceJsonValue } from "../utils/replace-json-value.js";
+
+import { diffFunctionOutput } from "./diff-function-output.js";
+import { injectFetchResult } from "./inject-fetch-result.js";
+import { FetchFixtureData, FixtureData, loadFixture } from "./load-fixture.js";
+import { loadInputQuery } from "./load-input-query.js";
+import { loadSchema } from "./load-schema.js";
+import { resolveInputVariables } from "./resolve-input-variables.js";
+import { runFunction } from "./run-function.js";
+
+/**
+ * Interface for the record fixture result
+ */
+export interface RecordFixtureResult {
+  /** The output the function produced, or null if the run failed */
+  output: any;
+  /** For a fixture with a fetch stage, the output of the fetch export */
+  fetchOutput?: any;
+  /** Whether the fixture file was rewritten (false if the outputs already matched) */
+  updated: boolean;
+  error: string | null;
+}
+
+/**
+ * Run a fixture through function-runner and write the actual output to its `payload.output`
+ *
+ * This is the fixture equivalent of updating a snapshot. Only the `payload.output`
+ * value (and `payload.fetch.output` for a fixture with a fetch stage) is rewritten:
+ * every other field of the fixture, and the formatting of the file, is kept as it
+ * was. Outputs are compared with diffFunctionOutput, so an output that only differs
+ * in how a value is written, such as the Decimal `"10"` instead of `"10.0"`, is kept.
+ * The file is left untouched when the outputs already match, or when the function
+ * fails to run.
+ *
+ * A fixture with a fetch stage runs the fetch export first and records the request it
+ * produces, then runs the run export with the canned response as `fetchResult`.
+ *
+ * Given the metafield bound by `[extensions.input.variables]`, each stage runs with
+ * its variables taken from that metafield in its input (see resolveInputVariables),
+ * as runFixture does.
+ * @param {string} fixturePath - Path to the fixture JSON file
+ * @param {string} functionRunnerPath - Path to the function runner binary
+ * @param {string} wasmPath - Path to the WASM file
+ * @param {string} queryPath - Path to the input query file
+ * @param {string} schemaPath - Path to the schema file
+ * @param {string} [fetchQueryPath] - Path to the input query of the fetch target, for a fixture with a fetch stage
+ * @param {ExtensionInputVariablesConfig} [inputVariables] - The metafield's namespace and key (see FunctionInfo.inputVariables)
+ * @returns {Promise<RecordFixtureResult>} The recorded outputs and whether the file changed
+ */
+export async function recordFixture(
+  fixturePath: string,
+  functionRunnerPath: string,
+  wasmPath: string,
+  queryPath: string,
+  schemaPath: string,
+  fetchQueryPath?: string,
+  inputVariables?: ExtensionInputVariablesConfig,
+): Promise<RecordFixtureResult> {
+  try {
+    const loadedFixture = await loadFixture(fixturePath);
+    const schema = await loadSchema(schemaPath);
+    const updates: { path: string[]; value: any }[] = [];
+
+    let fetchOutput: any;
+    if (loadedFixture.fetch) {
+      if (fetchQueryPath === undefined) {
+        throw new Error(
+          `The fixture has a fetch stage, so the input query of ${loadedFixture.fetch.target} is needed to record it`,
+        );
+      }
+      const fetchStage = await runStage(
+        loadedFixture.fetch,
+        schema,
+        functionRunnerPath,
+        wasmPath,
+        fetchQueryPath,
+        schemaPath,
+        inputVariables,
+      );
+      if (fetchStage.error !== null) {
+        return { output: null, updated: false, error: fetchStage.error };
+      }
+      fetchOutput = fetchStage.output;
+      if (fetchStage.changed) {
+        updates.push({
+          path: ["payload", "fetch", "output"],
+          value: fetchOutput,
+        });
+      }
+    }
+
+    const fixture = loadedFixture.fetch
+      ? injectFetchResult(loadedFixture, await loadInputQuery(queryPath))
+      : loadedFixture;
+    const { output, changed, error } = await runStage(
+      fixture,
+      schema,
+      functionRunnerPath,
+      wasmPath,
+      queryPath,
+      schemaPath,
+      inputVariables,
+    );
+    if (error !== null) {
+      return { output: null, updated: false, error };
+    }
+    if (changed) {
+      updates.push({ path: ["payload", "output"], value: output });
+    }
+
+    const result: RecordFixtureResult = loadedFixture.fetch
+      ? { output, fetchOutput, updated: false, error: null }
+      : { output, updated: false, error: null };
+    if (updates.length === 0) {
+      return result;
+    }
+
+    const fixtureContent = await fs.promises.readFile(fixturePath, "utf-8");
+    await fs.promises.writeFile(
+      fixturePath,
+      applyUpdates(fixtureContent, updates),
+    );
+    return { ...result, updated: true };
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    return {
+      output: null,
+      updated: false,
+      error: `Failed to record fixture ${fixturePath}: ${errorMessage}`,
+    };
+  }
+}
+
+/**
+ * Run one stage of a fixture and compare its output with the stage's expected output
+ */
+async function runStage(
+  stage: FixtureData | FetchFixtureData,
+  schema: GraphQLSchema,
+  functionRunnerPath: string,
+  wasmPath: string,
+  queryPath: string,
+  schemaPath: string,
+  inputVariables: ExtensionInputVariablesConfig | undefined,
+): Promise<{ output: any; changed: boolean; error: string | null }> {
+  const fixture = inputVariables
+    ? resolveInputVariables(
+        stage,
+        await loadInputQuery(queryPath),
+        schema,
+        inputVariables,
+      )
+    : stage;
+  const { result, error } = await runFunction(
+    fixture,
+    functionRunnerPath,
+    wasmPath,
+    queryPath,
+    schemaPath,
+  );
+  if (error !== null || result === null) {
+    return { output: null, changed: false, error };
+  }
+
+  const { differences } = diffFunctionOutput({
+    schema,
+    actual: result.output,
+    expected: stage.expectedOutput,
+    target: stage.target,
+  });
+  return {
+    output: result.output,
+    changed: differences.length > 0,
+    error: null,
+  };
+}
+
+/**
+ * Write each value to its path in the fixture's JSON text, keeping the formatting of
+ * the rest of the file
+ */
+function applyUpdates(
+  fixtureContent: string,
+  updates: { path: string[]; value: any }[],
+): string {
+  let updatedContent: string | null = fixtureContent;
+  for (const { path, value } of updates) {
+    updatedContent =
+      updatedContent && replaceJsonValue(updatedContent, path, value);
+  }
+  if (updatedContent !== null) {
+    return updatedContent;
+  }
+
+  // An output the fixture has no value for yet is added after the other fields
+  const fixtureJson = JSON.parse(fixtureContent);
+  for (const { path, value } of updates) {
+    const parent = path
+      .slice(0, -1)
+      .reduce((object, key) => object[key], fixtureJson);
+    parent[path[path.length - 1]] = value;
+  }
+  return `${JSON.stringify(fixtureJson, null, 2)}\n`;
+}
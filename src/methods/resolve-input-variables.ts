@@ -0,0 +1,111 @@
+/**
+ * Take a fixture's input query variables from the metafield Shopify reads them from
+ */
+
+import { DocumentNode, FieldNode, GraphQLSchema, Kind } from "graphql";
+
+import type { ExtensionInputVariablesConfig } from "../utils/read-extension-toml.js";
+
+import { FixtureData } from "./load-fixture.js";
+import { visitFixtureInput } from "./validate-fixture-input.js";
+
+/**
+ * Returns the fixture with its input query variables taken from the metafield bound by
+ * `[extensions.input.variables]`, when its input has that metafield
+ *
+ * Shopify binds the variables to the JSON value of that metafield, so an input query that
+ * selects it, e.g. `metafield(namespace: "$app:discount", key: "input-variables")
+ * { jsonValue }`, carries the values in the fixture input. The metafield is found by its
+ * literal `namespace` and `key` arguments, and its `jsonValue` (or its `value`, parsed as
+ * JSON) becomes `variables`. Fixtures whose input does not have the metafield keep the
+ * values from `payload.variables`, and are returned unchanged.
+ * @param {FixtureData} fixture - The fixture (from loadFixture)
+ * @param {DocumentNode} inputQueryAST - The input query AST of the fixture's target
+ * @param {GraphQLSchema} schema - The GraphQL schema
+ * @param {ExtensionInputVariablesConfig} [inputVariables] - The metafield's namespace and key (see FunctionInfo.inputVariables)
+ * @returns {FixtureData} The fixture with the metafield's value as `variables`
+ * @throws {Error} If the metafield's value is not a JSON object
+ */
+export function resolveInputVariables(
+  fixture: FixtureData,
+  inputQueryAST: DocumentNode,
+  schema: GraphQLSchema,
+  inputVariables: ExtensionInputVariablesConfig | undefined,
+): FixtureData {
+  if (!inputVariables) {
+    return fixture;
+  }
+
+  const { namespace, key } = inputVariables;
+  const values: any[] = [];
+  visitFixtureInput(
+    inputQueryAST,
+    schema,
+    fixture.input,
+    {},
+    ({ node, values: metafields }) => {
+      if (
+        node.name.value === "metafield" &&
+        stringLiteral(node, "namespace") === namespace &&
+        stringLiteral(node, "key") === key
+      ) {
+        values.push(
+          ...metafields
+            .map((metafield) => metafieldValue(node, metafield))
+            .filter((value) => value !== undefined),
+        );
+      }
+    },
+  );
+  if (values.length === 0) {
+    return fixture;
+  }
+
+  const [variables] = values;
+  if (
+    typeof variables !== "object" ||
+    variables === null ||
+    Array.isArray(variables)
+  ) {
+    throw new Error(
+      `The \`${namespace}.${key}\` metafield bound by [extensions.input.variables] must hold a JSON object, but got ${JSON.stringify(variables)}`,
+    );
+  }
+  return { ...fixture, variables };
+}
+
+/**
+ * The value of a metafield in the fixture input, from its `jsonValue` or `value`
+ */
+function metafieldValue(field: FieldNode, metafield: any): any {
+  if (typeof metafield !== "object" || metafield === null) {
+    return undefined;
+  }
+
+  for (const selection of field.selectionSet?.selections ?? []) {
+    if (selection.kind !== Kind.FIELD) {
+      continue;
+    }
+    const value = metafield[selection.alias?.value ?? selection.name.value];
+    if (selection.name.value === "jsonValue" && value !== undefined) {
+      return value;
+    }
+    if (selection.name.value === "value" && typeof value === "string") {
+      try {
+        return JSON.parse(value);
+      } catch {
+        return value;
+      }
+    }
+  }
+  return undefined;
+}
+
+function stringLiteral(field: FieldNode, name: string): string | undefined {
+  const argument = field.arguments?.find(
+    (candidate) => candidate.name.value === name,
+  );
+  return argument?.value.kind === Kind.STRING
+    ? argument.value.value
+    : undefined;
+}
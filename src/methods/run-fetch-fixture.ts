@@ -0,0 +1,177 @@
+/**
+ * Run a fixture's fetch target, then its run target with the canned response
+ */
+
+import { GraphQLSchema } from "graphql";
+
+import { determineMutationFromTarget } from "../utils/determine-mutation-from-target.js";
+
+import {
+  diffFunctionOutput,
+  OutputDifference,
+} from "./diff-function-output.js";
+import { MockHttpServer } from "./create-mock-http-server.js";
+import { FunctionInfo } from "./get-function-info.js";
+import { injectFetchResult } from "./inject-fetch-result.js";
+import { FixtureData, HttpResponseFixture } from "./load-fixture.js";
+import { loadInputQuery } from "./load-input-query.js";
+import { runFunction, RunFunctionOutput } from "./run-function.js";
+import { validateFixtureOutput } from "./validate-fixture-output.js";
+
+/**
+ * Interface for run fetch fixture options
+ */
+export interface RunFetchFixtureOptions {
+  schema: GraphQLSchema;
+  /** A fixture with a fetch stage (from loadFixture) */
+  fixture: FixtureData;
+  functionInfo: FunctionInfo;
+  /** Send the request to this server and use its response instead of `payload.fetch.response` */
+  httpServer?: MockHttpServer;
+}
+
+/**
+ * Interface for the run fetch fixture result
+ */
+export interface RunFetchFixtureResult {
+  /** The fetch export's run, whose output holds the HTTP request */
+  fetch: RunFunctionOutput | null;
+  /** Errors validating the request against the fetch target's result type */
+  requestErrors: { message: string }[];
+  /** Differences between the request and the fetch stage's expected output */
+  requestDifferences: OutputDifference[];
+  /** The response injected as `fetchResult` */
+  response: HttpResponseFixture | null;
+  /** Whether the request to the HTTP server exceeded its readTimeoutMs */
+  timedOut: boolean;
+  /** The run fixture, with the canned response injected as `fetchResult` */
+  runFixture: FixtureData | null;
+  /** The run export's run */
+  run: RunFunctionOutput | null;
+  error: string | null;
+}
+
+/**
+ * Run both stages of a fixture for a target with network access
+ *
+ * This function:
+ * 1. Runs the fetch export with `payload.fetch.input`
+ * 2. Validates the HTTP request it produced against the fetch target's result type,
+ *    and compares it with `payload.fetch.output`
+ * 3. Injects `payload.fetch.response` into the run input as `fetchResult` (see injectFetchResult)
+ * 4. Runs the run export with that input
+ *
+ * With an `httpServer` (see createMockHttpServer), the request is sent to the server
+ * instead and its actual response is injected, so the request's shape and timeout
+ * policy are exercised end to end. A request that times out, or that the function
+ * does not make, results in a null `fetchResult`.
+ *
+ * The run output is not compared here, so it can be checked with diffFunctionOutput
+ * like the output of any other fixture.
+ * @param {RunFetchFixtureOptions} options - The schema, the fixture and the function info
+ * @returns {Promise<RunFetchFixtureResult>} The result of both stages
+ */
+export async function runFetchFixture({
+  schema,
+  fixture,
+  functionInfo,
+  httpServer,
+}: RunFetchFixtureOptions): Promise<RunFetchFixtureResult> {
+  const result: RunFetchFixtureResult = {
+    fetch: null,
+    requestErrors: [],
+    requestDifferences: [],
+    response: null,
+    timedOut: false,
+    runFixture: null,
+    run: null,
+    error: null,
+  };
+
+  try {
+    const { fetch } = fixture;
+    if (!fetch) {
+      throw new Error("Fixture has no fetch stage (payload.fetch)");
+    }
+
+    const fetchQueryPath = inputQueryPath(functionInfo, fetch.target);
+    const runQueryPath = inputQueryPath(functionInfo, fixture.target);
+
+    const fetchRun = await runFunction(
+      fetch,
+      functionInfo.functionRunnerPath,
+      functionInfo.wasmPath,
+      fetchQueryPath,
+      functionInfo.schemaPath,
+    );
+    if (fetchRun.error !== null || fetchRun.result === null) {
+      result.error = `Fetch stage failed: ${fetchRun.error ?? "no output"}`;
+      return result;
+    }
+    result.fetch = fetchRun.result;
+
+    const { mutationName, resultParameterName } = determineMutationFromTarget(
+      fetch.target,
+      schema,
+    );
+    const requestValidation = await validateFixtureOutput(
+      fetchRun.result.output,
+      schema,
+      mutationName,
+      resultParameterName,
+    );
+    result.requestErrors = requestValidation.errors;
+    if (fetch.expectedOutput !== undefined) {
+      result.requestDifferences = diffFunctionOutput({
+        schema,
+        actual: fetchRun.result.output,
+        expected: fetch.expectedOutput,
+        mutationName,
+        resultParameterName,
+      }).differences;
+    }
+
+    result.response = fetch.response ?? null;
+    if (httpServer) {
+      const request = fetchRun.result.output?.request ?? null;
+      const fetched = request
+        ? await httpServer.fetch(request)
+        : { response: null, errors: [], timedOut: false };
+      result.requestErrors = [...result.requestErrors, ...fetched.errors];
+      result.response = fetched.response;
+      result.timedOut = fetched.timedOut;
+    }
+
+    const runQueryAST = await loadInputQuery(runQueryPath);
+    result.runFixture = injectFetchResult(
+      { ...fixture, fetch: { ...fetch, response: result.response } },
+      runQueryAST,
+    );
+
+    const run = await runFunction(
+      result.runFixture,
+      functionInfo.functionRunnerPath,
+      functionInfo.wasmPath,
+      runQueryPath,
+      functionInfo.schemaPath,
+    );
+    if (run.error !== null || run.result === null) {
+      result.error = `Run stage failed: ${run.error ?? "no output"}`;
+      return result;
+    }
+    result.run = run.result;
+
+    return result;
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    return { ...result, error: errorMessage };
+  }
+}
+
+function inputQueryPath(functionInfo: FunctionInfo, target: string): string {
+  const targetInfo = functionInfo.targeting[target];
+  if (!targetInfo?.inputQueryPath) {
+    throw new Error(`'${target}' is not a target of this function`);
+  }
+  return targetInfo.inputQueryPath;
+}
@@ -0,0 +1,291 @@
+/**
+ * Validate and run a fixture file, comparing the output with its expected output
+ */
+
+import fs from "fs";
+import path from "path";
+
+import { GraphQLSchema } from "graphql";
+
+import {
+  diffFunctionOutput,
+  OutputDifference,
+} from "./diff-function-output.js";
+import { FunctionInfo } from "./get-function-info.js";
+import { injectFetchResult } from "./inject-fetch-result.js";
+import { FixtureData, loadFixture } from "./load-fixture.js";
+import { loadInputQuery } from "./load-input-query.js";
+import { MetafieldSchemaRegistry } from "./load-metafield-schemas.js";
+import { resolveInputVariables } from "./resolve-input-variables.js";
+import { runFunction, RunFunctionOutput } from "./run-function.js";
+import {
+  CompleteValidationResult,
+  validateTestAssets,
+} from "./validate-test-assets.js";
+
+/**
+ * Interface for run fixture options
+ */
+export interface RunFixtureOptions {
+  schema: GraphQLSchema;
+  functionInfo: FunctionInfo;
+  /** The path to the fixture file */
+  fixturePath: string;
+  /** Schemas to check the fixture's metafields against (see loadMetafieldSchemas) */
+  metafieldSchemas?: MetafieldSchemaRegistry;
+}
+
+/**
+ * Interface for the result of one export of a fixture
+ */
+export interface FixtureStageResult {
+  target: string;
+  /** The validation of the stage's test assets, or null if its input query could not be loaded */
+  validation: CompleteValidationResult | null;
+  /** The run, or null if the test assets are invalid or the run failed */
+  run: RunFunctionOutput | null;
+  /** Differences between the output and the expected output */
+  differences: OutputDifference[];
+  error: string | null;
+  passed: boolean;
+}
+
+/**
+ * Interface for the result of a fixture file
+ */
+export interface FixtureRunResult {
+  fixturePath: string;
+  /** The fetch stage of a fixture for a target with network access, then the run stage */
+  stages: FixtureStageResult[];
+  /** Why the fixture could not be loaded */
+  error: string | null;
+  passed: boolean;
+}
+
+/**
+ * Validates and runs a fixture file the way the example test suites do
+ *
+ * This function:
+ * 1. Loads the fixture and the input query of its target from the function info
+ * 2. Validates the test assets with validateTestAssets
+ * 3. Runs the function with runFunction, if the test assets are valid
+ * 4. Compares the output with the expected output with diffFunctionOutput
+ *
+ * A fixture with a fetch stage runs it first, then runs the run stage with the canned
+ * response injected as `fetchResult` (see injectFetchResult). Each stage's input query
+ * variables come from the `[extensions.input.variables]` metafield when its input has it
+ * (see resolveInputVariables). The fixture passes if every stage is valid, runs and
+ * produces its expected output.
+ * @param {RunFixtureOptions} options - The schema, the function info and the fixture file
+ * @returns {Promise<FixtureRunResult>} The result of each stage
+ */
+export async function runFixture({
+  schema,
+  functionInfo,
+  fixturePath,
+  metafieldSchemas,
+}: RunFixtureOptions): Promise<FixtureRunResult> {
+  let fixture: FixtureData;
+  try {
+    fixture = await loadFixture(fixturePath);
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    return { fixturePath, stages: [], error: errorMessage, passed: false };
+  }
+
+  const stages: FixtureStageResult[] = [];
+  if (fixture.fetch) {
+    stages.push(
+      await runStage(schema, functionInfo, fixture.fetch, metafieldSchemas),
+    );
+  }
+  stages.push(await runStage(schema, functionInfo, fixture, metafieldSchemas));
+
+  return {
+    fixturePath,
+    stages,
+    error: null,
+    passed: stages.every((stage) => stage.passed),
+  };
+}
+
+/**
+ * Lists why a fixture failed, one line per problem, prefixed with the stage's target, e.g.
+ * "cart.lines.discounts.generate.run: output: operations[0]: Missing item, expected ..."
+ * @param {FixtureRunResult} result - The fixture's result (from runFixture)
+ * @returns {string[]} The problems, empty if the fixture passed
+ */
+export function fixtureFailures(result: FixtureRunResult): string[] {
+  if (result.error !== null) {
+    return [result.error];
+  }
+
+  return result.stages.flatMap((stage) =>
+    stageFailures(stage).map((failure) => `${stage.target}: ${failure}`),
+  );
+}
+
+/**
+ * Lists why one stage of a fixture failed, one line per problem, naming the phase that
+ * found it: `input query`, `input fixture`, `output fixture`, the run or `output`
+ * @param {FixtureStageResult} stage - The stage's result
+ * @returns {string[]} The problems, empty if the stage passed
+ */
+export function stageFailures({
+  validation,
+  error,
+  differences,
+}: FixtureStageResult): string[] {
+  const failures = validation ? validationFailures(validation) : [];
+  if (error !== null) {
+    failures.push(error);
+  }
+  for (const { path, message } of differences) {
+    failures.push(`output: ${path}: ${message}`);
+  }
+  return failures;
+}
+
+/**
+ * Lists the errors validateTestAssets found, one line per error, naming the phase that
+ * found it and the path of the fixture field, e.g.
+ * "input fixture: Missing expected fixture data for `cost` at cart.lines[0].cost"
+ * @param {CompleteValidationResult} validation - The result of validateTestAssets
+ * @returns {string[]} The errors, empty if the test assets are valid
+ */
+export function validationFailures(
+  validation: CompleteValidationResult,
+): string[] {
+  const failures: string[] = [];
+  if (validation.error) {
+    failures.push(`validation: ${validation.error}`);
+  }
+  for (const { message } of validation.inputQuery.errors) {
+    failures.push(`input query: ${message}`);
+  }
+  for (const { message, path } of validation.inputFixture.errors) {
+    failures.push(
+      path.length > 0
+        ? `input fixture: ${message} at ${formatFieldPath(path)}`
+        : `input fixture: ${message}`,
+    );
+  }
+  for (const { message } of validation.outputFixture.errors) {
+    failures.push(`output fixture: ${message}`);
+  }
+  return failures;
+}
+
+/**
+ * Formats the path of a fixture input error the way GraphQL paths are written, e.g.
+ * `cart.lines[0].cost`
+ * @param {(string | number)[]} path - The path, from FixtureInputValidationError.path
+ * @returns {string} The formatted path
+ */
+export function formatFieldPath(path: (string | number)[]): string {
+  return path
+    .map((segment, index) =>
+      typeof segment === "number"
+        ? `[${segment}]`
+        : `${index > 0 ? "." : ""}${segment}`,
+    )
+    .join("");
+}
+
+/**
+ * Formats whether a fixture passed as a `PASS` or `FAIL` line with its file name,
+ * followed by an indented line for each reason it failed
+ * @param {FixtureRunResult} result - The fixture's result (from runFixture)
+ * @returns {string} The formatted result
+ */
+export function formatFixtureResult(result: FixtureRunResult): string {
+  const name = path.basename(result.fixturePath);
+  if (result.passed) {
+    return `PASS ${name}`;
+  }
+  const failures = fixtureFailures(result).map((failure) => `  ${failure}`);
+  return [`FAIL ${name}`, ...failures].join("\n");
+}
+
+/**
+ * Finds the fixture files directly in a directory
+ * @param {string} fixturesDir - The fixtures directory, e.g. `tests/fixtures`
+ * @returns {Promise<string[]>} The paths of its JSON files, sorted
+ */
+export async function findFixtures(fixturesDir: string): Promise<string[]> {
+  const files = await fs.promises.readdir(fixturesDir);
+  return files
+    .filter((file) => file.endsWith(".json"))
+    .map((file) => path.join(fixturesDir, file))
+    .sort();
+}
+
+async function runStage(
+  schema: GraphQLSchema,
+  functionInfo: FunctionInfo,
+  stageFixture: FixtureData,
+  metafieldSchemas: MetafieldSchemaRegistry | undefined,
+): Promise<FixtureStageResult> {
+  const stage: FixtureStageResult = {
+    target: stageFixture.target,
+    validation: null,
+    run: null,
+    differences: [],
+    error: null,
+    passed: false,
+  };
+
+  try {
+    const inputQueryPath =
+      functionInfo.targeting[stageFixture.target]?.inputQueryPath;
+    if (!inputQueryPath) {
+      throw new Error(
+        `'${stageFixture.target}' is not a target of this function`,
+      );
+    }
+    const inputQueryAST = await loadInputQuery(inputQueryPath);
+    // Fixtures with a fetch stage get its canned response as the run input's fetchResult,
+    // and fixtures with the `[extensions.input.variables]` metafield get its variables
+    const fixture = resolveInputVariables(
+      injectFetchResult(stageFixture, inputQueryAST),
+      inputQueryAST,
+      schema,
+      functionInfo.inputVariables,
+    );
+
+    stage.validation = await validateTestAssets({
+      schema,
+      fixture,
+      inputQueryAST,
+      metafieldSchemas,
+    });
+    if (validationFailures(stage.validation).length > 0) {
+      return stage;
+    }
+
+    const { result, error } = await runFunction(
+      fixture,
+      functionInfo.functionRunnerPath,
+      functionInfo.wasmPath,
+      inputQueryPath,
+      functionInfo.schemaPath,
+    );
+    if (error !== null || result === null) {
+      stage.error = `Run failed: ${error ?? "no output"}`;
+      return stage;
+    }
+    stage.run = result;
+
+    stage.differences = diffFunctionOutput({
+      schema,
+      actual: result.output,
+      expected: fixture.expectedOutput,
+      target: fixture.target,
+    }).differences;
+    stage.passed = stage.differences.length === 0;
+    return stage;
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    return { ...stage, error: errorMessage };
+  }
+}
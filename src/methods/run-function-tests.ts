@@ -0,0 +1,105 @@
+/**
+ * Build a function and run every fixture in its fixtures directory
+ */
+
+import path from "path";
+
+import {
+  buildFunction,
+  BuildFunctionOptions,
+  BuildFunctionResult,
+} from "./build-function.js";
+import { getFunctionInfo } from "./get-function-info.js";
+import { MetafieldSchemaRegistry } from "./load-metafield-schemas.js";
+import { loadSchema } from "./load-schema.js";
+import { findFixtures, FixtureRunResult, runFixture } from "./run-fixture.js";
+
+/**
+ * Interface for run function tests options
+ */
+export interface RunFunctionTestsOptions {
+  /** The function directory, containing shopify.extension.toml */
+  functionDir: string;
+  /** The directory of fixture files (defaults to `tests/fixtures` in the function directory) */
+  fixturesDir?: string;
+  /** Schemas to check the fixtures' metafields against (see loadMetafieldSchemas) */
+  metafieldSchemas?: MetafieldSchemaRegistry;
+  /** Options for the build, e.g. `{ direct: true }` to build without the Shopify CLI */
+  buildOptions?: BuildFunctionOptions;
+  /** Called as each fixture passes or fails */
+  onFixtureResult?: (result: FixtureRunResult) => void;
+}
+
+/**
+ * Interface for the result of running a function's fixtures
+ */
+export interface RunFunctionTestsResult {
+  /** The build, or null if the function info could not be loaded */
+  build: BuildFunctionResult | null;
+  /** The result of each fixture, in file name order */
+  results: FixtureRunResult[];
+  /** Why the fixtures could not be run, e.g. a failed build or no fixtures */
+  error: string | null;
+  /** Whether the build succeeded and every fixture passed */
+  passed: boolean;
+}
+
+/**
+ * Builds a function and runs every fixture in its fixtures directory, the way the
+ * example `tests/default.test.js` suites do, without a test framework
+ *
+ * This function:
+ * 1. Loads the function info with getFunctionInfo and its schema
+ * 2. Builds the function with buildFunction
+ * 3. Runs each `.json` fixture with runFixture, which validates it with
+ *    validateTestAssets, runs it with runFunction and compares the output
+ * @param {RunFunctionTestsOptions} options - The function directory and fixtures
+ * @returns {Promise<RunFunctionTestsResult>} The build and the result of each fixture
+ */
+export async function runFunctionTests(
+  options: RunFunctionTestsOptions,
+): Promise<RunFunctionTestsResult> {
+  const functionDir = path.resolve(options.functionDir);
+  const fixturesDir = path.resolve(
+    functionDir,
+    options.fixturesDir ?? "tests/fixtures",
+  );
+  const result: RunFunctionTestsResult = {
+    build: null,
+    results: [],
+    error: null,
+    passed: false,
+  };
+
+  try {
+    const functionInfo = await getFunctionInfo(functionDir);
+    const schema = await loadSchema(functionInfo.schemaPath);
+
+    result.build = await buildFunction(functionDir, options.buildOptions);
+    if (!result.build.success) {
+      throw new Error(result.build.error ?? "Build failed");
+    }
+
+    const fixturePaths = await findFixtures(fixturesDir);
+    if (fixturePaths.length === 0) {
+      throw new Error(`No fixtures found in ${fixturesDir}`);
+    }
+
+    for (const fixturePath of fixturePaths) {
+      const fixtureResult = await runFixture({
+        schema,
+        functionInfo,
+        fixturePath,
+        metafieldSchemas: options.metafieldSchemas,
+      });
+      result.results.push(fixtureResult);
+      options.onFixtureResult?.(fixtureResult);
+    }
+  } catch (error) {
+    result.error = error instanceof Error ? error.message : String(error);
+    return result;
+  }
+
+  result.passed = result.results.every((fixture) => fixture.passed);
+  return result;
+}
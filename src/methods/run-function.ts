@@ -3,14 +3,40 @@
  */
 
 import { spawn } from "child_process";
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import { Kind, print } from "graphql";
+
+import { inlineQueryVariables } from "../utils/inline-query-variables.js";
 
 import { FixtureData } from "./load-fixture.js";
+import { loadInputQuery } from "./load-input-query.js";
+import { loadSchema } from "./load-schema.js";
+
+/**
+ * Interface for the output and resource usage of a successful run
+ */
+export interface RunFunctionOutput {
+  output: any;
+  /** Number of instructions executed, or null if the runner did not report it */
+  instructions: number | null;
+  /** Linear memory used by the module in kilobytes, or null if the runner did not report it */
+  memoryUsage: number | null;
+  /** Size of the JSON input passed to the function, in bytes */
+  inputSize: number;
+  /** Size of the JSON output returned by the function, in bytes */
+  outputSize: number;
+  /** Anything the function logged to stderr */
+  logs: string;
+}
 
 /**
  * Interface for the run function result
  */
 export interface RunFunctionResult {
-  result: { output: any } | null;
+  result: RunFunctionOutput | null;
   error: string | null;
 }
 
@@ -19,6 +45,8 @@ export interface RunFunctionResult {
  *
  * This function:
  * - Uses function-runner binary directly to run the function.
+ * - Reports the instructions, memory usage, input/output sizes and logs from function-runner's --json output.
+ * - Inlines the fixture's `variables` into the input query, since function-runner does not take variables.
  * @param {String} functionRunnerPath - Path to the function runner binary
  * @param {String} wasmPath - Path to the WASM file
  * @param {FixtureData} fixture - The fixture data containing export, input, and target
@@ -34,10 +62,23 @@ export async function runFunction(
   queryPath: string,
   schemaPath: string,
 ): Promise<RunFunctionResult> {
+  let inlinedQueryDir: string | null = null;
   try {
     const inputJson = JSON.stringify(fixture.input);
 
-    return new Promise((resolve) => {
+    let runnerQueryPath = queryPath;
+    const inlinedQuery =
+      fixture.variables &&
+      (await queryWithVariables(queryPath, schemaPath, fixture.variables));
+    if (inlinedQuery) {
+      inlinedQueryDir = await fs.promises.mkdtemp(
+        path.join(os.tmpdir(), "function-query-"),
+      );
+      runnerQueryPath = path.join(inlinedQueryDir, path.basename(queryPath));
+      await fs.promises.writeFile(runnerQueryPath, inlinedQuery);
+    }
+
+    return await new Promise((resolve) => {
       const runnerProcess = spawn(
         functionRunnerPath,
         [
@@ -46,7 +87,7 @@ export async function runFunction(
           "--export",
           fixture.export,
           "--query-path",
-          queryPath,
+          runnerQueryPath,
           "--schema-path",
           schemaPath,
           "--json",
@@ -89,7 +130,20 @@ export async function runFunction(
           }
 
           resolve({
-            result: { output: result.output },
+            result: {
+              output: result.output,
+              instructions:
+                typeof result.instructions === "number"
+                  ? result.instructions
+                  : null,
+              memoryUsage:
+                typeof result.memory_usage === "number"
+                  ? result.memory_usage
+                  : null,
+              inputSize: Buffer.byteLength(inputJson),
+              outputSize: Buffer.byteLength(JSON.stringify(result.output)),
+              logs: typeof result.logs === "string" ? result.logs : "",
+            },
             error: null,
           });
         } catch (parseError) {
@@ -122,5 +176,30 @@ export async function runFunction(
         error: "Unknown error occurred",
       };
     }
+  } finally {
+    if (inlinedQueryDir !== null) {
+      await fs.promises.rm(inlinedQueryDir, { recursive: true, force: true });
+    }
+  }
+}
+
+/**
+ * The input query with the variables inlined, or null if it declares no variables
+ */
+async function queryWithVariables(
+  queryPath: string,
+  schemaPath: string,
+  variables: Record<string, any>,
+): Promise<string | null> {
+  const queryAST = await loadInputQuery(queryPath);
+  const declaresVariables = queryAST.definitions.some(
+    (definition) =>
+      definition.kind === Kind.OPERATION_DEFINITION &&
+      (definition.variableDefinitions?.length ?? 0) > 0,
+  );
+  if (!declaresVariables) {
+    return null;
   }
+  const schema = await loadSchema(schemaPath);
+  return print(inlineQueryVariables(queryAST, schema, variables));
 }
@@ -3,10 +3,12 @@ import "core-js/actual/set/symmetric-difference.js";
 import {
   coerceInputValue,
   DocumentNode,
+  FieldNode,
   getNamedType,
   getNullableType,
   GraphQLList,
   GraphQLNamedType,
+  GraphQLOutputType,
   GraphQLSchema,
   isAbstractType,
   isInputType,
@@ -23,12 +25,26 @@ import {
 } from "graphql";
 
 import { inlineNamedFragmentSpreads } from "../utils/inline-named-fragment-spreads.js";
+import { validateArgumentResponses } from "../utils/validate-argument-responses.js";
+import {
+  findMetafieldSchema,
+  validateMetafield,
+} from "../utils/validate-metafield.js";
+
+import { MetafieldSchemaRegistry } from "./load-metafield-schemas.js";
 
 export interface FixtureInputValidationError {
   message: string;
   path: (string | number)[];
 }
 
+export interface ValidateFixtureInputOptions {
+  /** Schemas to check the fixture's metafields against (see loadMetafieldSchemas) */
+  metafieldSchemas?: MetafieldSchemaRegistry;
+  /** The input query's variable values (see FixtureData.variables) */
+  variables?: Record<string, any>;
+}
+
 export interface ValidateFixtureInputResult {
   errors: FixtureInputValidationError[];
 }
@@ -38,22 +54,73 @@ interface NestedValue {
   path: (string | number)[];
 }
 
+/**
+ * A field of the input query, with its values in the fixture data, as the validator
+ * visits it
+ */
+export interface FixtureFieldVisit {
+  node: FieldNode;
+  /** The field's path in the query by response key, e.g. `["cart", "lines", "merchandise"]` */
+  queryPath: string[];
+  fieldType: GraphQLOutputType;
+  /** The concrete types the field applies to, narrowed by the inline fragments around it */
+  possibleTypes: ReadonlySet<string>;
+  /** The fixture objects the field is selected on */
+  objects: any[];
+  /** The field's value in each fixture object that has it */
+  values: any[];
+}
+
 /**
  * Validates that fixture input data matches the structure and types defined in a GraphQL query.
  *
  * @param queryAST - The parsed GraphQL query document that defines the expected data structure
  * @param schema - The GraphQL schema containing type definitions
  * @param value - The fixture data to validate against the query
+ * @param options - Optional metafield schemas to check `metafield` values against, and
+ *   the input query's variable values
  * @returns A result object containing any validation errors (empty array if valid)
  *
  * @remarks
  * The validator traverses the query AST using the GraphQL visitor pattern and validates
  * the corresponding fixture data at each field.
+ *
+ * `Metafield.value` and `Metafield.jsonValue` are opaque to the schema. When a schema is
+ * registered for a metafield's namespace and key, its value is also checked against the
+ * declared metafield type and JSON Schema.
+ *
+ * Fields that answer a list argument one value at a time, such as `hasTags(tags:)` and
+ * `inCollections(ids:)`, must have one response per requested value, in order. Arguments
+ * can refer to variables, whose values are taken from `options.variables`.
  */
 export function validateFixtureInput(
   queryAST: DocumentNode,
   schema: GraphQLSchema,
   value: any,
+  options: ValidateFixtureInputOptions = {},
+): ValidateFixtureInputResult {
+  return visitFixtureInput(queryAST, schema, value, options);
+}
+
+/**
+ * Runs the validateFixtureInput visitor, also calling `onField` with each field of the
+ * query and its values in the fixture data, e.g. to measure which branches of the
+ * query the fixture exercises
+ *
+ * @param queryAST - The parsed GraphQL query document that defines the expected data structure
+ * @param schema - The GraphQL schema containing type definitions
+ * @param value - The fixture data to validate against the query
+ * @param options - Optional metafield schemas and variable values, as for validateFixtureInput
+ * @param onField - Called as each field is entered, before its selections. A value it
+ *   sets on `objects` is visited like fixture data, e.g. to generate a fixture
+ * @returns A result object containing any validation errors (empty array if valid)
+ */
+export function visitFixtureInput(
+  queryAST: DocumentNode,
+  schema: GraphQLSchema,
+  value: any,
+  options: ValidateFixtureInputOptions = {},
+  onField?: (field: FixtureFieldVisit) => void,
 ): ValidateFixtureInputResult {
   const inlineFragmentSpreadsAst = inlineNamedFragmentSpreads(queryAST);
   const typeInfo = new TypeInfo(schema);
@@ -128,6 +195,24 @@ export function validateFixtureInput(
           }
           const fieldType = fieldDefinition.type;
 
+          if (onField) {
+            const fieldValues = currentValues
+              .map(({ value: currentValue }) => currentValue?.[responseKey])
+              .filter((fieldValue) => fieldValue !== undefined);
+            onField({
+              node,
+              queryPath: [...pathFromAncestors(ancestors), responseKey].map(
+                String,
+              ),
+              fieldType,
+              possibleTypes: currentPossibleTypes,
+              objects: currentValues.map(
+                ({ value: currentValue }) => currentValue,
+              ),
+              values: fieldValues,
+            });
+          }
+
           for (const {
             value: currentValue,
             path: currentPath,
@@ -195,6 +280,15 @@ export function validateFixtureInput(
                     );
                   nestedValues.push(...flattened);
                   errors.push(...flattenErrors);
+                  errors.push(
+                    ...validateArgumentResponses(
+                      getNamedType(unwrappedFieldType).name,
+                      node,
+                      valueForResponseKey,
+                      [...currentPath, responseKey],
+                      options.variables,
+                    ),
+                  );
                 } else {
                   errors.push({
                     message: `Expected array, but got ${typeof valueForResponseKey}`,
@@ -242,6 +336,31 @@ export function validateFixtureInput(
           }
 
           const namedType = getNamedType(fieldType);
+
+          // Metafields with a registered schema
+          const { metafieldSchemas, variables } = options;
+          const metafieldSchema =
+            metafieldSchemas && namedType.name === "Metafield"
+              ? findMetafieldSchema(
+                  metafieldSchemas,
+                  node,
+                  currentPossibleTypes,
+                  variables,
+                )
+              : undefined;
+          if (metafieldSchema) {
+            for (const { value: metafield, path } of nestedValues) {
+              const metafieldErrors = validateMetafield(
+                metafieldSchema,
+                node,
+                metafield,
+              );
+              for (const { message, responseKey: key } of metafieldErrors) {
+                errors.push({ message, path: [...path, key] });
+              }
+            }
+          }
+
           let possibleTypes: string[] = [];
           if (isLeafType(namedType)) {
             // do nothing
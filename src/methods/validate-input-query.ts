@@ -1,20 +1,131 @@
-import { validate, GraphQLSchema, GraphQLError, DocumentNode } from "graphql";
+import {
+  validate,
+  getDirectiveValues,
+  getVariableValues,
+  specifiedRules,
+  GraphQLSchema,
+  GraphQLError,
+  DocumentNode,
+  Kind,
+  ValidationRule,
+} from "graphql";
 
 /**
  * Validate a GraphQL input query AST against a schema
  * @param {DocumentNode} queryAST - The GraphQL query AST
  * @param {GraphQLSchema} schema - Pre-built GraphQL schema
+ * @param {string} [target] - The function target the query is for. When provided, fields
+ *   whose `@restrictTarget(only: [...])` excludes the target are reported.
  * @returns {readonly GraphQLError[]} Array of GraphQL validation errors (empty if valid).
  *   Each error has a 'message' property with the error description.
  */
 export function validateInputQuery(
   queryAST: DocumentNode,
   schema: GraphQLSchema,
+  target?: string,
 ): ReadonlyArray<GraphQLError> {
   try {
-    return validate(schema, queryAST);
+    const rules = target
+      ? [...specifiedRules, restrictTargetRule(target)]
+      : specifiedRules;
+    return validate(schema, queryAST, rules);
   } catch (error) {
     const errorMessage = error instanceof Error ? error.message : String(error);
     return [new GraphQLError(`Failed to validate query: ${errorMessage}`)];
   }
 }
+
+/**
+ * Validate the values a fixture provides for the input query's variables
+ *
+ * Shopify binds input query variables to the metafield named by
+ * `[extensions.input.variables]` in shopify.extension.toml, and fixtures carry their
+ * values in `payload.variables`. Each variable the query declares must have a value of
+ * its type unless it is nullable or has a default, and each value must be for a
+ * variable the query declares.
+ * @param {DocumentNode} queryAST - The GraphQL query AST
+ * @param {GraphQLSchema} schema - Pre-built GraphQL schema
+ * @param {Record<string, any>} variables - The variable values, by name without the `$`
+ * @returns {readonly GraphQLError[]} Array of GraphQL errors (empty if valid)
+ */
+export function validateInputQueryVariables(
+  queryAST: DocumentNode,
+  schema: GraphQLSchema,
+  variables: Record<string, any>,
+): ReadonlyArray<GraphQLError> {
+  try {
+    const operation = queryAST.definitions.find(
+      (definition) => definition.kind === Kind.OPERATION_DEFINITION,
+    );
+    if (!operation || operation.kind !== Kind.OPERATION_DEFINITION) {
+      return [];
+    }
+
+    const variableDefinitions = operation.variableDefinitions ?? [];
+    const errors = [
+      ...(getVariableValues(schema, variableDefinitions, variables).errors ??
+        []),
+    ];
+
+    for (const name of Object.keys(variables)) {
+      if (
+        !variableDefinitions.some(
+          (definition) => definition.variable.name.value === name,
+        )
+      ) {
+        errors.push(
+          new GraphQLError(
+            `Variable "$${name}" is not declared by the input query.`,
+          ),
+        );
+      }
+    }
+
+    return errors;
+  } catch (error) {
+    const errorMessage = error instanceof Error ? error.message : String(error);
+    return [new GraphQLError(`Failed to validate variables: ${errorMessage}`)];
+  }
+}
+
+/**
+ * Reports fields that are annotated with `@restrictTarget(only: [...])` when the
+ * list of allowed targets does not include `target`
+ * @param {string} target - The function target the query is for
+ * @returns {ValidationRule} A graphql-js validation rule
+ */
+function restrictTargetRule(target: string): ValidationRule {
+  return (context) => {
+    const restrictTargetDirective = context
+      .getSchema()
+      .getDirective("restrictTarget");
+
+    return {
+      Field(node) {
+        const parentType = context.getParentType();
+        const fieldDefinition = context.getFieldDef();
+        if (
+          !restrictTargetDirective ||
+          !parentType ||
+          !fieldDefinition?.astNode
+        ) {
+          return;
+        }
+
+        const allowedTargets: string[] | undefined = getDirectiveValues(
+          restrictTargetDirective,
+          fieldDefinition.astNode,
+        )?.only;
+        if (allowedTargets && !allowedTargets.includes(target)) {
+          context.reportError(
+            new GraphQLError(
+              `Cannot query field "${fieldDefinition.name}" on type "${parentType.name}" for target "${target}". ` +
+                `It is restricted to targets: ${allowedTargets.map((allowed) => `"${allowed}"`).join(", ")}.`,
+              { nodes: node },
+            ),
+          );
+        }
+      },
+    };
+  };
+}
@@ -2,13 +2,17 @@ import { GraphQLSchema, GraphQLError, DocumentNode } from "graphql";
 
 import { determineMutationFromTarget } from "../utils/determine-mutation-from-target.js";
 
-import { validateInputQuery } from "./validate-input-query.js";
+import {
+  validateInputQuery,
+  validateInputQueryVariables,
+} from "./validate-input-query.js";
 import { validateFixtureOutput } from "./validate-fixture-output.js";
 import {
   validateFixtureInput,
   FixtureInputValidationError,
 } from "./validate-fixture-input.js";
 import { FixtureData } from "./load-fixture.js";
+import { MetafieldSchemaRegistry } from "./load-metafield-schemas.js";
 
 /**
  * Interface for validate test assets options
@@ -17,8 +21,11 @@ export interface ValidateTestAssetsOptions {
   schema: GraphQLSchema;
   fixture: FixtureData;
   inputQueryAST: DocumentNode;
+  target?: string;
   mutationName?: string;
   resultParameterName?: string;
+  /** Schemas to check the fixture's metafields against (see loadMetafieldSchemas) */
+  metafieldSchemas?: MetafieldSchemaRegistry;
 }
 
 /**
@@ -45,7 +52,8 @@ export interface CompleteValidationResult {
  * Validates test assets (input query and fixture) before function execution
  *
  * This function provides a one-stop validation solution that:
- * 1. Validates the input query against the schema
+ * 1. Validates the input query against the schema, and the fixture's variable values
+ *    against the variables it declares
  * 2. Validates the input fixture data against the schema and query structure
  * 3. Validates the output fixture data against the specified mutation
  *
@@ -53,8 +61,10 @@ export interface CompleteValidationResult {
  * @param {GraphQLSchema} options.schema - The built GraphQL schema
  * @param {Object} options.fixture - The loaded fixture data (from loadFixture)
  * @param {DocumentNode} options.inputQueryAST - The parsed input query AST (from loadInputQuery)
+ * @param {string} [options.target] - The function target, used to check `@restrictTarget` fields in the input query (defaults to the fixture target)
  * @param {string} [options.mutationName] - The mutation name for output validation (auto-determined from target if not provided)
  * @param {string} [options.resultParameterName] - The mutation parameter name (auto-determined from target if not provided)
+ * @param {MetafieldSchemaRegistry} [options.metafieldSchemas] - Schemas to check the fixture's metafields against
  * @returns {Promise<Object>} Complete validation results with structure:
  *   - mutationName: string - Mutation name used for validation
  *   - resultParameterName: string - Parameter name used for validation
@@ -66,8 +76,10 @@ export async function validateTestAssets({
   schema,
   fixture,
   inputQueryAST,
+  target = fixture.target,
   mutationName,
   resultParameterName,
+  metafieldSchemas,
 }: ValidateTestAssetsOptions): Promise<CompleteValidationResult> {
   const results: CompleteValidationResult = {
     mutationName,
@@ -82,8 +94,15 @@ export async function validateTestAssets({
   };
 
   try {
-    // Step 1: Validate input query
-    const inputQueryErrors = validateInputQuery(inputQueryAST, schema);
+    // Step 1: Validate input query and its variables
+    const inputQueryErrors = [
+      ...validateInputQuery(inputQueryAST, schema, target),
+      ...validateInputQueryVariables(
+        inputQueryAST,
+        schema,
+        fixture.variables ?? {},
+      ),
+    ];
     results.inputQuery = {
       errors: inputQueryErrors,
     };
@@ -93,6 +112,7 @@ export async function validateTestAssets({
       inputQueryAST,
       schema,
       fixture.input,
+      { metafieldSchemas, variables: fixture.variables },
     );
     results.inputFixture = {
       errors: inputFixtureResult.errors,
@@ -101,7 +121,6 @@ export async function validateTestAssets({
     // Step 3: Determine mutation details for output validation
     let determined;
     if (!mutationName || !resultParameterName) {
-      const target = fixture.target;
       if (!target) {
         throw new Error(
           "Fixture must contain target when mutationName and resultParameterName are not provided",
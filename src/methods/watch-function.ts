@@ -0,0 +1,389 @@
+/**
+ * Rebuild a function and rerun the affected fixtures whenever their files change
+ */
+
+import fs from "fs";
+import path from "path";
+
+import { GraphQLSchema } from "graphql";
+
+import {
+  extensionTomlPath,
+  readExtensionToml,
+} from "../utils/read-extension-toml.js";
+import {
+  globBase,
+  globToRegExp,
+  isIgnoredPath,
+  watchGlobs,
+} from "../utils/watched-files.js";
+
+import {
+  buildFunction,
+  BuildFunctionOptions,
+  BuildFunctionResult,
+} from "./build-function.js";
+import { FunctionInfo, getFunctionInfo } from "./get-function-info.js";
+import { loadFixture } from "./load-fixture.js";
+import { MetafieldSchemaRegistry } from "./load-metafield-schemas.js";
+import { loadSchema } from "./load-schema.js";
+import {
+  findFixtures,
+  FixtureRunResult,
+  formatFixtureResult,
+  runFixture,
+} from "./run-fixture.js";
+
+/**
+ * Interface for watch function options
+ */
+export interface WatchFunctionOptions {
+  /** The function directory, containing shopify.extension.toml */
+  functionDir: string;
+  /** The directory of fixture files (defaults to `tests/fixtures` in the function directory) */
+  fixturesDir?: string;
+  /** Schemas to check the fixtures' metafields against (see loadMetafieldSchemas) */
+  metafieldSchemas?: MetafieldSchemaRegistry;
+  /** Options for each build, e.g. `{ direct: true }` to build without the Shopify CLI */
+  buildOptions?: BuildFunctionOptions;
+  /**
+   * Called as each fixture passes or fails. Defaults to printing a PASS or FAIL line,
+   * with the reasons for a failure.
+   */
+  onFixtureResult?: (result: FixtureRunResult) => void;
+  /** Called after each build and rerun */
+  onCycle?: (cycle: WatchCycle) => void;
+  /** How long to wait for further changes before rebuilding, in milliseconds (defaults to 100) */
+  debounceMs?: number;
+}
+
+/**
+ * Interface for one build and rerun of the affected fixtures
+ */
+export interface WatchCycle {
+  /** The files whose changes started the cycle, empty for the initial run */
+  changedFiles: string[];
+  /** The build, or null if nothing the build depends on changed */
+  build: BuildFunctionResult | null;
+  /** The fixtures that were rerun */
+  results: FixtureRunResult[];
+  /** Why the cycle stopped before rerunning the fixtures, e.g. a failed build */
+  error: string | null;
+}
+
+/**
+ * Interface for a running watcher
+ */
+export interface FunctionWatcher {
+  /** Resolves with the initial build and run of every fixture */
+  ready: Promise<WatchCycle>;
+  /** Stops watching */
+  close: () => void;
+}
+
+/**
+ * What a set of changed files requires: a rebuild, and which fixtures to rerun
+ */
+interface AffectedFixtures {
+  /** Reload the function info and schema */
+  reload: boolean;
+  rebuild: boolean;
+  /** Rerun every fixture, e.g. because the function's code changed */
+  all: boolean;
+  /** Targets whose input query changed */
+  targets: Set<string>;
+  /** Fixture files that changed */
+  fixtures: Set<string>;
+}
+
+/**
+ * Builds a function, runs every fixture, then watches for changes and reruns the
+ * fixtures they affect
+ *
+ * The watcher monitors the `[extensions.build].watch` globs and the Cargo manifest of
+ * shopify.extension.toml, the input query of each target and the fixtures directory.
+ * It watches only the directories these are in, and ignores `.git`, `node_modules` and
+ * `target` directories, so build output does not start another cycle:
+ * - A changed source file rebuilds the function and reruns every fixture
+ * - A changed input query rebuilds the function and reruns the fixtures for its target
+ * - A changed fixture reruns that fixture
+ * - A changed shopify.extension.toml or schema reloads the function info and reruns
+ *   every fixture
+ *
+ * Changes made while a cycle runs start another cycle once it ends.
+ * @param {WatchFunctionOptions} options - The function directory and how to report results
+ * @returns {FunctionWatcher} The watcher, whose `ready` promise resolves after the initial run
+ */
+export function watchFunction(options: WatchFunctionOptions): FunctionWatcher {
+  const functionDir = path.resolve(options.functionDir);
+  const fixturesDir = path.resolve(
+    functionDir,
+    options.fixturesDir ?? "tests/fixtures",
+  );
+  const onFixtureResult =
+    options.onFixtureResult ??
+    ((result: FixtureRunResult) => console.log(formatFixtureResult(result)));
+  const debounceMs = options.debounceMs ?? 100;
+
+  let functionInfo: FunctionInfo | undefined;
+  let schema: GraphQLSchema;
+  let sourcePatterns: RegExp[] = [];
+  /** The targets of each fixture file, to find the fixtures an input query affects */
+  const fixtureTargets = new Map<string, string[]>();
+
+  const loadFunction = async () => {
+    const info = await getFunctionInfo(functionDir);
+    schema = await loadSchema(info.schemaPath);
+    const extension = await readExtensionToml(functionDir);
+    const globs = watchGlobs(extension.build);
+    sourcePatterns = [...globs, "Cargo.toml", "Cargo.lock"].map(globToRegExp);
+    functionInfo = info;
+
+    // Recursively watch the directories of the globs, and the directories of the
+    // schema and input queries
+    const directories = new Map<string, boolean>([
+      [functionDir, false],
+      [fixturesDir, false],
+    ]);
+    for (const glob of globs) {
+      directories.set(path.resolve(functionDir, globBase(glob)), true);
+    }
+    for (const file of [
+      info.schemaPath,
+      ...Object.values(info.targeting).map(
+        ({ inputQueryPath }) => inputQueryPath,
+      ),
+    ]) {
+      const directory = path.dirname(path.resolve(functionDir, file));
+      directories.set(directory, directories.get(directory) ?? false);
+    }
+    updateWatchers(directories);
+    return info;
+  };
+
+  const rerun = async (info: FunctionInfo, fixturePaths: string[]) => {
+    const results: FixtureRunResult[] = [];
+    for (const fixturePath of fixturePaths) {
+      const result = await runFixture({
+        schema,
+        functionInfo: info,
+        fixturePath,
+        metafieldSchemas: options.metafieldSchemas,
+      });
+      results.push(result);
+      onFixtureResult(result);
+    }
+    return results;
+  };
+
+  const runCycle = async (
+    changedFiles: string[],
+    affected: AffectedFixtures,
+  ): Promise<WatchCycle> => {
+    const cycle: WatchCycle = {
+      changedFiles,
+      build: null,
+      results: [],
+      error: null,
+    };
+    try {
+      const info = affected.reload ? await loadFunction() : functionInfo;
+      if (!info) {
+        throw new Error("The function info is not loaded");
+      }
+      if (affected.rebuild) {
+        cycle.build = await buildFunction(functionDir, options.buildOptions);
+        if (!cycle.build.success) {
+          throw new Error(cycle.build.error ?? "Build failed");
+        }
+      }
+
+      const fixturePaths = await findFixtures(fixturesDir);
+      for (const fixturePath of fixtureTargets.keys()) {
+        if (!fixturePaths.includes(fixturePath)) {
+          fixtureTargets.delete(fixturePath);
+        }
+      }
+      for (const fixturePath of fixturePaths) {
+        if (
+          !fixtureTargets.has(fixturePath) ||
+          affected.fixtures.has(fixturePath)
+        ) {
+          fixtureTargets.set(fixturePath, await targetsOf(fixturePath));
+        }
+      }
+
+      cycle.results = await rerun(
+        info,
+        fixturePaths.filter(
+          (fixturePath) =>
+            affected.all ||
+            affected.fixtures.has(fixturePath) ||
+            fixtureTargets
+              .get(fixturePath)
+              ?.some((target) => affected.targets.has(target)),
+        ),
+      );
+    } catch (error) {
+      cycle.error = error instanceof Error ? error.message : String(error);
+    }
+    options.onCycle?.(cycle);
+    return cycle;
+  };
+
+  const classify = (changedFiles: string[]): AffectedFixtures => {
+    // Until the function loads, any change retries the initial run
+    const loaded = functionInfo !== undefined;
+    const affected: AffectedFixtures = {
+      reload: !loaded,
+      rebuild: !loaded,
+      all: !loaded,
+      targets: new Set(),
+      fixtures: new Set(),
+    };
+    for (const file of changedFiles) {
+      const relativePath = path
+        .relative(functionDir, file)
+        .split(path.sep)
+        .join("/");
+      if (
+        file === extensionTomlPath(functionDir) ||
+        file === functionInfo?.schemaPath
+      ) {
+        affected.reload = true;
+        affected.rebuild = true;
+        affected.all = true;
+      } else if (
+        sourcePatterns.some((pattern) => pattern.test(relativePath))
+      ) {
+        affected.rebuild = true;
+        affected.all = true;
+      } else if (isFixtureFile(fixturesDir, file)) {
+        affected.fixtures.add(file);
+      }
+      for (const [target, { inputQueryPath }] of Object.entries(
+        functionInfo?.targeting ?? {},
+      )) {
+        if (path.resolve(inputQueryPath) === file) {
+          affected.rebuild = true;
+          affected.targets.add(target);
+        }
+      }
+    }
+    return affected;
+  };
+
+  let closed = false;
+  let running: Promise<WatchCycle>;
+  let timer: NodeJS.Timeout | undefined;
+  const pendingFiles = new Set<string>();
+
+  const scheduleCycle = () => {
+    clearTimeout(timer);
+    timer = setTimeout(() => {
+      running = running.then(() => {
+        const changedFiles = [...pendingFiles].sort();
+        pendingFiles.clear();
+        const affected = classify(changedFiles);
+        if (
+          closed ||
+          (!affected.rebuild &&
+            affected.fixtures.size === 0 &&
+            affected.targets.size === 0)
+        ) {
+          return { changedFiles, build: null, results: [], error: null };
+        }
+        return runCycle(changedFiles, affected);
+      });
+    }, debounceMs);
+  };
+
+  const onChange =
+    (directory: string) => (_event: string, filename: string | null) => {
+      if (!filename || closed) {
+        return;
+      }
+      const file = path.resolve(directory, filename);
+      const relativePath = path
+        .relative(functionDir, file)
+        .split(path.sep)
+        .join("/");
+      if (isIgnoredPath(relativePath)) {
+        return;
+      }
+      pendingFiles.add(file);
+      scheduleCycle();
+    };
+
+  /** The watcher of each directory, keyed by the directory and whether it is recursive */
+  const watchers = new Map<string, fs.FSWatcher>();
+  const updateWatchers = (directories: Map<string, boolean>) => {
+    const keys = new Set(
+      [...directories].map(([directory, recursive]) =>
+        JSON.stringify([directory, recursive]),
+      ),
+    );
+    for (const [key, watcher] of watchers) {
+      if (!keys.has(key)) {
+        watcher.close();
+        watchers.delete(key);
+      }
+    }
+    for (const [directory, recursive] of directories) {
+      const key = JSON.stringify([directory, recursive]);
+      if (closed || watchers.has(key) || !fs.existsSync(directory)) {
+        continue;
+      }
+      watchers.set(
+        key,
+        fs.watch(directory, { recursive }, onChange(directory)),
+      );
+    }
+  };
+
+  // Until the function loads, watch shopify.extension.toml and the fixtures
+  updateWatchers(
+    new Map([
+      [functionDir, false],
+      [fixturesDir, false],
+    ]),
+  );
+
+  running = runCycle([], {
+    reload: true,
+    rebuild: true,
+    all: true,
+    targets: new Set(),
+    fixtures: new Set(),
+  });
+
+  return {
+    ready: running,
+    close: () => {
+      closed = true;
+      clearTimeout(timer);
+      for (const watcher of watchers.values()) {
+        watcher.close();
+      }
+      watchers.clear();
+    },
+  };
+}
+
+function isFixtureFile(fixturesDir: string, file: string): boolean {
+  return path.dirname(file) === fixturesDir && file.endsWith(".json");
+}
+
+/**
+ * The targets a fixture file runs, or none if it cannot be loaded
+ */
+async function targetsOf(fixturePath: string): Promise<string[]> {
+  try {
+    const fixture = await loadFixture(fixturePath);
+    return fixture.fetch
+      ? [fixture.fetch.target, fixture.target]
+      : [fixture.target];
+  } catch {
+    return [];
+  }
+}
@@ -0,0 +1,105 @@
+import crypto from "crypto";
+import fs from "fs";
+import path from "path";
+
+import { ExtensionConfig, extensionTomlPath } from "./read-extension-toml.js";
+import { findWatchedFiles, watchGlobs } from "./watched-files.js";
+
+/**
+ * The files next to a function's Cargo manifest that change what it builds
+ */
+const CARGO_FILES = ["Cargo.toml", "Cargo.lock"];
+
+/**
+ * Hashes everything a function's build depends on
+ *
+ * That is the files matching the `[extensions.build].watch` globs, the Cargo manifest
+ * and lock file, the input queries of each target and the `shopify.extension.toml`
+ * itself, along with how the function is built.
+ *
+ * @param functionDir - The function directory
+ * @param extension - The function's `shopify.extension.toml` configuration
+ * @param buildMode - How the function is built, e.g. `cli` or `direct`
+ * @returns The hash, or undefined if the function declares no watch globs, so that
+ *   changes to its sources cannot be detected
+ */
+export async function hashBuildInputs(
+  functionDir: string,
+  extension: ExtensionConfig,
+  buildMode: string,
+): Promise<string | undefined> {
+  const globs = watchGlobs(extension.build);
+  if (globs.length === 0) {
+    return undefined;
+  }
+
+  const root = path.resolve(functionDir);
+  const inputQueries = extension.targeting.flatMap((target) =>
+    target.input_query ? [path.resolve(root, target.input_query)] : [],
+  );
+  const files = new Set([
+    extensionTomlPath(root),
+    ...CARGO_FILES.map((file) => path.join(root, file)),
+    ...inputQueries,
+    ...(await findWatchedFiles(root, globs)),
+  ]);
+
+  const hash = crypto.createHash("sha256");
+  hash.update(`${buildMode}\0`);
+  for (const file of [...files].sort()) {
+    hash.update(`${path.relative(root, file).split(path.sep).join("/")}\0`);
+    try {
+      hash.update(await fs.promises.readFile(file));
+    } catch {
+      // A missing file hashes differently from an empty one
+      hash.update("\0missing");
+    }
+    hash.update("\0");
+  }
+  return hash.digest("hex");
+}
+
+/**
+ * The file that records the hash of the inputs a wasm module was built from
+ *
+ * @param wasmPath - The built wasm module
+ * @returns The path of the hash file, next to the module
+ */
+export function buildHashPath(wasmPath: string): string {
+  return `${wasmPath}.build-hash`;
+}
+
+/**
+ * Checks whether a wasm module was built from inputs with the given hash
+ *
+ * @param wasmPath - The built wasm module
+ * @param buildHash - The hash of the current build inputs
+ * @returns The size of the module if it exists and was built from the same inputs
+ */
+export async function findCachedBuild(
+  wasmPath: string,
+  buildHash: string,
+): Promise<number | undefined> {
+  try {
+    const [recordedHash, { size }] = await Promise.all([
+      fs.promises.readFile(buildHashPath(wasmPath), "utf-8"),
+      fs.promises.stat(wasmPath),
+    ]);
+    return recordedHash.trim() === buildHash ? size : undefined;
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Records the hash of the inputs a wasm module was built from
+ *
+ * @param wasmPath - The built wasm module
+ * @param buildHash - The hash of the build inputs
+ */
+export async function recordBuildHash(
+  wasmPath: string,
+  buildHash: string,
+): Promise<void> {
+  await fs.promises.writeFile(buildHashPath(wasmPath), `${buildHash}\n`);
+}
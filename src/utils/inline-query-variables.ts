@@ -0,0 +1,55 @@
+import {
+  astFromValue,
+  DocumentNode,
+  GraphQLInputType,
+  GraphQLSchema,
+  isInputType,
+  Kind,
+  typeFromAST,
+  visit,
+} from "graphql";
+
+/**
+ * Replaces the variables of a query with their values, and removes their definitions.
+ *
+ * function-runner reads the input query from a file and has no way to pass variables, so
+ * the query it gets is self-contained, e.g. `hasAnyTag(tags: $tags)` with `tags: ["VIP"]`
+ * becomes `hasAnyTag(tags: ["VIP"])`.
+ *
+ * @param document - The query document, e.g. from loadInputQuery
+ * @param schema - The GraphQL schema, for the types of the variables
+ * @param variables - The variable values, by name without the `$`
+ * @returns A new document without variables
+ * @throws {Error} If a variable has no value of its type
+ */
+export function inlineQueryVariables(
+  document: DocumentNode,
+  schema: GraphQLSchema,
+  variables: Record<string, any>,
+): DocumentNode {
+  const types = new Map<string, GraphQLInputType>();
+  for (const definition of document.definitions) {
+    if (definition.kind !== Kind.OPERATION_DEFINITION) {
+      continue;
+    }
+    for (const { variable, type } of definition.variableDefinitions ?? []) {
+      const inputType = typeFromAST(schema, type);
+      if (inputType && isInputType(inputType)) {
+        types.set(variable.name.value, inputType);
+      }
+    }
+  }
+
+  return visit(document, {
+    VariableDefinition: () => null,
+    Variable(node) {
+      const name = node.name.value;
+      const type = types.get(name);
+      const value = type && astFromValue(variables[name] ?? null, type);
+      if (!value) {
+        throw new Error(`Variable "$${name}" has no value to inline`);
+      }
+      return value;
+    },
+  });
+}
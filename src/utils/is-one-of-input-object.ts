@@ -0,0 +1,20 @@
+import { GraphQLInputObjectType } from "graphql";
+
+/**
+ * Whether an input object type is annotated with @oneOf
+ *
+ * graphql-js only sets `isOneOf` from 16.9 on, so the directive on the type's
+ * AST node is checked as well.
+ * @param {GraphQLInputObjectType} type - The input object type
+ * @returns {boolean} True if exactly one field of the type must be set
+ */
+export function isOneOfInputObject(type: GraphQLInputObjectType): boolean {
+  return (
+    (type as { isOneOf?: boolean }).isOneOf === true ||
+    Boolean(
+      type.astNode?.directives?.some(
+        (directive) => directive.name.value === "oneOf",
+      ),
+    )
+  );
+}
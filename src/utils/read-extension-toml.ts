@@ -0,0 +1,112 @@
+import fs from "fs";
+import path from "path";
+
+import { parse } from "smol-toml";
+
+/**
+ * Interface for a function target declared in `[[extensions.targeting]]`
+ */
+export interface ExtensionTargetConfig {
+  target: string;
+  input_query?: string;
+  export?: string;
+}
+
+/**
+ * Interface for the `[extensions.build]` table of a function
+ */
+export interface ExtensionBuildConfig {
+  /** The command that builds the wasm module, e.g. `cargo build --target=wasm32-wasip1 --release` */
+  command?: string;
+  /** The built wasm module, relative to the function directory */
+  path?: string;
+  /** Globs of the source files the build depends on, e.g. `src/**\/*.rs` */
+  watch?: string | string[];
+}
+
+/**
+ * Interface for the `[extensions.input.variables]` table of a function, naming the
+ * metafield whose JSON value Shopify binds to the input query's variables
+ */
+export interface ExtensionInputVariablesConfig {
+  namespace: string;
+  key: string;
+}
+
+/**
+ * Interface for the function entry of a `shopify.extension.toml`
+ */
+export interface ExtensionConfig {
+  type?: string;
+  targeting: ExtensionTargetConfig[];
+  build?: ExtensionBuildConfig;
+  input?: { variables?: ExtensionInputVariablesConfig };
+}
+
+/**
+ * The wasm path the Shopify CLI builds to when `[extensions.build].path` is omitted
+ */
+export const DEFAULT_WASM_PATH = "dist/index.wasm";
+
+/**
+ * The path to a function's `shopify.extension.toml`
+ *
+ * @param functionDir - The function directory
+ * @returns The absolute path to the file
+ */
+export function extensionTomlPath(functionDir: string): string {
+  return path.join(path.resolve(functionDir), "shopify.extension.toml");
+}
+
+/**
+ * Reads the function entry of a function's `shopify.extension.toml`
+ *
+ * The entry is the first `[[extensions]]` of type `function`, or the first entry if
+ * none has that type.
+ *
+ * @param functionDir - The function directory
+ * @returns The function's configuration
+ * @throws {Error} If the file cannot be read or parsed, or has no usable entry
+ */
+export async function readExtensionToml(
+  functionDir: string,
+): Promise<ExtensionConfig> {
+  const content = await fs.promises.readFile(
+    extensionTomlPath(functionDir),
+    "utf-8",
+  );
+  const config = parse(content) as Record<string, any>;
+
+  const extensions = Array.isArray(config.extensions) ? config.extensions : [];
+  const extension =
+    extensions.find((candidate) => candidate?.type === "function") ??
+    extensions[0];
+  if (!extension) {
+    throw new Error("Expected an [[extensions]] entry");
+  }
+
+  const targeting = extension.targeting ?? [];
+  for (const [index, target] of targeting.entries()) {
+    if (typeof target?.target !== "string") {
+      throw new Error(`Targeting ${index} must have a string target`);
+    }
+  }
+
+  const variables = extension.input?.variables;
+  if (
+    variables !== undefined &&
+    (typeof variables?.namespace !== "string" ||
+      typeof variables?.key !== "string")
+  ) {
+    throw new Error(
+      "[extensions.input.variables] must have a string namespace and key",
+    );
+  }
+
+  return {
+    type: extension.type,
+    targeting,
+    build: extension.build,
+    input: extension.input,
+  };
+}
@@ -0,0 +1,124 @@
+/**
+ * Replace one value in a JSON document without reformatting the rest of it
+ */
+
+/**
+ * Replaces the value at an object key path in JSON text
+ *
+ * Only the text of the value is rewritten, so the rest of the document keeps its
+ * key order, whitespace and line endings. The new value is serialized with the
+ * document's indentation unit and aligned with the line holding its key.
+ * @param {string} text - The JSON document
+ * @param {string[]} path - The object keys leading to the value, e.g. `["payload", "output"]`
+ * @param {any} value - The value to write
+ * @returns {string | null} The updated document, or null if the path does not exist
+ * @throws {Error} If the document has an unterminated string
+ */
+export function replaceJsonValue(
+  text: string,
+  path: string[],
+  value: any,
+): string | null {
+  let position = skipWhitespace(text, 0);
+
+  for (const key of path) {
+    const valueStart = findObjectValue(text, position, key);
+    if (valueStart === null) {
+      return null;
+    }
+    position = valueStart;
+  }
+
+  const start = position;
+  const end = skipValue(text, start);
+
+  const lineStart = text.lastIndexOf("\n", start - 1) + 1;
+  const lineIndent = /^[ \t]*/.exec(text.slice(lineStart, start))![0];
+  // Documents without indented lines are minified, so the value is too
+  const indentUnit = /^([ \t]+)\S/m.exec(text)?.[1] ?? "";
+  const newline = text.includes("\r\n") ? "\r\n" : "\n";
+
+  const serialized = JSON.stringify(value, null, indentUnit)
+    .split("\n")
+    .join(`${newline}${lineIndent}`);
+
+  return text.slice(0, start) + serialized + text.slice(end);
+}
+
+/**
+ * The start of the value for `key` in the object starting at `position`, or null
+ * if the value there is not an object or has no such key
+ */
+function findObjectValue(
+  text: string,
+  position: number,
+  key: string,
+): number | null {
+  if (text[position] !== "{") {
+    return null;
+  }
+
+  position = skipWhitespace(text, position + 1);
+  while (text[position] === '"') {
+    const keyEnd = skipValue(text, position);
+    const currentKey = JSON.parse(text.slice(position, keyEnd));
+
+    // Skip the colon separating the key from its value
+    const valueStart = skipWhitespace(
+      text,
+      skipWhitespace(text, keyEnd) + 1,
+    );
+    if (currentKey === key) {
+      return valueStart;
+    }
+
+    position = skipWhitespace(text, skipValue(text, valueStart));
+    if (text[position] === ",") {
+      position = skipWhitespace(text, position + 1);
+    }
+  }
+  return null;
+}
+
+/**
+ * The end of the JSON value starting at `position`
+ */
+function skipValue(text: string, position: number): number {
+  let depth = 0;
+
+  do {
+    const char = text[position];
+    if (char === '"') {
+      position += 1;
+      while (position < text.length && text[position] !== '"') {
+        position += text[position] === "\\" ? 2 : 1;
+      }
+      if (position >= text.length) {
+        throw new Error("Unterminated string in JSON document");
+      }
+      position += 1;
+    } else if (char === "{" || char === "[") {
+      depth += 1;
+      position += 1;
+    } else if (char === "}" || char === "]") {
+      depth -= 1;
+      position += 1;
+    } else if (depth > 0) {
+      position += 1;
+    } else {
+      // Numbers, booleans and null end at the next delimiter
+      while (position < text.length && !/[\s,}\]]/.test(text[position])) {
+        position += 1;
+      }
+    }
+  } while (depth > 0 && position < text.length);
+
+  return position;
+}
+
+function skipWhitespace(text: string, position: number): number {
+  while (position < text.length && /\s/.test(text[position])) {
+    position += 1;
+  }
+  return position;
+}
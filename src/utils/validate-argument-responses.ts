@@ -0,0 +1,130 @@
+import { FieldNode, Kind, valueFromASTUntyped } from "graphql";
+
+/**
+ * Interface for a response that does not match the argument it answers
+ */
+export interface ArgumentResponseError {
+  message: string;
+  path: (string | number)[];
+}
+
+/**
+ * Interface for the values a field requests responses for
+ */
+export interface RequestedArgumentValues {
+  /** The argument the values are passed to, e.g. `tags` */
+  argument: string;
+  values: any[];
+  /** The response key of the field that echoes each value, e.g. `tag`, if it is selected */
+  echoResponseKey?: string;
+}
+
+/**
+ * Types that answer a list argument with one object per requested value, e.g.
+ * `hasTags(tags: ["VIP"])` returns `[{ tag: "VIP", hasTag: true }]`
+ */
+const ARGUMENT_RESPONSES: Record<string, { argument: string; field: string }> =
+  {
+    CollectionMembership: { argument: "ids", field: "collectionId" },
+    HasTagResponse: { argument: "tags", field: "tag" },
+  };
+
+/**
+ * Finds the values a field such as `hasTags(tags: $tags)` requests one response for each
+ *
+ * @param typeName - The named type of the field, e.g. `HasTagResponse`
+ * @param field - The field that takes the argument
+ * @param variables - The input query's variable values
+ * @returns The requested values, or undefined if the type does not answer an argument, the
+ *   argument is omitted or it refers to a variable without a value
+ */
+export function requestedArgumentValues(
+  typeName: string,
+  field: FieldNode,
+  variables: Record<string, any> | undefined,
+): RequestedArgumentValues | undefined {
+  const argumentResponse = ARGUMENT_RESPONSES[typeName];
+  const argument = field.arguments?.find(
+    (candidate) => candidate.name.value === argumentResponse?.argument,
+  );
+  if (!argumentResponse || !argument) {
+    return undefined;
+  }
+
+  const value = valueFromASTUntyped(argument.value, variables);
+  if (value === undefined || value === null) {
+    return undefined;
+  }
+  // A single value stands for a list of one, as in GraphQL input coercion
+  const values = Array.isArray(value) ? value : [value];
+  if (values.some((item) => item === undefined)) {
+    return undefined;
+  }
+
+  const echoField = field.selectionSet?.selections.find(
+    (selection) =>
+      selection.kind === Kind.FIELD &&
+      selection.name.value === argumentResponse.field,
+  );
+  return {
+    argument: argumentResponse.argument,
+    values,
+    echoResponseKey:
+      echoField?.kind === Kind.FIELD
+        ? echoField.alias?.value || echoField.name.value
+        : undefined,
+  };
+}
+
+/**
+ * Checks that a list of argument responses answers each requested value, in order
+ *
+ * For example, the `hasTags(tags: $tags)` responses must have one `HasTagResponse` per
+ * tag in `$tags`, and each selected `tag` must be the tag it answers.
+ *
+ * @param typeName - The named type of the field, e.g. `HasTagResponse`
+ * @param field - The field that takes the argument, e.g. `hasTags(tags: $tags)`
+ * @param responses - The fixture's list of responses
+ * @param path - The path to the list in the fixture
+ * @param variables - The input query's variable values
+ * @returns The mismatches found, empty if the responses match the argument
+ */
+export function validateArgumentResponses(
+  typeName: string,
+  field: FieldNode,
+  responses: any[],
+  path: (string | number)[],
+  variables: Record<string, any> | undefined,
+): ArgumentResponseError[] {
+  const requested = requestedArgumentValues(typeName, field, variables);
+  if (!requested) {
+    return [];
+  }
+  const { argument, values, echoResponseKey } = requested;
+
+  if (responses.length !== values.length) {
+    return [
+      {
+        message: `Expected a \`${typeName}\` for each value of the \`${argument}\` argument (${values.length}), but got ${responses.length}`,
+        path,
+      },
+    ];
+  }
+  if (echoResponseKey === undefined) {
+    return [];
+  }
+
+  const errors: ArgumentResponseError[] = [];
+  for (const [index, response] of responses.entries()) {
+    const echoed = response?.[echoResponseKey];
+    // Missing and mistyped fields are reported by validateFixtureInput
+    if (typeof echoed !== "string" || echoed === values[index]) {
+      continue;
+    }
+    errors.push({
+      message: `Expected ${JSON.stringify(values[index])} from the \`${argument}\` argument, but got ${JSON.stringify(echoed)}`,
+      path: [...path, index, echoResponseKey],
+    });
+  }
+  return errors;
+}
@@ -0,0 +1,218 @@
+import Ajv from "ajv";
+
+/**
+ * Interface for a value that does not match a JSON Schema
+ */
+export interface JsonSchemaError {
+  message: string;
+  /** The path to the value within the validated JSON */
+  path: (string | number)[];
+}
+
+/**
+ * The keywords of JSON Schema 2019-09 and 2020-12 that draft-07 would silently ignore
+ */
+const LATER_DRAFT_KEYWORDS = new Set([
+  "$dynamicAnchor",
+  "$dynamicRef",
+  "$recursiveAnchor",
+  "$recursiveRef",
+  "$vocabulary",
+  "dependentRequired",
+  "dependentSchemas",
+  "maxContains",
+  "minContains",
+  "prefixItems",
+  "unevaluatedItems",
+  "unevaluatedProperties",
+]);
+
+const TYPE_DESCRIPTIONS: Record<string, string> = {
+  null: "null",
+  boolean: "a boolean",
+  object: "an object",
+  array: "an array",
+  number: "a number",
+  integer: "an integer",
+  string: "a string",
+};
+
+// ajv 6 validates schemas as draft-07, as the Rust `validate_json_schema` does
+const ajv = new Ajv({ allErrors: true, verbose: true, jsonPointers: true });
+
+/**
+ * Validates a JSON value against a JSON Schema with ajv, as draft-07
+ *
+ * Mismatched types, enums and constants, missing required properties and unexpected
+ * additional properties are described the same way as the Rust
+ * `validate_json_schema`, e.g. `expected a number, but got "15"` or
+ * ``missing required property `code` ``. Other keywords keep ajv's message. A schema
+ * that cannot be compiled, e.g. because of an unresolvable `$ref`, is reported as a
+ * single error.
+ *
+ * @param schema - The JSON Schema
+ * @param value - The JSON value to validate
+ * @returns The mismatches, empty if the value matches
+ */
+export function validateJsonSchema(schema: any, value: any): JsonSchemaError[] {
+  let validate: Ajv.ValidateFunction;
+  try {
+    validate = ajv.compile(schema);
+  } catch (error) {
+    const message = error instanceof Error ? error.message : String(error);
+    return [{ message: `invalid JSON Schema: ${message}`, path: [] }];
+  }
+
+  if (validate(value)) {
+    return [];
+  }
+  return (validate.errors ?? [])
+    .filter(({ schemaPath }) => !isBranchError(schemaPath))
+    .map((error) => ({
+      message: describeError(error),
+      path: instancePath(value, error.dataPath),
+    }));
+}
+
+/**
+ * Describes why a schema cannot be validated as draft-07, the draft both the
+ * TypeScript and Rust validators use: it declares another `$schema`, or uses a
+ * keyword of a later draft that draft-07 would ignore. `$defs` is allowed, as a
+ * `$ref` to it is a JSON Pointer like any other.
+ *
+ * @param schema - The JSON Schema
+ * @returns The problem, or null if the schema is a draft-07 schema
+ */
+export function draft07Problem(schema: any): string | null {
+  if (schema !== null && typeof schema === "object" && "$schema" in schema) {
+    const declared = schema.$schema;
+    if (
+      typeof declared !== "string" ||
+      declared.replace(/#+$/, "") !== "http://json-schema.org/draft-07/schema"
+    ) {
+      return `declares \`$schema\` ${JSON.stringify(declared)}, but only JSON Schema draft-07 is supported`;
+    }
+  }
+  const keyword = findLaterDraftKeyword(schema);
+  return keyword === null
+    ? null
+    : `uses \`${keyword}\`, which JSON Schema draft-07 does not support`;
+}
+
+/**
+ * The first keyword of a later draft in a schema and its subschemas
+ */
+function findLaterDraftKeyword(schema: any): string | null {
+  if (Array.isArray(schema)) {
+    for (const subschema of schema) {
+      const keyword = findLaterDraftKeyword(subschema);
+      if (keyword !== null) {
+        return keyword;
+      }
+    }
+    return null;
+  }
+  if (schema === null || typeof schema !== "object") {
+    return null;
+  }
+
+  for (const [keyword, value] of Object.entries(schema)) {
+    if (LATER_DRAFT_KEYWORDS.has(keyword)) {
+      return keyword;
+    }
+    let found: string | null;
+    switch (keyword) {
+      // Values, not schemas
+      case "const":
+      case "default":
+      case "enum":
+      case "examples":
+        found = null;
+        break;
+      // Schemas by property or definition name, whose names are not keywords
+      case "$defs":
+      case "definitions":
+      case "dependencies":
+      case "patternProperties":
+      case "properties":
+        found =
+          value !== null && typeof value === "object"
+            ? findLaterDraftKeyword(Object.values(value))
+            : null;
+        break;
+      default:
+        found = findLaterDraftKeyword(value);
+    }
+    if (found !== null) {
+      return found;
+    }
+  }
+  return null;
+}
+
+/**
+ * Whether an error is from a branch of `oneOf`, `anyOf` or `not`, which ajv reports
+ * alongside the error for the keyword itself
+ */
+function isBranchError(schemaPath: string): boolean {
+  return /\/(oneOf|anyOf|not)\//.test(schemaPath);
+}
+
+function describeError({
+  keyword,
+  params,
+  message,
+  data,
+}: Ajv.ErrorObject): string {
+  switch (keyword) {
+    case "type": {
+      const expected = String((params as Ajv.TypeParams).type)
+        .split(",")
+        .map((type) => TYPE_DESCRIPTIONS[type] ?? type)
+        .join(" or ");
+      return mismatch(expected, data);
+    }
+    case "enum": {
+      const options = (params as Ajv.EnumParams).allowedValues
+        .map((option: any) => JSON.stringify(option))
+        .join(", ");
+      return mismatch(`one of ${options}`, data);
+    }
+    case "const":
+      return mismatch(JSON.stringify((params as any).allowedValue), data);
+    case "required": {
+      const property = (params as Ajv.RequiredParams).missingProperty
+        .replace(/^[./]/, "")
+        .replace(/^\['(.*)'\]$/, "$1");
+      return `missing required property \`${property}\``;
+    }
+    case "additionalProperties":
+      return `unexpected property \`${
+        (params as Ajv.AdditionalPropertiesParams).additionalProperty
+      }\``;
+    default:
+      return `${JSON.stringify(data)} ${message}`;
+  }
+}
+
+/**
+ * The path segments of a JSON Pointer into `value`, with array indices as numbers
+ */
+function instancePath(value: any, pointer: string): (string | number)[] {
+  const path: (string | number)[] = [];
+  let current = value;
+  for (const encoded of pointer.split("/").slice(1)) {
+    const segment = encoded.replace(/~1/g, "/").replace(/~0/g, "~");
+    if (Array.isArray(current) && /^\d+$/.test(segment)) {
+      path.push(Number(segment));
+    } else {
+      path.push(segment);
+    }
+    current = current?.[segment];
+  }
+  return path;
+}
+
+function mismatch(expected: string, value: any): string {
+  return `expected ${expected}, but got ${JSON.stringify(value)}`;
+}
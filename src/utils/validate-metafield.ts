@@ -0,0 +1,313 @@
+import { FieldNode, Kind } from "graphql";
+
+import {
+  MetafieldSchema,
+  MetafieldSchemaRegistry,
+} from "../methods/load-metafield-schemas.js";
+
+import { JsonSchemaError, validateJsonSchema } from "./validate-json-schema.js";
+
+/**
+ * Interface for a problem with a metafield's value
+ */
+export interface MetafieldError {
+  message: string;
+  /** The response key of the metafield field that holds the value, e.g. `jsonValue` */
+  responseKey: string;
+}
+
+interface MetafieldType {
+  expected: string;
+  matches: (value: any) => boolean;
+}
+
+const DECIMAL = /^-?\d+(\.\d+)?$/;
+
+const METAFIELD_TYPES: Record<string, MetafieldType> = {
+  boolean: {
+    expected: "a boolean",
+    matches: (value) => typeof value === "boolean",
+  },
+  color: {
+    expected: 'a hex color such as "#FF0000"',
+    matches: (value) =>
+      typeof value === "string" && /^#[0-9A-Fa-f]{6}$/.test(value),
+  },
+  date: {
+    expected: 'a date such as "2025-01-31"',
+    matches: (value) =>
+      typeof value === "string" && /^\d{4}-\d{2}-\d{2}$/.test(value),
+  },
+  date_time: {
+    expected: 'a date and time such as "2025-01-31T12:00:00Z"',
+    matches: (value) =>
+      typeof value === "string" &&
+      /^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})?$/.test(
+        value,
+      ),
+  },
+  dimension: measurement(),
+  json: {
+    expected: "any JSON value",
+    matches: () => true,
+  },
+  money: {
+    expected: "an object with a decimal `amount` and a `currency_code`",
+    matches: (value) =>
+      isJsonObject(value) &&
+      isDecimalString(value.amount) &&
+      typeof value.currency_code === "string",
+  },
+  multi_line_text_field: {
+    expected: "a string",
+    matches: (value) => typeof value === "string",
+  },
+  number_decimal: {
+    expected: "a decimal number",
+    matches: (value) =>
+      (typeof value === "number" && Number.isFinite(value)) ||
+      isDecimalString(value),
+  },
+  number_integer: {
+    expected: "an integer",
+    matches: (value) => Number.isInteger(value),
+  },
+  rating: {
+    expected: "an object with a decimal `value`, `scale_min` and `scale_max`",
+    matches: (value) =>
+      isJsonObject(value) &&
+      isDecimalString(value.value) &&
+      isDecimalString(value.scale_min) &&
+      isDecimalString(value.scale_max),
+  },
+  rich_text_field: {
+    expected: "an object",
+    matches: (value) => isJsonObject(value),
+  },
+  single_line_text_field: {
+    expected: "a single line of text",
+    matches: (value) => typeof value === "string" && !value.includes("\n"),
+  },
+  url: {
+    expected: 'a URL such as "https://example.com"',
+    matches: (value) =>
+      typeof value === "string" &&
+      /^(https?:\/\/|mailto:|sms:|tel:)\S+$/.test(value),
+  },
+  volume: measurement(),
+  weight: measurement(),
+};
+
+const REFERENCE: MetafieldType = {
+  expected: 'a GID such as "gid://shopify/Product/1"',
+  matches: (value) =>
+    typeof value === "string" && /^gid:\/\/shopify\/\w+\/\S+$/.test(value),
+};
+
+/**
+ * Types whose `value` is the string itself rather than serialized JSON
+ */
+const STRING_VALUE_TYPES = new Set([
+  "color",
+  "date",
+  "date_time",
+  "multi_line_text_field",
+  "single_line_text_field",
+  "url",
+]);
+
+/**
+ * Finds the schema registered for a `metafield` field of the input query
+ *
+ * @param registry - The metafield schemas
+ * @param field - The `metafield(namespace:, key:)` field
+ * @param ownerTypes - The possible types of the object the field is selected on
+ * @param variables - The input query's variable values, for arguments such as `key: $key`
+ * @returns The matching schema, or undefined if there is none or the arguments are not strings
+ */
+export function findMetafieldSchema(
+  registry: MetafieldSchemaRegistry,
+  field: FieldNode,
+  ownerTypes: Set<string>,
+  variables?: Record<string, any>,
+): MetafieldSchema | undefined {
+  const namespace = stringArgument(field, "namespace", variables);
+  const key = stringArgument(field, "key", variables);
+  if (namespace === null || typeof key !== "string") {
+    return undefined;
+  }
+
+  return registry.find(
+    (metafieldSchema) =>
+      metafieldSchema.key === key &&
+      (metafieldSchema.namespace ?? undefined) === namespace &&
+      (metafieldSchema.ownerType === undefined ||
+        metafieldSchema.ownerType === null ||
+        ownerTypes.has(metafieldSchema.ownerType)),
+  );
+}
+
+/**
+ * Checks the `type`, `value` and `jsonValue` of a fixture metafield against its schema
+ *
+ * `value` is parsed the way Shopify serializes it for the metafield type: text, date,
+ * color and URL types are stored as is, and other types as JSON. The parsed value and
+ * `jsonValue` must match the metafield type and, if given, the JSON Schema.
+ *
+ * @param metafieldSchema - The schema registered for the metafield
+ * @param field - The `metafield` field of the input query
+ * @param metafield - The fixture's metafield object
+ * @returns The problems found, empty if the metafield matches its schema
+ */
+export function validateMetafield(
+  metafieldSchema: MetafieldSchema,
+  field: FieldNode,
+  metafield: Record<string, any>,
+): MetafieldError[] {
+  const name =
+    metafieldSchema.namespace === undefined ||
+    metafieldSchema.namespace === null
+      ? metafieldSchema.key
+      : `${metafieldSchema.namespace}.${metafieldSchema.key}`;
+  const type = metafieldSchema.type ?? "json";
+  const errors: MetafieldError[] = [];
+
+  for (const selection of field.selectionSet?.selections ?? []) {
+    if (selection.kind !== Kind.FIELD) {
+      continue;
+    }
+    const responseKey = selection.alias?.value || selection.name.value;
+    const fieldValue = metafield[responseKey];
+    // Missing and mistyped fields are reported by validateFixtureInput
+    if (typeof fieldValue === "undefined" || fieldValue === null) {
+      continue;
+    }
+
+    let problems: JsonSchemaError[] = [];
+    if (selection.name.value === "type") {
+      if (metafieldSchema.type !== undefined && fieldValue !== type) {
+        problems = [
+          {
+            message: `expected type \`${type}\`, but got ${JSON.stringify(fieldValue)}`,
+            path: [],
+          },
+        ];
+      }
+    } else if (selection.name.value === "value") {
+      if (typeof fieldValue === "string") {
+        problems = validateValue(
+          type,
+          metafieldSchema.schema,
+          parseValue(type, fieldValue),
+        );
+      }
+    } else if (selection.name.value === "jsonValue") {
+      problems = validateValue(type, metafieldSchema.schema, fieldValue);
+    }
+
+    for (const { message, path } of problems) {
+      const location = path.length > 0 ? ` at \`${path.join(".")}\`` : "";
+      errors.push({
+        message: `Invalid \`${name}\` metafield${location}: ${message}`,
+        responseKey,
+      });
+    }
+  }
+
+  return errors;
+}
+
+function validateValue(
+  type: string,
+  schema: any,
+  value: any,
+): JsonSchemaError[] {
+  const typeErrors = validateMetafieldType(type, value, []);
+  if (typeErrors.length > 0 || schema === undefined) {
+    return typeErrors;
+  }
+  return validateJsonSchema(schema, value);
+}
+
+function validateMetafieldType(
+  type: string,
+  value: any,
+  path: (string | number)[],
+): JsonSchemaError[] {
+  if (type.startsWith("list.")) {
+    if (!Array.isArray(value)) {
+      return [{ message: mismatch("an array", value), path }];
+    }
+    const elementType = type.slice("list.".length);
+    return value.flatMap((element, index) =>
+      validateMetafieldType(elementType, element, [...path, index]),
+    );
+  }
+
+  const metafieldType = type.endsWith("_reference")
+    ? REFERENCE
+    : METAFIELD_TYPES[type];
+  if (!metafieldType) {
+    return [{ message: `unsupported metafield type \`${type}\``, path }];
+  }
+  if (!metafieldType.matches(value)) {
+    return [{ message: mismatch(metafieldType.expected, value), path }];
+  }
+  return [];
+}
+
+function parseValue(type: string, value: string): any {
+  if (STRING_VALUE_TYPES.has(type) || type.endsWith("_reference")) {
+    return value;
+  }
+  try {
+    return JSON.parse(value);
+  } catch {
+    // Reported as a mismatch with the metafield type
+    return value;
+  }
+}
+
+/**
+ * The value of a string argument, given as a literal or a variable: undefined if it is
+ * omitted, null if it is not a string
+ */
+function stringArgument(
+  field: FieldNode,
+  name: string,
+  variables: Record<string, any> | undefined,
+): string | null | undefined {
+  const argument = field.arguments?.find(
+    (candidate) => candidate.name.value === name,
+  );
+  if (!argument) {
+    return undefined;
+  }
+  if (argument.value.kind === Kind.VARIABLE) {
+    const value = variables?.[argument.value.name.value];
+    return typeof value === "string" ? value : null;
+  }
+  return argument.value.kind === Kind.STRING ? argument.value.value : null;
+}
+
+function measurement(): MetafieldType {
+  return {
+    expected: "an object with a numeric `value` and a `unit`",
+    matches: (value) =>
+      isJsonObject(value) &&
+      typeof value.value === "number" &&
+      typeof value.unit === "string",
+  };
+}
+
+function isDecimalString(value: any): boolean {
+  return typeof value === "string" && DECIMAL.test(value);
+}
+
+function isJsonObject(value: any): value is Record<string, any> {
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}
+
+function mismatch(expected: string, value: any): string {
+  return `expected ${expected}, but got ${JSON.stringify(value)}`;
+}
@@ -0,0 +1,135 @@
+import fs from "fs";
+import path from "path";
+
+import type { ExtensionBuildConfig } from "./read-extension-toml.js";
+
+/**
+ * Directories that hold dependencies or build output rather than function sources
+ */
+const IGNORED_DIRECTORIES = new Set([".git", "node_modules", "target"]);
+
+/**
+ * The `[extensions.build].watch` globs of a function, as a list
+ *
+ * @param build - The function's `[extensions.build]` table
+ * @returns The globs, empty if none are declared
+ */
+export function watchGlobs(build: ExtensionBuildConfig | undefined): string[] {
+  const watch = build?.watch;
+  if (typeof watch === "string") {
+    return [watch];
+  }
+  return Array.isArray(watch) ? watch : [];
+}
+
+/**
+ * The directory a glob matches files in, before its first wildcard, e.g. `src` for
+ * `src/**\/*.rs` or an empty string for `*.toml`
+ *
+ * @param glob - The glob, relative to the function directory
+ * @returns The `/`-separated directory, relative to the function directory
+ */
+export function globBase(glob: string): string {
+  const segments = glob.split("/");
+  const wildcard = segments.findIndex((segment) => /[*?{[]/.test(segment));
+  return segments
+    .slice(0, wildcard === -1 ? segments.length - 1 : wildcard)
+    .join("/");
+}
+
+/**
+ * Whether a path relative to a function directory is inside a `.git`, `node_modules`
+ * or `target` directory, which hold dependencies and build output
+ *
+ * @param relativePath - The `/`-separated path, relative to the function directory
+ * @returns True if the path is in an ignored directory
+ */
+export function isIgnoredPath(relativePath: string): boolean {
+  return relativePath
+    .split("/")
+    .some((segment) => IGNORED_DIRECTORIES.has(segment));
+}
+
+/**
+ * Converts a glob such as `src/**\/*.{rs,graphql}` to a regular expression matching
+ * `/`-separated relative paths
+ *
+ * Supports `**` for any number of directories, `*` and `?` within a path segment and
+ * `{a,b}` alternatives.
+ *
+ * @param glob - The glob to convert
+ * @returns A regular expression matching the whole path
+ */
+export function globToRegExp(glob: string): RegExp {
+  let pattern = "";
+  let alternatives = 0;
+  for (let index = 0; index < glob.length; index++) {
+    const char = glob[index];
+    if (glob.startsWith("**/", index)) {
+      pattern += "(?:.*/)?";
+      index += 2;
+    } else if (glob.startsWith("**", index)) {
+      pattern += ".*";
+      index += 1;
+    } else if (char === "*") {
+      pattern += "[^/]*";
+    } else if (char === "?") {
+      pattern += "[^/]";
+    } else if (char === "{") {
+      pattern += "(?:";
+      alternatives++;
+    } else if (char === "}" && alternatives > 0) {
+      pattern += ")";
+      alternatives--;
+    } else if (char === "," && alternatives > 0) {
+      pattern += "|";
+    } else {
+      pattern += char.replace(/[.+^$()|[\]\\{}]/g, "\\$&");
+    }
+  }
+  return new RegExp(`^${pattern}$`);
+}
+
+/**
+ * Finds the files of a function directory that match any of the watch globs
+ *
+ * Skips `.git`, `node_modules` and `target` directories, which hold dependencies and
+ * build output.
+ *
+ * @param functionDir - The function directory the globs are relative to
+ * @param globs - The watch globs, e.g. `src/**\/*.rs`
+ * @returns The absolute paths of the matching files, sorted
+ */
+export async function findWatchedFiles(
+  functionDir: string,
+  globs: string[],
+): Promise<string[]> {
+  if (globs.length === 0) {
+    return [];
+  }
+  const patterns = globs.map(globToRegExp);
+  const root = path.resolve(functionDir);
+  const files: string[] = [];
+
+  const walk = async (directory: string) => {
+    const entries = await fs.promises.readdir(directory, {
+      withFileTypes: true,
+    });
+    for (const entry of entries) {
+      const entryPath = path.join(directory, entry.name);
+      if (entry.isDirectory()) {
+        if (!IGNORED_DIRECTORIES.has(entry.name)) {
+          await walk(entryPath);
+        }
+        continue;
+      }
+      const relativePath = path.relative(root, entryPath).split(path.sep);
+      if (patterns.some((pattern) => pattern.test(relativePath.join("/")))) {
+        files.push(entryPath);
+      }
+    }
+  };
+  await walk(root);
+
+  return files.sort();
+}
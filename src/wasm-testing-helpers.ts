@@ -9,23 +9,145 @@
 export { loadFixture } from "./methods/load-fixture.js";
 export { loadSchema } from "./methods/load-schema.js";
 export { loadInputQuery } from "./methods/load-input-query.js";
+export { loadMetafieldSchemas } from "./methods/load-metafield-schemas.js";
 export { buildFunction } from "./methods/build-function.js";
 export { runFunction } from "./methods/run-function.js";
+export { recordFixture } from "./methods/record-fixture.js";
+export { injectFetchResult } from "./methods/inject-fetch-result.js";
+export { resolveInputVariables } from "./methods/resolve-input-variables.js";
+export { runFetchFixture } from "./methods/run-fetch-fixture.js";
+export {
+  runFixture,
+  fixtureFailures,
+  stageFailures,
+  validationFailures,
+  formatFixtureResult,
+  formatFieldPath,
+} from "./methods/run-fixture.js";
+export { runFunctionTests } from "./methods/run-function-tests.js";
+export {
+  createFixtureReport,
+  formatJsonReport,
+  formatJUnitReport,
+  FIXTURE_REPORT_VERSION,
+} from "./methods/create-fixture-report.js";
+export { watchFunction } from "./methods/watch-function.js";
+export { functionMatchers } from "./methods/function-matchers.js";
+export { createMockHttpServer } from "./methods/create-mock-http-server.js";
+export { diffFunctionOutput } from "./methods/diff-function-output.js";
+export { createFunctionRunner } from "./methods/create-function-runner.js";
 export { getFunctionInfo } from "./methods/get-function-info.js";
+export {
+  loadFunctionInfo,
+  FUNCTION_RUNNER_PATH_ENV,
+} from "./methods/load-function-info.js";
 export { validateTestAssets } from "./methods/validate-test-assets.js";
-export { validateInputQuery } from "./methods/validate-input-query.js";
+export {
+  validateInputQuery,
+  validateInputQueryVariables,
+} from "./methods/validate-input-query.js";
 export { validateFixtureOutput } from "./methods/validate-fixture-output.js";
-export { validateFixtureInput } from "./methods/validate-fixture-input.js";
+export {
+  validateFixtureInput,
+  visitFixtureInput,
+} from "./methods/validate-fixture-input.js";
+export { checkInputCoverage } from "./methods/check-input-coverage.js";
+export { generateFixture } from "./methods/generate-fixture.js";
+export {
+  checkInstructionBudget,
+  computeScaleFactor,
+  DEFAULT_INSTRUCTION_LIMIT,
+  MAX_SCALE_FACTOR,
+} from "./methods/check-instruction-budget.js";
 
 // Export types for consumers
-export type { FixtureData } from "./methods/load-fixture.js";
-export type { BuildFunctionResult } from "./methods/build-function.js";
-export type { RunFunctionResult } from "./methods/run-function.js";
+export type {
+  FixtureData,
+  FetchFixtureData,
+  HttpResponseFixture,
+} from "./methods/load-fixture.js";
+export type {
+  MetafieldSchema,
+  MetafieldSchemaRegistry,
+} from "./methods/load-metafield-schemas.js";
+export type {
+  BuildFunctionOptions,
+  BuildFunctionResult,
+} from "./methods/build-function.js";
+export type {
+  RunFunctionResult,
+  RunFunctionOutput,
+} from "./methods/run-function.js";
+export type {
+  DiffFunctionOutputOptions,
+  DiffFunctionOutputResult,
+  OutputDifference,
+} from "./methods/diff-function-output.js";
+export type { RecordFixtureResult } from "./methods/record-fixture.js";
+export type {
+  RunFetchFixtureOptions,
+  RunFetchFixtureResult,
+} from "./methods/run-fetch-fixture.js";
+export type {
+  RunFixtureOptions,
+  FixtureRunResult,
+  FixtureStageResult,
+} from "./methods/run-fixture.js";
+export type {
+  RunFunctionTestsOptions,
+  RunFunctionTestsResult,
+} from "./methods/run-function-tests.js";
+export type {
+  CreateFixtureReportOptions,
+  FixtureReport,
+  FixtureReportEntry,
+  ReportedValidationError,
+  TargetReport,
+} from "./methods/create-fixture-report.js";
+export type {
+  FunctionMatcherContext,
+  FunctionMatcherResult,
+  FunctionMatchers,
+  ProduceOutputOptions,
+} from "./methods/function-matchers.js";
+export type {
+  WatchFunctionOptions,
+  WatchCycle,
+  FunctionWatcher,
+} from "./methods/watch-function.js";
+export type {
+  CreateFunctionRunnerOptions,
+  FunctionRunner,
+} from "./methods/create-function-runner.js";
+export type {
+  HttpRequestData,
+  MockHttpFetchResult,
+  MockHttpRoute,
+  MockHttpServer,
+  MockHttpServerRequest,
+} from "./methods/create-mock-http-server.js";
 export type { FunctionInfo } from "./methods/get-function-info.js";
+export type { ExtensionInputVariablesConfig } from "./utils/read-extension-toml.js";
 export type {
   ValidateTestAssetsOptions,
   CompleteValidationResult,
 } from "./methods/validate-test-assets.js";
 export type { OutputValidationResult } from "./methods/validate-fixture-output.js";
 export type { MutationTarget } from "./utils/determine-mutation-from-target.js";
-export type { ValidateFixtureInputResult } from "./methods/validate-fixture-input.js";
+export type {
+  ValidateFixtureInputOptions,
+  ValidateFixtureInputResult,
+  FixtureFieldVisit,
+} from "./methods/validate-fixture-input.js";
+export type {
+  InputCoverageOptions,
+  InputCoverageResult,
+  InputFieldCoverage,
+  InputCoverageGap,
+} from "./methods/check-input-coverage.js";
+export type {
+  InstructionBudgetOptions,
+  InstructionBudgetResult,
+  ScaleFactorResult,
+  ScaledListField,
+} from "./methods/check-instruction-budget.js";
@@ -1,6 +1,8 @@
 import path from "path";
 import fs from "fs";
-import { buildFunction, loadFixture, runFunction, validateTestAssets, loadSchema, loadInputQuery, getFunctionInfo } from "@shopify/shopify-function-test-helpers";
+import { buildFunction, loadFixture, runFunction, validateTestAssets, loadSchema, loadInputQuery, getFunctionInfo, functionMatchers } from "@shopify/shopify-function-test-helpers";
+
+expect.extend(functionMatchers);
 
 describe("Default Integration Test", () => {
   let schema;
@@ -13,7 +15,8 @@ describe("Default Integration Test", () => {
 
   beforeAll(async () => {
     functionDir = path.dirname(__dirname);
-    await buildFunction(functionDir);
+    // Skips the build when the function's sources are unchanged since the last run
+    await buildFunction(functionDir, { cache: true });
 
     // Get function info from Shopify CLI
     const functionInfo = await getFunctionInfo(functionDir);
@@ -40,9 +43,7 @@ describe("Default Integration Test", () => {
         fixture,
         inputQueryAST
       });
-      expect(validationResult.inputQuery.errors).toHaveLength(0);
-      expect(validationResult.inputFixture.errors).toHaveLength(0);
-      expect(validationResult.outputFixture.errors).toHaveLength(0);
+      expect(validationResult).toHaveValidTestAssets();
 
       // Run the actual function
       const runResult = await runFunction(
@@ -53,9 +54,10 @@ describe("Default Integration Test", () => {
         schemaPath
       );
 
-      const { result, error } = runResult;
-      expect(error).toBeNull();
-      expect(result.output).toEqual(fixture.expectedOutput);
+      expect(runResult).toProduceOutput(fixture.expectedOutput, {
+        schema,
+        target: fixture.target
+      });
     }, 10000);
   });
 });
@@ -0,0 +1,15 @@
+use crate::discount_codes::validation_request;
+use crate::schema::CartDeliveryOptionsDiscountsGenerateFetchResult;
+
+use super::schema;
+use shopify_function::prelude::*;
+use shopify_function::Result;
+
+#[shopify_function]
+fn cart_delivery_options_discounts_generate_fetch(
+    input: schema::cart_delivery_options_discounts_generate_fetch::Input,
+) -> Result<CartDeliveryOptionsDiscountsGenerateFetchResult> {
+    Ok(CartDeliveryOptionsDiscountsGenerateFetchResult {
+        request: validation_request(input.entered_discount_codes()),
+    })
+}
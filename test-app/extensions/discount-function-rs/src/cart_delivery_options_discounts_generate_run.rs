@@ -1,12 +1,14 @@
+use crate::configuration::Configuration;
+use crate::discount_codes::accepted_discount_codes;
 use crate::schema::CartDeliveryOptionsDiscountsGenerateRunResult;
 use crate::schema::DeliveryDiscountCandidate;
 use crate::schema::DeliveryDiscountCandidateTarget;
 use crate::schema::DeliveryDiscountCandidateValue;
-use crate::schema::DeliveryDiscountSelectionStrategy;
 use crate::schema::DeliveryDiscountsAddOperation;
 use crate::schema::DeliveryGroupTarget;
 use crate::schema::DeliveryOperation;
 use crate::schema::DiscountClass;
+use crate::schema::EnteredDiscountCodesAcceptOperation;
 use crate::schema::Percentage;
 
 use super::schema;
@@ -17,13 +19,32 @@ use shopify_function::Result;
 fn cart_delivery_options_discounts_generate_run(
     input: schema::cart_delivery_options_discounts_generate_run::Input,
 ) -> Result<CartDeliveryOptionsDiscountsGenerateRunResult> {
+    // Without a configuration metafield every discount uses its defaults
+    let configuration = match input.discount().metafield() {
+        Some(metafield) => Configuration::from_json_value(metafield.json_value())?,
+        None => Configuration::default(),
+    };
+
     let has_shipping_discount_class = input
         .discount()
         .discount_classes()
         .contains(&DiscountClass::Shipping);
 
+    let mut operations = vec![];
+
+    // Accept the entered codes the external service validated in the fetch target
+    let accepted_discount_codes =
+        accepted_discount_codes(input.fetch_result().and_then(|result| result.json_body()));
+    if !accepted_discount_codes.is_empty() {
+        operations.push(DeliveryOperation::EnteredDiscountCodesAccept(
+            EnteredDiscountCodesAcceptOperation {
+                codes: accepted_discount_codes,
+            },
+        ));
+    }
+
     if !has_shipping_discount_class {
-        return Ok(CartDeliveryOptionsDiscountsGenerateRunResult { operations: vec![] });
+        return Ok(CartDeliveryOptionsDiscountsGenerateRunResult { operations });
     }
 
     let first_delivery_group = input
@@ -32,23 +53,24 @@ fn cart_delivery_options_discounts_generate_run(
         .first()
         .ok_or("No delivery groups found")?;
 
-    Ok(CartDeliveryOptionsDiscountsGenerateRunResult {
-        operations: vec![DeliveryOperation::DeliveryDiscountsAdd(
-            DeliveryDiscountsAddOperation {
-                selection_strategy: DeliveryDiscountSelectionStrategy::All,
-                candidates: vec![DeliveryDiscountCandidate {
-                    targets: vec![DeliveryDiscountCandidateTarget::DeliveryGroup(
-                        DeliveryGroupTarget {
-                            id: first_delivery_group.id().clone(),
-                        },
-                    )],
-                    value: DeliveryDiscountCandidateValue::Percentage(Percentage {
-                        value: Decimal(100.0),
-                    }),
-                    message: Some("FREE DELIVERY".to_string()),
-                    associated_discount_code: None,
-                }],
-            },
-        )],
-    })
+    let delivery = configuration.delivery()?;
+    operations.push(DeliveryOperation::DeliveryDiscountsAdd(
+        DeliveryDiscountsAddOperation {
+            selection_strategy: delivery.selection_strategy,
+            candidates: vec![DeliveryDiscountCandidate {
+                targets: vec![DeliveryDiscountCandidateTarget::DeliveryGroup(
+                    DeliveryGroupTarget {
+                        id: first_delivery_group.id().clone(),
+                    },
+                )],
+                value: DeliveryDiscountCandidateValue::Percentage(Percentage {
+                    value: Decimal(delivery.percentage),
+                }),
+                message: Some(delivery.message),
+                associated_discount_code: None,
+            }],
+        },
+    ));
+
+    Ok(CartDeliveryOptionsDiscountsGenerateRunResult { operations })
 }
@@ -0,0 +1,15 @@
+use crate::discount_codes::validation_request;
+use crate::schema::CartLinesDiscountsGenerateFetchResult;
+
+use super::schema;
+use shopify_function::prelude::*;
+use shopify_function::Result;
+
+#[shopify_function]
+fn cart_lines_discounts_generate_fetch(
+    input: schema::cart_lines_discounts_generate_fetch::Input,
+) -> Result<CartLinesDiscountsGenerateFetchResult> {
+    Ok(CartLinesDiscountsGenerateFetchResult {
+        request: validation_request(input.entered_discount_codes()),
+    })
+}
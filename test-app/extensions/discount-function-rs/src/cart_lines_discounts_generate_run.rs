@@ -1,18 +1,19 @@
+use crate::configuration::Configuration;
+use crate::discount_codes::accepted_discount_codes;
 use crate::schema::CartLineTarget;
 use crate::schema::CartLinesDiscountsGenerateRunResult;
 use crate::schema::CartOperation;
 use crate::schema::DiscountClass;
+use crate::schema::EnteredDiscountCodesAcceptOperation;
 use crate::schema::OrderDiscountCandidate;
 use crate::schema::OrderDiscountCandidateTarget;
 use crate::schema::OrderDiscountCandidateValue;
-use crate::schema::OrderDiscountSelectionStrategy;
 use crate::schema::OrderDiscountsAddOperation;
 use crate::schema::OrderSubtotalTarget;
 use crate::schema::Percentage;
 use crate::schema::ProductDiscountCandidate;
 use crate::schema::ProductDiscountCandidateTarget;
 use crate::schema::ProductDiscountCandidateValue;
-use crate::schema::ProductDiscountSelectionStrategy;
 use crate::schema::ProductDiscountsAddOperation;
 
 use super::schema;
@@ -36,6 +37,12 @@ fn cart_lines_discounts_generate_run(
         })
         .ok_or("No cart lines found")?;
 
+    // Without a configuration metafield every discount uses its defaults
+    let configuration = match input.discount().metafield() {
+        Some(metafield) => Configuration::from_json_value(metafield.json_value())?,
+        None => Configuration::default(),
+    };
+
     let has_order_discount_class = input
         .discount()
         .discount_classes()
@@ -45,26 +52,42 @@ fn cart_lines_discounts_generate_run(
         .discount_classes()
         .contains(&DiscountClass::Product);
 
-    if !has_order_discount_class && !has_product_discount_class {
+    let accepted_discount_codes =
+        accepted_discount_codes(input.fetch_result().and_then(|result| result.json_body()));
+
+    if !has_order_discount_class
+        && !has_product_discount_class
+        && accepted_discount_codes.is_empty()
+    {
         return Ok(CartLinesDiscountsGenerateRunResult { operations: vec![] });
     }
 
     let mut operations = vec![];
 
+    // Accept the entered codes the external service validated in the fetch target
+    if !accepted_discount_codes.is_empty() {
+        operations.push(CartOperation::EnteredDiscountCodesAccept(
+            EnteredDiscountCodesAcceptOperation {
+                codes: accepted_discount_codes,
+            },
+        ));
+    }
+
     // Check if the discount has the ORDER class
     if has_order_discount_class {
+        let order = configuration.order()?;
         operations.push(CartOperation::OrderDiscountsAdd(
             OrderDiscountsAddOperation {
-                selection_strategy: OrderDiscountSelectionStrategy::First,
+                selection_strategy: order.selection_strategy,
                 candidates: vec![OrderDiscountCandidate {
                     targets: vec![OrderDiscountCandidateTarget::OrderSubtotal(
                         OrderSubtotalTarget {
                             excluded_cart_line_ids: vec![],
                         },
                     )],
-                    message: Some("10% OFF ORDER".to_string()),
+                    message: Some(order.message),
                     value: OrderDiscountCandidateValue::Percentage(Percentage {
-                        value: Decimal(10.0),
+                        value: Decimal(order.percentage),
                     }),
                     conditions: None,
                     associated_discount_code: None,
@@ -75,17 +98,18 @@ fn cart_lines_discounts_generate_run(
 
     // Check if the discount has the PRODUCT class
     if has_product_discount_class {
+        let product = configuration.product()?;
         operations.push(CartOperation::ProductDiscountsAdd(
             ProductDiscountsAddOperation {
-                selection_strategy: ProductDiscountSelectionStrategy::First,
+                selection_strategy: product.selection_strategy,
                 candidates: vec![ProductDiscountCandidate {
                     targets: vec![ProductDiscountCandidateTarget::CartLine(CartLineTarget {
                         id: max_cart_line.id().clone(),
                         quantity: None,
                     })],
-                    message: Some("20% OFF PRODUCT".to_string()),
+                    message: Some(product.message),
                     value: ProductDiscountCandidateValue::Percentage(Percentage {
-                        value: Decimal(20.0),
+                        value: Decimal(product.percentage),
                     }),
                     associated_discount_code: None,
                 }],
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use crate::schema::DeliveryDiscountSelectionStrategy;
+use crate::schema::OrderDiscountSelectionStrategy;
+use crate::schema::ProductDiscountSelectionStrategy;
+
+use shopify_function::prelude::*;
+
+/// The discount's configuration, stored as JSON in its `function-configuration`
+/// metafield, e.g.
+///
+/// ```json
+/// {
+///   "order": { "percentage": 10, "message": "10% OFF ORDER", "selectionStrategy": "FIRST" },
+///   "product": { "percentage": 20, "message": "20% OFF PRODUCT", "selectionStrategy": "FIRST" },
+///   "delivery": { "percentage": 100, "message": "FREE DELIVERY", "selectionStrategy": "ALL" }
+/// }
+/// ```
+///
+/// The metafield's `jsonValue` is read as loose JSON and checked here, so a value of the
+/// wrong type is reported with the field it is in. Every section and field is optional
+/// and defaults to the values above.
+#[derive(Default, PartialEq)]
+pub struct Configuration {
+    pub order: Option<DiscountConfiguration>,
+    pub product: Option<DiscountConfiguration>,
+    pub delivery: Option<DiscountConfiguration>,
+}
+
+/// The configuration of one kind of discount, as stored in the metafield
+#[derive(Default, PartialEq)]
+pub struct DiscountConfiguration {
+    pub percentage: Option<f64>,
+    pub message: Option<String>,
+    pub selection_strategy: Option<String>,
+}
+
+/// One kind of discount, with the defaults in place of missing fields
+pub struct Discount<S> {
+    pub percentage: f64,
+    pub message: String,
+    pub selection_strategy: S,
+}
+
+impl Configuration {
+    /// Read the configuration from the metafield's `jsonValue`
+    pub fn from_json_value(json_value: &JsonValue) -> Result<Configuration, String> {
+        let JsonValue::Object(fields) = json_value else {
+            return Err(
+                "Invalid discount configuration: the metafield must be a JSON object".to_string(),
+            );
+        };
+        Ok(Configuration {
+            order: section(fields, "order")?,
+            product: section(fields, "product")?,
+            delivery: section(fields, "delivery")?,
+        })
+    }
+
+    /// The order discount, 10% off with the `FIRST` strategy by default
+    pub fn order(&self) -> Result<Discount<OrderDiscountSelectionStrategy>, String> {
+        resolve(
+            self.order.as_ref(),
+            "order",
+            Discount {
+                percentage: 10.0,
+                message: "10% OFF ORDER".to_string(),
+                selection_strategy: OrderDiscountSelectionStrategy::First,
+            },
+            |strategy| match strategy {
+                "FIRST" => Some(OrderDiscountSelectionStrategy::First),
+                "MAXIMUM" => Some(OrderDiscountSelectionStrategy::Maximum),
+                _ => None,
+            },
+        )
+    }
+
+    /// The product discount, 20% off with the `FIRST` strategy by default
+    pub fn product(&self) -> Result<Discount<ProductDiscountSelectionStrategy>, String> {
+        resolve(
+            self.product.as_ref(),
+            "product",
+            Discount {
+                percentage: 20.0,
+                message: "20% OFF PRODUCT".to_string(),
+                selection_strategy: ProductDiscountSelectionStrategy::First,
+            },
+            |strategy| match strategy {
+                "ALL" => Some(ProductDiscountSelectionStrategy::All),
+                "FIRST" => Some(ProductDiscountSelectionStrategy::First),
+                "MAXIMUM" => Some(ProductDiscountSelectionStrategy::Maximum),
+                _ => None,
+            },
+        )
+    }
+
+    /// The delivery discount, free delivery with the `ALL` strategy by default
+    pub fn delivery(&self) -> Result<Discount<DeliveryDiscountSelectionStrategy>, String> {
+        resolve(
+            self.delivery.as_ref(),
+            "delivery",
+            Discount {
+                percentage: 100.0,
+                message: "FREE DELIVERY".to_string(),
+                selection_strategy: DeliveryDiscountSelectionStrategy::All,
+            },
+            |strategy| match strategy {
+                "ALL" => Some(DeliveryDiscountSelectionStrategy::All),
+                _ => None,
+            },
+        )
+    }
+}
+
+/// Read one kind of discount from the metafield, checking the type of each field
+fn section(
+    fields: &BTreeMap<String, JsonValue>,
+    path: &str,
+) -> Result<Option<DiscountConfiguration>, String> {
+    let fields = match fields.get(path) {
+        None | Some(JsonValue::Null) => return Ok(None),
+        Some(JsonValue::Object(fields)) => fields,
+        Some(_) => {
+            return Err(format!(
+                "Invalid discount configuration: `{path}` must be an object"
+            ))
+        }
+    };
+
+    let percentage = match fields.get("percentage") {
+        None | Some(JsonValue::Null) => None,
+        Some(JsonValue::Number(percentage)) => Some(*percentage),
+        Some(_) => return Err(invalid(path, "percentage", "must be a number")),
+    };
+    let string = |field: &str| match fields.get(field) {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(JsonValue::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(invalid(path, field, "must be a string")),
+    };
+
+    Ok(Some(DiscountConfiguration {
+        percentage,
+        message: string("message")?,
+        selection_strategy: string("selectionStrategy")?,
+    }))
+}
+
+/// Fill in the defaults of one kind of discount and check the values the schema
+/// types cannot express: the percentage range and the strategies of this kind
+fn resolve<S>(
+    configuration: Option<&DiscountConfiguration>,
+    path: &str,
+    default: Discount<S>,
+    parse_selection_strategy: fn(&str) -> Option<S>,
+) -> Result<Discount<S>, String> {
+    let Some(configuration) = configuration else {
+        return Ok(default);
+    };
+
+    let percentage = configuration.percentage.unwrap_or(default.percentage);
+    if !(0.0..=100.0).contains(&percentage) {
+        return Err(invalid(
+            path,
+            "percentage",
+            "must be a number from 0 to 100",
+        ));
+    }
+    let selection_strategy = match &configuration.selection_strategy {
+        Some(strategy) => parse_selection_strategy(strategy).ok_or_else(|| {
+            invalid(
+                path,
+                "selectionStrategy",
+                &format!("has an unsupported value \"{strategy}\""),
+            )
+        })?,
+        None => default.selection_strategy,
+    };
+
+    Ok(Discount {
+        percentage,
+        message: configuration.message.clone().unwrap_or(default.message),
+        selection_strategy,
+    })
+}
+
+fn invalid(path: &str, field: &str, problem: &str) -> String {
+    format!("Invalid discount configuration: `{path}.{field}` {problem}")
+}
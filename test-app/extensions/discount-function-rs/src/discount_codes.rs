@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use crate::schema::DiscountCode;
+use crate::schema::HttpRequest;
+use crate::schema::HttpRequestHeader;
+use crate::schema::HttpRequestMethod;
+use crate::schema::HttpRequestPolicy;
+
+use shopify_function::prelude::*;
+
+/// The request asking the external service which of the entered discount codes
+/// to accept, or `None` when there are no codes to validate
+pub fn validation_request(entered_discount_codes: &[String]) -> Option<HttpRequest> {
+    if entered_discount_codes.is_empty() {
+        return None;
+    }
+
+    let json_body = JsonValue::Object(BTreeMap::from([(
+        "enteredDiscountCodes".to_string(),
+        JsonValue::Array(
+            entered_discount_codes
+                .iter()
+                .map(|code| JsonValue::String(code.clone()))
+                .collect(),
+        ),
+    )]));
+
+    Some(HttpRequest {
+        headers: vec![
+            HttpRequestHeader {
+                name: "accept".to_string(),
+                value: "application/json".to_string(),
+            },
+            HttpRequestHeader {
+                name: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+            },
+        ],
+        method: HttpRequestMethod::Post,
+        policy: HttpRequestPolicy {
+            read_timeout_ms: 2000,
+        },
+        url: "https://discounts.example.com/validate".to_string(),
+        body: None,
+        json_body: Some(json_body),
+    })
+}
+
+/// The entered discount codes the external service accepted, read from the
+/// `{"acceptedCodes": [...]}` body of the response to the validation request
+pub fn accepted_discount_codes(json_body: Option<&JsonValue>) -> Vec<DiscountCode> {
+    let Some(JsonValue::Object(body)) = json_body else {
+        return vec![];
+    };
+    let Some(JsonValue::Array(codes)) = body.get("acceptedCodes") else {
+        return vec![];
+    };
+
+    codes
+        .iter()
+        .filter_map(|code| match code {
+            JsonValue::String(code) => Some(DiscountCode { code: code.clone() }),
+            _ => None,
+        })
+        .collect()
+}
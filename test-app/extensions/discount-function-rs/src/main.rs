@@ -1,14 +1,24 @@
 use shopify_function::prelude::*;
 use std::process;
 
+pub mod cart_delivery_options_discounts_generate_fetch;
 pub mod cart_delivery_options_discounts_generate_run;
+pub mod cart_lines_discounts_generate_fetch;
 pub mod cart_lines_discounts_generate_run;
+pub mod configuration;
+pub mod discount_codes;
 
 #[typegen("schema.graphql")]
 pub mod schema {
+    #[query("src/cart_lines_discounts_generate_fetch.graphql")]
+    pub mod cart_lines_discounts_generate_fetch {}
+
     #[query("src/cart_lines_discounts_generate_run.graphql")]
     pub mod cart_lines_discounts_generate_run {}
 
+    #[query("src/cart_delivery_options_discounts_generate_fetch.graphql")]
+    pub mod cart_delivery_options_discounts_generate_fetch {}
+
     #[query("src/cart_delivery_options_discounts_generate_run.graphql")]
     pub mod cart_delivery_options_discounts_generate_run {}
 }
@@ -1,6 +1,9 @@
 import path from "path";
 import fs from "fs";
-import { buildFunction, loadFixture, runFunction, validateTestAssets, loadSchema, loadInputQuery, getFunctionInfo } from "@shopify/shopify-function-test-helpers";
+import { buildFunction, loadFixture, runFunction, validateTestAssets, loadSchema, loadInputQuery, getFunctionInfo, checkInstructionBudget, injectFetchResult, diffFunctionOutput, loadMetafieldSchemas } from "@shopify/shopify-function-test-helpers";
+
+// Shopify Functions memory limit
+const MEMORY_LIMIT_KB = 10 * 1024;
 
 describe("Default Integration Test", () => {
   let schema;
@@ -10,16 +13,21 @@ describe("Default Integration Test", () => {
   let targeting;
   let functionRunnerPath;
   let wasmPath;
+  let metafieldSchemas;
 
   beforeAll(async () => {
     functionDir = path.dirname(__dirname);
-    await buildFunction(functionDir);
+    // Skips the build when the function's sources are unchanged since the last run
+    await buildFunction(functionDir, { cache: true });
 
     // Get function info from Shopify CLI
     const functionInfo = await getFunctionInfo(functionDir);
     ({ schemaPath, functionRunnerPath, wasmPath, targeting } = functionInfo);
 
     schema = await loadSchema(schemaPath);
+
+    // The shape of the discount's function-configuration metafield
+    metafieldSchemas = await loadMetafieldSchemas(path.join(__dirname, "metafield-schemas.json"));
   }, 60000); // 60 second timeout for building and obtaining information about the function
 
   const fixturesDir = path.join(__dirname, "fixtures");
@@ -30,15 +38,19 @@ describe("Default Integration Test", () => {
 
   fixtureFiles.forEach((fixtureFile) => {
     test(`runs ${path.relative(fixturesDir, fixtureFile)}`, async () => {
-      const fixture = await loadFixture(fixtureFile);
-      const inputQueryPath = targeting[fixture.target].inputQueryPath;
+      const loadedFixture = await loadFixture(fixtureFile);
+      const inputQueryPath = targeting[loadedFixture.target].inputQueryPath;
       inputQueryAST = await loadInputQuery(inputQueryPath);
 
+      // Fixtures with a fetch stage get its canned response as the run input's fetchResult
+      const fixture = injectFetchResult(loadedFixture, inputQueryAST);
+
       // Validate fixture using our comprehensive validation system
       const validationResult = await validateTestAssets({
         schema,
         fixture,
-        inputQueryAST
+        inputQueryAST,
+        metafieldSchemas
       });
       expect(validationResult.inputQuery.errors).toHaveLength(0);
       expect(validationResult.inputFixture.errors).toHaveLength(0);
@@ -56,6 +68,55 @@ describe("Default Integration Test", () => {
       const { result, error } = runResult;
       expect(error).toBeNull();
       expect(result.output).toEqual(fixture.expectedOutput);
+
+      // The instruction limit scales with the number of cart lines (@scaleLimits)
+      const budget = checkInstructionBudget({
+        schema,
+        fixture,
+        inputQueryAST,
+        instructions: result.instructions
+      });
+      expect(budget.errors).toHaveLength(0);
+      expect(result.memoryUsage).toBeLessThanOrEqual(MEMORY_LIMIT_KB);
+    }, 10000);
+  });
+
+  const fetchFixtureFiles = fixtureFiles.filter(
+    (fixtureFile) => JSON.parse(fs.readFileSync(fixtureFile, "utf-8")).payload.fetch
+  );
+
+  fetchFixtureFiles.forEach((fixtureFile) => {
+    test(`runs the fetch stage of ${path.relative(fixturesDir, fixtureFile)}`, async () => {
+      const { fetch } = await loadFixture(fixtureFile);
+      const inputQueryPath = targeting[fetch.target].inputQueryPath;
+      const fetchQueryAST = await loadInputQuery(inputQueryPath);
+
+      const validationResult = await validateTestAssets({
+        schema,
+        fixture: fetch,
+        inputQueryAST: fetchQueryAST
+      });
+      expect(validationResult.inputQuery.errors).toHaveLength(0);
+      expect(validationResult.inputFixture.errors).toHaveLength(0);
+      expect(validationResult.outputFixture.errors).toHaveLength(0);
+
+      const { result, error } = await runFunction(
+        fetch,
+        functionRunnerPath,
+        wasmPath,
+        inputQueryPath,
+        schemaPath
+      );
+      expect(error).toBeNull();
+
+      // The request the function makes, compared by GraphQL path
+      const { differences } = diffFunctionOutput({
+        schema,
+        actual: result.output,
+        expected: fetch.expectedOutput,
+        target: fetch.target
+      });
+      expect(differences).toEqual([]);
     }, 10000);
   });
 });
@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::Path;
+
+use shopify_function_test_helpers::{
+    create_function_runner, fixture_tests, get_function_info, inject_fetch_result, load_fixture,
+    load_input_query, run_function, CreateFunctionRunnerOptions, FunctionInfo,
+};
+
+// One test per file in tests/fixtures, run against the built wasm, with the
+// discount's function-configuration metafield checked against its schema
+#[fixture_tests("tests/fixtures", metafield_schemas = "tests/metafield-schemas.json")]
+fn discount_function() -> FunctionInfo {
+    get_function_info(env!("CARGO_MANIFEST_DIR")).expect("Failed to get function info")
+}
+
+// Each of these fixtures has a configuration metafield the function rejects, naming the
+// field that is wrong
+#[test]
+fn reports_invalid_configurations() {
+    let function_info = discount_function();
+    let invalid_configurations = [
+        (
+            "malformed-configuration-fixture.json",
+            "Invalid discount configuration: `order` must be an object",
+        ),
+        (
+            "wrong-type-configuration-fixture.json",
+            "Invalid discount configuration: `order.percentage` must be a number",
+        ),
+    ];
+
+    for (fixture_name, message) in invalid_configurations {
+        let fixture_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/invalid-configuration")
+            .join(fixture_name);
+        let fixture = load_fixture(&fixture_path).unwrap();
+        let result = run_function(
+            &fixture,
+            &function_info.function_runner_path,
+            &function_info.wasm_path,
+            &function_info.targeting[&fixture.target].input_query_path,
+            &function_info.schema_path,
+        );
+
+        // The error is in function-runner's error, or in the logs of a run it reports
+        let report = result.error.unwrap_or_else(|| {
+            let output = result.result.unwrap();
+            format!("{}\n{}", output.output, output.logs)
+        });
+        assert!(
+            report.contains(message),
+            "{}: {report}",
+            fixture_path.display()
+        );
+    }
+}
+
+// Runs every fixture in-process and checks it against function-runner. The function is
+// built on the Shopify Functions WASM API, so this needs the directory holding its
+// shopify_function_v1.wasm provider in FUNCTION_PROVIDERS_DIR, which CI builds.
+#[test]
+fn runs_the_fixtures_in_process() {
+    let providers_dir = std::env::var_os("FUNCTION_PROVIDERS_DIR").expect(
+        "FUNCTION_PROVIDERS_DIR must be the directory of the shopify_function_v1.wasm provider",
+    );
+    let function_info = discount_function();
+    let runner = create_function_runner(
+        &function_info.wasm_path,
+        CreateFunctionRunnerOptions {
+            providers_dir: Some(providers_dir.into()),
+        },
+    )
+    .unwrap_or_else(|e| panic!("{e}"));
+
+    let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut fixture_paths: Vec<_> = fs::read_dir(&fixtures_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    fixture_paths.sort();
+
+    for fixture_path in fixture_paths {
+        let fixture = load_fixture(&fixture_path).unwrap();
+        let input_query_path = &function_info.targeting[&fixture.target].input_query_path;
+        let input_query = load_input_query(input_query_path).unwrap();
+        let fixture = inject_fetch_result(&fixture, &input_query).unwrap();
+
+        let result = runner.run(&fixture);
+        let expected = run_function(
+            &fixture,
+            &function_info.function_runner_path,
+            &function_info.wasm_path,
+            input_query_path,
+            &function_info.schema_path,
+        );
+
+        assert_eq!(result.error, None, "{}", fixture_path.display());
+        let (output, expected) = (result.result.unwrap(), expected.result.unwrap());
+        assert_eq!(output.output, expected.output, "{}", fixture_path.display());
+        assert!(output
+            .instructions
+            .is_some_and(|instructions| instructions > 0));
+        assert!(output
+            .memory_usage
+            .is_some_and(|memory_usage| memory_usage > 0));
+    }
+}
@@ -0,0 +1,206 @@
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import { describe, it, expect, vi, afterEach } from "vitest";
+
+import { main } from "../src/cli.ts";
+import { runFunctionTests } from "../src/methods/run-function-tests.ts";
+import { FixtureRunResult } from "../src/methods/run-fixture.ts";
+
+vi.mock("../src/methods/run-function-tests.ts", () => ({
+  runFunctionTests: vi.fn(),
+}));
+
+function fixtureResult(name: string, passed: boolean): FixtureRunResult {
+  return {
+    fixturePath: `/function/tests/fixtures/${name}`,
+    stages: [
+      {
+        target: "cart.lines.discounts.generate.run",
+        validation: null,
+        run: null,
+        differences: passed
+          ? []
+          : [{ path: "operations[0]", message: "Unexpected item {}" }],
+        error: null,
+        passed,
+      },
+    ],
+    error: null,
+    passed,
+  };
+}
+
+describe("main", () => {
+  const mockRunFunctionTests = vi.mocked(runFunctionTests);
+
+  function captureOutput() {
+    const output = { log: vi.fn(), error: vi.fn() };
+    const lines = () =>
+      [...output.log.mock.calls, ...output.error.mock.calls].map(
+        ([message]) => message,
+      );
+    return { output, lines };
+  }
+
+  function mockFixtureResults(
+    results: FixtureRunResult[],
+    error: string | null = null,
+  ) {
+    mockRunFunctionTests.mockImplementation(async ({ onFixtureResult }) => {
+      results.forEach((result) => onFixtureResult?.(result));
+      return {
+        build: { success: true, output: "", error: null },
+        results,
+        error,
+        passed: error === null && results.every((result) => result.passed),
+      };
+    });
+  }
+
+  afterEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("should run the fixtures of the function directory and exit with 0 when they pass", async () => {
+    mockFixtureResults([fixtureResult("valid.json", true)]);
+    const { output, lines } = captureOutput();
+
+    const exitCode = await main(
+      ["extensions/discount", "--direct", "--cache", "--fixtures", "fixtures"],
+      output,
+    );
+
+    expect(exitCode).toBe(0);
+    expect(mockRunFunctionTests).toHaveBeenCalledWith(
+      expect.objectContaining({
+        functionDir: "extensions/discount",
+        fixturesDir: "fixtures",
+        buildOptions: { direct: true, cache: true },
+      }),
+    );
+    expect(lines()).toEqual([
+      "PASS valid.json",
+      "\nFixtures: 1 passed, 1 total",
+    ]);
+  });
+
+  it("should load the metafield schemas relative to the function directory", async () => {
+    mockFixtureResults([fixtureResult("valid.json", true)]);
+    const { output } = captureOutput();
+
+    const exitCode = await main(
+      [
+        "test-app/extensions/discount-function-rs",
+        "--metafield-schemas",
+        "tests/metafield-schemas.json",
+      ],
+      output,
+    );
+
+    expect(exitCode).toBe(0);
+    expect(mockRunFunctionTests).toHaveBeenCalledWith(
+      expect.objectContaining({
+        metafieldSchemas: [
+          expect.objectContaining({ key: "function-configuration" }),
+        ],
+      }),
+    );
+  });
+
+  it("should print the failures and exit with 1 when a fixture fails", async () => {
+    mockFixtureResults([
+      fixtureResult("valid.json", true),
+      fixtureResult("broken.json", false),
+    ]);
+    const { output, lines } = captureOutput();
+
+    const exitCode = await main([], output);
+
+    expect(exitCode).toBe(1);
+    expect(mockRunFunctionTests).toHaveBeenCalledWith(
+      expect.objectContaining({ functionDir: "." }),
+    );
+    expect(lines()).toEqual([
+      "PASS valid.json",
+      "FAIL broken.json\n  cart.lines.discounts.generate.run: output: operations[0]: Unexpected item {}",
+      "\nFixtures: 1 failed, 1 passed, 2 total",
+    ]);
+  });
+
+  it("should print the error and exit with 1 when the fixtures cannot run", async () => {
+    mockFixtureResults([], "Build command failed with exit code 101");
+    const { output } = captureOutput();
+
+    const exitCode = await main([], output);
+
+    expect(exitCode).toBe(1);
+    expect(mockRunFunctionTests).toHaveBeenCalledWith(
+      expect.objectContaining({
+        buildOptions: { direct: undefined, cache: undefined },
+      }),
+    );
+    expect(output.error).toHaveBeenCalledWith(
+      "Build command failed with exit code 101",
+    );
+  });
+
+  it("should write JSON and JUnit reports of the results", async () => {
+    mockFixtureResults([
+      fixtureResult("valid.json", true),
+      fixtureResult("broken.json", false),
+    ]);
+    const { output } = captureOutput();
+    const tempDir = fs.mkdtempSync(path.join(os.tmpdir(), "cli-"));
+
+    try {
+      const exitCode = await main(
+        [
+          "extensions/discount",
+          "--json",
+          path.join(tempDir, "reports/discount.json"),
+          "--junit",
+          path.join(tempDir, "reports/discount.xml"),
+        ],
+        output,
+      );
+
+      expect(exitCode).toBe(1);
+      const report = JSON.parse(
+        fs.readFileSync(path.join(tempDir, "reports/discount.json"), "utf-8"),
+      );
+      expect(report.function).toBe("discount");
+      expect(report.summary).toEqual({ fixtures: 2, passed: 1, failed: 1 });
+      expect(
+        fs.readFileSync(path.join(tempDir, "reports/discount.xml"), "utf-8"),
+      ).toContain(
+        '<testsuites name="discount" tests="2" failures="1" errors="0">',
+      );
+    } finally {
+      fs.rmSync(tempDir, { recursive: true, force: true });
+    }
+  });
+
+  it("should print the usage for --help", async () => {
+    const { output } = captureOutput();
+
+    const exitCode = await main(["--help"], output);
+
+    expect(exitCode).toBe(0);
+    expect(output.log.mock.calls[0][0]).toMatch(
+      /^Usage: shopify-function-test/,
+    );
+    expect(mockRunFunctionTests).not.toHaveBeenCalled();
+  });
+
+  it("should exit with 1 for an unknown option", async () => {
+    const { output } = captureOutput();
+
+    const exitCode = await main(["--watch"], output);
+
+    expect(exitCode).toBe(1);
+    expect(output.error.mock.calls[0][0]).toMatch(/Unknown option '--watch'/);
+    expect(mockRunFunctionTests).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,18 @@
+;; Source of echo-function.wasm: a WASI-only module whose `echo` export copies
+;; stdin to stdout, whose `grow` export grows its memory by a page before
+;; echoing, and whose `fail` export traps.
+(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 2)
+  ;; read iovec at 0 (buf 1024, len 64000), nread at 8, write iovec at 16 (buf 1024)
+  (data (i32.const 0) "\00\04\00\00\00\fa\00\00\00\00\00\00\00\00\00\00\00\04\00\00\00\00\00\00")
+  (func $echo (export "echo")
+    (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (i32.store (i32.const 20) (i32.load (i32.const 8)))
+    (drop (call $fd_write (i32.const 1) (i32.const 16) (i32.const 1) (i32.const 24))))
+  (func (export "grow")
+    (drop (memory.grow (i32.const 1)))
+    (call $echo))
+  (func (export "fail")
+    unreachable))
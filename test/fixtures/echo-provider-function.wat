@@ -0,0 +1,7 @@
+;; Source of echo-provider-function.wasm: a module importing from a provider
+;; that reads and writes JSON. See providers/echo_provider_v1.wat for the
+;; provider it runs with.
+(module
+  (import "echo_provider_v1" "echo" (func $echo))
+  (func (export "run")
+    call $echo))
@@ -0,0 +1,7 @@
+;; Source of provider-function.wasm: a module importing from a provider
+;; instead of WASI, like functions built on the Shopify Functions WASM API.
+;; See providers/shopify_function_v1.wat for the provider it runs with.
+(module
+  (import "shopify_function_v1" "shopify_function_input_get" (func $input_get))
+  (func (export "run")
+    call $input_get))
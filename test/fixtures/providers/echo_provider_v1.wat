@@ -0,0 +1,17 @@
+;; Source of echo_provider_v1.wasm: a provider whose `echo` copies stdin to
+;; stdout, for functions that read and write JSON through a provider, like
+;; Javy's. `_initialize` sets up the iovecs, so it must run before the
+;; function does.
+(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "_initialize")
+    ;; read iovec at 0 (buf 1024, len 64000), write iovec at 16 (buf 1024)
+    (i32.store (i32.const 0) (i32.const 1024))
+    (i32.store (i32.const 4) (i32.const 64000))
+    (i32.store (i32.const 16) (i32.const 1024)))
+  (func (export "echo")
+    (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (i32.store (i32.const 20) (i32.load (i32.const 8)))
+    (drop (call $fd_write (i32.const 1) (i32.const 16) (i32.const 1) (i32.const 24)))))
@@ -0,0 +1,34 @@
+;; Source of shopify_function_v1.wasm: a stand-in for the Shopify Functions
+;; WASM API provider whose `shopify_function_input_get` checks that stdin is
+;; the MessagePack encoding of {"cart":{"lines":[{"quantity":1}]}}, trapping
+;; otherwise, and writes a fixed MessagePack output to stdout.
+;; `_initialize` sets up the iovecs, so it must run before the function does.
+(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  ;; The expected input, 25 bytes
+  (data (i32.const 1024) "\81\a4cart\81\a5lines\91\81\a8quantity\01")
+  ;; The output, 118 bytes: {"operations":[{"validationAdd":{"errors":[{
+  ;; "message":"Each line can have at most 300 items","target":"$.cart"}]}}],
+  ;; "stats":[-3,1.5,null,true,false]}
+  (data (i32.const 2048) "\82\aaoperations\91\81\advalidationAdd\81\a6errors\91\82\a7message\d9$Each line can have at most 300 items\a6target\a6$.cart\a5stats\95\fd\cb?\f8\00\00\00\00\00\00\c0\c3\c2")
+  (func (export "_initialize")
+    ;; read iovec at 0 (buf 4096, len 60000), write iovec at 16 (buf 2048, len 118)
+    (i32.store (i32.const 0) (i32.const 4096))
+    (i32.store (i32.const 4) (i32.const 60000))
+    (i32.store (i32.const 16) (i32.const 2048))
+    (i32.store (i32.const 20) (i32.const 118)))
+  (func (export "shopify_function_input_get")
+    (local $i i32)
+    (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (if (i32.ne (i32.load (i32.const 8)) (i32.const 25))
+      (then unreachable))
+    (loop $compare
+      (if (i32.ne
+            (i32.load8_u (i32.add (i32.const 4096) (local.get $i)))
+            (i32.load8_u (i32.add (i32.const 1024) (local.get $i))))
+        (then unreachable))
+      (local.set $i (i32.add (local.get $i) (i32.const 1)))
+      (br_if $compare (i32.lt_u (local.get $i) (i32.const 25))))
+    (drop (call $fd_write (i32.const 1) (i32.const 16) (i32.const 1) (i32.const 24)))))
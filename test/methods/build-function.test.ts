@@ -1,5 +1,8 @@
 import { EventEmitter } from "events";
 import { spawn } from "child_process";
+import fs from "fs";
+import os from "os";
+import path from "path";
 
 import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
 
@@ -33,13 +36,9 @@ describe("buildFunction", () => {
     );
 
     // Simulate successful build
-    setImmediate(() => {
-      mockProcess.stdout.emit(
-        "data",
-        Buffer.from("Build completed successfully"),
-      );
-      mockProcess.emit("close", 0);
-    });
+    await vi.waitFor(() => expect(mockSpawn).toHaveBeenCalled());
+    mockProcess.stdout.emit("data", Buffer.from("Build completed successfully"));
+    mockProcess.emit("close", 0);
 
     const result = await resultPromise;
 
@@ -68,13 +67,9 @@ describe("buildFunction", () => {
     );
 
     // Simulate build failure
-    setImmediate(() => {
-      mockProcess.stderr.emit(
-        "data",
-        Buffer.from("Build failed: syntax error"),
-      );
-      mockProcess.emit("close", 1);
-    });
+    await vi.waitFor(() => expect(mockSpawn).toHaveBeenCalled());
+    mockProcess.stderr.emit("data", Buffer.from("Build failed: syntax error"));
+    mockProcess.emit("close", 1);
 
     await expect(resultPromise).rejects.toThrow(
       "Build command failed with exit code 1",
@@ -87,13 +82,242 @@ describe("buildFunction", () => {
     );
 
     // Simulate spawn error
-    setImmediate(() => {
-      const error = new Error("ENOENT: command not found");
-      mockProcess.emit("error", error);
-    });
+    await vi.waitFor(() => expect(mockSpawn).toHaveBeenCalled());
+    const error = new Error("ENOENT: command not found");
+    mockProcess.emit("error", error);
 
     await expect(resultPromise).rejects.toThrow(
       "Failed to start shopify build command",
     );
   });
+
+  describe("direct", () => {
+    let functionDir: string;
+
+    beforeEach(() => {
+      functionDir = fs.mkdtempSync(path.join(os.tmpdir(), "build-function-"));
+      fs.writeFileSync(
+        path.join(functionDir, "shopify.extension.toml"),
+        [
+          "[[extensions]]",
+          'type = "function"',
+          "",
+          "  [extensions.build]",
+          '  command = "cargo build --target=wasm32-wasip1 --release"',
+          '  path = "target/wasm32-wasip1/release/my-function.wasm"',
+        ].join("\n"),
+      );
+    });
+
+    afterEach(() => {
+      fs.rmSync(functionDir, { recursive: true, force: true });
+    });
+
+    it("should run the build command and return the built wasm", async () => {
+      const onOutput = vi.fn();
+      const resultPromise = buildFunction(functionDir, {
+        direct: true,
+        onOutput,
+      });
+
+      await vi.waitFor(() => expect(mockSpawn).toHaveBeenCalled());
+      const wasmPath = path.join(
+        functionDir,
+        "target/wasm32-wasip1/release/my-function.wasm",
+      );
+      fs.mkdirSync(path.dirname(wasmPath), { recursive: true });
+      fs.writeFileSync(wasmPath, Buffer.alloc(1024));
+      mockProcess.stderr.emit(
+        "data",
+        Buffer.from("   Compiling my-function v0.1.0\n"),
+      );
+      mockProcess.emit("close", 0);
+
+      const result = await resultPromise;
+
+      expect(result).toStrictEqual({
+        success: true,
+        output: "",
+        error: null,
+        cached: false,
+        wasmPath,
+        wasmSize: 1024,
+      });
+      expect(onOutput).toHaveBeenCalledWith(
+        "   Compiling my-function v0.1.0\n",
+        "stderr",
+      );
+      expect(mockSpawn).toHaveBeenCalledWith(
+        "cargo build --target=wasm32-wasip1 --release",
+        [],
+        expect.objectContaining({ cwd: functionDir, shell: true }),
+      );
+    });
+
+    it("should reject when the build does not produce the wasm", async () => {
+      const resultPromise = buildFunction(functionDir, {
+        direct: true,
+        onOutput: () => {},
+      });
+
+      await vi.waitFor(() => expect(mockSpawn).toHaveBeenCalled());
+      mockProcess.emit("close", 0);
+
+      await expect(resultPromise).rejects.toThrow(
+        `The build did not produce ${path.join(functionDir, "target/wasm32-wasip1/release/my-function.wasm")}`,
+      );
+    });
+
+    it("should handle build failures", async () => {
+      const resultPromise = buildFunction(functionDir, {
+        direct: true,
+        onOutput: () => {},
+      });
+
+      await vi.waitFor(() => expect(mockSpawn).toHaveBeenCalled());
+      mockProcess.stderr.emit(
+        "data",
+        Buffer.from("error[E0425]: cannot find value `x` in this scope"),
+      );
+      mockProcess.emit("close", 101);
+
+      await expect(resultPromise).rejects.toThrow(
+        "Build command failed with exit code 101: error[E0425]",
+      );
+    });
+
+    it("should fail without a build command", async () => {
+      const result = await buildFunction(
+        "test-app/extensions/cart-validation-js",
+        { direct: true },
+      );
+
+      expect(result.success).toBe(false);
+      expect(result.error).toBe(
+        `${path.resolve("test-app/extensions/cart-validation-js/shopify.extension.toml")} has no [extensions.build].command; build the function with the Shopify CLI instead`,
+      );
+      expect(mockSpawn).not.toHaveBeenCalled();
+    });
+  });
+
+  describe("cache", () => {
+    let functionDir: string;
+    let wasmPath: string;
+
+    beforeEach(() => {
+      functionDir = fs.mkdtempSync(path.join(os.tmpdir(), "build-function-"));
+      wasmPath = path.join(functionDir, "dist/function.wasm");
+      fs.writeFileSync(
+        path.join(functionDir, "shopify.extension.toml"),
+        [
+          "[[extensions]]",
+          'type = "function"',
+          "",
+          "  [[extensions.targeting]]",
+          '  target = "cart.lines.discounts.generate.run"',
+          '  input_query = "src/run.graphql"',
+          "",
+          "  [extensions.build]",
+          '  command = "cargo build --target=wasm32-wasip1 --release"',
+          '  path = "dist/function.wasm"',
+          '  watch = [ "src/**/*.rs" ]',
+        ].join("\n"),
+      );
+      fs.mkdirSync(path.join(functionDir, "src/nested"), { recursive: true });
+      fs.writeFileSync(path.join(functionDir, "Cargo.toml"), "[package]\n");
+      fs.writeFileSync(path.join(functionDir, "src/run.graphql"), "{ cart }");
+      fs.writeFileSync(path.join(functionDir, "src/main.rs"), "fn main() {}");
+      fs.writeFileSync(path.join(functionDir, "src/nested/lib.rs"), "");
+      fs.writeFileSync(path.join(functionDir, "src/notes.txt"), "");
+    });
+
+    afterEach(() => {
+      fs.rmSync(functionDir, { recursive: true, force: true });
+    });
+
+    const build = async (options = {}) => {
+      mockSpawn.mockClear();
+      const resultPromise = buildFunction(functionDir, {
+        direct: true,
+        onOutput: () => {},
+        cache: true,
+        ...options,
+      });
+      // Either the build is skipped or it spawns the build command
+      let settled = false;
+      const settle = () => {
+        settled = true;
+      };
+      resultPromise.then(settle, settle);
+      await vi.waitFor(() =>
+        expect(settled || mockSpawn.mock.calls.length > 0).toBe(true),
+      );
+      if (mockSpawn.mock.calls.length === 0) {
+        return resultPromise;
+      }
+      fs.mkdirSync(path.dirname(wasmPath), { recursive: true });
+      fs.writeFileSync(wasmPath, Buffer.alloc(512));
+      mockProcess.emit("close", 0);
+      return resultPromise;
+    };
+
+    it("should skip the build when nothing it depends on changed", async () => {
+      expect(await build()).toMatchObject({ cached: false, wasmSize: 512 });
+      expect(fs.existsSync(`${wasmPath}.build-hash`)).toBe(true);
+
+      expect(await build()).toStrictEqual({
+        success: true,
+        output: "",
+        error: null,
+        cached: true,
+        wasmPath,
+        wasmSize: 512,
+      });
+      expect(mockSpawn).not.toHaveBeenCalled();
+    });
+
+    it("should rebuild when a watched file, Cargo.toml or an input query changes", async () => {
+      await build();
+
+      for (const [file, content] of [
+        ["src/nested/lib.rs", "pub fn discount() {}"],
+        ["Cargo.toml", "[package]\nname = 'function'\n"],
+        ["src/run.graphql", "{ cart { cost } }"],
+      ]) {
+        fs.writeFileSync(path.join(functionDir, file), content);
+        expect(await build()).toMatchObject({ cached: false });
+        expect(await build()).toMatchObject({ cached: true });
+      }
+    });
+
+    it("should ignore files that are not watched", async () => {
+      await build();
+      fs.writeFileSync(path.join(functionDir, "src/notes.txt"), "TODO");
+
+      expect(await build()).toMatchObject({ cached: true });
+    });
+
+    it("should rebuild when the wasm is missing or the cache is not enabled", async () => {
+      await build();
+
+      expect(await build({ cache: false })).toMatchObject({ cached: false });
+      expect(await build({ cache: undefined })).toMatchObject({
+        cached: false,
+      });
+      fs.rmSync(wasmPath);
+      expect(await build()).toMatchObject({ cached: false });
+    });
+
+    it("should not record failed builds", async () => {
+      const resultPromise = buildFunction(functionDir, {
+        direct: true,
+        onOutput: () => {},
+      });
+      await vi.waitFor(() => expect(mockSpawn).toHaveBeenCalled());
+      mockProcess.emit("close", 101);
+      await expect(resultPromise).rejects.toThrow("Build command failed");
+
+      expect(fs.existsSync(`${wasmPath}.build-hash`)).toBe(false);
+    });
+  });
 });
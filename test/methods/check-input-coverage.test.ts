@@ -0,0 +1,187 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { DocumentNode, GraphQLSchema, parse } from "graphql";
+
+import { checkInputCoverage } from "../../src/methods/check-input-coverage.ts";
+import { FixtureData } from "../../src/methods/load-fixture.ts";
+import { loadSchema } from "../../src/methods/load-schema.ts";
+
+const TARGET = "data.processing.generate.run";
+const FETCH_TARGET = "data.fetching.generate.run";
+
+function fixture(
+  input: Record<string, any>,
+  target: string = TARGET,
+): FixtureData {
+  return { export: "run", input, expectedOutput: {}, target };
+}
+
+describe("checkInputCoverage", () => {
+  let schema: GraphQLSchema;
+  let inputQueryAST: DocumentNode;
+
+  beforeAll(async () => {
+    schema = await loadSchema("./test/fixtures/test-schema.graphql");
+    inputQueryAST = parse(`
+      query {
+        data {
+          items {
+            id
+            count
+          }
+          metadata {
+            email
+          }
+          products {
+            __typename
+            ... on PhysicalProduct {
+              sku
+            }
+            ... on GiftCard {
+              code
+            }
+          }
+        }
+      }
+    `);
+  });
+
+  const physicalFixture = fixture({
+    data: {
+      items: [{ id: "gid://test/Item/1", count: 1 }],
+      metadata: null,
+      products: [{ __typename: "PhysicalProduct", sku: "SKU-1" }],
+    },
+  });
+  const giftCardFixture = fixture({
+    data: {
+      items: [],
+      metadata: { email: null },
+      products: [{ __typename: "GiftCard", code: "GIFT" }],
+    },
+  });
+
+  it("should count the branches each field took across the fixtures", () => {
+    const result = checkInputCoverage({
+      schema,
+      inputQueryAST,
+      target: TARGET,
+      fixtures: [physicalFixture, giftCardFixture],
+    });
+
+    expect(result.target).toBe(TARGET);
+    expect(result.fixtures).toBe(2);
+    expect(result.fields).toEqual([
+      { path: "data", nullable: { null: 0, nonNull: 2 } },
+      { path: "data.items", list: { empty: 1, nonEmpty: 1 } },
+      { path: "data.items.id", nullable: { null: 0, nonNull: 1 } },
+      { path: "data.metadata", nullable: { null: 1, nonNull: 1 } },
+      { path: "data.metadata.email", nullable: { null: 1, nonNull: 0 } },
+      {
+        path: "data.products",
+        list: { empty: 0, nonEmpty: 2 },
+        types: { PhysicalProduct: 1, DigitalProduct: 0, GiftCard: 1 },
+      },
+    ]);
+  });
+
+  it("should list the branches no fixture exercised", () => {
+    const { gaps } = checkInputCoverage({
+      schema,
+      inputQueryAST,
+      target: TARGET,
+      fixtures: [physicalFixture, giftCardFixture],
+    });
+
+    expect(gaps).toEqual([
+      { path: "data", branch: "null", message: "Never null" },
+      { path: "data.items.id", branch: "null", message: "Never null" },
+      {
+        path: "data.metadata.email",
+        branch: "non-null",
+        message: "Always null",
+      },
+      {
+        path: "data.products",
+        branch: "empty",
+        message: "Never an empty list",
+      },
+      {
+        path: "data.products",
+        branch: "type",
+        typeName: "DigitalProduct",
+        message: "Never a `DigitalProduct`",
+      },
+    ]);
+  });
+
+  it("should count union members by an aliased __typename", () => {
+    const { fields } = checkInputCoverage({
+      schema,
+      inputQueryAST: parse(`
+        query {
+          data {
+            searchResults {
+              kind: __typename
+            }
+          }
+        }
+      `),
+      target: TARGET,
+      fixtures: [
+        fixture({
+          data: {
+            searchResults: [{ kind: "Item" }, { kind: "Item" }],
+          },
+        }),
+      ],
+    });
+
+    expect(fields[1]).toEqual({
+      path: "data.searchResults",
+      list: { empty: 0, nonEmpty: 1 },
+      types: { Item: 2, Metadata: 0 },
+    });
+  });
+
+  it("should measure fetch stages for the target and skip other targets", () => {
+    const withFetch: FixtureData = {
+      ...fixture(physicalFixture.input),
+      fetch: {
+        export: "fetch",
+        input: giftCardFixture.input,
+        expectedOutput: {},
+        target: FETCH_TARGET,
+        response: null,
+      },
+    };
+
+    const result = checkInputCoverage({
+      schema,
+      inputQueryAST,
+      target: FETCH_TARGET,
+      fixtures: [withFetch, fixture(physicalFixture.input, "other.target")],
+    });
+
+    expect(result.fixtures).toBe(1);
+    expect(result.fields[3]).toEqual({
+      path: "data.metadata",
+      nullable: { null: 0, nonNull: 1 },
+    });
+  });
+
+  it("should report no fields without fixtures for the target", () => {
+    const result = checkInputCoverage({
+      schema,
+      inputQueryAST,
+      target: FETCH_TARGET,
+      fixtures: [physicalFixture],
+    });
+
+    expect(result).toEqual({
+      target: FETCH_TARGET,
+      fixtures: 0,
+      fields: [],
+      gaps: [],
+    });
+  });
+});
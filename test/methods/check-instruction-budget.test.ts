@@ -0,0 +1,198 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { GraphQLSchema, parse } from "graphql";
+
+import {
+  checkInstructionBudget,
+  computeScaleFactor,
+  DEFAULT_INSTRUCTION_LIMIT,
+} from "../../src/methods/check-instruction-budget.ts";
+import { loadSchema } from "../../src/methods/load-schema.ts";
+import { FixtureData } from "../../src/methods/load-fixture.ts";
+
+const discountSchemaPath =
+  "./test-app/extensions/discount-function-rs/schema.graphql";
+
+function cartLines(count: number) {
+  return Array.from({ length: count }, (_, index) => ({
+    id: `gid://shopify/CartLine/${index}`,
+  }));
+}
+
+function fixtureWithInput(input: Record<string, any>): FixtureData {
+  return {
+    export: "cart_lines_discounts_generate_run",
+    input,
+    expectedOutput: {},
+    target: "cart.lines.discounts.generate.run",
+  };
+}
+
+describe("computeScaleFactor", () => {
+  let schema: GraphQLSchema;
+
+  beforeAll(async () => {
+    schema = await loadSchema(discountSchemaPath);
+  });
+
+  it("should not scale limits for small carts", () => {
+    const queryAST = parse("query { cart { lines { id } } }");
+
+    const result = computeScaleFactor(queryAST, schema, {
+      cart: { lines: cartLines(3) },
+    });
+
+    expect(result.scaleFactor).toBe(1);
+    expect(result.scaledFields).toEqual([
+      { coordinate: "Cart.lines", rate: 0.005, count: 3 },
+    ]);
+  });
+
+  it("should scale limits by the rate times the number of cart lines", () => {
+    const queryAST = parse("query { cart { lines { id } } }");
+
+    const result = computeScaleFactor(queryAST, schema, {
+      cart: { lines: cartLines(400) },
+    });
+
+    expect(result.scaleFactor).toBe(2);
+  });
+
+  it("should cap the scale factor", () => {
+    const queryAST = parse("query { cart { lines { id } } }");
+
+    const result = computeScaleFactor(queryAST, schema, {
+      cart: { lines: cartLines(5000) },
+    });
+
+    expect(result.scaleFactor).toBe(10);
+  });
+
+  it("should count nested list items across all parents", () => {
+    const queryAST = parse(`
+      query {
+        cart {
+          deliveryGroups {
+            id
+            cartLines { id }
+          }
+        }
+      }
+    `);
+
+    const result = computeScaleFactor(queryAST, schema, {
+      cart: {
+        deliveryGroups: [
+          { id: "gid://shopify/CartDeliveryGroup/0", cartLines: cartLines(300) },
+          { id: "gid://shopify/CartDeliveryGroup/1", cartLines: cartLines(500) },
+        ],
+      },
+    });
+
+    expect(result.scaledFields).toEqual([
+      { coordinate: "CartDeliveryGroup.cartLines", rate: 0.005, count: 800 },
+    ]);
+    expect(result.scaleFactor).toBe(4);
+  });
+
+  it("should follow aliases and count fields selected twice once", () => {
+    const queryAST = parse(`
+      query {
+        cart {
+          allLines: lines { id }
+          ...LineCosts
+        }
+      }
+
+      fragment LineCosts on Cart {
+        allLines: lines { cost { subtotalAmount { amount } } }
+      }
+    `);
+
+    const result = computeScaleFactor(queryAST, schema, {
+      cart: { allLines: cartLines(600) },
+    });
+
+    expect(result.scaledFields).toEqual([
+      { coordinate: "Cart.lines", rate: 0.005, count: 600 },
+    ]);
+    expect(result.scaleFactor).toBe(3);
+  });
+
+  it("should not scale limits for schemas without @scaleLimits", async () => {
+    const testSchema = await loadSchema("./test/fixtures/test-schema.graphql");
+    const queryAST = parse("query { data { items { id } } }");
+
+    const result = computeScaleFactor(queryAST, testSchema, {
+      data: { items: [{ id: "1" }] },
+    });
+
+    expect(result).toEqual({ scaleFactor: 1, scaledFields: [] });
+  });
+});
+
+describe("checkInstructionBudget", () => {
+  let schema: GraphQLSchema;
+  const inputQueryAST = parse("query { cart { lines { id } } }");
+
+  beforeAll(async () => {
+    schema = await loadSchema(discountSchemaPath);
+  });
+
+  it("should pass runs within the scaled limit", () => {
+    const result = checkInstructionBudget({
+      schema,
+      inputQueryAST,
+      fixture: fixtureWithInput({ cart: { lines: cartLines(400) } }),
+      instructions: 15_000_000,
+    });
+
+    expect(result.scaleFactor).toBe(2);
+    expect(result.instructionLimit).toBe(2 * DEFAULT_INSTRUCTION_LIMIT);
+    expect(result.errors).toHaveLength(0);
+  });
+
+  it("should report runs exceeding the scaled limit", () => {
+    const result = checkInstructionBudget({
+      schema,
+      inputQueryAST,
+      fixture: fixtureWithInput({ cart: { lines: cartLines(1) } }),
+      instructions: 12_000_000,
+    });
+
+    expect(result.errors).toEqual([
+      {
+        message:
+          "Function used 12000000 instructions, exceeding the limit of 11000000 (11000000 scaled by 1)",
+      },
+    ]);
+  });
+
+  it("should accept a custom base limit", () => {
+    const result = checkInstructionBudget({
+      schema,
+      inputQueryAST,
+      fixture: fixtureWithInput({ cart: { lines: cartLines(400) } }),
+      instructions: 150_000,
+      instructionLimit: 100_000,
+    });
+
+    expect(result.instructionLimit).toBe(200_000);
+    expect(result.errors).toHaveLength(0);
+  });
+
+  it("should report runs without an instruction count", () => {
+    const result = checkInstructionBudget({
+      schema,
+      inputQueryAST,
+      fixture: fixtureWithInput({ cart: { lines: cartLines(1) } }),
+      instructions: null,
+    });
+
+    expect(result.errors).toEqual([
+      {
+        message:
+          "The run did not report an instruction count. Run the function with runFunction to measure instructions.",
+      },
+    ]);
+  });
+});
@@ -0,0 +1,242 @@
+import { describe, it, expect } from "vitest";
+
+import {
+  createFixtureReport,
+  formatJsonReport,
+  formatJUnitReport,
+} from "../../src/methods/create-fixture-report.ts";
+import {
+  FixtureRunResult,
+  FixtureStageResult,
+} from "../../src/methods/run-fixture.ts";
+
+const RUN_TARGET = "cart.lines.discounts.generate.run";
+const FETCH_TARGET = "cart.lines.discounts.generate.fetch";
+
+function stage(
+  target: string,
+  overrides: Partial<FixtureStageResult> = {},
+): FixtureStageResult {
+  return {
+    target,
+    validation: {
+      inputQuery: { errors: [] },
+      inputFixture: { errors: [] },
+      outputFixture: {
+        errors: [],
+        mutationName: null,
+        resultParameterType: null,
+      },
+    },
+    run: {
+      output: { operations: [] },
+      instructions: 12345,
+      memoryUsage: 64,
+      inputSize: 100,
+      outputSize: 20,
+      logs: "",
+    },
+    differences: [],
+    error: null,
+    passed: true,
+    ...overrides,
+  };
+}
+
+function fixtureResult(
+  name: string,
+  stages: FixtureStageResult[],
+): FixtureRunResult {
+  return {
+    fixturePath: `/function/tests/fixtures/${name}`,
+    stages,
+    error: null,
+    passed: stages.every((fixtureStage) => fixtureStage.passed),
+  };
+}
+
+const RESULTS: FixtureRunResult[] = [
+  fixtureResult("valid.json", [stage(RUN_TARGET)]),
+  fixtureResult("fetch.json", [stage(FETCH_TARGET), stage(RUN_TARGET)]),
+  fixtureResult("invalid.json", [
+    stage(RUN_TARGET, {
+      validation: {
+        inputQuery: { errors: [] },
+        inputFixture: {
+          errors: [
+            {
+              message: "Missing expected fixture data for `cost`",
+              path: ["cart", "lines", 0, "cost"],
+            },
+          ],
+        },
+        outputFixture: {
+          errors: [],
+          mutationName: null,
+          resultParameterType: null,
+        },
+      },
+      run: null,
+      passed: false,
+    }),
+  ]),
+  fixtureResult("wrong-output.json", [
+    stage(RUN_TARGET, {
+      differences: [
+        { path: "operations[0]", message: 'Unexpected item { a: "<b>" }' },
+      ],
+      passed: false,
+    }),
+  ]),
+  {
+    fixturePath: "/function/tests/fixtures/broken.json",
+    stages: [],
+    error: "Unexpected token } in JSON at position 12",
+    passed: false,
+  },
+];
+
+describe("createFixtureReport", () => {
+  it("should group the fixtures by target, sorted by name", () => {
+    const report = createFixtureReport(RESULTS, {
+      functionName: "discount-function-rs",
+    });
+
+    expect(report.version).toBe(1);
+    expect(report.function).toBe("discount-function-rs");
+    expect(report.summary).toEqual({ fixtures: 5, passed: 2, failed: 3 });
+    expect(report.error).toBeNull();
+    expect(report.fixtureErrors).toEqual([
+      {
+        fixture: "broken.json",
+        error: "Unexpected token } in JSON at position 12",
+      },
+    ]);
+    expect(
+      report.targets.map(({ target, passed, failed, fixtures }) => ({
+        target,
+        passed,
+        failed,
+        fixtures: fixtures.map((entry) => entry.fixture),
+      })),
+    ).toEqual([
+      {
+        target: FETCH_TARGET,
+        passed: 1,
+        failed: 0,
+        fixtures: ["fetch.json"],
+      },
+      {
+        target: RUN_TARGET,
+        passed: 2,
+        failed: 2,
+        fixtures: [
+          "fetch.json",
+          "invalid.json",
+          "valid.json",
+          "wrong-output.json",
+        ],
+      },
+    ]);
+  });
+
+  it("should report the validation errors, differences and instructions of each fixture", () => {
+    const report = createFixtureReport(RESULTS, {
+      functionName: "discount-function-rs",
+    });
+    const [fetchEntry, invalidEntry, validEntry, wrongOutputEntry] =
+      report.targets[1].fixtures;
+
+    expect(fetchEntry.instructions).toBe(12345);
+    expect(validEntry).toEqual({
+      fixture: "valid.json",
+      passed: true,
+      validation: {
+        error: null,
+        inputQuery: [],
+        inputFixture: [],
+        outputFixture: [],
+      },
+      differences: [],
+      instructions: 12345,
+      memoryUsage: 64,
+      error: null,
+      failures: [],
+    });
+    expect(invalidEntry.validation?.inputFixture).toEqual([
+      {
+        message: "Missing expected fixture data for `cost`",
+        path: "cart.lines[0].cost",
+      },
+    ]);
+    expect(invalidEntry.instructions).toBeNull();
+    expect(invalidEntry.failures).toEqual([
+      "input fixture: Missing expected fixture data for `cost` at cart.lines[0].cost",
+    ]);
+    expect(wrongOutputEntry.differences).toHaveLength(1);
+  });
+
+  it("should give the same JSON for the same results in any order", () => {
+    const report = createFixtureReport(RESULTS, {
+      functionName: "discount-function-rs",
+    });
+    const reversed = createFixtureReport([...RESULTS].reverse(), {
+      functionName: "discount-function-rs",
+    });
+
+    expect(formatJsonReport(reversed)).toBe(formatJsonReport(report));
+    expect(JSON.parse(formatJsonReport(report))).toEqual(report);
+  });
+});
+
+describe("formatJUnitReport", () => {
+  it("should write a test suite per target and a test case per fixture", () => {
+    const xml = formatJUnitReport(
+      createFixtureReport(RESULTS, { functionName: "discount-function-rs" }),
+    );
+
+    expect(xml).toMatch(/^<\?xml version="1.0" encoding="UTF-8"\?>\n/);
+    expect(xml).toContain(
+      '<testsuites name="discount-function-rs" tests="6" failures="2" errors="1">',
+    );
+    expect(xml).toContain(
+      `<testsuite name="discount-function-rs.${RUN_TARGET}" tests="4" failures="2" errors="0">`,
+    );
+    expect(xml).toContain(
+      `<testcase classname="discount-function-rs.${FETCH_TARGET}" name="fetch.json">`,
+    );
+    expect(xml).toContain('<property name="instructions" value="12345"/>');
+  });
+
+  it("should list the problems of a failing fixture, escaped", () => {
+    const xml = formatJUnitReport(
+      createFixtureReport(RESULTS, { functionName: "discount-function-rs" }),
+    );
+
+    expect(xml).toContain(
+      '<failure message="input fixture: Missing expected fixture data for `cost` at cart.lines[0].cost" type="validation">',
+    );
+    expect(xml).toContain(
+      '<failure message="output: operations[0]: Unexpected item { a: &quot;&lt;b&gt;&quot; }" type="output">',
+    );
+    expect(xml).toContain(
+      '<error message="Unexpected token } in JSON at position 12">',
+    );
+  });
+
+  it("should report a function that could not run as an error", () => {
+    const xml = formatJUnitReport(
+      createFixtureReport([], {
+        functionName: "discount-function-rs",
+        error: "Build command failed with exit code 101",
+      }),
+    );
+
+    expect(xml).toContain(
+      '<testsuites name="discount-function-rs" tests="1" failures="0" errors="1">',
+    );
+    expect(xml).toContain(
+      '<testcase classname="discount-function-rs" name="setup">',
+    );
+  });
+});
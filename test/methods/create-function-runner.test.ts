@@ -0,0 +1,136 @@
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+
+import {
+  createFunctionRunner,
+  FunctionRunner,
+} from "../../src/methods/create-function-runner.ts";
+import { FixtureData } from "../../src/methods/load-fixture.ts";
+
+// See echo-function.wat, echo-provider-function.wat, provider-function.wat and
+// the providers directory for the module sources
+const echoWasmPath = "./test/fixtures/echo-function.wasm";
+const echoProviderWasmPath = "./test/fixtures/echo-provider-function.wasm";
+const providerWasmPath = "./test/fixtures/provider-function.wasm";
+const providersDir = "./test/fixtures/providers";
+
+function fixtureFor(exportName: string): FixtureData {
+  return {
+    export: exportName,
+    input: {
+      cart: {
+        lines: [{ quantity: 1 }],
+      },
+    },
+    expectedOutput: {},
+    target: "cart.validations.generate.run",
+  };
+}
+
+describe("createFunctionRunner", () => {
+  let runner: FunctionRunner;
+
+  beforeAll(async () => {
+    runner = await createFunctionRunner(echoWasmPath);
+  });
+
+  afterAll(async () => {
+    await runner.close();
+  });
+
+  it("should run an export with the fixture input on stdin", async () => {
+    const fixture = fixtureFor("echo");
+
+    const result = await runner.run(fixture);
+
+    expect(result.error).toBeNull();
+    expect(result.result).toEqual({
+      output: fixture.input,
+      // The JavaScript engine does not count instructions
+      instructions: null,
+      // The echo module declares two 64 KiB pages
+      memoryUsage: 128,
+      inputSize: JSON.stringify(fixture.input).length,
+      outputSize: JSON.stringify(fixture.input).length,
+      logs: "",
+    });
+  });
+
+  it("should report the memory the export grows", async () => {
+    const result = await runner.run(fixtureFor("grow"));
+
+    expect(result.result?.memoryUsage).toBe(192);
+  });
+
+  it("should reuse the compiled module across runs", async () => {
+    const results = await Promise.all(
+      [1, 2, 3].map((quantity) =>
+        runner.run({
+          ...fixtureFor("echo"),
+          input: { cart: { lines: [{ quantity }] } },
+        }),
+      ),
+    );
+
+    expect(results.map((result) => result.result?.output)).toEqual([
+      { cart: { lines: [{ quantity: 1 }] } },
+      { cart: { lines: [{ quantity: 2 }] } },
+      { cart: { lines: [{ quantity: 3 }] } },
+    ]);
+  });
+
+  it("should return an error when the export traps", async () => {
+    const result = await runner.run(fixtureFor("fail"));
+
+    expect(result.result).toBeNull();
+    expect(result.error).toBe("Function export 'fail' failed: unreachable");
+  });
+
+  it("should return an error when the export does not exist", async () => {
+    const result = await runner.run(fixtureFor("missing-export"));
+
+    expect(result.result).toBeNull();
+    expect(result.error).toBe(
+      `Export 'missing-export' not found in ${echoWasmPath}`,
+    );
+  });
+
+  it("should link imports to provider modules", async () => {
+    const providerRunner = await createFunctionRunner(echoProviderWasmPath, {
+      providersDir,
+    });
+    const fixture = fixtureFor("run");
+
+    try {
+      const result = await providerRunner.run(fixture);
+
+      expect(result.error).toBeNull();
+      expect(result.result?.output).toEqual(fixture.input);
+      // Only the provider has a memory, of one page
+      expect(result.result?.memoryUsage).toBe(64);
+    } finally {
+      await providerRunner.close();
+    }
+  });
+
+  it("should reject modules whose imports have no provider", async () => {
+    await expect(createFunctionRunner(echoProviderWasmPath)).rejects.toThrow(
+      `WASM module ${echoProviderWasmPath} imports from echo_provider_v1, which needs a provider module.`,
+    );
+  });
+
+  it("should reject modules built on the Shopify Functions WASM API", async () => {
+    await expect(
+      createFunctionRunner(providerWasmPath, { providersDir }),
+    ).rejects.toThrow(
+      `WASM module ${providerWasmPath} imports from shopify_function_v1, whose MessagePack input and output are not supported in-process.`,
+    );
+  });
+
+  it("should reject files that are not WASM modules", async () => {
+    await expect(
+      createFunctionRunner("./test/fixtures/valid-fixture.json"),
+    ).rejects.toThrow(
+      "Failed to compile WASM module ./test/fixtures/valid-fixture.json",
+    );
+  });
+});
@@ -0,0 +1,163 @@
+import { describe, it, expect, beforeEach, afterEach } from "vitest";
+
+import {
+  createMockHttpServer,
+  MockHttpServer,
+} from "../../src/wasm-testing-helpers.ts";
+
+const URL = "https://discounts.example.com/validate";
+
+const REQUEST = {
+  method: "POST",
+  url: URL,
+  headers: [{ name: "Content-Type", value: "application/json" }],
+  body: null,
+  jsonBody: { codes: ["10OFF"] },
+  policy: { readTimeoutMs: 500 },
+};
+
+describe("createMockHttpServer", () => {
+  let server: MockHttpServer;
+
+  beforeEach(async () => {
+    server = await createMockHttpServer([
+      {
+        method: "POST",
+        url: URL,
+        body: { codes: ["10OFF"] },
+        response: {
+          status: 200,
+          jsonBody: { valid: true },
+          headers: [{ name: "Content-Type", value: "application/json" }],
+        },
+      },
+      {
+        method: "POST",
+        url: URL,
+        body: { codes: ["SLOW"] },
+        response: { status: 200, body: "late" },
+        delayMs: 1000,
+      },
+      {
+        method: "GET",
+        url: "https://discounts.example.com/codes?shop=1",
+        response: { status: 204 },
+      },
+      {
+        method: "GET",
+        url: "https://discounts.example.com/codes?shop=2",
+        response: {
+          status: 200,
+          body: "[]",
+          headers: [
+            { name: "Set-Cookie", value: "session=1" },
+            { name: "Set-Cookie", value: "region=ca" },
+          ],
+        },
+      },
+    ]);
+  });
+
+  afterEach(async () => {
+    await server.close();
+  });
+
+  it("should respond to a request matching a route by method, URL and body", async () => {
+    const result = await server.fetch(REQUEST);
+
+    expect(result.errors).toEqual([]);
+    expect(result.timedOut).toBe(false);
+    expect(result.response?.status).toBe(200);
+    expect(result.response?.body).toBe('{"valid":true}');
+    expect(result.response?.headers).toContainEqual({
+      name: "Content-Type",
+      value: "application/json",
+    });
+  });
+
+  it("should receive the request with its original URL, headers and body", async () => {
+    await server.fetch(REQUEST);
+
+    expect(server.requests).toHaveLength(1);
+    expect(server.requests[0]).toMatchObject({
+      method: "POST",
+      url: URL,
+      body: '{"codes":["10OFF"]}',
+      matched: true,
+    });
+    expect(server.requests[0].headers).toContainEqual({
+      name: "Content-Type",
+      value: "application/json",
+    });
+    expect(server.requests[0].headers).toContainEqual({
+      name: "Host",
+      value: "discounts.example.com",
+    });
+  });
+
+  it("should respond with a 404 to a request without a route", async () => {
+    const result = await server.fetch({
+      ...REQUEST,
+      jsonBody: { codes: ["UNKNOWN"] },
+    });
+
+    expect(result.response?.status).toBe(404);
+    expect(result.response?.body).toBe(`No mock response for POST ${URL}`);
+    expect(server.requests[0].matched).toBe(false);
+  });
+
+  it("should match GET requests without a body", async () => {
+    const result = await server.fetch({
+      method: "GET",
+      url: "https://discounts.example.com/codes?shop=1",
+      headers: [],
+      policy: { readTimeoutMs: 500 },
+    });
+
+    expect(result.response?.status).toBe(204);
+  });
+
+  it("should keep repeated response headers and drop transport headers", async () => {
+    const result = await server.fetch({
+      method: "GET",
+      url: "https://discounts.example.com/codes?shop=2",
+      headers: [],
+      policy: { readTimeoutMs: 500 },
+    });
+
+    expect(result.response?.headers).toEqual([
+      { name: "Set-Cookie", value: "session=1" },
+      { name: "Set-Cookie", value: "region=ca" },
+    ]);
+  });
+
+  it("should time out after the request's readTimeoutMs", async () => {
+    const result = await server.fetch({
+      ...REQUEST,
+      jsonBody: { codes: ["SLOW"] },
+      policy: { readTimeoutMs: 50 },
+    });
+
+    expect(result).toEqual({ response: null, errors: [], timedOut: true });
+  });
+
+  it("should not send a request with an invalid shape", async () => {
+    const result = await server.fetch({
+      method: "GET",
+      url: "http://discounts.example.com/validate",
+      headers: [],
+      body: "codes=10OFF",
+      jsonBody: { codes: ["10OFF"] },
+      policy: { readTimeoutMs: 0 },
+    });
+
+    expect(result.response).toBeNull();
+    expect(result.errors.map((error) => error.message)).toEqual([
+      'Request URL "http://discounts.example.com/validate" must use HTTPS',
+      "Request must not set both body and jsonBody",
+      "GET request must not have a body",
+      "Request policy readTimeoutMs must be a positive integer, received 0",
+    ]);
+    expect(server.requests).toEqual([]);
+  });
+});
@@ -0,0 +1,276 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { buildSchema, GraphQLSchema } from "graphql";
+
+import {
+  diffFunctionOutput,
+  loadFixture,
+  loadSchema,
+} from "../../src/wasm-testing-helpers.ts";
+
+const DISCOUNT_FUNCTION_PATH = "./test-app/extensions/discount-function-rs";
+const TARGET = "cart.lines.discounts.generate.run";
+
+describe("diffFunctionOutput", () => {
+  let schema: GraphQLSchema;
+  let expected: any;
+
+  beforeAll(async () => {
+    schema = await loadSchema(`${DISCOUNT_FUNCTION_PATH}/schema.graphql`);
+    const fixture = await loadFixture(
+      `${DISCOUNT_FUNCTION_PATH}/tests/fixtures/cart-lines-valid-fixture.json`,
+    );
+    expected = fixture.expectedOutput;
+  });
+
+  function diff(actual: any) {
+    return diffFunctionOutput({ schema, actual, expected, target: TARGET })
+      .differences;
+  }
+
+  it("should report no differences for equal outputs", () => {
+    expect(diff(structuredClone(expected))).toEqual([]);
+  });
+
+  it("should ignore object key order", () => {
+    const actual = structuredClone(expected);
+    const { value, message, ...rest } =
+      actual.operations[1].productDiscountsAdd.candidates[0];
+    actual.operations[1].productDiscountsAdd.candidates[0] = {
+      value,
+      ...rest,
+      message,
+    };
+
+    expect(diff(actual)).toEqual([]);
+  });
+
+  it("should report changed values by GraphQL path", () => {
+    const actual = structuredClone(expected);
+    actual.operations[1].productDiscountsAdd.candidates[0].value.percentage.value =
+      "15.0";
+
+    expect(diff(actual)).toEqual([
+      {
+        path: "operations[1].productDiscountsAdd.candidates[0].value.percentage.value",
+        message: 'Expected "20.0", received "15.0"',
+        expected: "20.0",
+        actual: "15.0",
+      },
+    ]);
+  });
+
+  it("should report missing and unexpected fields", () => {
+    const actual = structuredClone(expected);
+    delete actual.operations[0].orderDiscountsAdd.selectionStrategy;
+    actual.operations[0].orderDiscountsAdd.candidates[0].targets[0].orderSubtotal.extra = 1;
+
+    expect(diff(actual)).toEqual([
+      {
+        path: "operations[0].orderDiscountsAdd.candidates[0].targets[0].orderSubtotal.extra",
+        message: "Unexpected field with value 1",
+        expected: undefined,
+        actual: 1,
+      },
+      {
+        path: "operations[0].orderDiscountsAdd.selectionStrategy",
+        message: 'Missing field, expected "FIRST"',
+        expected: "FIRST",
+        actual: undefined,
+      },
+    ]);
+  });
+
+  it("should report missing and unexpected list items", () => {
+    const missing = structuredClone(expected);
+    missing.operations.pop();
+    const unexpected = structuredClone(expected);
+    unexpected.operations.push({ enteredDiscountCodesAccept: { codes: [] } });
+
+    expect(diff(missing)).toEqual([
+      {
+        path: "operations[1]",
+        message: `Missing item, expected ${JSON.stringify(expected.operations[1])}`,
+        expected: expected.operations[1],
+        actual: undefined,
+      },
+    ]);
+    expect(diff(unexpected)).toEqual([
+      {
+        path: "operations[2]",
+        message:
+          'Unexpected item {"enteredDiscountCodesAccept":{"codes":[]}}',
+        expected: undefined,
+        actual: { enteredDiscountCodesAccept: { codes: [] } },
+      },
+    ]);
+  });
+
+  it("should report a different @oneOf field once for the object", () => {
+    const actual = structuredClone(expected);
+    actual.operations.reverse();
+
+    expect(diff(actual)).toEqual([
+      {
+        path: "operations[0]",
+        message:
+          'Expected CartOperation "orderDiscountsAdd", received "productDiscountsAdd"',
+        expected: expected.operations[0],
+        actual: expected.operations[1],
+      },
+      {
+        path: "operations[1]",
+        message:
+          'Expected CartOperation "productDiscountsAdd", received "orderDiscountsAdd"',
+        expected: expected.operations[1],
+        actual: expected.operations[0],
+      },
+    ]);
+  });
+
+  it("should report a different nested @oneOf field", () => {
+    const actual = structuredClone(expected);
+    actual.operations[0].orderDiscountsAdd.candidates[0].value = {
+      fixedAmount: { amount: "10.0" },
+    };
+
+    expect(diff(actual)).toEqual([
+      {
+        path: "operations[0].orderDiscountsAdd.candidates[0].value",
+        message:
+          'Expected OrderDiscountCandidateValue "percentage", received "fixedAmount"',
+        expected: { percentage: { value: "10.0" } },
+        actual: { fixedAmount: { amount: "10.0" } },
+      },
+    ]);
+  });
+
+  it("should report values of a different shape", () => {
+    const actual = structuredClone(expected);
+    actual.operations = null;
+
+    expect(diff(actual)).toEqual([
+      {
+        path: "operations",
+        message: `Expected ${JSON.stringify(expected.operations)}, received null`,
+        expected: expected.operations,
+        actual: null,
+      },
+    ]);
+  });
+
+  it("should accept an explicit mutation instead of a target", () => {
+    const actual = structuredClone(expected);
+    actual.operations.reverse();
+
+    const { differences } = diffFunctionOutput({
+      schema,
+      actual,
+      expected,
+      mutationName: "cartLinesDiscountsGenerateRun",
+      resultParameterName: "result",
+    });
+
+    expect(differences.map((difference) => difference.path)).toEqual([
+      "operations[0]",
+      "operations[1]",
+    ]);
+  });
+
+  it("should throw without a target or mutation", () => {
+    expect(() =>
+      diffFunctionOutput({ schema, actual: expected, expected }),
+    ).toThrow(
+      "target is required when mutationName and resultParameterName are not provided",
+    );
+  });
+
+  it("should compare the outputs as JSON without a schema", () => {
+    const actual = structuredClone(expected);
+    actual.operations[0].orderDiscountsAdd.candidates[0].value.percentage.value =
+      "10";
+
+    const { differences } = diffFunctionOutput({ actual, expected });
+
+    expect(differences.map((difference) => difference.path)).toEqual([
+      "operations[0].orderDiscountsAdd.candidates[0].value.percentage.value",
+    ]);
+  });
+
+  describe("Scalars", () => {
+    const scalarSchema = buildSchema(`
+      scalar Decimal
+
+      type Query {
+        version: String
+      }
+
+      type Mutation {
+        run(result: Result!): Boolean!
+      }
+
+      input Result {
+        amount: Decimal
+        ratio: Float
+        id: ID
+        name: String
+      }
+    `);
+
+    function diffResult(actual: any, expected: any) {
+      return diffFunctionOutput({
+        schema: scalarSchema,
+        actual,
+        expected,
+        mutationName: "run",
+        resultParameterName: "result",
+      }).differences;
+    }
+
+    it("should compare Decimal values by value", () => {
+      const actual = structuredClone(expected);
+      actual.operations[1].productDiscountsAdd.candidates[0].value.percentage.value =
+        "20";
+      actual.operations[0].orderDiscountsAdd.candidates[0].value.percentage.value = 10;
+
+      expect(diff(actual)).toEqual([]);
+      expect(diffResult({ amount: "0.50" }, { amount: ".5" })).toEqual([]);
+      expect(diffResult({ amount: "1e2" }, { amount: "100.00" })).toEqual([]);
+    });
+
+    it("should report Decimal values that differ", () => {
+      expect(diffResult({ amount: "10.5" }, { amount: "10.0" })).toEqual([
+        {
+          path: "amount",
+          message: 'Expected "10.0", received "10.5"',
+          expected: "10.0",
+          actual: "10.5",
+        },
+      ]);
+      expect(diffResult({ amount: "abc" }, { amount: "abc" })).toEqual([]);
+      expect(diffResult({ amount: null }, { amount: "0" })).toHaveLength(1);
+    });
+
+    it("should compare Float values by value", () => {
+      expect(diffResult({ ratio: 1.0 }, { ratio: 1 })).toEqual([]);
+      expect(diffResult({ ratio: "1" }, { ratio: 1 })).toHaveLength(1);
+    });
+
+    it("should compare ID values by value", () => {
+      expect(diffResult({ id: 12345 }, { id: "12345" })).toEqual([]);
+      expect(diffResult({ id: 1 }, { id: "gid://shopify/CartLine/1" })).toEqual(
+        [
+          {
+            path: "id",
+            message: 'Expected "gid://shopify/CartLine/1", received 1',
+            expected: "gid://shopify/CartLine/1",
+            actual: 1,
+          },
+        ],
+      );
+    });
+
+    it("should compare other scalars by their JSON representation", () => {
+      expect(diffResult({ name: "10" }, { name: "10.0" })).toHaveLength(1);
+    });
+  });
+});
@@ -0,0 +1,183 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { DocumentNode, GraphQLSchema } from "graphql";
+
+import { loadSchema } from "../../src/methods/load-schema.ts";
+import { loadFixture, FixtureData } from "../../src/methods/load-fixture.ts";
+import { loadInputQuery } from "../../src/methods/load-input-query.ts";
+import { validateTestAssets } from "../../src/methods/validate-test-assets.ts";
+import {
+  functionMatchers,
+  FunctionMatchers,
+} from "../../src/methods/function-matchers.ts";
+
+const DISCOUNT_FUNCTION_PATH = "./test-app/extensions/discount-function-rs";
+const TARGET = "cart.lines.discounts.generate.run";
+
+expect.extend(functionMatchers);
+
+function matchers(value: unknown) {
+  return expect(value) as unknown as FunctionMatchers<void> & {
+    not: FunctionMatchers<void>;
+  };
+}
+
+function runResult(output: any, instructions: number | null = 1000) {
+  return {
+    result: {
+      output,
+      instructions,
+      memoryUsage: null,
+      inputSize: 0,
+      outputSize: 0,
+      logs: "",
+    },
+    error: null,
+  };
+}
+
+describe("functionMatchers", () => {
+  let schema: GraphQLSchema;
+  let inputQueryAST: DocumentNode;
+  let fixture: FixtureData;
+
+  beforeAll(async () => {
+    schema = await loadSchema(`${DISCOUNT_FUNCTION_PATH}/schema.graphql`);
+    inputQueryAST = await loadInputQuery(
+      `${DISCOUNT_FUNCTION_PATH}/src/cart_lines_discounts_generate_run.graphql`,
+    );
+    fixture = await loadFixture(
+      `${DISCOUNT_FUNCTION_PATH}/tests/fixtures/cart-lines-valid-fixture.json`,
+    );
+  });
+
+  describe("toHaveValidTestAssets", () => {
+    it("should pass for valid test assets", async () => {
+      const result = await validateTestAssets({
+        schema,
+        fixture,
+        inputQueryAST,
+      });
+
+      matchers(result).toHaveValidTestAssets();
+    });
+
+    it("should list the errors with the path of each fixture field", async () => {
+      const result = await validateTestAssets({
+        schema,
+        fixture: {
+          ...fixture,
+          input: {
+            ...fixture.input,
+            cart: { lines: [{ id: "gid://shopify/CartLine/0" }] },
+          },
+        },
+        inputQueryAST,
+      });
+
+      expect(() => matchers(result).toHaveValidTestAssets()).toThrow(
+        "Expected valid test assets, but validateTestAssets found 1 error:\n" +
+          "  input fixture: Missing expected fixture data for `cost` at cart.lines[0].cost",
+      );
+    });
+
+    it("should list output fixture errors", async () => {
+      const result = await validateTestAssets({
+        schema,
+        fixture: { ...fixture, expectedOutput: { operations: "none" } },
+        inputQueryAST,
+      });
+
+      expect(() => matchers(result).toHaveValidTestAssets()).toThrow(
+        /^Expected valid test assets, but validateTestAssets found \d+ errors?:\n {2}output fixture: /,
+      );
+    });
+  });
+
+  describe("toProduceOutput", () => {
+    it("should pass for the expected output", () => {
+      const output = structuredClone(fixture.expectedOutput);
+
+      matchers(runResult(output)).toProduceOutput(fixture.expectedOutput);
+    });
+
+    it("should list the differences by path", () => {
+      const output = structuredClone(fixture.expectedOutput);
+      output.operations[0].orderDiscountsAdd.selectionStrategy = "ALL";
+
+      expect(() =>
+        matchers(runResult(output)).toProduceOutput(fixture.expectedOutput),
+      ).toThrow(
+        "Expected the function to produce the expected output, but found 1 difference:\n" +
+          '  operations[0].orderDiscountsAdd.selectionStrategy: Expected "FIRST", received "ALL"',
+      );
+    });
+
+    it("should compare values by type with a schema and target", () => {
+      const output = structuredClone(fixture.expectedOutput);
+      output.operations[0].orderDiscountsAdd.candidates[0].value.percentage.value =
+        "10";
+
+      matchers(runResult(output)).toProduceOutput(fixture.expectedOutput, {
+        schema,
+        target: TARGET,
+      });
+      expect(() =>
+        matchers(runResult(output)).toProduceOutput(fixture.expectedOutput),
+      ).toThrow("found 1 difference");
+    });
+
+    it("should throw for a schema without a target", () => {
+      expect(() =>
+        matchers(runResult(fixture.expectedOutput)).toProduceOutput(
+          fixture.expectedOutput,
+          { schema },
+        ),
+      ).toThrow(
+        "toProduceOutput needs a target with a schema, to find the target's result type",
+      );
+    });
+
+    it("should fail for a run that failed", () => {
+      expect(() =>
+        matchers({
+          result: null,
+          error: "function-runner exited with code 1",
+        }).toProduceOutput(fixture.expectedOutput),
+      ).toThrow(
+        "Expected the function to run, but it failed: function-runner exited with code 1",
+      );
+    });
+  });
+
+  describe("toStayWithinInstructionBudget", () => {
+    it("should pass for a run within the budget", () => {
+      matchers(runResult({}, 1000)).toStayWithinInstructionBudget(1000);
+      matchers(runResult({}, 1000).result).toStayWithinInstructionBudget(2000);
+    });
+
+    it("should report the instructions over the budget", () => {
+      expect(() =>
+        matchers(runResult({}, 12_500_000)).toStayWithinInstructionBudget(
+          11_000_000,
+        ),
+      ).toThrow(
+        "Expected at most 11,000,000 instructions, but the run executed 12,500,000 (114% of the budget)",
+      );
+    });
+
+    it("should support not", () => {
+      matchers(runResult({}, 2000)).not.toStayWithinInstructionBudget(1000);
+      expect(() =>
+        matchers(runResult({}, 500)).not.toStayWithinInstructionBudget(1000),
+      ).toThrow("Expected more than 1,000 instructions");
+    });
+
+    it("should fail for a run without an instruction count", () => {
+      expect(() =>
+        matchers(runResult({}, null)).toStayWithinInstructionBudget(1000),
+      ).toThrow(
+        "Expected an instruction count, but the run did not report one",
+      );
+    });
+  });
+});
@@ -0,0 +1,262 @@
+import { describe, it, expect } from "vitest";
+import { parse } from "graphql";
+
+import {
+  generateFixture,
+  loadInputQuery,
+  loadSchema,
+  validateTestAssets,
+} from "../../src/wasm-testing-helpers.ts";
+
+const DISCOUNT_FUNCTION_PATH = "./test-app/extensions/discount-function-rs";
+
+describe("generateFixture", () => {
+  it("should generate a fixture that passes validateTestAssets", async () => {
+    const schema = await loadSchema("./test/fixtures/test-schema.graphql");
+    const inputQueryAST = await loadInputQuery(
+      "./test/fixtures/valid-query.graphql",
+    );
+
+    const fixture = generateFixture(
+      schema,
+      inputQueryAST,
+      "data.processing.generate.run",
+      "test-data-processing",
+    );
+    const result = await validateTestAssets({ schema, fixture, inputQueryAST });
+
+    expect(result.error).toBeUndefined();
+    expect(result.inputQuery.errors).toHaveLength(0);
+    expect(result.inputFixture.errors).toHaveLength(0);
+    expect(result.outputFixture.errors).toHaveLength(0);
+  });
+
+  it("should generate required variables and one response per requested value", async () => {
+    const schema = await loadSchema(`${DISCOUNT_FUNCTION_PATH}/schema.graphql`);
+    const inputQueryAST = await loadInputQuery(
+      "./test/fixtures/variables/tagged-cart-query.graphql",
+    );
+
+    const fixture = generateFixture(
+      schema,
+      inputQueryAST,
+      "cart.lines.discounts.generate.run",
+      "cart_lines_discounts_generate_run",
+    );
+    const result = await validateTestAssets({ schema, fixture, inputQueryAST });
+
+    expect(fixture.variables).toEqual({
+      tags: [],
+      configurationKey: "configurationKey",
+    });
+    expect(fixture.input.cart.buyerIdentity.customer.hasTags).toEqual([]);
+    expect(result.inputQuery.errors).toHaveLength(0);
+    expect(result.inputFixture.errors).toHaveLength(0);
+    expect(result.outputFixture.errors).toHaveLength(0);
+  });
+
+  it("should generate placeholder values for each selected field", async () => {
+    const schema = await loadSchema("./test/fixtures/test-schema.graphql");
+    const inputQueryAST = await loadInputQuery(
+      "./test/fixtures/valid-query.graphql",
+    );
+
+    const fixture = generateFixture(
+      schema,
+      inputQueryAST,
+      "data.processing.generate.run",
+      "test-data-processing",
+    );
+
+    expect(fixture).toEqual({
+      export: "test-data-processing",
+      target: "data.processing.generate.run",
+      input: {
+        data: {
+          items: [
+            {
+              id: "gid://shopify/Item/1",
+              count: 1,
+              details: {
+                id: "gid://shopify/ItemDetails/1",
+                name: "name",
+              },
+            },
+          ],
+          metadata: {
+            email: "email",
+          },
+        },
+      },
+      expectedOutput: {
+        title: "title",
+        count: 1,
+        items: [],
+      },
+    });
+  });
+
+  it("should generate GIDs, decimal strings and enum members for a Shopify schema", async () => {
+    const schema = await loadSchema(`${DISCOUNT_FUNCTION_PATH}/schema.graphql`);
+    const inputQueryAST = await loadInputQuery(
+      `${DISCOUNT_FUNCTION_PATH}/src/cart_lines_discounts_generate_run.graphql`,
+    );
+
+    const fixture = generateFixture(
+      schema,
+      inputQueryAST,
+      "cart.lines.discounts.generate.run",
+      "cart_lines_discounts_generate_run",
+    );
+
+    expect(fixture.input).toEqual({
+      cart: {
+        lines: [
+          {
+            id: "gid://shopify/CartLine/1",
+            cost: { subtotalAmount: { amount: "10.0" } },
+          },
+        ],
+      },
+      discount: { discountClasses: ["ORDER"], metafield: { jsonValue: {} } },
+      fetchResult: { jsonBody: {} },
+    });
+    expect(fixture.expectedOutput).toEqual({ operations: [] });
+
+    const result = await validateTestAssets({ schema, fixture, inputQueryAST });
+    expect(result.inputQuery.errors).toHaveLength(0);
+    expect(result.inputFixture.errors).toHaveLength(0);
+    expect(result.outputFixture.errors).toHaveLength(0);
+  });
+
+  it("should resolve abstract types to the possible type matching the most fields", async () => {
+    const schema = await loadSchema("./test/fixtures/test-schema.graphql");
+    const inputQueryAST = parse(`
+      query {
+        data {
+          interfaceImplementers {
+            ... on HasId {
+              id
+              ... on HasName {
+                name
+              }
+            }
+          }
+          searchResults {
+            __typename
+            ... on Item {
+              count
+            }
+            ... on Metadata {
+              email
+            }
+          }
+        }
+      }
+    `);
+
+    const fixture = generateFixture(
+      schema,
+      inputQueryAST,
+      "data.processing.generate.run",
+      "test-data-processing",
+    );
+
+    expect(fixture.input.data).toEqual({
+      interfaceImplementers: [
+        { id: "gid://shopify/InterfaceImplementer1/1", name: "name" },
+      ],
+      searchResults: [{ __typename: "Item", count: 1 }],
+    });
+
+    const result = await validateTestAssets({ schema, fixture, inputQueryAST });
+    expect(result.inputFixture.errors).toHaveLength(0);
+  });
+
+  it("should merge fields selected more than once and use aliases", async () => {
+    const schema = await loadSchema("./test/fixtures/test-schema.graphql");
+    const inputQueryAST = parse(`
+      query {
+        data {
+          firstItems: items(first: 1) {
+            id
+          }
+          firstItems: items(first: 1) {
+            ...ItemCount
+          }
+        }
+      }
+
+      fragment ItemCount on Item {
+        count
+      }
+    `);
+
+    const fixture = generateFixture(
+      schema,
+      inputQueryAST,
+      "data.processing.generate.run",
+      "test-data-processing",
+    );
+
+    expect(fixture.input).toEqual({
+      data: {
+        firstItems: [{ id: "gid://shopify/Item/1", count: 1 }],
+      },
+    });
+
+    const result = await validateTestAssets({ schema, fixture, inputQueryAST });
+    expect(result.inputFixture.errors).toHaveLength(0);
+  });
+
+  it("should follow named fragments on abstract types with an aliased __typename", async () => {
+    const schema = await loadSchema("./test/fixtures/test-schema.graphql");
+    const inputQueryAST = parse(`
+      query {
+        data {
+          searchResults {
+            kind: __typename
+            ...ItemFields
+          }
+        }
+      }
+
+      fragment ItemFields on Item {
+        itemId: id
+        count
+      }
+    `);
+
+    const fixture = generateFixture(
+      schema,
+      inputQueryAST,
+      "data.processing.generate.run",
+      "test-data-processing",
+    );
+
+    expect(fixture.input.data).toEqual({
+      searchResults: [
+        { kind: "Item", itemId: "gid://shopify/Item/1", count: 1 },
+      ],
+    });
+
+    const result = await validateTestAssets({ schema, fixture, inputQueryAST });
+    expect(result.inputFixture.errors).toHaveLength(0);
+  });
+
+  it("should throw when the target does not match a mutation", async () => {
+    const schema = await loadSchema("./test/fixtures/test-schema.graphql");
+    const inputQueryAST = await loadInputQuery(
+      "./test/fixtures/valid-query.graphql",
+    );
+
+    expect(() =>
+      generateFixture(
+        schema,
+        inputQueryAST,
+        "unknown.target",
+        "test-data-processing",
+      ),
+    ).toThrow("No mutation found for target 'unknown.target'");
+  });
+});
@@ -5,6 +5,10 @@ import path from "path";
 import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
 
 import { getFunctionInfo } from "../../src/methods/get-function-info.ts";
+import {
+  loadFunctionInfo,
+  FUNCTION_RUNNER_PATH_ENV,
+} from "../../src/methods/load-function-info.ts";
 
 vi.mock("child_process", () => ({
   spawn: vi.fn(),
@@ -153,4 +157,50 @@ describe("getFunctionInfo", () => {
     await expect(promise).rejects.toThrow("Error line 2");
     await expect(promise).rejects.toThrow("Error line 3");
   });
+
+  describe("without the Shopify CLI", () => {
+    const functionDir = "test-app/extensions/discount-function-rs";
+
+    beforeEach(() => {
+      vi.stubEnv(FUNCTION_RUNNER_PATH_ENV, "/opt/bin/function-runner");
+    });
+
+    afterEach(() => {
+      vi.unstubAllEnvs();
+    });
+
+    it("should fall back to shopify.extension.toml when the command is not found", async () => {
+      const promise = getFunctionInfo(functionDir);
+
+      setTimeout(() => {
+        mockProcess.stderr.emit("data", "shopify: command not found");
+        mockProcess.emit("close", 127);
+      }, 10);
+
+      expect(await promise).toStrictEqual(await loadFunctionInfo(functionDir));
+    });
+
+    it("should fall back to shopify.extension.toml when shopify cannot be started", async () => {
+      const promise = getFunctionInfo(functionDir);
+
+      setTimeout(() => {
+        mockProcess.emit("error", new Error("spawn shopify ENOENT"));
+      }, 10);
+
+      expect(await promise).toStrictEqual(await loadFunctionInfo(functionDir));
+    });
+
+    it("should not fall back when the command fails", async () => {
+      const promise = getFunctionInfo(functionDir);
+
+      setTimeout(() => {
+        mockProcess.stderr.emit("data", "Error: Function not found\n");
+        mockProcess.emit("close", 1);
+      }, 10);
+
+      await expect(promise).rejects.toThrow(
+        "Function info command failed with exit code 1",
+      );
+    });
+  });
 });
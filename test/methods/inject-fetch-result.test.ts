@@ -0,0 +1,135 @@
+import { describe, it, expect } from "vitest";
+import { parse } from "graphql";
+
+import {
+  injectFetchResult,
+  FixtureData,
+} from "../../src/wasm-testing-helpers.ts";
+
+const RUN_QUERY = parse(`
+  query Input {
+    cart {
+      lines {
+        id
+      }
+    }
+    fetchResult {
+      status
+      body
+      jsonBody
+      contentType: header(name: "Content-Type") {
+        value
+      }
+      missing: header(name: "X-Missing") {
+        value
+      }
+    }
+  }
+`);
+
+function fixtureWith(response: any): FixtureData {
+  return {
+    export: "cart_lines_discounts_generate_run",
+    target: "cart.lines.discounts.generate.run",
+    input: { cart: { lines: [{ id: "gid://shopify/CartLine/1" }] } },
+    expectedOutput: { operations: [] },
+    fetch: {
+      export: "cart_lines_discounts_generate_fetch",
+      target: "cart.lines.discounts.generate.fetch",
+      input: { enteredDiscountCodes: ["10OFF"] },
+      expectedOutput: { request: null },
+      response,
+    },
+  };
+}
+
+describe("injectFetchResult", () => {
+  it("should inject the response shaped by the run query", () => {
+    const runFixture = injectFetchResult(
+      fixtureWith({
+        status: 200,
+        body: '{"discount":10}',
+        headers: [{ name: "content-type", value: "application/json" }],
+      }),
+      RUN_QUERY,
+    );
+
+    expect(runFixture.fetch).toBeUndefined();
+    expect(runFixture.input).toEqual({
+      cart: { lines: [{ id: "gid://shopify/CartLine/1" }] },
+      fetchResult: {
+        status: 200,
+        body: '{"discount":10}',
+        jsonBody: { discount: 10 },
+        contentType: { value: "application/json" },
+        missing: null,
+      },
+    });
+  });
+
+  it("should serialize body from jsonBody", () => {
+    const runFixture = injectFetchResult(
+      fixtureWith({ status: 200, jsonBody: { discount: 10 } }),
+      RUN_QUERY,
+    );
+
+    expect(runFixture.input.fetchResult.body).toBe('{"discount":10}');
+    expect(runFixture.input.fetchResult.jsonBody).toEqual({ discount: 10 });
+  });
+
+  it("should keep a body that is not JSON as jsonBody", () => {
+    const runFixture = injectFetchResult(
+      fixtureWith({ status: 500, body: "Internal Server Error" }),
+      RUN_QUERY,
+    );
+
+    expect(runFixture.input.fetchResult).toMatchObject({
+      status: 500,
+      body: "Internal Server Error",
+      jsonBody: "Internal Server Error",
+    });
+  });
+
+  it("should inject null for a missing response", () => {
+    const runFixture = injectFetchResult(fixtureWith(null), RUN_QUERY);
+
+    expect(runFixture.input.fetchResult).toBeNull();
+  });
+
+  it("should follow aliases, fragments and __typename", () => {
+    const runFixture = injectFetchResult(
+      fixtureWith({
+        status: 200,
+        headers: [{ name: "X-Request-Id", value: "abc" }],
+      }),
+      parse(`
+        query Input {
+          response: fetchResult {
+            ...Response
+          }
+        }
+
+        fragment Response on HttpResponse {
+          __typename
+          code: status
+          headers {
+            name
+            value
+          }
+        }
+      `),
+    );
+
+    expect(runFixture.input.response).toEqual({
+      __typename: "HttpResponse",
+      code: 200,
+      headers: [{ name: "X-Request-Id", value: "abc" }],
+    });
+  });
+
+  it("should return a fixture without a fetch stage unchanged", () => {
+    const fixture = { ...fixtureWith({ status: 200 }), fetch: undefined };
+
+    expect(injectFetchResult(fixture, RUN_QUERY)).toBe(fixture);
+  });
+});
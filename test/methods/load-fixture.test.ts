@@ -28,6 +28,43 @@ describe("loadFixture", () => {
     expect(fixture.expectedOutput.items).toHaveLength(2);
   });
 
+  it("should load the fetch stage of a fixture", async () => {
+    const fixture = await loadFixture(
+      "./test/fixtures/fetch/cart-lines-fetch-fixture.json",
+    );
+    expect(fixture.target).toBe("cart.lines.discounts.generate.run");
+    expect(fixture.fetch).toBeDefined();
+    expect(fixture.fetch?.export).toBe("cart_lines_discounts_generate_fetch");
+    expect(fixture.fetch?.target).toBe("cart.lines.discounts.generate.fetch");
+    expect(fixture.fetch?.input.enteredDiscountCodes).toEqual(["10OFF"]);
+    expect(fixture.fetch?.expectedOutput.request.method).toBe("POST");
+    expect(fixture.fetch?.response).toEqual({
+      status: 200,
+      jsonBody: { valid: true },
+    });
+  });
+
+  it("should leave out the fetch stage of a fixture without one", async () => {
+    const fixture = await loadFixture("./test/fixtures/valid-fixture.json");
+    expect(fixture.fetch).toBeUndefined();
+  });
+
+  it("should load the input query variables of a fixture", async () => {
+    const fixture = await loadFixture(
+      "./test/fixtures/variables/tagged-cart-fixture.json",
+    );
+    expect(fixture.variables).toEqual({
+      tags: ["VIP", "Wholesale"],
+      collectionIds: ["gid://shopify/Collection/1"],
+      configurationKey: "function-configuration",
+    });
+  });
+
+  it("should leave out the variables of a fixture without them", async () => {
+    const fixture = await loadFixture("./test/fixtures/valid-fixture.json");
+    expect(fixture.variables).toBeUndefined();
+  });
+
   it("should throw an error for non-existent file", async () => {
     await expect(loadFixture("non-existent-file.json")).rejects.toThrow();
   });
@@ -0,0 +1,151 @@
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
+
+import {
+  loadFunctionInfo,
+  FUNCTION_RUNNER_PATH_ENV,
+} from "../../src/methods/load-function-info.ts";
+
+describe("loadFunctionInfo", () => {
+  const functionDir = path.resolve("test-app/extensions/discount-function-rs");
+  let tempDir: string;
+
+  beforeEach(() => {
+    tempDir = fs.mkdtempSync(path.join(os.tmpdir(), "load-function-info-"));
+    vi.stubEnv(FUNCTION_RUNNER_PATH_ENV, "/opt/bin/function-runner");
+  });
+
+  afterEach(() => {
+    vi.unstubAllEnvs();
+    fs.rmSync(tempDir, { recursive: true, force: true });
+  });
+
+  it("should load function info from the extension's shopify.extension.toml", async () => {
+    const functionInfo = await loadFunctionInfo(functionDir);
+
+    expect(functionInfo.schemaPath).toBe(
+      path.join(functionDir, "schema.graphql"),
+    );
+    expect(functionInfo.functionRunnerPath).toBe(
+      path.resolve("/opt/bin/function-runner"),
+    );
+    expect(functionInfo.wasmPath).toBe(
+      path.join(
+        functionDir,
+        "target/wasm32-wasip1/release/discount-function-rs.wasm",
+      ),
+    );
+    expect(Object.keys(functionInfo.targeting)).toHaveLength(4);
+    expect(
+      functionInfo.targeting["cart.lines.discounts.generate.run"],
+    ).toStrictEqual({
+      inputQueryPath: path.join(
+        functionDir,
+        "src/cart_lines_discounts_generate_run.graphql",
+      ),
+      export: "cart_lines_discounts_generate_run",
+    });
+  });
+
+  it("should default the wasm path and skip targets without an input query", async () => {
+    fs.writeFileSync(
+      path.join(tempDir, "shopify.extension.toml"),
+      [
+        'api_version = "2025-07"',
+        "",
+        "[[extensions]]",
+        'handle = "minimal-function"',
+        'type = "function"',
+        "",
+        "  [[extensions.targeting]]",
+        '  target = "cart.validations.generate.run"',
+        '  input_query = "src/run.graphql"',
+        "",
+        "  [[extensions.targeting]]",
+        '  target = "cart.validations.generate.fetch"',
+      ].join("\n"),
+    );
+
+    const functionInfo = await loadFunctionInfo(tempDir);
+
+    expect(functionInfo.wasmPath).toBe(path.join(tempDir, "dist/index.wasm"));
+    expect(functionInfo.targeting).toStrictEqual({
+      "cart.validations.generate.run": {
+        inputQueryPath: path.join(tempDir, "src/run.graphql"),
+      },
+    });
+  });
+
+  it("should load the metafield bound by [extensions.input.variables]", async () => {
+    const inputVariablesDir = path.resolve("test/fixtures/input-variables");
+
+    const functionInfo = await loadFunctionInfo(inputVariablesDir);
+
+    expect(functionInfo.inputVariables).toStrictEqual({
+      namespace: "$app:tagged-discount",
+      key: "input-variables",
+    });
+    expect(functionInfo.wasmPath).toBe(
+      path.join(inputVariablesDir, "function.wasm"),
+    );
+
+    const discountFunctionInfo = await loadFunctionInfo(functionDir);
+    expect(discountFunctionInfo.inputVariables).toBeUndefined();
+  });
+
+  it.skipIf(process.platform === "win32")(
+    "should find function-runner on PATH",
+    async () => {
+      const runner = path.join(tempDir, "function-runner");
+      fs.writeFileSync(runner, "#!/bin/sh\n");
+      fs.chmodSync(runner, 0o755);
+      vi.stubEnv(FUNCTION_RUNNER_PATH_ENV, undefined);
+      vi.stubEnv("PATH", [os.tmpdir(), tempDir].join(path.delimiter));
+
+      const functionInfo = await loadFunctionInfo(functionDir);
+
+      expect(functionInfo.functionRunnerPath).toBe(runner);
+    },
+  );
+
+  it("should throw an error when function-runner cannot be found", async () => {
+    vi.stubEnv(FUNCTION_RUNNER_PATH_ENV, undefined);
+    vi.stubEnv("PATH", tempDir);
+
+    await expect(loadFunctionInfo(functionDir)).rejects.toThrow(
+      `Failed to load function info from ${path.join(functionDir, "shopify.extension.toml")}: Could not find function-runner on PATH; install it or set FUNCTION_RUNNER_PATH`,
+    );
+  });
+
+  it("should throw an error for a missing or invalid shopify.extension.toml", async () => {
+    const filename = path.join(tempDir, "shopify.extension.toml");
+
+    await expect(loadFunctionInfo(tempDir)).rejects.toThrow(
+      `Failed to load function info from ${filename}`,
+    );
+
+    fs.writeFileSync(filename, 'api_version = "2025-07"\n');
+    await expect(loadFunctionInfo(tempDir)).rejects.toThrow(
+      `Failed to load function info from ${filename}: Expected an [[extensions]] entry`,
+    );
+
+    fs.writeFileSync(
+      filename,
+      '[[extensions]]\ntype = "function"\n\n[[extensions.targeting]]\ninput_query = "run.graphql"\n',
+    );
+    await expect(loadFunctionInfo(tempDir)).rejects.toThrow(
+      `Failed to load function info from ${filename}: Targeting 0 must have a string target`,
+    );
+
+    fs.writeFileSync(
+      filename,
+      '[[extensions]]\ntype = "function"\n\n[extensions.input.variables]\nkey = "input-variables"\n',
+    );
+    await expect(loadFunctionInfo(tempDir)).rejects.toThrow(
+      `Failed to load function info from ${filename}: [extensions.input.variables] must have a string namespace and key`,
+    );
+  });
+});
@@ -0,0 +1,85 @@
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import { describe, it, expect } from "vitest";
+
+import { loadMetafieldSchemas } from "../../src/methods/load-metafield-schemas.ts";
+
+describe("loadMetafieldSchemas", () => {
+  it("should load metafield schemas from a JSON file", async () => {
+    const metafieldSchemas = await loadMetafieldSchemas(
+      "./test/fixtures/metafields/metafield-schemas.json",
+    );
+
+    expect(metafieldSchemas).toHaveLength(3);
+    expect(metafieldSchemas[0]).toMatchObject({
+      ownerType: "Discount",
+      key: "function-configuration",
+      type: "json",
+    });
+    expect(metafieldSchemas[0].schema.additionalProperties).toBe(false);
+    expect(metafieldSchemas[1]).toStrictEqual({
+      ownerType: "Shop",
+      namespace: "settings",
+      key: "max-discounts",
+      type: "number_integer",
+    });
+  });
+
+  it("should throw an error for a file that is not an array of metafield schemas", async () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), "metafield-schemas-"));
+    const notAnArray = path.join(dir, "not-an-array.json");
+    const missingKey = path.join(dir, "missing-key.json");
+    fs.writeFileSync(notAnArray, JSON.stringify({ key: "config" }));
+    fs.writeFileSync(missingKey, JSON.stringify([{ type: "json" }]));
+
+    try {
+      await expect(loadMetafieldSchemas(notAnArray)).rejects.toThrow(
+        `Failed to load metafield schemas from ${notAnArray}: Expected an array of metafield schemas`,
+      );
+      await expect(loadMetafieldSchemas(missingKey)).rejects.toThrow(
+        `Failed to load metafield schemas from ${missingKey}: Metafield schema 0 must have a string key`,
+      );
+    } finally {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  it("should throw an error for schemas of later JSON Schema drafts", async () => {
+    const laterDraft =
+      "./test/fixtures/metafields/draft-2020-12-metafield-schemas.json";
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), "metafield-schemas-"));
+    const declaredDraft = path.join(dir, "declared-draft.json");
+    fs.writeFileSync(
+      declaredDraft,
+      JSON.stringify([
+        {
+          key: "config",
+          schema: {
+            $schema: "https://json-schema.org/draft/2020-12/schema",
+          },
+        },
+      ]),
+    );
+
+    try {
+      await expect(loadMetafieldSchemas(laterDraft)).rejects.toThrow(
+        `Failed to load metafield schemas from ${laterDraft}: Metafield schema 0 uses \`prefixItems\`, which JSON Schema draft-07 does not support`,
+      );
+      await expect(loadMetafieldSchemas(declaredDraft)).rejects.toThrow(
+        `Failed to load metafield schemas from ${declaredDraft}: Metafield schema 0 declares \`$schema\` "https://json-schema.org/draft/2020-12/schema", but only JSON Schema draft-07 is supported`,
+      );
+    } finally {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  it("should throw an error for non-existent file", async () => {
+    await expect(
+      loadMetafieldSchemas("./test/fixtures/metafields/missing.json"),
+    ).rejects.toThrow(
+      "Failed to load metafield schemas from ./test/fixtures/metafields/missing.json",
+    );
+  });
+});
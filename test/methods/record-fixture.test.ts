@@ -0,0 +1,333 @@
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
+
+import { recordFixture } from "../../src/methods/record-fixture.ts";
+import { runFunction } from "../../src/methods/run-function.ts";
+
+vi.mock("../../src/methods/run-function.ts", () => ({
+  runFunction: vi.fn(),
+}));
+
+const SCHEMA_PATH = "./test-app/extensions/cart-validation-js/schema.graphql";
+const DISCOUNT_SCHEMA_PATH =
+  "./test-app/extensions/discount-function-rs/schema.graphql";
+const FETCH_FIXTURE_PATH =
+  "./test/fixtures/fetch/cart-lines-fetch-fixture.json";
+
+const FIXTURE = `{
+    "shopId": 1,
+    "payload": {
+        "export": "run",
+        "target": "cart.validations.generate.run",
+        "input": { "cart": { "lines": [] } },
+        "output": {
+            "operations": []
+        }
+    },
+    "logs": []
+}
+`;
+
+describe("recordFixture", () => {
+  const mockRunFunction = vi.mocked(runFunction);
+  let tempDir: string;
+  let fixturePath: string;
+
+  beforeEach(() => {
+    tempDir = fs.mkdtempSync(path.join(os.tmpdir(), "record-fixture-"));
+    fixturePath = path.join(tempDir, "fixture.json");
+    fs.writeFileSync(fixturePath, FIXTURE);
+  });
+
+  afterEach(() => {
+    fs.rmSync(tempDir, { recursive: true, force: true });
+    vi.clearAllMocks();
+  });
+
+  function mockOutput(output: any) {
+    mockRunFunction.mockResolvedValue({
+      result: {
+        output,
+        instructions: null,
+        memoryUsage: null,
+        inputSize: 0,
+        outputSize: 0,
+        logs: "",
+      },
+      error: null,
+    });
+  }
+
+  function record() {
+    return recordFixture(
+      fixturePath,
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      "/path/to/query.graphql",
+      SCHEMA_PATH,
+    );
+  }
+
+  it("should write the actual output to payload.output and keep the rest of the file", async () => {
+    const output = {
+      operations: [{ validationAdd: { errors: [] } }],
+    };
+    mockOutput(output);
+
+    const result = await record();
+
+    expect(result).toEqual({ output, updated: true, error: null });
+    expect(fs.readFileSync(fixturePath, "utf-8")).toBe(`{
+    "shopId": 1,
+    "payload": {
+        "export": "run",
+        "target": "cart.validations.generate.run",
+        "input": { "cart": { "lines": [] } },
+        "output": {
+            "operations": [
+                {
+                    "validationAdd": {
+                        "errors": []
+                    }
+                }
+            ]
+        }
+    },
+    "logs": []
+}
+`);
+  });
+
+  it("should run the fixture loaded from the file", async () => {
+    mockOutput({ operations: [] });
+
+    await record();
+
+    expect(mockRunFunction).toHaveBeenCalledWith(
+      {
+        export: "run",
+        target: "cart.validations.generate.run",
+        input: { cart: { lines: [] } },
+        expectedOutput: { operations: [] },
+      },
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      "/path/to/query.graphql",
+      SCHEMA_PATH,
+    );
+  });
+
+  it("should leave the file untouched when the output already matches", async () => {
+    mockOutput({ operations: [] });
+
+    const result = await record();
+
+    expect(result).toEqual({
+      output: { operations: [] },
+      updated: false,
+      error: null,
+    });
+    expect(fs.readFileSync(fixturePath, "utf-8")).toBe(FIXTURE);
+  });
+
+  it("should add payload.output when the fixture has none", async () => {
+    fs.writeFileSync(
+      fixturePath,
+      JSON.stringify({
+        payload: {
+          export: "run",
+          target: "cart.validations.generate.run",
+          input: {},
+        },
+      }),
+    );
+    mockOutput({ operations: [] });
+
+    const result = await record();
+
+    expect(result.updated).toBe(true);
+    expect(JSON.parse(fs.readFileSync(fixturePath, "utf-8"))).toEqual({
+      payload: {
+        export: "run",
+        target: "cart.validations.generate.run",
+        input: {},
+        output: { operations: [] },
+      },
+    });
+  });
+
+  it("should return the run error and leave the file untouched when the function fails", async () => {
+    mockRunFunction.mockResolvedValue({
+      result: null,
+      error: "function-runner failed with exit code 1: boom",
+    });
+
+    const result = await record();
+
+    expect(result).toEqual({
+      output: null,
+      updated: false,
+      error: "function-runner failed with exit code 1: boom",
+    });
+    expect(fs.readFileSync(fixturePath, "utf-8")).toBe(FIXTURE);
+  });
+
+  it("should return an error when the fixture cannot be loaded", async () => {
+    const result = await recordFixture(
+      path.join(tempDir, "missing.json"),
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      "/path/to/query.graphql",
+      SCHEMA_PATH,
+    );
+
+    expect(result.updated).toBe(false);
+    expect(result.error).toContain("Failed to record fixture");
+    expect(mockRunFunction).not.toHaveBeenCalled();
+  });
+
+  it("should run with the variables of the input variables metafield", async () => {
+    const inputVariablesDir = "./test/fixtures/input-variables";
+    fs.copyFileSync(`${inputVariablesDir}/fixture.json`, fixturePath);
+    mockOutput({ operations: [] });
+
+    const result = await recordFixture(
+      fixturePath,
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      `${inputVariablesDir}/query.graphql`,
+      DISCOUNT_SCHEMA_PATH,
+      undefined,
+      { namespace: "$app:tagged-discount", key: "input-variables" },
+    );
+
+    expect(result.error).toBeNull();
+    expect(mockRunFunction).toHaveBeenCalledWith(
+      expect.objectContaining({ variables: { tags: ["VIP", "Wholesale"] } }),
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      `${inputVariablesDir}/query.graphql`,
+      DISCOUNT_SCHEMA_PATH,
+    );
+  });
+
+  describe("with a fetch stage", () => {
+    const request = {
+      request: {
+        method: "GET",
+        url: "https://other.example.com",
+        headers: [],
+        body: null,
+        jsonBody: null,
+        policy: { readTimeoutMs: 2000 },
+      },
+    };
+
+    beforeEach(() => {
+      fs.writeFileSync(
+        path.join(tempDir, "fetch.graphql"),
+        "query Input { enteredDiscountCodes }",
+      );
+      fs.writeFileSync(
+        path.join(tempDir, "run.graphql"),
+        "query Input { cart { lines { id } } fetchResult { status jsonBody } }",
+      );
+    });
+
+    function mockStageOutputs(fetchOutput: any, output: any) {
+      mockRunFunction.mockImplementation(async (fixture) => ({
+        result: {
+          output: fixture.export.endsWith("_fetch") ? fetchOutput : output,
+          instructions: null,
+          memoryUsage: null,
+          inputSize: 0,
+          outputSize: 0,
+          logs: "",
+        },
+        error: null,
+      }));
+    }
+
+    function recordFetch(fetchQueryPath?: string) {
+      return recordFixture(
+        fixturePath,
+        "/path/to/function-runner",
+        "/path/to/function.wasm",
+        path.join(tempDir, "run.graphql"),
+        DISCOUNT_SCHEMA_PATH,
+        fetchQueryPath,
+      );
+    }
+
+    it("should record the request of the fetch stage and the run output", async () => {
+      fs.copyFileSync(FETCH_FIXTURE_PATH, fixturePath);
+      mockStageOutputs(request, { operations: [] });
+
+      const result = await recordFetch(path.join(tempDir, "fetch.graphql"));
+
+      expect(result).toEqual({
+        output: { operations: [] },
+        fetchOutput: request,
+        updated: true,
+        error: null,
+      });
+      const { payload } = JSON.parse(fs.readFileSync(fixturePath, "utf-8"));
+      expect(payload.fetch.output).toEqual(request);
+      expect(payload.output).toEqual({ operations: [] });
+      expect(mockRunFunction).toHaveBeenCalledWith(
+        expect.objectContaining({
+          export: "cart_lines_discounts_generate_fetch",
+        }),
+        "/path/to/function-runner",
+        "/path/to/function.wasm",
+        path.join(tempDir, "fetch.graphql"),
+        DISCOUNT_SCHEMA_PATH,
+      );
+    });
+
+    it("should keep outputs that only differ in how values are written", async () => {
+      const fixture = JSON.parse(fs.readFileSync(FETCH_FIXTURE_PATH, "utf-8"));
+      const output = (amount: string) => ({
+        operations: [
+          {
+            orderDiscountsAdd: {
+              candidates: [
+                {
+                  targets: [{ orderSubtotal: { excludedCartLineIds: [] } }],
+                  value: { fixedAmount: { amount } },
+                },
+              ],
+              selectionStrategy: "FIRST",
+            },
+          },
+        ],
+      });
+      // "10" and "10.0" are the same Decimal
+      fixture.payload.output = output("10.0");
+      const content = `${JSON.stringify(fixture, null, 2)}\n`;
+      fs.writeFileSync(fixturePath, content);
+      mockStageOutputs(fixture.payload.fetch.output, output("10"));
+
+      const result = await recordFetch(path.join(tempDir, "fetch.graphql"));
+
+      expect(result.error).toBeNull();
+      expect(result.updated).toBe(false);
+      expect(fs.readFileSync(fixturePath, "utf-8")).toBe(content);
+    });
+
+    it("should need the input query of the fetch target", async () => {
+      fs.copyFileSync(FETCH_FIXTURE_PATH, fixturePath);
+
+      const result = await recordFetch();
+
+      expect(result.updated).toBe(false);
+      expect(result.error).toContain(
+        "the input query of cart.lines.discounts.generate.fetch is needed to record it",
+      );
+      expect(mockRunFunction).not.toHaveBeenCalled();
+    });
+  });
+});
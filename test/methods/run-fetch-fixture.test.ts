@@ -0,0 +1,264 @@
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import {
+  describe,
+  it,
+  expect,
+  vi,
+  beforeAll,
+  afterAll,
+  afterEach,
+} from "vitest";
+import { GraphQLSchema } from "graphql";
+
+import { loadSchema } from "../../src/methods/load-schema.ts";
+import { runFetchFixture } from "../../src/methods/run-fetch-fixture.ts";
+import { createMockHttpServer } from "../../src/methods/create-mock-http-server.ts";
+import { runFunction } from "../../src/methods/run-function.ts";
+import { FixtureData } from "../../src/methods/load-fixture.ts";
+import { FunctionInfo } from "../../src/methods/get-function-info.ts";
+
+vi.mock("../../src/methods/run-function.ts", () => ({
+  runFunction: vi.fn(),
+}));
+
+const FETCH_TARGET = "cart.lines.discounts.generate.fetch";
+const RUN_TARGET = "cart.lines.discounts.generate.run";
+
+const REQUEST = {
+  request: {
+    method: "POST",
+    url: "https://discounts.example.com/validate",
+    headers: [{ name: "Content-Type", value: "application/json" }],
+    body: '{"codes":["10OFF"]}',
+    jsonBody: null,
+    policy: { readTimeoutMs: 2000 },
+  },
+};
+
+const FIXTURE: FixtureData = {
+  export: "cart_lines_discounts_generate_run",
+  target: RUN_TARGET,
+  input: { cart: { lines: [] } },
+  expectedOutput: { operations: [] },
+  fetch: {
+    export: "cart_lines_discounts_generate_fetch",
+    target: FETCH_TARGET,
+    input: { enteredDiscountCodes: ["10OFF"] },
+    expectedOutput: REQUEST,
+    response: { status: 200, jsonBody: { valid: true } },
+  },
+};
+
+function runOutput(output: any) {
+  return {
+    result: {
+      output,
+      instructions: null,
+      memoryUsage: null,
+      inputSize: 0,
+      outputSize: 0,
+      logs: "",
+    },
+    error: null,
+  };
+}
+
+describe("runFetchFixture", () => {
+  const mockRunFunction = vi.mocked(runFunction);
+  let schema: GraphQLSchema;
+  let tempDir: string;
+  let functionInfo: FunctionInfo;
+
+  beforeAll(async () => {
+    schema = await loadSchema(
+      "./test-app/extensions/discount-function-rs/schema.graphql",
+    );
+    tempDir = fs.mkdtempSync(path.join(os.tmpdir(), "run-fetch-fixture-"));
+    fs.writeFileSync(
+      path.join(tempDir, "fetch.graphql"),
+      "query Input { enteredDiscountCodes }",
+    );
+    fs.writeFileSync(
+      path.join(tempDir, "run.graphql"),
+      "query Input { cart { lines { id } } fetchResult { status jsonBody } }",
+    );
+    functionInfo = {
+      schemaPath: "/path/to/schema.graphql",
+      functionRunnerPath: "/path/to/function-runner",
+      wasmPath: "/path/to/function.wasm",
+      targeting: {
+        [FETCH_TARGET]: { inputQueryPath: path.join(tempDir, "fetch.graphql") },
+        [RUN_TARGET]: { inputQueryPath: path.join(tempDir, "run.graphql") },
+      },
+    };
+  });
+
+  afterAll(() => {
+    fs.rmSync(tempDir, { recursive: true, force: true });
+  });
+
+  afterEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("should run the fetch export, then the run export with the response", async () => {
+    mockRunFunction
+      .mockResolvedValueOnce(runOutput(REQUEST))
+      .mockResolvedValueOnce(runOutput({ operations: [] }));
+
+    const result = await runFetchFixture({
+      schema,
+      fixture: FIXTURE,
+      functionInfo,
+    });
+
+    expect(result.error).toBeNull();
+    expect(result.requestErrors).toEqual([]);
+    expect(result.requestDifferences).toEqual([]);
+    expect(result.fetch?.output).toEqual(REQUEST);
+    expect(result.run?.output).toEqual({ operations: [] });
+    expect(mockRunFunction).toHaveBeenNthCalledWith(
+      1,
+      FIXTURE.fetch,
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      path.join(tempDir, "fetch.graphql"),
+      "/path/to/schema.graphql",
+    );
+    expect(mockRunFunction).toHaveBeenNthCalledWith(
+      2,
+      {
+        export: "cart_lines_discounts_generate_run",
+        target: RUN_TARGET,
+        input: {
+          cart: { lines: [] },
+          fetchResult: { status: 200, jsonBody: { valid: true } },
+        },
+        expectedOutput: { operations: [] },
+      },
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      path.join(tempDir, "run.graphql"),
+      "/path/to/schema.graphql",
+    );
+  });
+
+  it("should report an invalid request and differences from the expected request", async () => {
+    const request = {
+      request: { ...REQUEST.request, method: "PUT", policy: undefined },
+    };
+    mockRunFunction
+      .mockResolvedValueOnce(runOutput(request))
+      .mockResolvedValueOnce(runOutput({ operations: [] }));
+
+    const result = await runFetchFixture({
+      schema,
+      fixture: FIXTURE,
+      functionInfo,
+    });
+
+    expect(result.requestErrors.length).toBeGreaterThan(0);
+    expect(
+      result.requestDifferences.map((difference) => difference.path),
+    ).toEqual(["request.method", "request.policy"]);
+  });
+
+  it("should stop when the fetch export fails", async () => {
+    mockRunFunction.mockResolvedValueOnce({
+      result: null,
+      error: "function-runner exited with code 1",
+    });
+
+    const result = await runFetchFixture({
+      schema,
+      fixture: FIXTURE,
+      functionInfo,
+    });
+
+    expect(result.error).toBe(
+      "Fetch stage failed: function-runner exited with code 1",
+    );
+    expect(result.run).toBeNull();
+    expect(mockRunFunction).toHaveBeenCalledTimes(1);
+  });
+
+  it("should return an error for a fixture without a fetch stage", async () => {
+    const result = await runFetchFixture({
+      schema,
+      fixture: { ...FIXTURE, fetch: undefined },
+      functionInfo,
+    });
+
+    expect(result.error).toBe("Fixture has no fetch stage (payload.fetch)");
+    expect(mockRunFunction).not.toHaveBeenCalled();
+  });
+
+  it("should inject the response of the HTTP server instead of the canned one", async () => {
+    const httpServer = await createMockHttpServer([
+      {
+        method: "POST",
+        url: REQUEST.request.url,
+        body: { codes: ["10OFF"] },
+        response: { status: 200, jsonBody: { valid: false } },
+      },
+    ]);
+    mockRunFunction
+      .mockResolvedValueOnce(runOutput(REQUEST))
+      .mockResolvedValueOnce(runOutput({ operations: [] }));
+
+    try {
+      const result = await runFetchFixture({
+        schema,
+        fixture: FIXTURE,
+        functionInfo,
+        httpServer,
+      });
+
+      expect(result.error).toBeNull();
+      expect(result.timedOut).toBe(false);
+      expect(result.response?.body).toBe('{"valid":false}');
+      expect(result.runFixture?.input.fetchResult).toEqual({
+        status: 200,
+        jsonBody: { valid: false },
+      });
+      expect(httpServer.requests).toHaveLength(1);
+    } finally {
+      await httpServer.close();
+    }
+  });
+
+  it("should inject a null fetchResult when the request times out", async () => {
+    const httpServer = await createMockHttpServer([
+      {
+        method: "POST",
+        url: REQUEST.request.url,
+        response: { status: 200 },
+        delayMs: 1000,
+      },
+    ]);
+    const slowRequest = {
+      request: { ...REQUEST.request, policy: { readTimeoutMs: 50 } },
+    };
+    mockRunFunction
+      .mockResolvedValueOnce(runOutput(slowRequest))
+      .mockResolvedValueOnce(runOutput({ operations: [] }));
+
+    try {
+      const result = await runFetchFixture({
+        schema,
+        fixture: FIXTURE,
+        functionInfo,
+        httpServer,
+      });
+
+      expect(result.timedOut).toBe(true);
+      expect(result.response).toBeNull();
+      expect(result.runFixture?.input.fetchResult).toBeNull();
+    } finally {
+      await httpServer.close();
+    }
+  });
+});
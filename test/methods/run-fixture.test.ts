@@ -0,0 +1,295 @@
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import {
+  describe,
+  it,
+  expect,
+  vi,
+  beforeAll,
+  afterAll,
+  afterEach,
+} from "vitest";
+import { GraphQLSchema } from "graphql";
+
+import { loadSchema } from "../../src/methods/load-schema.ts";
+import { loadFixture } from "../../src/methods/load-fixture.ts";
+import { fixtureFailures, runFixture } from "../../src/methods/run-fixture.ts";
+import { runFunction } from "../../src/methods/run-function.ts";
+import { FunctionInfo } from "../../src/methods/get-function-info.ts";
+
+vi.mock("../../src/methods/run-function.ts", () => ({
+  runFunction: vi.fn(),
+}));
+
+const FUNCTION_DIR = "./test-app/extensions/discount-function-rs";
+const FIXTURES_DIR = path.join(FUNCTION_DIR, "tests/fixtures");
+const VALID_FIXTURE = path.join(FIXTURES_DIR, "cart-lines-valid-fixture.json");
+const FETCH_FIXTURE = path.join(FIXTURES_DIR, "cart-lines-fetch-fixture.json");
+
+const RUN_TARGET = "cart.lines.discounts.generate.run";
+const FETCH_TARGET = "cart.lines.discounts.generate.fetch";
+
+function runOutput(output: any) {
+  return {
+    result: {
+      output,
+      instructions: null,
+      memoryUsage: null,
+      inputSize: 0,
+      outputSize: 0,
+      logs: "",
+    },
+    error: null,
+  };
+}
+
+describe("runFixture", () => {
+  const mockRunFunction = vi.mocked(runFunction);
+  let schema: GraphQLSchema;
+  let tempDir: string;
+  let functionInfo: FunctionInfo;
+
+  beforeAll(async () => {
+    schema = await loadSchema(path.join(FUNCTION_DIR, "schema.graphql"));
+    tempDir = fs.mkdtempSync(path.join(os.tmpdir(), "run-fixture-"));
+    functionInfo = {
+      schemaPath: "/path/to/schema.graphql",
+      functionRunnerPath: "/path/to/function-runner",
+      wasmPath: "/path/to/function.wasm",
+      targeting: {
+        [RUN_TARGET]: {
+          inputQueryPath: path.join(
+            FUNCTION_DIR,
+            "src/cart_lines_discounts_generate_run.graphql",
+          ),
+        },
+        [FETCH_TARGET]: {
+          inputQueryPath: path.join(
+            FUNCTION_DIR,
+            "src/cart_lines_discounts_generate_fetch.graphql",
+          ),
+        },
+      },
+    };
+  });
+
+  afterAll(() => {
+    fs.rmSync(tempDir, { recursive: true, force: true });
+  });
+
+  afterEach(() => {
+    vi.clearAllMocks();
+  });
+
+  function writeFixture(name: string, payload: Record<string, any>) {
+    const fixturePath = path.join(tempDir, name);
+    fs.writeFileSync(fixturePath, JSON.stringify({ payload }));
+    return fixturePath;
+  }
+
+  it("should pass a fixture whose output matches the expected output", async () => {
+    const fixture = await loadFixture(VALID_FIXTURE);
+    mockRunFunction.mockResolvedValueOnce(runOutput(fixture.expectedOutput));
+
+    const result = await runFixture({
+      schema,
+      functionInfo,
+      fixturePath: VALID_FIXTURE,
+    });
+
+    expect(result.passed).toBe(true);
+    expect(result.error).toBeNull();
+    expect(result.stages).toHaveLength(1);
+    expect(result.stages[0].target).toBe(RUN_TARGET);
+    expect(result.stages[0].run?.output).toEqual(fixture.expectedOutput);
+    expect(fixtureFailures(result)).toEqual([]);
+    expect(mockRunFunction).toHaveBeenCalledWith(
+      fixture,
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      functionInfo.targeting[RUN_TARGET].inputQueryPath,
+      "/path/to/schema.graphql",
+    );
+  });
+
+  it("should report differences from the expected output", async () => {
+    mockRunFunction.mockResolvedValueOnce(runOutput({ operations: [] }));
+
+    const result = await runFixture({
+      schema,
+      functionInfo,
+      fixturePath: VALID_FIXTURE,
+    });
+
+    expect(result.passed).toBe(false);
+    expect(result.stages[0].differences.length).toBeGreaterThan(0);
+    expect(fixtureFailures(result)[0]).toMatch(
+      /^cart\.lines\.discounts\.generate\.run: output: operations\[0\]: Missing item/,
+    );
+  });
+
+  it("should not run a fixture with invalid test assets", async () => {
+    const fixture = await loadFixture(VALID_FIXTURE);
+    const fixturePath = writeFixture("invalid.json", {
+      export: fixture.export,
+      target: fixture.target,
+      input: {
+        ...fixture.input,
+        cart: { lines: [{ id: "gid://shopify/CartLine/0" }] },
+      },
+      output: fixture.expectedOutput,
+    });
+
+    const result = await runFixture({ schema, functionInfo, fixturePath });
+
+    expect(result.passed).toBe(false);
+    expect(result.stages[0].run).toBeNull();
+    expect(mockRunFunction).not.toHaveBeenCalled();
+    expect(fixtureFailures(result)).toContain(
+      "cart.lines.discounts.generate.run: input fixture: Missing expected fixture data for `cost` at cart.lines[0].cost",
+    );
+  });
+
+  it("should run the fetch stage, then the run stage with the canned response", async () => {
+    const fixture = await loadFixture(FETCH_FIXTURE);
+    mockRunFunction
+      .mockResolvedValueOnce(runOutput(fixture.fetch!.expectedOutput))
+      .mockResolvedValueOnce(runOutput(fixture.expectedOutput));
+
+    const result = await runFixture({
+      schema,
+      functionInfo,
+      fixturePath: FETCH_FIXTURE,
+    });
+
+    expect(result.passed).toBe(true);
+    expect(result.stages.map((stage) => stage.target)).toEqual([
+      FETCH_TARGET,
+      RUN_TARGET,
+    ]);
+    expect(mockRunFunction.mock.calls[1][0].input.fetchResult).toEqual(
+      fixture.fetch!.response,
+    );
+  });
+
+  describe("with [extensions.input.variables]", () => {
+    const inputVariablesDir = "./test/fixtures/input-variables";
+    const inputVariablesFixture = path.join(inputVariablesDir, "fixture.json");
+    const inputVariablesInfo = (): FunctionInfo => ({
+      ...functionInfo,
+      targeting: {
+        [RUN_TARGET]: {
+          inputQueryPath: path.join(inputVariablesDir, "query.graphql"),
+        },
+      },
+      inputVariables: {
+        namespace: "$app:tagged-discount",
+        key: "input-variables",
+      },
+    });
+
+    it("should take the variables from the fixture's metafield", async () => {
+      mockRunFunction.mockResolvedValueOnce(runOutput({ operations: [] }));
+
+      const result = await runFixture({
+        schema,
+        functionInfo: inputVariablesInfo(),
+        fixturePath: inputVariablesFixture,
+      });
+
+      expect(fixtureFailures(result)).toEqual([]);
+      expect(mockRunFunction.mock.calls[0][0].variables).toEqual({
+        tags: ["VIP", "Wholesale"],
+      });
+    });
+
+    it("should validate the fixture against the metafield's variables", async () => {
+      const fixture = await loadFixture(inputVariablesFixture);
+      const fixturePath = writeFixture("input-variables.json", {
+        export: fixture.export,
+        target: fixture.target,
+        input: {
+          ...fixture.input,
+          discount: { inputVariables: { jsonValue: { tags: ["VIP"] } } },
+        },
+        output: fixture.expectedOutput,
+      });
+
+      const result = await runFixture({
+        schema,
+        functionInfo: inputVariablesInfo(),
+        fixturePath,
+      });
+
+      expect(mockRunFunction).not.toHaveBeenCalled();
+      expect(fixtureFailures(result)).toEqual([
+        "cart.lines.discounts.generate.run: input fixture: Expected a `HasTagResponse` for each value of the `tags` argument (1), but got 2 at cart.buyerIdentity.customer.hasTags",
+      ]);
+    });
+
+    it("should not resolve variables without the table", async () => {
+      const { inputVariables, ...withoutInputVariables } =
+        inputVariablesInfo();
+
+      const result = await runFixture({
+        schema,
+        functionInfo: withoutInputVariables,
+        fixturePath: inputVariablesFixture,
+      });
+
+      expect(inputVariables).toBeDefined();
+      expect(mockRunFunction).not.toHaveBeenCalled();
+      expect(fixtureFailures(result)).toContain(
+        'cart.lines.discounts.generate.run: input query: Variable "$tags" of required type "[String!]!" was not provided.',
+      );
+    });
+  });
+
+  it("should report a run that fails", async () => {
+    mockRunFunction.mockResolvedValueOnce({
+      result: null,
+      error: "function-runner exited with code 1",
+    });
+
+    const result = await runFixture({
+      schema,
+      functionInfo,
+      fixturePath: VALID_FIXTURE,
+    });
+
+    expect(result.passed).toBe(false);
+    expect(fixtureFailures(result)).toEqual([
+      "cart.lines.discounts.generate.run: Run failed: function-runner exited with code 1",
+    ]);
+  });
+
+  it("should report a target the function does not have", async () => {
+    const fixturePath = writeFixture("unknown-target.json", {
+      export: "run",
+      target: "purchase.unknown.run",
+      input: {},
+      output: {},
+    });
+
+    const result = await runFixture({ schema, functionInfo, fixturePath });
+
+    expect(result.passed).toBe(false);
+    expect(result.stages[0].error).toBe(
+      "'purchase.unknown.run' is not a target of this function",
+    );
+  });
+
+  it("should report a fixture that cannot be loaded", async () => {
+    const result = await runFixture({
+      schema,
+      functionInfo,
+      fixturePath: path.join(tempDir, "missing.json"),
+    });
+
+    expect(result.passed).toBe(false);
+    expect(result.stages).toEqual([]);
+    expect(fixtureFailures(result)).toEqual([result.error]);
+  });
+});
@@ -0,0 +1,179 @@
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import {
+  describe,
+  it,
+  expect,
+  vi,
+  beforeAll,
+  afterAll,
+  beforeEach,
+  afterEach,
+} from "vitest";
+
+import { buildFunction } from "../../src/methods/build-function.ts";
+import { getFunctionInfo } from "../../src/methods/get-function-info.ts";
+import { runFunction } from "../../src/methods/run-function.ts";
+import { runFunctionTests } from "../../src/methods/run-function-tests.ts";
+
+vi.mock("../../src/methods/build-function.ts", () => ({
+  buildFunction: vi.fn(),
+}));
+vi.mock("../../src/methods/get-function-info.ts", () => ({
+  getFunctionInfo: vi.fn(),
+}));
+vi.mock("../../src/methods/run-function.ts", () => ({
+  runFunction: vi.fn(),
+}));
+
+const RUN_TARGET = "cart.lines.discounts.generate.run";
+
+function runOutput(output: any) {
+  return {
+    result: {
+      output,
+      instructions: null,
+      memoryUsage: null,
+      inputSize: 0,
+      outputSize: 0,
+      logs: "",
+    },
+    error: null,
+  };
+}
+
+describe("runFunctionTests", () => {
+  const mockBuildFunction = vi.mocked(buildFunction);
+  const mockGetFunctionInfo = vi.mocked(getFunctionInfo);
+  const mockRunFunction = vi.mocked(runFunction);
+  let functionDir: string;
+
+  beforeAll(() => {
+    functionDir = fs.mkdtempSync(path.join(os.tmpdir(), "run-function-tests-"));
+    fs.mkdirSync(path.join(functionDir, "tests/fixtures"), {
+      recursive: true,
+    });
+    fs.writeFileSync(
+      path.join(functionDir, "run.graphql"),
+      "query Input { cart { lines { id } } }",
+    );
+    for (const [name, lines] of [
+      ["empty-cart.json", []],
+      ["one-line.json", [{ id: "gid://shopify/CartLine/1" }]],
+    ] as const) {
+      fs.writeFileSync(
+        path.join(functionDir, "tests/fixtures", name),
+        JSON.stringify({
+          payload: {
+            export: "run",
+            target: RUN_TARGET,
+            input: { cart: { lines } },
+            output: { operations: [] },
+          },
+        }),
+      );
+    }
+  });
+
+  afterAll(() => {
+    fs.rmSync(functionDir, { recursive: true, force: true });
+  });
+
+  beforeEach(() => {
+    mockGetFunctionInfo.mockResolvedValue({
+      schemaPath: path.resolve(
+        "./test-app/extensions/discount-function-rs/schema.graphql",
+      ),
+      functionRunnerPath: "/path/to/function-runner",
+      wasmPath: "/path/to/function.wasm",
+      targeting: {
+        [RUN_TARGET]: { inputQueryPath: path.join(functionDir, "run.graphql") },
+      },
+    });
+    mockBuildFunction.mockResolvedValue({
+      success: true,
+      output: "",
+      error: null,
+    });
+  });
+
+  afterEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("should build the function and run every fixture", async () => {
+    mockRunFunction.mockResolvedValue(runOutput({ operations: [] }));
+    const onFixtureResult = vi.fn();
+
+    const result = await runFunctionTests({
+      functionDir,
+      buildOptions: { direct: true },
+      onFixtureResult,
+    });
+
+    expect(result.error).toBeNull();
+    expect(result.passed).toBe(true);
+    expect(mockBuildFunction).toHaveBeenCalledWith(functionDir, {
+      direct: true,
+    });
+    expect(
+      result.results.map((fixture) => path.basename(fixture.fixturePath)),
+    ).toEqual(["empty-cart.json", "one-line.json"]);
+    expect(onFixtureResult).toHaveBeenCalledTimes(2);
+  });
+
+  it("should fail if any fixture fails", async () => {
+    mockRunFunction
+      .mockResolvedValueOnce(runOutput({ operations: [] }))
+      .mockResolvedValueOnce(runOutput({ operations: [{}] }));
+
+    const result = await runFunctionTests({ functionDir });
+
+    expect(result.error).toBeNull();
+    expect(result.passed).toBe(false);
+    expect(result.results.map((fixture) => fixture.passed)).toEqual([
+      true,
+      false,
+    ]);
+  });
+
+  it("should not run the fixtures when the build fails", async () => {
+    mockBuildFunction.mockResolvedValue({
+      success: false,
+      output: null,
+      error: "Build command failed with exit code 101: error[E0425]",
+    });
+
+    const result = await runFunctionTests({ functionDir });
+
+    expect(result.passed).toBe(false);
+    expect(result.error).toBe(
+      "Build command failed with exit code 101: error[E0425]",
+    );
+    expect(mockRunFunction).not.toHaveBeenCalled();
+  });
+
+  it("should fail when the fixtures directory has no fixtures", async () => {
+    const result = await runFunctionTests({
+      functionDir,
+      fixturesDir: ".",
+    });
+
+    expect(result.passed).toBe(false);
+    expect(result.error).toBe(`No fixtures found in ${functionDir}`);
+  });
+
+  it("should fail when the function info cannot be loaded", async () => {
+    mockGetFunctionInfo.mockRejectedValue(
+      new Error("Command not found: shopify"),
+    );
+
+    const result = await runFunctionTests({ functionDir });
+
+    expect(result.passed).toBe(false);
+    expect(result.build).toBeNull();
+    expect(result.error).toBe("Command not found: shopify");
+  });
+});
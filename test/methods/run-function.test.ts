@@ -1,11 +1,12 @@
 import { EventEmitter } from "events";
+import fs from "fs";
 import { Writable } from "stream";
 import { spawn } from "child_process";
 
 import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
 
 import { runFunction } from "../../src/methods/run-function.ts";
-import { FixtureData } from "../../src/methods/load-fixture.ts";
+import { FixtureData, loadFixture } from "../../src/methods/load-fixture.ts";
 
 vi.mock("child_process", () => ({
   spawn: vi.fn(),
@@ -88,7 +89,14 @@ describe("runFunction", () => {
 
     expect(result).toBeDefined();
     expect(result.error).toBeNull();
-    expect(result.result).toEqual(expectedOutput);
+    expect(result.result).toEqual({
+      ...expectedOutput,
+      instructions: null,
+      memoryUsage: null,
+      inputSize: JSON.stringify(fixture.input).length,
+      outputSize: JSON.stringify(expectedOutput.output).length,
+      logs: "",
+    });
 
     // Verify spawn was called with correct arguments
     expect(mockSpawn).toHaveBeenCalledWith(
@@ -257,6 +265,59 @@ describe("runFunction", () => {
       output: {
         operations: [],
       },
+      instructions: null,
+      memoryUsage: null,
+      inputSize: JSON.stringify(fixture.input).length,
+      outputSize: '{"operations":[]}'.length,
+      logs: "",
+    });
+  });
+
+  it("should report resource usage and logs from function-runner", async () => {
+    const fixture: FixtureData = {
+      export: "cart_lines_discounts_generate_run",
+      input: { cart: { lines: [{ id: "gid://shopify/CartLine/0" }] } },
+      expectedOutput: {},
+      target: "cart.lines.discounts.generate.run",
+    };
+
+    const resultPromise = runFunction(
+      fixture,
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      "/path/to/query.graphql",
+      "/path/to/schema.graphql",
+    );
+
+    setImmediate(() => {
+      mockStdout.emit(
+        "data",
+        Buffer.from(
+          JSON.stringify({
+            name: "function.wasm",
+            size: 143,
+            memory_usage: 1088,
+            instructions: 50920,
+            logs: "Applying discount\n",
+            input: fixture.input,
+            output: { operations: [] },
+            success: true,
+          }),
+        ),
+      );
+      mockProcess.emit("close", 0);
+    });
+
+    const result = await resultPromise;
+
+    expect(result.error).toBeNull();
+    expect(result.result).toEqual({
+      output: { operations: [] },
+      instructions: 50920,
+      memoryUsage: 1088,
+      inputSize: JSON.stringify(fixture.input).length,
+      outputSize: '{"operations":[]}'.length,
+      logs: "Applying discount\n",
     });
   });
 
@@ -303,4 +364,42 @@ describe("runFunction", () => {
     expect(result.error).toContain("missing 'output' field");
     expect(result.result).toBeNull();
   });
+
+  it("should inline the fixture's variables into the input query", async () => {
+    const fixture = await loadFixture(
+      "./test/fixtures/variables/tagged-cart-fixture.json",
+    );
+    let queryPath = "";
+    let query = "";
+    mockSpawn.mockImplementation(((_command: string, args: string[]) => {
+      queryPath = args[args.indexOf("--query-path") + 1];
+      query = fs.readFileSync(queryPath, "utf-8");
+      setImmediate(() => {
+        mockStdout.emit(
+          "data",
+          Buffer.from(JSON.stringify({ output: { operations: [] } })),
+        );
+        mockProcess.emit("close", 0);
+      });
+      return mockProcess;
+    }) as any);
+
+    const result = await runFunction(
+      fixture,
+      "/path/to/function-runner",
+      "/path/to/function.wasm",
+      "./test/fixtures/variables/tagged-cart-query.graphql",
+      "./test-app/extensions/discount-function-rs/schema.graphql",
+    );
+
+    expect(result.error).toBeNull();
+    expect(query).toContain("query Input {");
+    expect(query).toContain('hasAnyTag(tags: ["VIP", "Wholesale"])');
+    expect(query).toContain(
+      'inCollections(ids: ["gid://shopify/Collection/1"])',
+    );
+    expect(query).toContain('metafield(key: "function-configuration")');
+    expect(query).not.toContain("$");
+    expect(fs.existsSync(queryPath)).toBe(false);
+  });
 });
@@ -5,6 +5,10 @@ import { validateFixtureInput } from "../../src/methods/validate-fixture-input.t
 import { loadSchema } from "../../src/methods/load-schema.ts";
 import { loadInputQuery } from "../../src/methods/load-input-query.ts";
 import { loadFixture } from "../../src/methods/load-fixture.ts";
+import {
+  loadMetafieldSchemas,
+  MetafieldSchemaRegistry,
+} from "../../src/methods/load-metafield-schemas.ts";
 
 describe("validateFixtureInput", () => {
   let schema: GraphQLSchema;
@@ -2215,4 +2219,322 @@ describe("validateFixtureInput", () => {
       });
     });
   });
+
+  describe("Metafield Schemas", () => {
+    let discountSchema: GraphQLSchema;
+    let metafieldSchemas: MetafieldSchemaRegistry;
+
+    beforeAll(async () => {
+      discountSchema = await loadSchema(
+        "./test-app/extensions/discount-function-rs/schema.graphql",
+      );
+      metafieldSchemas = await loadMetafieldSchemas(
+        "./test/fixtures/metafields/metafield-schemas.json",
+      );
+    });
+
+    const queryAST = parse(`
+      query {
+        discount {
+          metafield(key: "function-configuration") {
+            jsonValue
+          }
+        }
+        shop {
+          maxDiscounts: metafield(namespace: "settings", key: "max-discounts") {
+            type
+            value
+          }
+          excludedTags: metafield(namespace: "settings", key: "excluded-tags") {
+            jsonValue
+          }
+        }
+      }
+    `);
+
+    function fixtureInput({
+      configuration = {
+        order: { percentage: 15, selectionStrategy: "MAXIMUM" },
+      },
+      maxDiscounts = { type: "number_integer", value: "3" },
+      excludedTags = { jsonValue: ["sale", "clearance"] },
+    }: {
+      configuration?: any;
+      maxDiscounts?: any;
+      excludedTags?: any;
+    } = {}) {
+      return {
+        discount: { metafield: { jsonValue: configuration } },
+        shop: { maxDiscounts, excludedTags },
+      };
+    }
+
+    it("accepts metafields matching their schemas", () => {
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixtureInput(),
+        { metafieldSchemas },
+      );
+
+      expect(result.errors).toHaveLength(0);
+    });
+
+    it("treats metafield values as opaque without metafield schemas", () => {
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixtureInput({
+          configuration: { order: { percentage: 150 } },
+          maxDiscounts: { type: "number_integer", value: "three" },
+        }),
+      );
+
+      expect(result.errors).toHaveLength(0);
+    });
+
+    it("checks jsonValue against the JSON Schema", () => {
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixtureInput({
+          configuration: {
+            order: { percentage: 150, selectionStrategy: "LAST" },
+            shipping: {},
+          },
+        }),
+        { metafieldSchemas },
+      );
+
+      expect(result.errors).toStrictEqual([
+        {
+          message:
+            "Invalid `function-configuration` metafield: unexpected property `shipping`",
+          path: ["discount", "metafield", "jsonValue"],
+        },
+        {
+          message:
+            "Invalid `function-configuration` metafield at `order.percentage`: 150 should be <= 100",
+          path: ["discount", "metafield", "jsonValue"],
+        },
+        {
+          message:
+            'Invalid `function-configuration` metafield at `order.selectionStrategy`: expected one of "ALL", "FIRST", "MAXIMUM", but got "LAST"',
+          path: ["discount", "metafield", "jsonValue"],
+        },
+      ]);
+    });
+
+    it("checks value and type against the metafield type", () => {
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixtureInput({ maxDiscounts: { type: "json", value: "2.5" } }),
+        { metafieldSchemas },
+      );
+
+      expect(result.errors).toStrictEqual([
+        {
+          message:
+            'Invalid `settings.max-discounts` metafield: expected type `number_integer`, but got "json"',
+          path: ["shop", "maxDiscounts", "type"],
+        },
+        {
+          message:
+            "Invalid `settings.max-discounts` metafield: expected an integer, but got 2.5",
+          path: ["shop", "maxDiscounts", "value"],
+        },
+      ]);
+    });
+
+    it("checks each element of a list metafield", () => {
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixtureInput({ excludedTags: { jsonValue: ["sale", 42] } }),
+        { metafieldSchemas },
+      );
+
+      expect(result.errors).toStrictEqual([
+        {
+          message:
+            "Invalid `settings.excluded-tags` metafield at `1`: expected a single line of text, but got 42",
+          path: ["shop", "excludedTags", "jsonValue"],
+        },
+      ]);
+    });
+
+    it("only applies schemas to metafields of their owner type", () => {
+      const result = validateFixtureInput(
+        parse(`
+          query {
+            discount {
+              metafield(namespace: "settings", key: "max-discounts") {
+                value
+              }
+            }
+          }
+        `),
+        discountSchema,
+        { discount: { metafield: { value: "three" } } },
+        { metafieldSchemas },
+      );
+
+      expect(result.errors).toHaveLength(0);
+    });
+
+    it("skips null metafields", () => {
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        {
+          discount: { metafield: null },
+          shop: { maxDiscounts: null, excludedTags: null },
+        },
+        { metafieldSchemas },
+      );
+
+      expect(result.errors).toHaveLength(0);
+    });
+  });
+
+  describe("Variables", () => {
+    let discountSchema: GraphQLSchema;
+    let metafieldSchemas: MetafieldSchemaRegistry;
+
+    beforeAll(async () => {
+      discountSchema = await loadSchema(
+        "./test-app/extensions/discount-function-rs/schema.graphql",
+      );
+      metafieldSchemas = await loadMetafieldSchemas(
+        "./test/fixtures/metafields/metafield-schemas.json",
+      );
+    });
+
+    const queryPath = "./test/fixtures/variables/tagged-cart-query.graphql";
+    const fixturePath = "./test/fixtures/variables/tagged-cart-fixture.json";
+
+    it("accepts responses matching the variable values", async () => {
+      const queryAST = await loadInputQuery(queryPath);
+      const fixture = await loadFixture(fixturePath);
+
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixture.input,
+        { metafieldSchemas, variables: fixture.variables },
+      );
+
+      expect(result.errors).toHaveLength(0);
+    });
+
+    it("reports responses that do not answer each requested value", async () => {
+      const queryAST = await loadInputQuery(queryPath);
+      const fixture = await loadFixture(fixturePath);
+
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixture.input,
+        {
+          variables: {
+            ...fixture.variables,
+            tags: ["VIP", "Gold"],
+            collectionIds: [
+              "gid://shopify/Collection/1",
+              "gid://shopify/Collection/2",
+            ],
+          },
+        },
+      );
+
+      expect(result.errors).toStrictEqual([
+        {
+          message:
+            'Expected "Gold" from the `tags` argument, but got "Wholesale"',
+          path: ["cart", "buyerIdentity", "customer", "hasTags", 1, "tag"],
+        },
+        {
+          message:
+            "Expected a `CollectionMembership` for each value of the `ids` argument (2), but got 1",
+          path: [
+            "cart",
+            "lines",
+            0,
+            "merchandise",
+            "product",
+            "inCollections",
+          ],
+        },
+      ]);
+    });
+
+    it("checks literal arguments", () => {
+      const result = validateFixtureInput(
+        parse(`
+          query {
+            cart {
+              buyerIdentity {
+                customer {
+                  vip: hasTags(tags: "VIP") {
+                    name: tag
+                    hasTag
+                  }
+                }
+              }
+            }
+          }
+        `),
+        discountSchema,
+        {
+          cart: {
+            buyerIdentity: {
+              customer: { vip: [{ name: "Gold", hasTag: true }] },
+            },
+          },
+        },
+      );
+
+      expect(result.errors).toStrictEqual([
+        {
+          message: 'Expected "VIP" from the `tags` argument, but got "Gold"',
+          path: ["cart", "buyerIdentity", "customer", "vip", 0, "name"],
+        },
+      ]);
+    });
+
+    it("skips arguments whose variables have no value", async () => {
+      const queryAST = await loadInputQuery(queryPath);
+      const fixture = await loadFixture(fixturePath);
+
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixture.input,
+      );
+
+      expect(result.errors).toHaveLength(0);
+    });
+
+    it("finds metafield schemas by variable namespace and key", async () => {
+      const queryAST = await loadInputQuery(queryPath);
+      const fixture = await loadFixture(fixturePath);
+      fixture.input.discount.metafield.jsonValue.order.percentage = 150;
+
+      const result = validateFixtureInput(
+        queryAST,
+        discountSchema,
+        fixture.input,
+        { metafieldSchemas, variables: fixture.variables },
+      );
+
+      expect(result.errors).toStrictEqual([
+        {
+          message:
+            "Invalid `function-configuration` metafield at `order.percentage`: 150 should be <= 100",
+          path: ["discount", "metafield", "jsonValue"],
+        },
+      ]);
+    });
+  });
 });
@@ -2,7 +2,10 @@ import { describe, it, expect, beforeAll } from "vitest";
 import { GraphQLSchema } from "graphql";
 
 import { validateFixtureOutput } from "../../src/methods/validate-fixture-output.ts";
-import { loadSchema } from "../../src/wasm-testing-helpers.ts";
+import {
+  loadFixture,
+  loadSchema,
+} from "../../src/wasm-testing-helpers.ts";
 
 describe("validateFixtureOutput", () => {
   let schema: GraphQLSchema;
@@ -172,4 +175,131 @@ describe("validateFixtureOutput", () => {
       );
     });
   });
+
+  describe("@oneOf Input Objects", () => {
+    let discountSchema: GraphQLSchema;
+    let discountOutput: Record<string, any>;
+
+    beforeAll(async () => {
+      const functionDir = "./test-app/extensions/discount-function-rs";
+      discountSchema = await loadSchema(`${functionDir}/schema.graphql`);
+      discountOutput = (
+        await loadFixture(
+          `${functionDir}/tests/fixtures/cart-lines-valid-fixture.json`,
+        )
+      ).expectedOutput;
+    });
+
+    it("should accept operations that set exactly one field", async () => {
+      const result = await validateFixtureOutput(
+        discountOutput,
+        discountSchema,
+        "cartLinesDiscountsGenerateRun",
+        "result",
+      );
+
+      expect(result.errors).toHaveLength(0);
+    });
+
+    it("should report operations that set more than one field", async () => {
+      const [orderOperation, productOperation] = discountOutput.operations;
+      const output = {
+        operations: [
+          orderOperation,
+          { ...orderOperation, ...productOperation },
+        ],
+      };
+
+      const result = await validateFixtureOutput(
+        output,
+        discountSchema,
+        "cartLinesDiscountsGenerateRun",
+        "result",
+      );
+
+      expect(result.errors).toEqual([
+        {
+          message:
+            'Exactly one key must be specified for OneOf type "CartOperation". At "operations.1"',
+        },
+      ]);
+    });
+
+    it("should report operations that set no fields", async () => {
+      const output = {
+        operations: [discountOutput.operations[0], {}],
+      };
+
+      const result = await validateFixtureOutput(
+        output,
+        discountSchema,
+        "cartLinesDiscountsGenerateRun",
+        "result",
+      );
+
+      expect(result.errors).toEqual([
+        {
+          message:
+            'Exactly one key must be specified for OneOf type "CartOperation". At "operations.1"',
+        },
+      ]);
+    });
+
+    it("should report a single field set to null", async () => {
+      const output = {
+        operations: [{ productDiscountsAdd: null }],
+      };
+
+      const result = await validateFixtureOutput(
+        output,
+        discountSchema,
+        "cartLinesDiscountsGenerateRun",
+        "result",
+      );
+
+      expect(result.errors).toEqual([
+        {
+          message:
+            'Field "productDiscountsAdd" must be non-null. At "operations.0.productDiscountsAdd"',
+        },
+      ]);
+    });
+
+    it("should check nested @oneOf input objects", async () => {
+      const [orderOperation] = discountOutput.operations;
+      const [candidate] = orderOperation.orderDiscountsAdd.candidates;
+      const output = {
+        operations: [
+          {
+            orderDiscountsAdd: {
+              ...orderOperation.orderDiscountsAdd,
+              candidates: [
+                {
+                  ...candidate,
+                  value: {
+                    percentage: { value: "10.0" },
+                    fixedAmount: { amount: "5.0" },
+                  },
+                },
+              ],
+            },
+          },
+        ],
+      };
+
+      const result = await validateFixtureOutput(
+        output,
+        discountSchema,
+        "cartLinesDiscountsGenerateRun",
+        "result",
+      );
+
+      expect(result.errors).toEqual([
+        {
+          message:
+            'Exactly one key must be specified for OneOf type "OrderDiscountCandidateValue". At "operations.0.orderDiscountsAdd.candidates.0.value"',
+        },
+      ]);
+    });
+  });
 });
@@ -1,7 +1,9 @@
 import { describe, it, expect } from "vitest";
+import { parse } from "graphql";
 
 import {
   validateInputQuery,
+  validateInputQueryVariables,
   loadInputQuery,
   loadSchema,
 } from "../../src/wasm-testing-helpers.ts";
@@ -59,4 +61,134 @@ describe("validateInputQuery", () => {
     expect(errors[0]).toHaveProperty("message");
     expect(errors[0].message).toContain("Failed to validate query");
   });
+
+  describe("@restrictTarget", () => {
+    const discountSchemaPath =
+      "./test-app/extensions/discount-function-rs/schema.graphql";
+
+    it("should report fields restricted to other targets", async () => {
+      const schema = await loadSchema(discountSchemaPath);
+      const queryAST = parse(`
+        query Input {
+          enteredDiscountCodes
+          discount {
+            discountClasses
+          }
+        }
+      `);
+
+      const errors = validateInputQuery(
+        queryAST,
+        schema,
+        "cart.lines.discounts.generate.run",
+      );
+
+      expect(errors.map((error) => error.message)).toEqual([
+        'Cannot query field "enteredDiscountCodes" on type "Input" for target "cart.lines.discounts.generate.run". ' +
+          'It is restricted to targets: "cart.lines.discounts.generate.fetch", "cart.delivery-options.discounts.generate.fetch".',
+      ]);
+    });
+
+    it("should allow restricted fields for their targets", async () => {
+      const schema = await loadSchema(discountSchemaPath);
+      const queryAST = parse(`
+        query Input {
+          enteredDiscountCodes
+        }
+      `);
+
+      const errors = validateInputQuery(
+        queryAST,
+        schema,
+        "cart.lines.discounts.generate.fetch",
+      );
+
+      expect(errors).toEqual([]);
+    });
+
+    it("should report restricted fields selected in fragments", async () => {
+      const schema = await loadSchema(discountSchemaPath);
+      const queryAST = parse(`
+        query Input {
+          ...Codes
+        }
+
+        fragment Codes on Input {
+          triggeringDiscountCode
+        }
+      `);
+
+      const errors = validateInputQuery(
+        queryAST,
+        schema,
+        "cart.lines.discounts.generate.fetch",
+      );
+
+      expect(errors).toHaveLength(1);
+      expect(errors[0].message).toContain(
+        'Cannot query field "triggeringDiscountCode" on type "Input" for target "cart.lines.discounts.generate.fetch"',
+      );
+    });
+
+    it("should not check targets when no target is provided", async () => {
+      const schema = await loadSchema(discountSchemaPath);
+      const queryAST = parse(`
+        query Input {
+          enteredDiscountCodes
+        }
+      `);
+
+      const errors = validateInputQuery(queryAST, schema);
+
+      expect(errors).toEqual([]);
+    });
+  });
+
+  describe("validateInputQueryVariables", () => {
+    const discountSchemaPath =
+      "./test-app/extensions/discount-function-rs/schema.graphql";
+    const queryPath = "./test/fixtures/variables/tagged-cart-query.graphql";
+
+    it("should accept values for the declared variables", async () => {
+      const schema = await loadSchema(discountSchemaPath);
+      const queryAST = await loadInputQuery(queryPath);
+
+      const errors = validateInputQueryVariables(queryAST, schema, {
+        tags: ["VIP"],
+        configurationKey: "function-configuration",
+      });
+
+      expect(errors).toEqual([]);
+    });
+
+    it("should report missing, invalid and undeclared variables", async () => {
+      const schema = await loadSchema(discountSchemaPath);
+      const queryAST = await loadInputQuery(queryPath);
+
+      const errors = validateInputQueryVariables(queryAST, schema, {
+        tags: ["VIP", 1],
+        collectionIds: null,
+        minimumSubtotal: "100.0",
+      });
+
+      expect(errors.map((error) => error.message)).toEqual([
+        'Variable "$tags" got invalid value 1 at "tags[1]"; String cannot represent a non string value: 1',
+        'Variable "$configurationKey" of required type "String!" was not provided.',
+        'Variable "$minimumSubtotal" is not declared by the input query.',
+      ]);
+    });
+
+    it("should accept a query without variables and no values", async () => {
+      const schema = await loadSchema(discountSchemaPath);
+      const queryAST = parse(`
+        query Input {
+          discount {
+            discountClasses
+          }
+        }
+      `);
+
+      expect(validateInputQueryVariables(queryAST, schema, {})).toEqual([]);
+    });
+  });
 });
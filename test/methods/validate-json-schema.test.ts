@@ -0,0 +1,88 @@
+import { describe, it, expect } from "vitest";
+
+import { validateJsonSchema } from "../../src/utils/validate-json-schema.ts";
+
+describe("validateJsonSchema", () => {
+  const schema = {
+    type: "object",
+    required: ["code"],
+    properties: {
+      code: { type: "string", pattern: "^[A-Z0-9]+$", maxLength: 8 },
+      percentage: { type: "number", exclusiveMinimum: 0, maximum: 100 },
+      tags: { type: "array", items: { type: "string" }, maxItems: 2 },
+      target: { $ref: "#/$defs/target" },
+    },
+    additionalProperties: false,
+    $defs: {
+      target: { oneOf: [{ const: "ORDER" }, { const: "PRODUCT" }] },
+    },
+  };
+
+  it("returns no errors for a matching value", () => {
+    expect(
+      validateJsonSchema(schema, {
+        code: "SAVE10",
+        percentage: 10,
+        tags: ["sale"],
+        target: "ORDER",
+      }),
+    ).toStrictEqual([]);
+  });
+
+  it("reports each mismatch with its path", () => {
+    const errors = validateJsonSchema(schema, {
+      code: "save10",
+      percentage: 0,
+      tags: ["sale", 1, "clearance"],
+      target: "SHIPPING",
+      extra: true,
+    });
+
+    expect(errors).toHaveLength(6);
+    expect(errors).toEqual(
+      expect.arrayContaining([
+        { message: "unexpected property `extra`", path: [] },
+        {
+          message: '"save10" should match pattern "^[A-Z0-9]+$"',
+          path: ["code"],
+        },
+        { message: "0 should be > 0", path: ["percentage"] },
+        {
+          message: '["sale",1,"clearance"] should NOT have more than 2 items',
+          path: ["tags"],
+        },
+        { message: "expected a string, but got 1", path: ["tags", 1] },
+        {
+          message: '"SHIPPING" should match exactly one schema in oneOf',
+          path: ["target"],
+        },
+      ]),
+    );
+  });
+
+  it("reports missing required properties and mismatched types", () => {
+    expect(validateJsonSchema(schema, {})).toStrictEqual([
+      { message: "missing required property `code`", path: [] },
+    ]);
+    expect(validateJsonSchema(schema, ["SAVE10"])).toStrictEqual([
+      { message: 'expected an object, but got ["SAVE10"]', path: [] },
+    ]);
+    expect(
+      validateJsonSchema({ type: ["integer", "null"] }, 1.5),
+    ).toStrictEqual([
+      { message: "expected an integer or null, but got 1.5", path: [] },
+    ]);
+  });
+
+  it("reports schemas that cannot be compiled", () => {
+    expect(
+      validateJsonSchema({ $ref: "#/$defs/missing" }, "value"),
+    ).toStrictEqual([
+      {
+        message:
+          "invalid JSON Schema: can't resolve reference #/$defs/missing from id #",
+        path: [],
+      },
+    ]);
+  });
+});
@@ -1,10 +1,12 @@
 import { describe, it, expect } from "vitest";
+import { parse } from "graphql";
 
 import {
   validateTestAssets,
   loadFixture,
   loadInputQuery,
   loadSchema,
+  loadMetafieldSchemas,
 } from "../../src/wasm-testing-helpers.ts";
 
 describe("validateTestAssets", () => {
@@ -247,4 +249,143 @@ describe("validateTestAssets", () => {
       );
     });
   });
+
+  describe("Target Restrictions", () => {
+    async function loadDiscountTestData() {
+      const functionDir = "./test-app/extensions/discount-function-rs";
+      const schema = await loadSchema(`${functionDir}/schema.graphql`);
+      const fixture = await loadFixture(
+        `${functionDir}/tests/fixtures/cart-lines-valid-fixture.json`,
+      );
+      return { schema, fixture };
+    }
+
+    it("should report fields restricted to other targets than the fixture target", async () => {
+      const { schema, fixture } = await loadDiscountTestData();
+      const inputQueryAST = parse(`
+        query Input {
+          enteredDiscountCodes
+          cart {
+            lines {
+              id
+              cost {
+                subtotalAmount {
+                  amount
+                }
+              }
+            }
+          }
+          discount {
+            discountClasses
+          }
+        }
+      `);
+
+      const result = await validateTestAssets({
+        schema,
+        fixture,
+        inputQueryAST,
+      });
+
+      expect(result.inputQuery.errors).toHaveLength(1);
+      expect(result.inputQuery.errors[0].message).toContain(
+        'Cannot query field "enteredDiscountCodes" on type "Input" for target "cart.lines.discounts.generate.run"',
+      );
+    });
+
+    it("should check restrictions against an explicit target", async () => {
+      const { schema, fixture } = await loadDiscountTestData();
+      const inputQueryAST = await loadInputQuery(
+        "./test-app/extensions/discount-function-rs/src/cart_lines_discounts_generate_run.graphql",
+      );
+
+      const result = await validateTestAssets({
+        schema,
+        fixture,
+        inputQueryAST,
+        target: "cart.lines.discounts.generate.run",
+      });
+
+      expect(result.inputQuery.errors).toHaveLength(0);
+      expect(result.inputFixture.errors).toHaveLength(0);
+      expect(result.outputFixture.errors).toHaveLength(0);
+    });
+  });
+
+  describe("Metafield Schemas", () => {
+    const functionDir = "./test-app/extensions/discount-function-rs";
+
+    it("should check fixture metafields against their schemas", async () => {
+      const schema = await loadSchema(`${functionDir}/schema.graphql`);
+      const fixture = await loadFixture(
+        `${functionDir}/tests/fixtures/cart-lines-configured-fixture.json`,
+      );
+      const inputQueryAST = await loadInputQuery(
+        `${functionDir}/src/cart_lines_discounts_generate_run.graphql`,
+      );
+      const metafieldSchemas = await loadMetafieldSchemas(
+        `${functionDir}/tests/metafield-schemas.json`,
+      );
+
+      const valid = await validateTestAssets({
+        schema,
+        fixture,
+        inputQueryAST,
+        metafieldSchemas,
+      });
+      expect(valid.inputFixture.errors).toHaveLength(0);
+
+      fixture.input.discount.metafield.jsonValue.order.percentage = "15";
+      const invalid = await validateTestAssets({
+        schema,
+        fixture,
+        inputQueryAST,
+        metafieldSchemas,
+      });
+      expect(invalid.inputFixture.errors).toStrictEqual([
+        {
+          message:
+            'Invalid `function-configuration` metafield at `order.percentage`: expected a number, but got "15"',
+          path: ["discount", "metafield", "jsonValue"],
+        },
+      ]);
+    });
+  });
+
+  describe("Variables", () => {
+    it("should check the fixture's variables against the input query", async () => {
+      const schema = await loadSchema(
+        "./test-app/extensions/discount-function-rs/schema.graphql",
+      );
+      const fixture = await loadFixture(
+        "./test/fixtures/variables/tagged-cart-fixture.json",
+      );
+      const inputQueryAST = await loadInputQuery(
+        "./test/fixtures/variables/tagged-cart-query.graphql",
+      );
+
+      const valid = await validateTestAssets({ schema, fixture, inputQueryAST });
+      expect(valid.inputQuery.errors).toHaveLength(0);
+      expect(valid.inputFixture.errors).toHaveLength(0);
+
+      fixture.variables = { tags: ["VIP"] };
+      const invalid = await validateTestAssets({
+        schema,
+        fixture,
+        inputQueryAST,
+      });
+      expect(invalid.inputQuery.errors.map((error) => error.message)).toEqual(
+        [
+          'Variable "$configurationKey" of required type "String!" was not provided.',
+        ],
+      );
+      expect(invalid.inputFixture.errors).toStrictEqual([
+        {
+          message:
+            "Expected a `HasTagResponse` for each value of the `tags` argument (1), but got 2",
+          path: ["cart", "buyerIdentity", "customer", "hasTags"],
+        },
+      ]);
+    });
+  });
 });
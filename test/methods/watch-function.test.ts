@@ -0,0 +1,251 @@
+import fs from "fs";
+import os from "os";
+import path from "path";
+
+import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
+
+import { buildFunction } from "../../src/methods/build-function.ts";
+import { getFunctionInfo } from "../../src/methods/get-function-info.ts";
+import { runFunction } from "../../src/methods/run-function.ts";
+import {
+  watchFunction,
+  FunctionWatcher,
+  WatchCycle,
+} from "../../src/methods/watch-function.ts";
+
+vi.mock("../../src/methods/build-function.ts", () => ({
+  buildFunction: vi.fn(),
+}));
+vi.mock("../../src/methods/get-function-info.ts", () => ({
+  getFunctionInfo: vi.fn(),
+}));
+vi.mock("../../src/methods/run-function.ts", () => ({
+  runFunction: vi.fn(),
+}));
+
+const SCHEMA_PATH = path.resolve(
+  "./test-app/extensions/discount-function-rs/schema.graphql",
+);
+
+const RUN_TARGET = "cart.lines.discounts.generate.run";
+const FETCH_TARGET = "cart.lines.discounts.generate.fetch";
+
+const EXTENSION_TOML = `
+[[extensions]]
+type = "function"
+
+  [[extensions.targeting]]
+  target = "${RUN_TARGET}"
+  input_query = "src/run.graphql"
+
+  [[extensions.targeting]]
+  target = "${FETCH_TARGET}"
+  input_query = "src/fetch.graphql"
+
+  [extensions.build]
+  command = "cargo build --target=wasm32-wasip1 --release"
+  path = "dist/function.wasm"
+  watch = ["src/**/*.rs"]
+`;
+
+function fixture(
+  target: string,
+  input: Record<string, any>,
+  output: Record<string, any>,
+) {
+  return JSON.stringify({ payload: { export: "run", target, input, output } });
+}
+
+describe("watchFunction", () => {
+  const mockBuildFunction = vi.mocked(buildFunction);
+  const mockGetFunctionInfo = vi.mocked(getFunctionInfo);
+  const mockRunFunction = vi.mocked(runFunction);
+  let functionDir: string;
+  let watcher: FunctionWatcher | undefined;
+  let cycles: WatchCycle[];
+
+  beforeEach(() => {
+    functionDir = fs.mkdtempSync(path.join(os.tmpdir(), "watch-function-"));
+    fs.mkdirSync(path.join(functionDir, "src"));
+    fs.mkdirSync(path.join(functionDir, "tests/fixtures"), {
+      recursive: true,
+    });
+    fs.writeFileSync(
+      path.join(functionDir, "shopify.extension.toml"),
+      EXTENSION_TOML,
+    );
+    fs.writeFileSync(path.join(functionDir, "src/main.rs"), "fn main() {}");
+    fs.writeFileSync(
+      path.join(functionDir, "src/run.graphql"),
+      "query Input { cart { lines { id } } }",
+    );
+    fs.writeFileSync(
+      path.join(functionDir, "src/fetch.graphql"),
+      "query Input { enteredDiscountCodes }",
+    );
+    fs.writeFileSync(
+      path.join(functionDir, "tests/fixtures/run.json"),
+      fixture(RUN_TARGET, { cart: { lines: [] } }, { operations: [] }),
+    );
+    fs.writeFileSync(
+      path.join(functionDir, "tests/fixtures/fetch.json"),
+      fixture(FETCH_TARGET, { enteredDiscountCodes: [] }, { request: null }),
+    );
+
+    mockGetFunctionInfo.mockResolvedValue({
+      schemaPath: SCHEMA_PATH,
+      functionRunnerPath: "/path/to/function-runner",
+      wasmPath: path.join(functionDir, "dist/function.wasm"),
+      targeting: {
+        [RUN_TARGET]: {
+          inputQueryPath: path.join(functionDir, "src/run.graphql"),
+        },
+        [FETCH_TARGET]: {
+          inputQueryPath: path.join(functionDir, "src/fetch.graphql"),
+        },
+      },
+    });
+    mockBuildFunction.mockResolvedValue({
+      success: true,
+      output: "",
+      error: null,
+    });
+    mockRunFunction.mockImplementation(async (runFixture) => ({
+      result: {
+        output: runFixture.expectedOutput,
+        instructions: null,
+        memoryUsage: null,
+        inputSize: 0,
+        outputSize: 0,
+        logs: "",
+      },
+      error: null,
+    }));
+    cycles = [];
+  });
+
+  afterEach(() => {
+    watcher?.close();
+    watcher = undefined;
+    fs.rmSync(functionDir, { recursive: true, force: true });
+    vi.clearAllMocks();
+  });
+
+  function watch() {
+    watcher = watchFunction({
+      functionDir,
+      onFixtureResult: () => {},
+      onCycle: (cycle) => cycles.push(cycle),
+      debounceMs: 10,
+    });
+    return watcher.ready;
+  }
+
+  async function nextCycle(change: () => void) {
+    const count = cycles.length;
+    change();
+    await vi.waitFor(() => expect(cycles.length).toBeGreaterThan(count));
+    return cycles[cycles.length - 1];
+  }
+
+  function rerunFixtures(cycle: WatchCycle) {
+    return cycle.results.map((result) => path.basename(result.fixturePath));
+  }
+
+  it("should build the function and run every fixture initially", async () => {
+    const cycle = await watch();
+
+    expect(cycle.error).toBeNull();
+    expect(mockBuildFunction).toHaveBeenCalledWith(functionDir, undefined);
+    expect(rerunFixtures(cycle)).toEqual(["fetch.json", "run.json"]);
+    expect(cycle.results.every((result) => result.passed)).toBe(true);
+  });
+
+  it("should rerun only a fixture that changed", async () => {
+    await watch();
+    mockBuildFunction.mockClear();
+
+    const cycle = await nextCycle(() =>
+      fs.writeFileSync(
+        path.join(functionDir, "tests/fixtures/run.json"),
+        fixture(
+          RUN_TARGET,
+          { cart: { lines: [{ id: "gid://shopify/CartLine/1" }] } },
+          { operations: [] },
+        ),
+      ),
+    );
+
+    expect(cycle.build).toBeNull();
+    expect(mockBuildFunction).not.toHaveBeenCalled();
+    expect(rerunFixtures(cycle)).toEqual(["run.json"]);
+  });
+
+  it("should rebuild and rerun the fixtures of a target whose input query changed", async () => {
+    await watch();
+    mockBuildFunction.mockClear();
+
+    const cycle = await nextCycle(() =>
+      fs.writeFileSync(
+        path.join(functionDir, "src/fetch.graphql"),
+        "query Input { enteredDiscountCodes }\n",
+      ),
+    );
+
+    expect(mockBuildFunction).toHaveBeenCalledTimes(1);
+    expect(rerunFixtures(cycle)).toEqual(["fetch.json"]);
+  });
+
+  it("should rebuild and rerun every fixture when a source file changes", async () => {
+    await watch();
+    mockBuildFunction.mockClear();
+
+    const cycle = await nextCycle(() =>
+      fs.writeFileSync(path.join(functionDir, "src/main.rs"), "fn main() {}\n"),
+    );
+
+    expect(mockBuildFunction).toHaveBeenCalledTimes(1);
+    expect(rerunFixtures(cycle)).toEqual(["fetch.json", "run.json"]);
+  });
+
+  it("should ignore changes to build output and dependencies", async () => {
+    fs.writeFileSync(
+      path.join(functionDir, "shopify.extension.toml"),
+      EXTENSION_TOML.replace('["src/**/*.rs"]', '["**/*.rs"]'),
+    );
+    for (const directory of ["target/release", "node_modules/pkg"]) {
+      fs.mkdirSync(path.join(functionDir, directory), { recursive: true });
+    }
+    await watch();
+    mockBuildFunction.mockClear();
+
+    const fixturePath = path.join(functionDir, "tests/fixtures/run.json");
+    const cycle = await nextCycle(() => {
+      fs.writeFileSync(path.join(functionDir, "target/release/build.rs"), "");
+      fs.writeFileSync(path.join(functionDir, "node_modules/pkg/lib.rs"), "");
+      fs.writeFileSync(
+        fixturePath,
+        fixture(RUN_TARGET, { cart: { lines: [] } }, { operations: [] }),
+      );
+    });
+
+    expect(cycle.changedFiles).toEqual([fixturePath]);
+    expect(mockBuildFunction).not.toHaveBeenCalled();
+  });
+
+  it("should stop a cycle whose build fails", async () => {
+    mockBuildFunction.mockResolvedValue({
+      success: false,
+      output: null,
+      error: "Build command failed with exit code 101: error[E0425]",
+    });
+
+    const cycle = await watch();
+
+    expect(cycle.error).toBe(
+      "Build command failed with exit code 101: error[E0425]",
+    );
+    expect(cycle.results).toEqual([]);
+    expect(mockRunFunction).not.toHaveBeenCalled();
+  });
+});